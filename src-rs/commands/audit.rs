@@ -0,0 +1,45 @@
+use crate::error::Result;
+use crate::utils::audit::{AuditRecord, PendingAudit};
+use crate::utils::GitManager;
+
+pub async fn run(rev: Option<String>, json_output: bool) -> Result<()> {
+    let rev = rev.unwrap_or_else(|| "HEAD".to_string());
+    let commit = GitManager::rev_parse(&rev)?;
+    let record = AuditRecord::read(&commit)?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&record)?);
+        return Ok(());
+    }
+
+    let Some(record) = record else {
+        println!("ℹ️  No git-ai audit note on {}", rev);
+        println!("   Enable recording with: git-ai config set audit_notes true");
+        return Ok(());
+    };
+
+    println!(
+        "🔎 Audit trail for {} ({})\n",
+        rev,
+        &commit[..commit.len().min(12)]
+    );
+    println!("  Tool:         {}", record.tool);
+    println!("  Provider:     {}", record.provider);
+    println!("  Model:        {}", record.model);
+    println!("  Prompt hash:  {}", record.prompt_hash);
+    println!(
+        "  Human edited: {}",
+        if record.edited { "yes" } else { "no" }
+    );
+    println!("  Generated at: {}", record.generated_at);
+
+    Ok(())
+}
+
+/// Attach the note the prepare-commit-msg hook left for the commit just made
+pub fn attach_pending() -> Result<()> {
+    if let Err(e) = PendingAudit::attach_to_head() {
+        eprintln!("⚠️  Failed to record audit note: {}", e);
+    }
+    Ok(())
+}
@@ -0,0 +1,341 @@
+//! `git-ai bisect-hint`: read the commits between a good and a bad revision,
+//! rank the likeliest culprits for a symptom, and optionally have
+//! `git bisect` skip the commits that cannot plausibly cause it.
+
+use crate::commands::release::confirm;
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::{language_name, AIClient};
+use crate::utils::{ci, ConfigManager, GitManager};
+use serde::Serialize;
+
+/// Patches sent to the model, shared between the commits of the range
+const MAX_RANGE_CHARS: usize = 30000;
+
+/// Every commit gets at least its message and stat, however long the range
+const MIN_COMMIT_CHARS: usize = 300;
+
+/// Ranges longer than this are better narrowed by a few plain bisect steps first
+const MAX_COMMITS: usize = 200;
+
+/// Flags of `git-ai bisect-hint`
+#[derive(Debug, Default)]
+pub struct BisectHintOptions {
+    pub good: String,
+    pub bad: String,
+    pub symptom: String,
+    /// Suspects to list
+    pub top: usize,
+    /// Start (or reuse) a bisect session and skip the unrelated commits
+    pub skip: bool,
+    pub yes: bool,
+    pub json: bool,
+}
+
+/// One commit of the range as `git log --format="%h %cd %s"` shows it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RangeCommit {
+    hash: String,
+    date: String,
+    subject: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Suspect {
+    #[serde(flatten)]
+    commit: RangeCommit,
+    reason: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Ranking {
+    /// `(hash, reason)`, most likely first
+    suspects: Vec<(String, String)>,
+    unrelated: Vec<String>,
+}
+
+pub async fn run(options: BisectHintOptions) -> Result<()> {
+    let BisectHintOptions {
+        good,
+        bad,
+        symptom,
+        top,
+        skip,
+        yes,
+        json,
+    } = options;
+    GitManager::rev_parse(&good)?;
+    GitManager::rev_parse(&bad)?;
+    let commits: Vec<RangeCommit> = GitManager::get_commits_between_refs(&good, &bad)?
+        .iter()
+        .filter_map(|line| parse_commit_line(line))
+        .collect();
+    if commits.is_empty() {
+        return Err(GitAiError::InvalidArgument(format!(
+            "No commits between {} and {}; is {} an ancestor of {}?",
+            good, bad, good, bad
+        )));
+    }
+    if commits.len() > MAX_COMMITS {
+        return Err(GitAiError::InvalidArgument(format!(
+            "{}..{} has {} commits (at most {}); narrow it with a few 'git bisect' steps first",
+            good,
+            bad,
+            commits.len(),
+            MAX_COMMITS
+        )));
+    }
+    if !json {
+        println!(
+            "🔍 {} commits between {} and {} (a plain bisect needs about {} steps)\n",
+            commits.len(),
+            good,
+            bad,
+            bisect_steps(commits.len())
+        );
+    }
+
+    let budget = (MAX_RANGE_CHARS / commits.len()).max(MIN_COMMIT_CHARS);
+    let mut patches = Vec::with_capacity(commits.len());
+    for commit in &commits {
+        let patch = GitManager::get_commit_patch(&commit.hash)?;
+        let patch = if patch.chars().count() > budget {
+            patch.chars().take(budget).collect::<String>() + "\n... (patch truncated)"
+        } else {
+            patch
+        };
+        patches.push(format!(
+            "[{}] {} {}\n{}",
+            commit.hash, commit.date, commit.subject, patch
+        ));
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let pb = ci::spinner();
+    pb.set_message(format!("🔎 Reading {} commits...", commits.len()));
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let user_prompt = format!(
+        "Symptom: {}\n\nCommits after the last good revision, newest first:\n\n{}",
+        symptom,
+        patches.join("\n\n")
+    );
+    let response = ai_client
+        .generate_review(&get_bisect_system_prompt(&config.locale, top), &user_prompt)
+        .await;
+    pb.finish_and_clear();
+
+    let ranking = parse_ranking(&response?, &commits);
+    let suspects: Vec<Suspect> = ranking
+        .suspects
+        .iter()
+        .take(top)
+        .filter_map(|(hash, reason)| {
+            Some(Suspect {
+                commit: commits.iter().find(|c| &c.hash == hash)?.clone(),
+                reason: reason.clone(),
+            })
+        })
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "commits": commits.len(),
+                "suspects": suspects,
+                "unrelated": ranking.unrelated,
+            }))?
+        );
+    } else {
+        print_ranking(&suspects, &ranking.unrelated);
+    }
+    if config.show_usage.unwrap_or(false) {
+        println!("\n{}", ai_client.usage().summary());
+    }
+
+    if skip {
+        skip_unrelated(&good, &bad, &ranking.unrelated, yes)?;
+    }
+    Ok(())
+}
+
+fn print_ranking(suspects: &[Suspect], unrelated: &[String]) {
+    if suspects.is_empty() {
+        println!("ℹ️  No commit stands out; fall back to a plain 'git bisect'");
+    } else {
+        println!("🎯 Most likely culprits:");
+        for (rank, suspect) in suspects.iter().enumerate() {
+            println!(
+                "{:>2}. {} {}  {}",
+                rank + 1,
+                suspect.commit.hash,
+                suspect.commit.date,
+                suspect.commit.subject
+            );
+            println!("    ↳ {}", suspect.reason);
+        }
+        println!(
+            "\n💡 Check these first: 'git checkout {}', reproduce, then 'git checkout {}^' to confirm",
+            suspects[0].commit.hash, suspects[0].commit.hash
+        );
+    }
+    if !unrelated.is_empty() {
+        println!(
+            "\n🙈 Unlikely to matter ({}): {}",
+            unrelated.len(),
+            unrelated.join(" ")
+        );
+        println!("   Skip them while bisecting with 'git-ai bisect-hint ... --skip'");
+    }
+}
+
+/// Start a bisect session unless one is running, then mark the unrelated
+/// commits as skipped so `git bisect` never checks them out
+fn skip_unrelated(good: &str, bad: &str, unrelated: &[String], yes: bool) -> Result<()> {
+    if unrelated.is_empty() {
+        println!("\nℹ️  Nothing to skip");
+        return Ok(());
+    }
+    let bisecting = GitManager::is_bisecting()?;
+    let prompt = if bisecting {
+        format!("Skip {} commits in the running bisect?", unrelated.len())
+    } else {
+        format!(
+            "Start 'git bisect' on {}..{} and skip {} commits?",
+            good,
+            bad,
+            unrelated.len()
+        )
+    };
+    println!();
+    if !confirm(yes, &prompt)? {
+        println!("❌ Bisect left untouched");
+        return Ok(());
+    }
+    if !bisecting {
+        GitManager::bisect(&["start", bad, good])?;
+    }
+    let mut args = vec!["skip"];
+    args.extend(unrelated.iter().map(String::as_str));
+    let output = GitManager::bisect(&args)?;
+    println!("✅ Skipped {} commits", unrelated.len());
+    if let Some(next) = output.lines().find(|line| !line.trim().is_empty()) {
+        println!("   {}", next.trim());
+    }
+    println!("   Mark each checkout with 'git bisect good' or 'git bisect bad'; skipped commits can still turn out to be the culprit");
+    Ok(())
+}
+
+fn get_bisect_system_prompt(locale: &str, top: usize) -> String {
+    format!(
+        "You help find the commit that introduced a bug. Given a symptom and the commits after the last \
+         known good revision, rank the commits most likely to have caused it. Reply with at most {} lines \
+         of the form `SUSPECT <hash>: <one-sentence reason tied to the diff>`, most likely first, then one \
+         line `UNRELATED: <hash> <hash> ...` listing commits that cannot plausibly cause the symptom \
+         (docs, unrelated modules, formatting). Use the hashes shown in brackets. Leave a commit out of \
+         UNRELATED when in doubt. Write the reasons in {}.",
+        top,
+        language_name(locale)
+    )
+}
+
+/// `<hash> <date> <subject>`
+fn parse_commit_line(line: &str) -> Option<RangeCommit> {
+    let (hash, rest) = line.split_once(' ')?;
+    let (date, subject) = rest.split_once(' ').unwrap_or((rest, ""));
+    Some(RangeCommit {
+        hash: hash.to_string(),
+        date: date.to_string(),
+        subject: subject.to_string(),
+    })
+}
+
+/// Read `SUSPECT <hash>: <reason>` and `UNRELATED: <hash>...` lines, keeping
+/// only hashes that belong to the range (matched by prefix either way)
+fn parse_ranking(text: &str, commits: &[RangeCommit]) -> Ranking {
+    let resolve = |hash: &str| {
+        let hash = hash.trim().trim_matches(['[', ']', '`', ',', '*']);
+        if hash.len() < 4 {
+            return None;
+        }
+        commits
+            .iter()
+            .find(|c| c.hash.starts_with(hash) || hash.starts_with(&c.hash))
+            .map(|c| c.hash.clone())
+    };
+    let mut ranking = Ranking::default();
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', ' ']);
+        if let Some(rest) = line.strip_prefix("SUSPECT") {
+            let Some((hash, reason)) = rest.split_once(':') else {
+                continue;
+            };
+            if let Some(hash) = resolve(hash) {
+                if !ranking.suspects.iter().any(|(known, _)| *known == hash) {
+                    ranking
+                        .suspects
+                        .push((hash, reason.trim().trim_matches('*').trim().to_string()));
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("UNRELATED") {
+            let rest = rest.trim_start_matches([':', '*', ' ']);
+            for hash in rest.split_whitespace().filter_map(resolve) {
+                let suspected = ranking.suspects.iter().any(|(known, _)| *known == hash);
+                if !suspected && !ranking.unrelated.contains(&hash) {
+                    ranking.unrelated.push(hash);
+                }
+            }
+        }
+    }
+    ranking
+}
+
+/// Steps a plain bisect takes over `commits` commits
+fn bisect_steps(commits: usize) -> u32 {
+    (commits + 1).next_power_of_two().trailing_zeros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranking_against_the_range() {
+        let commits: Vec<RangeCommit> = [
+            "a1b2c3d 2026-10-01 feat(net): retry requests",
+            "0f0f0f0 2026-09-30 docs: fix typo",
+            "1234567 2026-09-29 style: format",
+        ]
+        .iter()
+        .filter_map(|line| parse_commit_line(line))
+        .collect();
+        assert_eq!(commits[1].subject, "docs: fix typo");
+
+        let ranking = parse_ranking(
+            "SUSPECT a1b2c3d9: Changes the timeout used by retries\n\
+             - **SUSPECT [ffffff1]**: not in range\n\
+             SUSPECT 0f0f0f0: Unlikely but touches config\n\
+             UNRELATED: 0f0f0f0, 1234567 deadbee",
+            &commits,
+        );
+        assert_eq!(
+            ranking,
+            Ranking {
+                suspects: vec![
+                    (
+                        "a1b2c3d".to_string(),
+                        "Changes the timeout used by retries".to_string()
+                    ),
+                    (
+                        "0f0f0f0".to_string(),
+                        "Unlikely but touches config".to_string()
+                    ),
+                ],
+                unrelated: vec!["1234567".to_string()],
+            }
+        );
+
+        assert_eq!(bisect_steps(1), 1);
+        assert_eq!(bisect_steps(40), 6);
+    }
+}
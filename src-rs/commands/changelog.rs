@@ -0,0 +1,266 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ConfigManager, GitManager};
+use std::fs;
+use std::path::Path;
+
+/// Section title used when the range does not end at a release
+pub const UNRELEASED: &str = "Unreleased";
+
+const MAX_CHANGELOG_COMMITS: usize = 200;
+
+const CHANGELOG_HEADER: &str = "# Changelog\n\nAll notable changes to this project will be documented in this file.\n\nThe format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/).\n";
+
+/// Closing marker of a generated section
+const END_MARKER: &str = "<!-- git-ai:changelog:end -->";
+
+/// Flags of `git-ai changelog`
+#[derive(Debug, Default)]
+pub struct ChangelogOptions {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub version: Option<String>,
+    pub write: bool,
+    pub file: Option<String>,
+}
+
+pub async fn run(options: ChangelogOptions) -> Result<()> {
+    let ChangelogOptions {
+        from,
+        to,
+        version,
+        write,
+        file,
+    } = options;
+
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+    let from = match from {
+        Some(from) => from,
+        None => GitManager::get_latest_tag()?.ok_or_else(|| {
+            GitAiError::InvalidArgument(
+                "No git tag found. Use --from <ref> to choose the start of the range.".to_string(),
+            )
+        })?,
+    };
+    // A range ending at a tag documents that release
+    let version = version.unwrap_or_else(|| {
+        if to != "HEAD" && GitManager::is_tag(&to) {
+            to.trim_start_matches('v').to_string()
+        } else {
+            UNRELEASED.to_string()
+        }
+    });
+
+    if !write {
+        println!("📝 Generating changelog for {}..{}...\n", from, to);
+    }
+    let section = generate_section(&from, &to, &version).await?;
+
+    if !write {
+        println!("{}", section);
+        return Ok(());
+    }
+
+    let path = file.unwrap_or_else(|| "CHANGELOG.md".to_string());
+    let path = Path::new(&path);
+    let existing = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    fs::write(path, upsert_section(&existing, &version, &section))?;
+    println!("✅ Updated {} ([{}])", path.display(), version);
+
+    Ok(())
+}
+
+/// Generate the Keep-a-Changelog section (`## [version] - date` plus
+/// `### Added/Changed/...` groups) for `from..to`
+pub async fn generate_section(from: &str, to: &str, version: &str) -> Result<String> {
+    let commits = GitManager::get_commits_between_refs(from, to)?;
+    if commits.is_empty() {
+        return Err(GitAiError::InvalidArgument(format!(
+            "No commits found in {}..{}",
+            from, to
+        )));
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+
+    let user_prompt = format!(
+        "Commit range: {}..{}\nTotal commits: {}\n\nGenerate the changelog entries for these commits:\n\n{}",
+        from,
+        to,
+        commits.len(),
+        commits
+            .iter()
+            .take(MAX_CHANGELOG_COMMITS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let body = ai_client
+        .generate_commit_message(&get_changelog_system_prompt(&config.locale), &user_prompt)
+        .await?;
+
+    let heading = if version == UNRELEASED {
+        format!("## [{}]", UNRELEASED)
+    } else {
+        format!("## [{}] - {}", version, GitManager::get_commit_date(to)?)
+    };
+    Ok(format!("{}\n\n{}", heading, normalize_entries(&body)))
+}
+
+/// Keep only the `### Group` blocks of the model output
+fn normalize_entries(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .skip_while(|line| !line.starts_with("### "))
+        .collect();
+    if lines.is_empty() {
+        // No groups: keep whatever bullets came back rather than losing them
+        return text.trim().to_string();
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn start_marker(version: &str) -> String {
+    format!("<!-- git-ai:changelog [{}] -->", version)
+}
+
+/// Insert or replace the generated section for `version`. Re-running replaces
+/// the previous block; releasing a version also drops the Unreleased block.
+pub fn upsert_section(existing: &str, version: &str, section: &str) -> String {
+    let block = format!(
+        "{}\n{}\n{}\n",
+        start_marker(version),
+        section.trim(),
+        END_MARKER
+    );
+
+    let mut content = if existing.trim().is_empty() {
+        CHANGELOG_HEADER.to_string()
+    } else {
+        existing.to_string()
+    };
+    if version != UNRELEASED {
+        if let Some(without) = remove_block(&content, UNRELEASED) {
+            content = without;
+        }
+    }
+
+    if let Some(start) = content.find(&start_marker(version)) {
+        if let Some(end) = content[start..].find(END_MARKER) {
+            let mut end = start + end + END_MARKER.len();
+            if content[end..].starts_with('\n') {
+                end += 1;
+            }
+            content.replace_range(start..end, &block);
+            return content;
+        }
+    }
+
+    // New sections go above the first existing release (generated or not),
+    // below the intro
+    let insert_at = ["\n## ", "\n<!-- git-ai:changelog ["]
+        .iter()
+        .filter_map(|needle| content.find(needle))
+        .min()
+        .map(|i| i + 1)
+        .unwrap_or(content.len());
+    let mut prefix = content[..insert_at].to_string();
+    if !prefix.is_empty() && !prefix.ends_with("\n\n") {
+        prefix.push_str(if prefix.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+    let suffix = &content[insert_at..];
+    if suffix.is_empty() {
+        format!("{}{}", prefix, block)
+    } else {
+        format!("{}{}\n{}", prefix, block, suffix)
+    }
+}
+
+fn remove_block(content: &str, version: &str) -> Option<String> {
+    let start = content.find(&start_marker(version))?;
+    let end = start + content[start..].find(END_MARKER)? + END_MARKER.len();
+    let rest = content[end..].trim_start_matches('\n');
+    Some(format!("{}{}", &content[..start], rest))
+}
+
+fn get_changelog_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => {
+            r#"你是一个 CHANGELOG 维护者，按照 Keep a Changelog 规范把提交记录整理成变更条目。
+
+输出要求：
+1) 只输出分组和条目，不要输出版本标题。
+2) 分组只能使用以下英文标题（没有内容的分组省略）：### Added、### Changed、### Deprecated、### Removed、### Fixed、### Security。
+3) 每条以 "- " 开头，面向用户描述变化，合并同类提交，不要出现提交哈希。
+4) 忽略纯粹的 CI、格式化、合并提交等对用户无感知的改动。
+5) 不要编造提交中没有的内容。"#
+                .to_string()
+        }
+        _ => {
+            r#"You maintain a CHANGELOG following Keep a Changelog. Turn the commit list into changelog entries.
+
+Output rules:
+1) Output only the groups and their entries, no version heading.
+2) Use only these group headings, omitting empty ones: ### Added, ### Changed, ### Deprecated, ### Removed, ### Fixed, ### Security.
+3) Each entry starts with "- ", describes the change for users, merges related commits and never includes commit hashes.
+4) Skip changes users cannot notice, such as CI tweaks, formatting or merge commits.
+5) Do not invent anything the commits do not show."#
+                .to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_model_output() {
+        assert_eq!(
+            normalize_entries("Here you go:\n```markdown\n### Added\n- Export\n```"),
+            "### Added\n- Export"
+        );
+    }
+
+    #[test]
+    fn upserts_sections_idempotently() {
+        let unreleased = "## [Unreleased]\n\n### Added\n- Export";
+        let created = upsert_section("", UNRELEASED, unreleased);
+        assert!(created.starts_with(CHANGELOG_HEADER));
+        assert_eq!(upsert_section(&created, UNRELEASED, unreleased), created);
+
+        let existing = format!("{}\n## [1.0.0] - 2024-01-01\n\n- First\n", created);
+        let updated = upsert_section(
+            &existing,
+            UNRELEASED,
+            "## [Unreleased]\n\n### Fixed\n- Crash",
+        );
+        assert!(updated.contains("### Fixed\n- Crash"));
+        assert!(!updated.contains("- Export"));
+        assert!(updated.find("[Unreleased]").unwrap() < updated.find("[1.0.0]").unwrap());
+
+        let released = upsert_section(
+            &updated,
+            "1.1.0",
+            "## [1.1.0] - 2024-02-01\n\n### Fixed\n- Crash",
+        );
+        assert!(!released.contains("[Unreleased]"));
+        assert!(released.find("[1.1.0]").unwrap() < released.find("[1.0.0]").unwrap());
+        assert!(released.starts_with(CHANGELOG_HEADER));
+
+        let next = upsert_section(
+            &released,
+            "1.2.0",
+            "## [1.2.0] - 2024-03-01\n\n### Added\n- Sync",
+        );
+        assert!(
+            next.find(&start_marker("1.2.0")).unwrap() < next.find(&start_marker("1.1.0")).unwrap()
+        );
+    }
+}
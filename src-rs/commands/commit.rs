@@ -1,20 +1,74 @@
 use crate::error::Result;
+use crate::types::split_locale;
 use crate::utils::agent_lite::AgentLite;
-use crate::utils::ai::{AIClient, PromptTemplates};
-use crate::utils::{ConfigManager, CopilotCLI, GitManager};
+use crate::utils::ai::{language_name, AIClient, PromptContext, PromptTemplates};
+use crate::utils::audit::AuditRecord;
+use crate::utils::codeowners;
+use crate::utils::commit_summary;
+use crate::utils::config::resolve_config_path;
+use crate::utils::conventional::MessageRules;
+use crate::utils::copilot::Severity;
+use crate::utils::diff_compact;
+use crate::utils::editor::open_in_editor;
+use crate::utils::follow_ups;
+use crate::utils::footer;
+use crate::utils::issues;
+use crate::utils::repo_state::RepoState;
+use crate::utils::{
+    ci, truncate_at_char_boundary, workspace, ConfigManager, CopilotCLI, GitManager,
+};
 use dialoguer::{MultiSelect, Select};
-use indicatif::ProgressBar;
 use std::collections::HashSet;
 
-pub async fn run(
-    yes: bool,
-    num: usize,
-    locale_override: Option<String>,
-    agent: bool,
-    copilot: bool,
-) -> Result<()> {
+/// Flags of `git-ai commit` (and the bare `git-ai` default)
+#[derive(Debug, Default)]
+pub struct CommitOptions {
+    /// `None` (no flag given) falls back to `auto_yes`
+    pub yes: Option<bool>,
+    pub num: Option<usize>,
+    pub locale: Option<String>,
+    /// `None` falls back to `agent_by_default`
+    pub agent: Option<bool>,
+    /// `None` falls back to `copilot_by_default`
+    pub copilot: Option<bool>,
+    /// Abort before committing when the Copilot analysis finds a risk at
+    /// this severity or above
+    pub fail_on: Option<Severity>,
+    /// With --copilot: print the impact analysis as JSON
+    pub json: bool,
+    pub style: Option<String>,
+    /// `None` falls back to `ignore_whitespace` from config
+    pub ignore_whitespace: Option<bool>,
+    /// `None` falls back to `expand_context` from config
+    pub expand_context: Option<bool>,
+    /// Pathspecs after `--`: only these staged files are described and committed
+    pub paths: Vec<String>,
+}
+
+pub async fn run(options: CommitOptions) -> Result<()> {
+    let CommitOptions {
+        yes,
+        num,
+        locale: locale_override,
+        agent,
+        copilot,
+        fail_on,
+        json,
+        style,
+        ignore_whitespace,
+        expand_context,
+        paths,
+    } = options;
+
+    let repo_state = RepoState::detect()?;
+    repo_state.ensure_can_generate()?;
+
     // Get staged files (offer interactive staging if empty)
-    let mut staged_files = GitManager::get_staged_files()?;
+    let mut staged_files = GitManager::get_staged_files(&paths)?;
+    if staged_files.is_empty() && !paths.is_empty() {
+        eprintln!("No staged changes match {}.", paths.join(" "));
+        return Err(crate::error::GitAiError::NoStagedChanges);
+    }
     if staged_files.is_empty() {
         let unstaged_files = GitManager::get_unstaged_files()?;
         if unstaged_files.is_empty() {
@@ -22,13 +76,17 @@ pub async fn run(
             return Err(crate::error::GitAiError::NoStagedChanges);
         }
 
+        if !ci::interactive() {
+            eprintln!("No staged changes. Stage files with 'git add' first.");
+            return Err(crate::error::GitAiError::NoStagedChanges);
+        }
         println!("⚠️  No staged changes found.");
         let labels: Vec<String> = unstaged_files.iter().map(|f| f.label.clone()).collect();
         let selections = MultiSelect::new()
             .with_prompt("Select files to stage")
             .items(&labels)
             .interact()
-            .map_err(|e| crate::error::GitAiError::Other(format!("Selection failed: {}", e)))?;
+            .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
 
         if selections.is_empty() {
             println!("No files selected. Exiting.");
@@ -49,7 +107,7 @@ pub async fn run(
         }
 
         GitManager::add_files(&unique_paths)?;
-        staged_files = GitManager::get_staged_files()?;
+        staged_files = GitManager::get_staged_files(&paths)?;
         println!("✅ Staged {} file(s).", unique_paths.len());
 
         if staged_files.is_empty() {
@@ -58,7 +116,7 @@ pub async fn run(
     }
 
     // Show staged files with line stats as a table
-    let file_stats = GitManager::get_file_stats().unwrap_or_default();
+    let file_stats = GitManager::get_file_stats(&paths).unwrap_or_default();
     let stats_map: std::collections::HashMap<&str, (u32, u32)> = file_stats
         .iter()
         .map(|(f, ins, del)| (f.as_str(), (*ins, *del)))
@@ -115,14 +173,14 @@ pub async fn run(
         let (ins, del) = stats_map.get(file.as_str()).copied().unwrap_or((0, 0));
         total_insertions += ins;
         total_deletions += del;
+        let inserted = format!("{:^ins_col$}", format!("+{}", ins));
+        let deleted = format!("{:^del_col$}", format!("-{}", del));
         println!(
-            "  │ {:<file_w$}│\x1b[32m{:^ins_w$}\x1b[0m│\x1b[31m{:^del_w$}\x1b[0m│",
+            "  │ {:<file_w$}│{}│{}│",
             file,
-            format!("+{}", ins),
-            format!("-{}", del),
+            console::style(inserted).green(),
+            console::style(deleted).red(),
             file_w = file_col_width - 1,
-            ins_w = ins_col,
-            del_w = del_col
         );
     }
     // Total separator
@@ -136,14 +194,14 @@ pub async fn run(
         del_w = del_col
     );
     // Total row
+    let inserted = format!("{:^ins_col$}", format!("+{}", total_insertions));
+    let deleted = format!("{:^del_col$}", format!("-{}", total_deletions));
     println!(
-        "  │ {:<file_w$}│\x1b[32m{:^ins_w$}\x1b[0m│\x1b[31m{:^del_w$}\x1b[0m│",
+        "  │ {:<file_w$}│{}│{}│",
         format!("Total ({} files)", staged_files.len()),
-        format!("+{}", total_insertions),
-        format!("-{}", total_deletions),
+        console::style(inserted).green(),
+        console::style(deleted).red(),
         file_w = file_col_width - 1,
-        ins_w = ins_col,
-        del_w = del_col
     );
     // Bottom border
     println!(
@@ -157,7 +215,7 @@ pub async fn run(
     );
 
     // Show diff statistics
-    match GitManager::get_diff_summary() {
+    match GitManager::get_diff_summary(&paths) {
         Ok(summary) => println!("\n{}", summary),
         Err(e) => eprintln!("⚠️  Failed to get diff statistics: {}", e),
     }
@@ -165,15 +223,49 @@ pub async fn run(
     // Get config
     let config = ConfigManager::get_merged_config()?;
 
+    // CLI flags win over behavior defaults from config
+    let yes = yes.or(config.auto_yes).unwrap_or(false) || !ci::interactive();
+    let num = num.or(config.default_num).unwrap_or(1).max(1);
+    let agent = agent.or(config.agent_by_default).unwrap_or(false);
+    let copilot = copilot.or(config.copilot_by_default).unwrap_or(false);
+    if fail_on.is_some() && !copilot {
+        return Err(crate::error::GitAiError::InvalidArgument(
+            "--fail-on needs the --copilot analysis; use 'git-ai review --fail-on' without Copilot"
+                .to_string(),
+        ));
+    }
+    let style = style.or_else(|| config.style.clone());
+    let show_usage = config.show_usage.unwrap_or(false);
+    let ignore_whitespace = ignore_whitespace
+        .or(config.ignore_whitespace)
+        .unwrap_or(false);
+    let expand_context = expand_context.or(config.expand_context).unwrap_or(false);
+
     // Determine locale
-    let locale = locale_override.unwrap_or(config.locale.clone());
+    let (locale, second_locale) = match locale_override {
+        Some(locale) => split_locale(&locale),
+        None => (config.locale.clone(), config.second_locale.clone()),
+    };
 
     // Get diff
-    let diff = GitManager::get_staged_diff()?;
+    let (mut diff, formatting_only) =
+        GitManager::get_prompt_diff(&paths, false, ignore_whitespace)?;
+    if expand_context && !formatting_only && !diff.is_empty() {
+        match AgentLite::build_expanded_diff(&paths, ignore_whitespace) {
+            Ok(expanded) if !expanded.is_empty() => diff = expanded,
+            Ok(_) => {}
+            Err(e) => eprintln!("⚠️  Context expansion failed, using the plain diff: {}", e),
+        }
+    }
     if diff.is_empty() {
         return Err(crate::error::GitAiError::NoStagedChanges);
     }
 
+    // Expanded context is asked for on purpose; keep it whole
+    if !expand_context && config.compact_diff.unwrap_or(false) {
+        diff = diff_compact::compact(&diff);
+    }
+
     // Truncate diff if needed
     let max_diff_chars = std::env::var("GIT_AI_MAX_DIFF_CHARS")
         .ok()
@@ -181,42 +273,144 @@ pub async fn run(
         .unwrap_or(5000);
 
     let (truncated_diff, _truncated) = if diff.len() > max_diff_chars {
-        // Find a valid UTF-8 char boundary to avoid panicking on multi-byte chars
-        let mut end = max_diff_chars;
-        while !diff.is_char_boundary(end) {
-            end -= 1;
-        }
-        (diff[..end].to_string(), true)
+        (
+            truncate_at_char_boundary(&diff, max_diff_chars).to_string(),
+            true,
+        )
     } else {
         (diff, false)
     };
 
-    // Get branch name and recent commits
-    let branch_name = GitManager::get_current_branch().ok();
+    // Get branch name and recent commits; a detached HEAD has no branch and
+    // a fresh repository no history, both are simply left out of the prompt
+    let is_initial_commit = !GitManager::has_commits();
+    let branch_name = GitManager::get_current_branch().ok().flatten();
     let recent_commits = GitManager::get_recent_commits(10).ok();
 
     // Create AI client
     let ai_client = AIClient::new(config.clone())?;
 
-    // Generate system and user prompts
-    let system_prompt = PromptTemplates::get_system_prompt(
-        &locale,
-        &config.provider,
-        config.custom_prompt.as_deref(),
-    );
-
-    let mut user_prompt = PromptTemplates::get_user_prompt(
-        &truncated_diff,
-        branch_name.as_deref(),
-        recent_commits.as_deref(),
-    );
+    // Generate system and user prompts; the system prompt is rebuilt when
+    // the user switches language
+    let system_prompt_for = |locale: &str, second_locale: Option<&str>| {
+        let system_prompt = PromptTemplates::apply_style(
+            PromptTemplates::get_system_prompt(
+                locale,
+                &config.provider,
+                config.custom_prompt.as_deref(),
+                PromptTemplates::preset_for_style(
+                    config.prompt_preset.as_deref(),
+                    style.as_deref(),
+                ),
+            ),
+            style.as_deref(),
+        );
+        let system_prompt =
+            PromptTemplates::apply_body_style(system_prompt, config.body_style.as_deref());
+        let system_prompt = match second_locale {
+            Some(second) => format!(
+                "{}\n\n{}",
+                system_prompt,
+                PromptTemplates::bilingual_instruction(locale, second)
+            ),
+            None => system_prompt,
+        };
+        let system_prompt = match repo_state.system_instruction(locale) {
+            Some(instruction) => format!("{}\n\n{}", system_prompt, instruction),
+            None => system_prompt,
+        };
+        match &config.conventions {
+            Some(conventions) => format!("{}\n\n{}", system_prompt, conventions.prompt_section()),
+            None => system_prompt,
+        }
+    };
+    let mut system_prompt = system_prompt_for(&locale, second_locale.as_deref());
+
+    let prompt_file = config.prompt_file.as_deref().map(resolve_config_path);
+    let mut user_prompt = PromptTemplates::build_user_prompt(
+        prompt_file.as_deref(),
+        &PromptContext {
+            diff: &truncated_diff,
+            branch: branch_name.as_deref(),
+            recent_commits: recent_commits.as_deref(),
+            files: &staged_files,
+            locale: &locale,
+        },
+    )?;
+    if formatting_only {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(PromptTemplates::formatting_only_note(&locale));
+    }
+    if is_initial_commit {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(PromptTemplates::initial_commit_note(&locale));
+    }
+    if let Some(context) = repo_state.prompt_context() {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(&context);
+    }
+    if let Some(hint) = workspace::scope_hint(&staged_files, config.allowed_scopes.as_deref()) {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(&hint);
+    }
+    match issues::fetch_for_branch(&config, branch_name.as_deref()).await {
+        Ok(Some(issue)) => {
+            println!("🎫 Linked issue {}: {}", issue.id, issue.title);
+            user_prompt.push_str("\n\n");
+            user_prompt.push_str(&issue.prompt_section());
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("⚠️  Issue context unavailable: {}", e),
+    }
 
     if agent {
         match AgentLite::run_analysis(&truncated_diff, branch_name.as_deref()).await {
-            Ok(context) => {
-                if !context.trim().is_empty() {
+            Ok(analysis) => {
+                if !analysis.related_tests.is_empty() {
+                    println!("🧪 Tests likely affected:");
+                    for test in &analysis.related_tests {
+                        println!("   • {}", test);
+                    }
+                    println!();
+                }
+                if !analysis.owners.is_empty() {
+                    println!("👥 Owners to notify: {}\n", analysis.owners.join(", "));
+                }
+                if !analysis.context.trim().is_empty() {
                     user_prompt.push_str("\n\n");
-                    user_prompt.push_str(&context);
+                    user_prompt.push_str(&analysis.context);
+                }
+                // Two-tier generation: a cheaper agent model analyzes first
+                let agent_config = config.agent_config().unwrap_or_else(|err| {
+                    eprintln!("⚠️  Skipping agent model analysis: {}", err);
+                    None
+                });
+                if let Some(agent_config) = agent_config {
+                    println!("🔎 Analyzing change with {}...", agent_config.model);
+                    let model_analysis = match AIClient::new(agent_config) {
+                        Ok(client) => {
+                            AgentLite::analyze_with_model(
+                                &client,
+                                &truncated_diff,
+                                &analysis.context,
+                                &locale,
+                            )
+                            .await
+                        }
+                        Err(err) => Err(err),
+                    };
+                    match model_analysis {
+                        Ok(model_analysis) => {
+                            user_prompt.push_str("\n\n");
+                            user_prompt.push_str(&model_analysis);
+                        }
+                        Err(err) => {
+                            eprintln!(
+                                "⚠️  Agent model analysis failed, continuing without it: {}",
+                                err
+                            );
+                        }
+                    }
                 }
             }
             Err(err) => {
@@ -225,81 +419,115 @@ pub async fn run(
         }
     }
 
+    // Stage 2 (GitHub Copilot CLI deep analysis) runs alongside generation
+    let copilot_available = copilot && CopilotCLI::is_available();
+
     // Show progress
-    let pb = ProgressBar::new_spinner();
-    pb.set_message("🤖 Generating commit message...");
+    let pb = ci::spinner();
+    pb.set_message(if copilot_available {
+        "🤖 Generating commit message (Copilot is analyzing code impact)..."
+    } else {
+        "🤖 Generating commit message..."
+    });
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
     // Generate messages
-    let messages = if num > 1 {
-        ai_client
-            .generate_multiple_messages(&system_prompt, &user_prompt, num)
-            .await?
-    } else {
-        vec![
-            ai_client
-                .generate_commit_message(&system_prompt, &user_prompt)
-                .await?,
-        ]
+    // Merge titles are git's, not conventional headers
+    let rules_for = |locale: &str| {
+        if repo_state.is_merge() {
+            MessageRules::default()
+        } else {
+            MessageRules::from_config(&config).with_format(&config, locale, style.as_deref())
+        }
+    };
+    let mut rules = rules_for(&locale);
+    let analysis = async {
+        if copilot_available {
+            Some(CopilotCLI::analyze_code_impact_cached(&truncated_diff, &staged_files, json).await)
+        } else {
+            None
+        }
     };
+    let (messages, analysis) = tokio::join!(
+        ai_client.generate_messages(&system_prompt, &user_prompt, num, &rules),
+        analysis
+    );
+    let follow_ups = follow_ups::collect(&config, &paths, false);
+    let with_follow_ups = |messages: Vec<String>| -> Vec<String> {
+        messages
+            .iter()
+            .map(|message| follow_ups::append(message, &follow_ups))
+            .collect()
+    };
+    let messages = with_follow_ups(messages?);
 
     pb.finish_and_clear();
+    if show_usage {
+        println!("{}", ai_client.usage().summary());
+    }
 
-    // Stage 2: GitHub Copilot CLI Deep Analysis (if enabled)
-    if copilot && CopilotCLI::is_available() {
-        println!("\n🔍 Analyzing code impact with GitHub Copilot CLI...\n");
-
-        match CopilotCLI::analyze_code_impact(&truncated_diff, &staged_files).await {
-            Ok(analysis) => {
-                // Display impact summary
-                println!("📊 Impact Analysis:");
-                println!("   {}\n", analysis.impact_summary);
-
-                // Display potential issues
-                if !analysis.potential_issues.is_empty() {
-                    println!("⚠️  Potential Risks:");
-                    for issue in &analysis.potential_issues {
-                        println!("   • {}", issue);
-                    }
-                    println!();
-                }
-
-                // Display affected areas
-                if !analysis.affected_areas.is_empty() {
-                    println!("🔗 Affected Areas:");
-                    for area in &analysis.affected_areas {
-                        println!("   • {}", area);
-                    }
-                    println!();
-                }
+    if let Some(analysis) = analysis {
+        if !json {
+            println!("\n🔍 Code impact analysis (GitHub Copilot CLI):\n");
+        }
 
-                // Display test recommendations
-                if !analysis.test_recommendations.is_empty() {
-                    println!("✅ Test Recommendations:");
-                    for test in &analysis.test_recommendations {
-                        println!("   • {}", test);
-                    }
-                    println!();
+        match analysis {
+            Ok(mut analysis) => {
+                analysis.owners = codeowners::owners_to_notify(&staged_files);
+                if json {
+                    let report = super::review::to_json(&analysis);
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    super::review::print_analysis(&analysis);
                 }
+                super::review::check_risk(&analysis, fail_on)?;
 
                 // Ask user if they want to continue
-                if !analysis.potential_issues.is_empty() {
+                if !json && !analysis.potential_issues.is_empty() {
                     println!("⚠️  Warning: Potential issues detected. Review carefully before committing.\n");
                 }
             }
+            // A risk gate must not pass just because the analysis did not run
+            Err(e) if fail_on.is_some() => {
+                return Err(crate::error::GitAiError::Other(format!(
+                    "Copilot analysis failed: {}",
+                    e
+                )));
+            }
             Err(e) => {
                 eprintln!("⚠️  Copilot analysis failed: {}", e);
                 eprintln!("    Continuing with commit...\n");
             }
         }
+    } else if copilot && fail_on.is_some() {
+        return Err(crate::error::GitAiError::Config(
+            "GitHub Copilot CLI not available; --fail-on cannot check the risk".to_string(),
+        ));
     } else if copilot {
         eprintln!("⚠️  GitHub Copilot CLI not available.");
         eprintln!("    Install with: gh auth login");
         eprintln!("    Continuing without analysis...\n");
     }
 
+    // Provenance is best-effort: the commit already exists when it is recorded
+    let record_audit = |system_prompt: &str, edited: bool| {
+        if config.audit_notes.unwrap_or(false) {
+            let record = AuditRecord::new(&config, system_prompt, &user_prompt, edited);
+            if let Err(e) = record.attach_to_head() {
+                eprintln!("⚠️  Failed to record audit note: {}", e);
+            }
+        }
+    };
+
+    let footer = footer::join([
+        codeowners::owners_trailer(&config, &staged_files),
+        footer::render(&config),
+    ]);
+
     // Interactive loop
     let mut current_messages = messages;
+    let mut locale = locale;
+    let mut second_locale = second_locale;
     loop {
         // Show messages
         println!("\n✨ Generated commit message(s):\n");
@@ -312,27 +540,33 @@ pub async fn run(
 
         if yes {
             // Auto-commit mode
-            let message = current_messages[0].clone();
-            GitManager::commit(&message)?;
+            let message = footer::append(&current_messages[0], footer.as_deref());
+            GitManager::commit(&message, &paths)?;
+            record_audit(&system_prompt, false);
             println!("\n✅ Commit created successfully!");
+            commit_summary::print(&config, &ai_client.usage());
             return Ok(());
         }
 
         // Show options
         println!("\n📋 Options:");
-        let options = vec!["Commit", "Edit", "Regenerate", "Cancel"];
+        let target = switch_target(&locale, second_locale.as_deref(), &config.locale);
+        let switch = format!("Switch language ({})", language_name(&target));
+        let options = vec!["Commit", "Edit", "Regenerate", switch.as_str(), "Cancel"];
         let selection = Select::new()
             .items(&options)
             .default(0)
             .interact()
-            .map_err(|e| crate::error::GitAiError::Other(format!("Selection failed: {}", e)))?;
+            .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
 
         match selection {
             0 => {
                 // Commit
-                let message = current_messages[0].clone();
-                GitManager::commit(&message)?;
+                let message = footer::append(&current_messages[0], footer.as_deref());
+                GitManager::commit(&message, &paths)?;
+                record_audit(&system_prompt, false);
                 println!("\n✅ Commit created successfully!");
+                commit_summary::print(&config, &ai_client.usage());
                 return Ok(());
             }
             1 => {
@@ -340,8 +574,14 @@ pub async fn run(
                 println!("\n✏️  Opening editor to edit commit message...");
                 let edited_message = edit_message(&current_messages[0])?;
                 if !edited_message.trim().is_empty() {
-                    GitManager::commit(&edited_message)?;
+                    let message = footer::append(&edited_message, footer.as_deref());
+                    GitManager::commit(&message, &paths)?;
+                    record_audit(
+                        &system_prompt,
+                        edited_message.trim() != current_messages[0].trim(),
+                    );
                     println!("\n✅ Commit created successfully!");
+                    commit_summary::print(&config, &ai_client.usage());
                     return Ok(());
                 } else {
                     println!("\n❌ Empty commit message, cancelled");
@@ -350,26 +590,50 @@ pub async fn run(
             }
             2 => {
                 // Regenerate
-                let pb = ProgressBar::new_spinner();
+                let pb = ci::spinner();
                 pb.set_message("🤖 Regenerating commit message...");
                 pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-                current_messages = if num > 1 {
+                current_messages = with_follow_ups(
                     ai_client
-                        .generate_multiple_messages(&system_prompt, &user_prompt, num)
-                        .await?
-                } else {
-                    vec![
-                        ai_client
-                            .generate_commit_message(&system_prompt, &user_prompt)
-                            .await?,
-                    ]
-                };
+                        .generate_messages(&system_prompt, &user_prompt, num, &rules)
+                        .await?,
+                );
 
                 pb.finish_and_clear();
+                if show_usage {
+                    println!("{}", ai_client.usage().summary());
+                }
                 // Continue loop with new messages
             }
             3 => {
+                // Switch language: rewrite the shown message in the other locale
+                let pb = ci::spinner();
+                pb.set_message(format!("🌐 Rewriting in {}...", language_name(&target)));
+                pb.enable_steady_tick(std::time::Duration::from_millis(100));
+
+                let prompt = format!(
+                    "{}\n\nPrevious commit message:\n{}\n\nWrite the same commit message in {}.",
+                    user_prompt,
+                    current_messages[0],
+                    language_name(&target)
+                );
+                locale = target;
+                second_locale = None;
+                system_prompt = system_prompt_for(&locale, None);
+                rules = rules_for(&locale);
+                current_messages = with_follow_ups(
+                    ai_client
+                        .generate_messages(&system_prompt, &prompt, 1, &rules)
+                        .await?,
+                );
+
+                pb.finish_and_clear();
+                if show_usage {
+                    println!("{}", ai_client.usage().summary());
+                }
+            }
+            4 => {
                 // Cancel
                 println!("\n❌ Commit cancelled");
                 return Err(crate::error::GitAiError::UserCancelled);
@@ -379,7 +643,21 @@ pub async fn run(
     }
 }
 
-fn edit_message(original: &str) -> Result<String> {
+/// Locale "Switch language" rewrites the message in: the second language of
+/// a bilingual locale, otherwise back to the `configured` locale after a
+/// `--locale` override or an earlier switch, and from the configured locale
+/// to English (or to Chinese when English is configured)
+fn switch_target(locale: &str, second_locale: Option<&str>, configured: &str) -> String {
+    match second_locale {
+        Some(second) => second.to_string(),
+        None if locale != configured && !configured.is_empty() => configured.to_string(),
+        None if locale == "en" => "zh".to_string(),
+        None => "en".to_string(),
+    }
+}
+
+/// Let the user edit `original` in their editor
+pub fn edit_message(original: &str) -> Result<String> {
     use std::time::{SystemTime, UNIX_EPOCH};
     use std::io::Write;
 
@@ -404,28 +682,8 @@ fn edit_message(original: &str) -> Result<String> {
     })?;
     drop(file);
 
-    // Get editor from environment or use default
-    let editor = std::env::var("EDITOR")
-        .or_else(|_| std::env::var("VISUAL"))
-        .unwrap_or_else(|_| {
-            if cfg!(windows) {
-                "notepad".to_string()
-            } else {
-                "vi".to_string()
-            }
-        });
-
     // Open editor
-    let status = std::process::Command::new(&editor)
-        .arg(&temp_file)
-        .status()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Failed to open editor: {}", e)))?;
-
-    if !status.success() {
-        return Err(crate::error::GitAiError::Other(
-            "Editor exited with error".to_string(),
-        ));
-    }
+    open_in_editor(&temp_file)?;
 
     // Read edited message
     let edited = std::fs::read_to_string(&temp_file).map_err(|e| {
@@ -437,3 +695,21 @@ fn edit_message(original: &str) -> Result<String> {
 
     Ok(edited)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::switch_target;
+
+    #[test]
+    fn switch_language_toggles_with_the_configured_locale() {
+        // Bilingual locale: the second language
+        assert_eq!(switch_target("en", Some("zh"), "en"), "zh");
+        // Configured language and English swap
+        assert_eq!(switch_target("ja", None, "ja"), "en");
+        assert_eq!(switch_target("en", None, "ja"), "ja");
+        assert_eq!(switch_target("zh", None, "zh"), "en");
+        assert_eq!(switch_target("en", None, "en"), "zh");
+        // After --locale fr, back to the configured language
+        assert_eq!(switch_target("fr", None, "de"), "de");
+    }
+}
@@ -1,14 +1,49 @@
 use crate::error::Result;
 use crate::types::{get_provider_presets, AIConfig};
-use crate::utils::ConfigManager;
-use dialoguer::{Confirm, Input, Select};
+use crate::utils::ai::{AIClient, AUTH_HEADER_STYLES, BODY_STYLES, COMMIT_STYLES, PROMPT_PRESETS};
+use crate::utils::config::{is_secret_key, normalize_key, redact_secret, ConfigScope, SECRET_KEYS};
+use crate::utils::editor::open_in_editor;
+use crate::utils::{ci, ConfigManager};
+use dialoguer::{Confirm, Input, Password, Select};
+use std::time::{Duration, Instant};
 
-pub async fn run_get(local: bool, json: bool) -> Result<()> {
-    let config = if local {
-        ConfigManager::read_local_config()?
-    } else {
-        ConfigManager::get_merged_config()?
+pub async fn run_get(key: Option<String>, scope: Option<ConfigScope>, json: bool) -> Result<()> {
+    let mut config = match scope {
+        Some(scope) => ConfigManager::read_scope_config(scope)?,
+        None => ConfigManager::get_merged_config()?,
     };
+    config.api_key = redact_secret(&config.api_key);
+    config.slack_webhook = config.slack_webhook.as_deref().map(redact_secret);
+    if let Some(second) = config.second_locale.take() {
+        config.locale = format!("{}+{}", config.locale, second);
+    }
+
+    if let Some(key) = key {
+        let field = normalize_key(&key);
+        let values = serde_json::to_value(&config)?;
+        let value = values.get(&field).ok_or_else(|| {
+            crate::error::GitAiError::InvalidArgument(format!(
+                "Unknown config key: '{}'. Run 'git-ai config describe' for available keys.",
+                key
+            ))
+        })?;
+
+        if json {
+            println!("{}", serde_json::json!({ field: value }));
+        } else {
+            match value {
+                serde_json::Value::Null => {
+                    return Err(crate::error::GitAiError::Config(format!(
+                        "'{}' is not set",
+                        key
+                    )));
+                }
+                serde_json::Value::String(s) => println!("{}", s),
+                other => println!("{}", other),
+            }
+        }
+        return Ok(());
+    }
 
     if json {
         println!("{}", serde_json::to_string_pretty(&config)?);
@@ -20,21 +55,97 @@ pub async fn run_get(local: bool, json: bool) -> Result<()> {
         if let Some(agent_model) = &config.agent_model {
             println!("  Agent Model: {}", agent_model);
         }
+        if let Some(embedding_model) = &config.embedding_model {
+            println!("  Embedding Model: {}", embedding_model);
+        }
         if let Some(custom_prompt) = &config.custom_prompt {
             println!("  Custom Prompt: {} chars", custom_prompt.len());
         }
-        println!("  Enable Footer: {}", config.enable_footer.unwrap_or(true));
+        println!("  Enable Footer: {}", config.enable_footer.unwrap_or(false));
+        if let Some(template) = &config.footer_template {
+            println!("  Footer Template: {:?}", template);
+        }
+        if let Some(timeout) = config.hook_timeout_secs {
+            println!("  Hook Timeout: {}s", timeout);
+        }
+        if let Some(types) = config.allowed_types.as_ref().filter(|t| !t.is_empty()) {
+            println!("  Allowed Types: {}", types.join(", "));
+        }
+        if let Some(authors) = config.exclude_authors.as_ref().filter(|a| !a.is_empty()) {
+            println!("  Exclude Authors: {}", authors.join(", "));
+        }
+        if let Some(patterns) = config.exclude_patterns.as_ref().filter(|p| !p.is_empty()) {
+            println!("  Exclude Patterns: {}", patterns.join(", "));
+        }
+        if let Some(scopes) = config.allowed_scopes.as_ref().filter(|s| !s.is_empty()) {
+            println!("  Allowed Scopes: {}", scopes.join(", "));
+        }
+        if let Some(num) = config.default_num {
+            println!("  Default Num: {}", num);
+        }
+        if let Some(tokens) = config.max_prompt_tokens {
+            println!("  Max Prompt Tokens: {}", tokens);
+        }
+        if let Some(price) = config.prompt_price {
+            println!("  Prompt Price: ${}/1M tokens", price);
+        }
+        if let Some(seed) = config.seed {
+            println!("  Seed: {}", seed);
+        }
+        if let Some(prompt_file) = &config.prompt_file {
+            println!("  Prompt File: {}", prompt_file);
+        }
+        if let Some(level) = &config.pre_commit_fail_on {
+            println!("  Pre-commit Fail On: {}", level);
+        }
+        if let Some(template) = &config.report_template {
+            println!("  Report Template: {}", template);
+        }
+        if let Some(webhook) = &config.slack_webhook {
+            println!("  Report Webhook: {}", redact_secret(webhook));
+        }
+        if let Some(preset) = &config.prompt_preset {
+            println!("  Prompt Preset: {}", preset);
+        }
+        if let Some(style) = &config.style {
+            println!("  Style: {}", style);
+        }
+        if let Some(body_style) = &config.body_style {
+            println!("  Body Style: {}", body_style);
+        }
+        if let Some(conventions) = &config.conventions {
+            println!(
+                "  Conventions: learned from {} commits",
+                conventions.sample_size
+            );
+        }
+        for (label, value) in [
+            ("Auto Yes", config.auto_yes),
+            ("Agent By Default", config.agent_by_default),
+            ("Copilot By Default", config.copilot_by_default),
+            ("Show Usage", config.show_usage),
+            ("UI Summary", config.ui_summary),
+            ("Ignore Whitespace", config.ignore_whitespace),
+            ("Expand Context", config.expand_context),
+            ("Compact Diff", config.compact_diff),
+            ("Deterministic", config.deterministic),
+            ("Audit Notes", config.audit_notes),
+            ("Audit Log", config.audit_log),
+            ("Owners Trailer", config.owners_trailer),
+            ("Follow-ups", config.follow_ups),
+            ("Issue Context", config.issue_context),
+        ] {
+            if let Some(value) = value {
+                println!("  {}: {}", label, value);
+            }
+        }
     }
 
     Ok(())
 }
 
-pub async fn run_set(key: &str, value: &str, local: bool) -> Result<()> {
-    let mut config = if local {
-        ConfigManager::read_local_config()?
-    } else {
-        ConfigManager::read_global_config()?
-    };
+pub async fn run_set(key: &str, value: &str, scope: ConfigScope) -> Result<()> {
+    let mut config = ConfigManager::read_scope_config(scope)?;
 
     match key {
         "provider" => config.provider = value.to_string(),
@@ -42,13 +153,150 @@ pub async fn run_set(key: &str, value: &str, local: bool) -> Result<()> {
         "base_url" | "baseUrl" => config.base_url = value.to_string(),
         "model" => config.model = value.to_string(),
         "agent_model" | "agentModel" => config.agent_model = Some(value.to_string()),
+        "embedding_model" | "embeddingModel" => config.embedding_model = Some(value.to_string()),
         "locale" => config.locale = value.to_string(),
         "custom_prompt" | "customPrompt" => config.custom_prompt = Some(value.to_string()),
-        "enable_footer" | "enableFooter" => {
-            config.enable_footer = Some(matches!(
-                value.to_lowercase().as_str(),
-                "1" | "true" | "yes" | "on"
-            ));
+        "enable_footer" | "enableFooter" => config.enable_footer = Some(parse_flag(value)),
+        "footer_template" | "footerTemplate" => config.footer_template = Some(value.to_string()),
+        "hook_timeout_secs" | "hookTimeoutSecs" => {
+            let secs = value.parse::<u64>().map_err(|_| {
+                crate::error::GitAiError::InvalidArgument(format!(
+                    "hook_timeout_secs must be a whole number of seconds, got '{}'",
+                    value
+                ))
+            })?;
+            config.hook_timeout_secs = Some(secs);
+        }
+        "default_num" | "defaultNum" => {
+            let num = value
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| {
+                    crate::error::GitAiError::InvalidArgument(format!(
+                        "default_num must be a positive number, got '{}'",
+                        value
+                    ))
+                })?;
+            config.default_num = Some(num);
+        }
+        "max_prompt_tokens" | "maxPromptTokens" => {
+            let tokens = value.parse::<u64>().map_err(|_| {
+                crate::error::GitAiError::InvalidArgument(format!(
+                    "max_prompt_tokens must be a number (0 disables the check), got '{}'",
+                    value
+                ))
+            })?;
+            config.max_prompt_tokens = Some(tokens);
+        }
+        "prompt_price" | "promptPrice" => {
+            let price = value
+                .parse::<f64>()
+                .ok()
+                .filter(|p| p.is_finite() && *p >= 0.0)
+                .ok_or_else(|| {
+                    crate::error::GitAiError::InvalidArgument(format!(
+                        "prompt_price must be USD per million input tokens, got '{}'",
+                        value
+                    ))
+                })?;
+            config.prompt_price = Some(price);
+        }
+        "deterministic" => config.deterministic = Some(parse_flag(value)),
+        "seed" => {
+            let seed = value.parse::<u64>().map_err(|_| {
+                crate::error::GitAiError::InvalidArgument(format!(
+                    "seed must be a non-negative whole number, got '{}'",
+                    value
+                ))
+            })?;
+            config.seed = Some(seed);
+        }
+        "auto_yes" | "autoYes" => config.auto_yes = Some(parse_flag(value)),
+        "agent_by_default" | "agentByDefault" => config.agent_by_default = Some(parse_flag(value)),
+        "copilot_by_default" | "copilotByDefault" => {
+            config.copilot_by_default = Some(parse_flag(value))
+        }
+        "show_usage" | "showUsage" => config.show_usage = Some(parse_flag(value)),
+        "ui.summary" | "ui_summary" | "uiSummary" => config.ui_summary = Some(parse_flag(value)),
+        "ignore_whitespace" | "ignoreWhitespace" => {
+            config.ignore_whitespace = Some(parse_flag(value))
+        }
+        "expand_context" | "expandContext" => config.expand_context = Some(parse_flag(value)),
+        "compact_diff" | "compactDiff" => config.compact_diff = Some(parse_flag(value)),
+        "audit_notes" | "auditNotes" => config.audit_notes = Some(parse_flag(value)),
+        "audit_log" | "auditLog" => config.audit_log = Some(parse_flag(value)),
+        "owners_trailer" | "ownersTrailer" => config.owners_trailer = Some(parse_flag(value)),
+        "follow_ups" | "followUps" => config.follow_ups = Some(parse_flag(value)),
+        "issue_context" | "issueContext" => config.issue_context = Some(parse_flag(value)),
+        "pre_commit_fail_on" | "preCommitFailOn" => {
+            let level = value.trim().to_lowercase();
+            if !matches!(level.as_str(), "high" | "medium" | "low" | "off") {
+                return Err(crate::error::GitAiError::InvalidArgument(
+                    "pre_commit_fail_on must be one of: high, medium, low, off".to_string(),
+                ));
+            }
+            config.pre_commit_fail_on = Some(level);
+        }
+        "issue_tracker" | "issueTracker" => {
+            if crate::utils::issues::Tracker::parse(value).is_none() {
+                return Err(crate::error::GitAiError::InvalidArgument(
+                    "issue_tracker must be one of: github, jira, linear".to_string(),
+                ));
+            }
+            config.issue_tracker = Some(value.to_lowercase());
+        }
+        "issue_pattern" | "issuePattern" => {
+            regex::Regex::new(value).map_err(|e| {
+                crate::error::GitAiError::InvalidArgument(format!(
+                    "issue_pattern is not a valid regex: {}",
+                    e
+                ))
+            })?;
+            config.issue_pattern = Some(value.to_string());
+        }
+        "style" => {
+            if !COMMIT_STYLES.contains(&value) {
+                return Err(crate::error::GitAiError::InvalidArgument(format!(
+                    "style must be one of: {}",
+                    COMMIT_STYLES.join(", ")
+                )));
+            }
+            config.style = Some(value.to_string());
+        }
+        "body_style" | "bodyStyle" => {
+            if !BODY_STYLES.contains(&value) {
+                return Err(crate::error::GitAiError::InvalidArgument(format!(
+                    "body_style must be one of: {}",
+                    BODY_STYLES.join(", ")
+                )));
+            }
+            config.body_style = Some(value.to_string());
+        }
+        "allowed_types" | "allowedTypes" => config.allowed_types = Some(parse_list(value)),
+        "allowed_scopes" | "allowedScopes" => config.allowed_scopes = Some(parse_list(value)),
+        "exclude_authors" | "excludeAuthors" => config.exclude_authors = Some(parse_list(value)),
+        "exclude_patterns" | "excludePatterns" => config.exclude_patterns = Some(parse_list(value)),
+        "auth_header" | "authHeader" => {
+            if !AUTH_HEADER_STYLES.contains(&value) {
+                return Err(crate::error::GitAiError::InvalidArgument(format!(
+                    "auth_header must be one of: {}",
+                    AUTH_HEADER_STYLES.join(", ")
+                )));
+            }
+            config.auth_header = Some(value.to_string());
+        }
+        "prompt_file" | "promptFile" => config.prompt_file = Some(value.to_string()),
+        "report_template" | "reportTemplate" => config.report_template = Some(value.to_string()),
+        "slack_webhook" | "slackWebhook" => config.slack_webhook = Some(value.to_string()),
+        "prompt_preset" | "promptPreset" => {
+            if !PROMPT_PRESETS.contains(&value) {
+                return Err(crate::error::GitAiError::InvalidArgument(format!(
+                    "prompt_preset must be one of: {}",
+                    PROMPT_PRESETS.join(", ")
+                )));
+            }
+            config.prompt_preset = Some(value.to_string());
         }
         _ => {
             return Err(crate::error::GitAiError::InvalidArgument(format!(
@@ -60,17 +308,592 @@ pub async fn run_set(key: &str, value: &str, local: bool) -> Result<()> {
 
     let display_value = match key {
         "api_key" | "apiKey" => "****".to_string(),
+        "slack_webhook" | "slackWebhook" => redact_secret(value),
         _ => value.to_string(),
     };
 
-    if local {
-        ConfigManager::write_local_config(&config)?;
-        println!("Set {} = {} (local)", key, display_value);
+    ConfigManager::write_scope_config(scope, &config)?;
+    println!("Set {} = {} ({})", key, display_value, scope.name());
+
+    Ok(())
+}
+
+/// Parse a comma-separated list (`feat, fix` -> ["feat", "fix"])
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_flag(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+pub async fn run_unset(key: &str, scope: ConfigScope) -> Result<()> {
+    let config = ConfigManager::read_scope_config(scope)?;
+
+    let field = normalize_key(key);
+    let mut values = serde_json::to_value(&config)?;
+    let removed = values
+        .as_object_mut()
+        .and_then(|map| map.remove(&field))
+        .is_some();
+    if !removed {
+        return Err(crate::error::GitAiError::InvalidArgument(format!(
+            "Unknown config key: '{}'. Run 'git-ai config describe' for available keys.",
+            key
+        )));
+    }
+
+    // Missing fields fall back to their serde defaults (empty / unset)
+    let config: AIConfig = serde_json::from_value(values)?;
+
+    ConfigManager::write_scope_config(scope, &config)?;
+    println!("Unset {} ({})", key, scope.name());
+
+    Ok(())
+}
+
+pub async fn run_reset(scope: ConfigScope, yes: bool) -> Result<()> {
+    let path = ConfigManager::get_scope_config_path(scope)?;
+
+    if !path.exists() {
+        println!("ℹ️  No {} config at {}", scope.name(), path.display());
+        return Ok(());
+    }
+
+    if !yes {
+        ci::require_input("Delete config", "pass --yes")?;
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Delete {} config at {}?",
+                scope.name(),
+                path.display()
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
+        if !confirmed {
+            return Err(crate::error::GitAiError::UserCancelled);
+        }
+    }
+
+    ConfigManager::clear_scope_config(scope)?;
+    println!("✅ {} config reset ({})", scope.name(), path.display());
+
+    Ok(())
+}
+
+/// Map an opencommit setting (`~/.opencommit` or `OCO_*` env var) to a config key
+fn map_opencommit_setting(key: &str, value: &str) -> Option<(&'static str, String)> {
+    let value = value.trim().trim_matches('"').trim_matches('\'');
+    if value.is_empty() || value == "undefined" || value == "null" {
+        return None;
+    }
+
+    match key {
+        "OCO_AI_PROVIDER" => {
+            let provider = value.to_lowercase();
+            get_provider_presets()
+                .contains_key(provider.as_str())
+                .then_some(("provider", provider))
+        }
+        "OCO_API_KEY" | "OCO_OPENAI_API_KEY" => Some(("api_key", value.to_string())),
+        "OCO_API_URL" | "OCO_OPENAI_BASE_PATH" => Some(("base_url", value.to_string())),
+        "OCO_MODEL" => Some(("model", value.to_string())),
+        "OCO_LANGUAGE" => {
+            let locale = value.to_lowercase();
+            if locale.starts_with("zh") {
+                Some(("locale", "zh".to_string()))
+            } else if locale.starts_with("en") {
+                Some(("locale", "en".to_string()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Parse `KEY=value` lines from an opencommit config file
+fn parse_opencommit_config(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// File format for `config export` / `config import <file>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SnapshotFormat {
+    Json,
+    Toml,
+}
+
+/// Serialize the config keys that are set as a shareable snapshot
+fn render_snapshot(
+    mut values: serde_json::Map<String, serde_json::Value>,
+    format: SnapshotFormat,
+    redact: bool,
+) -> Result<String> {
+    if redact {
+        values.retain(|key, _| !is_secret_key(key));
+    }
+    match format {
+        SnapshotFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(&values)?)),
+        SnapshotFormat::Toml => toml::to_string(&values).map_err(|e| {
+            crate::error::GitAiError::Config(format!("Failed to serialize TOML: {}", e))
+        }),
+    }
+}
+
+/// Parse a snapshot (JSON, or TOML by extension / as a fallback) into normalized keys
+fn parse_snapshot(
+    content: &str,
+    path: &std::path::Path,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+    let parsed: serde_json::Value = if is_toml {
+        toml::from_str(content).map_err(|e| {
+            crate::error::GitAiError::Config(format!("Invalid TOML in {}: {}", path.display(), e))
+        })?
+    } else {
+        match serde_json::from_str(content) {
+            Ok(value) => value,
+            Err(json_err) => toml::from_str(content).map_err(|_| {
+                crate::error::GitAiError::Config(format!(
+                    "Invalid config snapshot {}: {}",
+                    path.display(),
+                    json_err
+                ))
+            })?,
+        }
+    };
+
+    match parsed {
+        serde_json::Value::Object(values) => Ok(values
+            .into_iter()
+            .map(|(key, value)| (normalize_key(&key), value))
+            .collect()),
+        _ => Err(crate::error::GitAiError::Config(format!(
+            "Config snapshot {} must be an object",
+            path.display()
+        ))),
+    }
+}
+
+pub async fn run_export(
+    scope: Option<ConfigScope>,
+    format: SnapshotFormat,
+    redact: bool,
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    let config = match scope {
+        Some(scope) => ConfigManager::read_scope_config(scope)?,
+        None => ConfigManager::get_merged_config()?,
+    };
+    let values = ConfigManager::config_values(&config)?;
+    let has_secret = SECRET_KEYS.iter().any(|key| values.contains_key(*key));
+    let snapshot = render_snapshot(values, format, redact)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, snapshot)?;
+            println!("📦 Exported config to {}", path.display());
+        }
+        None => print!("{}", snapshot),
+    }
+    if has_secret && !redact {
+        eprintln!("⚠️  The snapshot contains secrets (API key or webhook). Use --redact before sharing it.");
+    }
+
+    Ok(())
+}
+
+/// Apply a snapshot file produced by `config export` to a config scope
+pub async fn run_import_file(
+    path: &std::path::Path,
+    scope: ConfigScope,
+    dry_run: bool,
+) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let snapshot = parse_snapshot(&content, path)?;
+
+    let current = ConfigManager::read_scope_config(scope)?;
+    let mut values = match serde_json::to_value(&current)? {
+        serde_json::Value::Object(values) => values,
+        _ => serde_json::Map::new(),
+    };
+
+    let mut imported = 0;
+    for (key, value) in snapshot {
+        if !values.contains_key(&key) {
+            println!("  ⏭️  {} (unknown key)", key);
+            continue;
+        }
+        if is_secret_key(&key) && value.as_str().is_some_and(|v| v.contains("****")) {
+            println!("  ⏭️  {} (redacted value)", key);
+            continue;
+        }
+        let display = display_value(&key, &value);
+        println!("  ✅ {} = {}", key, display);
+        values.insert(key, value);
+        imported += 1;
+    }
+
+    // Validate types before touching the config file
+    let config: AIConfig = serde_json::from_value(serde_json::Value::Object(values))
+        .map_err(|e| crate::error::GitAiError::Config(format!("Invalid config value: {}", e)))?;
+
+    if dry_run {
+        println!(
+            "\nDry run: {} value(s) would be written to the {} config",
+            imported,
+            scope.name()
+        );
+        return Ok(());
+    }
+
+    ConfigManager::write_scope_config(scope, &config)?;
+    println!(
+        "\n📦 Imported {} value(s) into the {} config",
+        imported,
+        scope.name()
+    );
+
+    Ok(())
+}
+
+pub async fn run_import(dry_run: bool, force: bool) -> Result<()> {
+    // (source, key, value) in increasing priority
+    let mut found: Vec<(String, String, serde_json::Value)> = Vec::new();
+
+    if let Some((path, legacy)) = ConfigManager::read_legacy_global_config()? {
+        let source = format!("git-ai v1 ({})", path.display());
+        for (key, value) in ConfigManager::config_values(&legacy)? {
+            found.push((source.clone(), key, value));
+        }
+    }
+
+    if let Some(path) = dirs::home_dir().map(|home| home.join(".opencommit")) {
+        if path.exists() {
+            let source = format!("opencommit ({})", path.display());
+            for (key, value) in parse_opencommit_config(&std::fs::read_to_string(&path)?) {
+                if let Some((field, value)) = map_opencommit_setting(&key, &value) {
+                    found.push((source.clone(), field.to_string(), value.into()));
+                }
+            }
+        }
+    }
+
+    for (key, value) in std::env::vars().filter(|(key, _)| key.starts_with("OCO_")) {
+        if let Some((field, value)) = map_opencommit_setting(&key, &value) {
+            found.push((format!("env ${}", key), field.to_string(), value.into()));
+        }
+    }
+
+    if found.is_empty() {
+        println!("ℹ️  No legacy git-ai or opencommit configuration found");
+        return Ok(());
+    }
+
+    let path = ConfigManager::get_global_config_path()?;
+    let current = ConfigManager::read_global_config()?;
+    // Built-in defaults of a missing config file don't count as user choices
+    let existing = if path.exists() {
+        ConfigManager::config_values(&current)?
     } else {
+        serde_json::Map::new()
+    };
+    let mut values = match serde_json::to_value(&current)? {
+        serde_json::Value::Object(values) => values,
+        _ => serde_json::Map::new(),
+    };
+
+    let mut imported = 0;
+    for (source, key, value) in last_per_key(found) {
+        let display = display_value(&key, &value);
+        if existing.get(&key) == Some(&value) {
+            println!("  ⏭️  {} = {} from {} (unchanged)", key, display, source);
+            continue;
+        }
+        if existing.contains_key(&key) && !force {
+            println!("  ⏭️  {} = {} from {} (already set)", key, display, source);
+            continue;
+        }
+        println!("  ✅ {} = {} from {}", key, display, source);
+        values.insert(key, value);
+        imported += 1;
+    }
+
+    if imported == 0 {
+        println!("ℹ️  Nothing to import (use --force to overwrite existing values)");
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "\nDry run: {} value(s) would be written to {}",
+            imported,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let config: AIConfig = serde_json::from_value(serde_json::Value::Object(values))?;
+    ConfigManager::write_global_config(&config)?;
+    println!(
+        "\n📦 Imported {} value(s) into {}",
+        imported,
+        path.display()
+    );
+
+    Ok(())
+}
+
+/// Value as printed by imports, with secrets redacted
+fn display_value(key: &str, value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) if is_secret_key(key) => redact_secret(s),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Legacy settings found by `config import`, one per key: a key set by
+/// several sources keeps the value of the last (highest-priority) one
+fn last_per_key(
+    found: Vec<(String, String, serde_json::Value)>,
+) -> Vec<(String, String, serde_json::Value)> {
+    let mut resolved: Vec<(String, String, serde_json::Value)> = Vec::new();
+    for (source, key, value) in found {
+        match resolved.iter_mut().find(|(_, k, _)| *k == key) {
+            Some(entry) => *entry = (source, key, value),
+            None => resolved.push((source, key, value)),
+        }
+    }
+    resolved
+}
+
+pub async fn run_origin(json: bool) -> Result<()> {
+    let origins = ConfigManager::get_config_origins()?;
+
+    if json {
+        let entries: Vec<serde_json::Value> = origins
+            .iter()
+            .map(|(key, value, origin)| {
+                let value = match value {
+                    serde_json::Value::String(s) if is_secret_key(key) => {
+                        serde_json::Value::String(redact_secret(s))
+                    }
+                    _ => value.clone(),
+                };
+                serde_json::json!({
+                    "key": key,
+                    "value": value,
+                    "origin": origin.as_ref().map(|o| o.to_string()),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("Effective configuration (highest priority: env > local > repo > global):");
+    for (key, value, origin) in &origins {
+        let display = match value {
+            serde_json::Value::Null => "<unset>".to_string(),
+            serde_json::Value::String(s) if is_secret_key(key) => redact_secret(s),
+            serde_json::Value::String(s) if s.is_empty() => "<unset>".to_string(),
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let origin = origin
+            .as_ref()
+            .map(|o| o.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!("  {:<18} {:<24} {}", key, display, origin);
+    }
+
+    Ok(())
+}
+
+/// Starting point for `config edit` when the file does not exist yet
+fn config_template(scope: ConfigScope) -> String {
+    let mut lines = vec![
+        format!(
+            "// git-ai {} config. Lines starting with // are ignored.",
+            scope.name()
+        ),
+        "// Uncomment the keys you need; run 'git-ai config describe' for all of them.".to_string(),
+        "{".to_string(),
+        "  // \"provider\": \"deepseek\",".to_string(),
+    ];
+    // The local file is meant to be committed; keep keys out of it
+    if scope != ConfigScope::Local {
+        lines.push("  // \"api_key\": \"sk-...\",".to_string());
+    }
+    lines.extend(
+        [
+            "  // \"base_url\": \"https://api.deepseek.com/v1\",",
+            "  // \"model\": \"deepseek-chat\",",
+            "  // \"prompt_preset\": \"conventional\",",
+            "  // \"allowed_types\": [\"feat\", \"fix\", \"docs\", \"refactor\", \"test\", \"chore\"],",
+            "  // \"default_num\": 1,",
+            "  // \"locale\": \"en\"",
+            "}",
+        ]
+        .map(str::to_string),
+    );
+    lines.join("\n") + "\n"
+}
+
+pub async fn run_edit(scope: ConfigScope) -> Result<()> {
+    let path = ConfigManager::get_scope_config_path(scope)?;
+    let encrypted = scope == ConfigScope::Global && ConfigManager::is_global_config_encrypted()?;
+    let original = if encrypted {
+        serde_json::to_string_pretty(&ConfigManager::read_global_config()?)?
+    } else if path.exists() {
+        std::fs::read_to_string(&path)?
+    } else {
+        config_template(scope)
+    };
+
+    // Edit a temporary copy so a broken save never replaces the real file
+    let temp = std::env::temp_dir().join(format!("git-ai-config-{}.json", std::process::id()));
+    std::fs::write(&temp, &original)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&temp, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    let edited = edit_until_valid(&temp, scope);
+    let _ = std::fs::remove_file(&temp);
+    let (content, config) = edited?;
+
+    if content == original {
+        println!("ℹ️  No changes");
+        return Ok(());
+    }
+
+    if encrypted {
         ConfigManager::write_global_config(&config)?;
-        println!("Set {} = {} (global)", key, display_value);
+    } else {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, content)?;
+    }
+    println!("✅ Saved {} config ({})", scope.name(), path.display());
+
+    Ok(())
+}
+
+/// Re-open the editor until the file parses or the user gives up
+fn edit_until_valid(temp: &std::path::Path, scope: ConfigScope) -> Result<(String, AIConfig)> {
+    loop {
+        open_in_editor(temp)?;
+        let content = std::fs::read_to_string(temp)?;
+        let errors = match ConfigManager::validate_config_text(&content, scope) {
+            Ok(config) => return Ok((content, config)),
+            Err(errors) => errors,
+        };
+
+        for error in &errors {
+            eprintln!("❌ {}", error);
+        }
+        let retry = ci::interactive()
+            && Confirm::new()
+                .with_prompt("Re-open the editor? (No discards your changes)")
+                .default(true)
+                .interact()
+                .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
+        if !retry {
+            return Err(crate::error::GitAiError::UserCancelled);
+        }
+    }
+}
+
+pub async fn run_encrypt() -> Result<()> {
+    let path = ConfigManager::get_global_config_path()?;
+    if !path.exists() {
+        return Err(crate::error::GitAiError::Config(format!(
+            "No global config at {}. Run 'git-ai config' first.",
+            path.display()
+        )));
+    }
+    if ConfigManager::is_global_config_encrypted()? {
+        println!("ℹ️  {} is already encrypted", path.display());
+        return Ok(());
+    }
+
+    let config = ConfigManager::read_global_config()?;
+    let passphrase = match ConfigManager::get_env_config_key()? {
+        Some(key) => key,
+        None => {
+            ci::require_input("New passphrase", "set GIT_AI_CONFIG_KEY")?;
+            Password::new()
+                .with_prompt("New passphrase")
+                .with_confirmation("Confirm passphrase", "Passphrases do not match")
+                .interact()
+                .map_err(|e| {
+                    crate::error::GitAiError::Config(format!("Failed to read passphrase: {}", e))
+                })?
+        }
+    };
+    if passphrase.is_empty() {
+        return Err(crate::error::GitAiError::InvalidArgument(
+            "Passphrase must not be empty".to_string(),
+        ));
+    }
+
+    ConfigManager::write_global_config_encryption(&config, Some(&passphrase))?;
+    println!("🔒 Encrypted {}", path.display());
+    println!("   Set GIT_AI_CONFIG_KEY (or GIT_AI_CONFIG_KEY_FILE) for non-interactive use.");
+
+    Ok(())
+}
+
+pub async fn run_decrypt() -> Result<()> {
+    let path = ConfigManager::get_global_config_path()?;
+    if !ConfigManager::is_global_config_encrypted()? {
+        println!("ℹ️  {} is not encrypted", path.display());
+        return Ok(());
     }
 
+    let config = ConfigManager::read_global_config()?;
+    ConfigManager::write_global_config_encryption(&config, None)?;
+    println!("🔓 Decrypted {}", path.display());
+
+    Ok(())
+}
+
+pub async fn run_test() -> Result<()> {
+    let config = ConfigManager::get_merged_config()?;
+    let client = AIClient::new(config.clone())?;
+
+    println!(
+        "🔌 Testing {} ({}) with model {}...",
+        config.provider, config.base_url, config.model
+    );
+
+    let started = Instant::now();
+    let (model, reply) = client.ping().await?;
+    let latency = started.elapsed();
+
+    let first_line = reply.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    println!("✅ Provider responded");
+    println!("   Latency: {} ms", latency.as_millis());
+    println!("   Model:   {}", model);
+    println!("   Reply:   {}", first_line.trim());
+
     Ok(())
 }
 
@@ -80,11 +903,72 @@ pub async fn run_describe() -> Result<()> {
     println!("  provider          - AI provider name (required)");
     println!("  api_key           - API authentication key");
     println!("  base_url          - API endpoint base URL");
+    println!("  auth_header       - How the key is sent: bearer, api-key, none");
+    println!("  allowed_types     - Comma-separated commit types the AI may use");
+    println!("  allowed_scopes    - Comma-separated commit scopes the AI may use");
+    println!("  exclude_authors   - Comma-separated authors left out of reports (e.g. dependabot,renovate)");
+    println!("  exclude_patterns  - Comma-separated subject fragments left out of reports (e.g. chore(release))");
+    println!("  enabled_skills    - Trusted repository skills (managed by 'git-ai skills enable')");
+    println!("  disabled_skills   - Skills turned off (managed by 'git-ai skills disable')");
+    println!("  report_template   - Markdown skeleton for reports ({{{{scope}}}}, {{{{total_commits}}}}, {{{{date}}}} are prefilled)");
+    println!("  slack_webhook     - Slack/Teams incoming webhook used by 'report --post'");
+    println!(
+        "  conventions       - Commit conventions learned from history (written by 'git-ai learn')"
+    );
     println!("  model             - Model name for basic mode");
     println!("  agent_model       - Separate model for agent mode");
+    println!("  embedding_model   - Embedding model for 'git-ai search' (default per provider)");
     println!("  locale            - Output language (zh/en)");
     println!("  custom_prompt     - Custom system prompt");
-    println!("  enable_footer     - Add footer to messages (true/false)");
+    println!(
+        "  prompt_preset     - Built-in system prompt: {} (style still applies on top; a conventional/gitmoji preset yields to a different style)",
+        PROMPT_PRESETS.join(", ")
+    );
+    println!("  prompt_file       - User prompt template ({{{{diff}}}}, {{{{branch}}}}, {{{{files}}}}, ...)");
+    println!("  enable_footer     - Add footer to messages (true/false, default: false)");
+    println!(
+        "  footer_template   - Footer trailer, e.g. 'Generated-by: git-ai/{{version}} ({{model}})'"
+    );
+    println!(
+        "                      ({{provider}} is also available; enable_footer=false drops it)"
+    );
+    println!("  hook_timeout_secs - Deadline for hook-driven generation (default: 15)");
+    println!("  default_num       - Messages to generate when -n is not given");
+    println!(
+        "  max_prompt_tokens - Ask before sending a larger prompt; 0 disables (default: 30000)"
+    );
+    println!("  prompt_price      - USD per 1M input tokens, to show the cost of a large prompt");
+    println!(
+        "  deterministic     - Temperature 0 and a fixed seed, for stable output in CI (true/false)"
+    );
+    println!("  seed              - Seed used in deterministic mode (default: 42)");
+    println!("  auto_yes          - Commit without prompting, like -y (true/false)");
+    println!("  agent_by_default  - Always use agent mode, like -a (true/false)");
+    println!("  copilot_by_default - Always use Copilot analysis, like --copilot");
+    println!("  style             - Message style: conventional, gitmoji, simple");
+    println!("  body_style        - Message body: paragraph, bullets, none (subject only)");
+    println!("  show_usage        - Print token usage after generation (true/false)");
+    println!("  ui.summary        - Recap SHA, files, line counts, model and tokens after committing (true/false)");
+    println!(
+        "  ignore_whitespace - Hide whitespace-only changes from the prompt diff (true/false)"
+    );
+    println!(
+        "  expand_context    - Show whole functions for the most important files (true/false)"
+    );
+    println!(
+        "  compact_diff      - Drop unchanged context and repeated hunks from the prompt diff (default: false)"
+    );
+    println!("  audit_notes       - Record provenance in refs/notes/git-ai after committing (true/false)");
+    println!("  audit_log         - Log provider, model, files and prompt hash of every request to audit.jsonl (true/false)");
+    println!("                      (share with: git push origin refs/notes/git-ai)");
+    println!("  owners_trailer    - Add a 'Cc:' trailer with the CODEOWNERS of the committed paths (true/false)");
+    println!("  follow_ups        - List added TODO/FIXME/HACK comments under 'Known follow-ups' (true/false)");
+    println!("  pre_commit_fail_on - Risk level the pre-commit hook blocks at: high, medium, low, off (default: high)");
+    println!(
+        "  issue_context     - Add the issue named by the branch to the prompt (default: false)"
+    );
+    println!("  issue_tracker     - Tracker for branch issue keys: github, jira, linear");
+    println!("  issue_pattern     - Regex capturing the issue id in branch names, e.g. '^[^/]+/([A-Z]+-\\d+)'");
     println!();
     println!("Environment variables:");
     println!("  GIT_AI_PROVIDER   - Override provider");
@@ -93,27 +977,171 @@ pub async fn run_describe() -> Result<()> {
     println!("  OCO_API_KEY       - OpenCommit-compatible API key override");
     println!("  OPENAI_API_KEY    - OpenAI key fallback");
     println!("  DEEPSEEK_API_KEY  - DeepSeek key fallback");
+    println!("  XAI_API_KEY       - xAI (Grok) key fallback for the xai provider");
+    println!("  GITHUB_TOKEN      - Key for the github-models provider (also GH_TOKEN or an authenticated gh)");
     println!("  GIT_AI_BASE_URL   - Override base URL");
     println!("  GIT_AI_MODEL      - Override model");
     println!("  OCO_MODEL         - OpenCommit-compatible model override");
     println!("  GIT_AI_LOCALE     - Override locale");
+    println!("  GIT_AI_CONFIG_PATH - Use an alternate global config file");
+    println!("  GIT_AI_CONFIG_KEY - Passphrase for an encrypted global config");
+    println!("  GIT_AI_CONFIG_KEY_FILE - File containing that passphrase");
+    println!("  GIT_AI_TIMEOUT    - HTTP request timeout in seconds (default: 120)");
+    println!("  GIT_AI_HOOK_TIMEOUT - Override hook_timeout_secs");
+    println!("  GIT_AI_GIT_BACKEND - Set to 'cli' to run every git query through the git binary");
+    println!("  GITHUB_TOKEN      - GitHub issues for issue context (or an authenticated gh)");
+    println!("  JIRA_BASE_URL     - Jira site, with JIRA_EMAIL + JIRA_API_TOKEN or JIRA_TOKEN");
+    println!("  LINEAR_API_KEY    - Linear issues for issue context");
+    println!("  GIT_AI_NO_UPDATE_CHECK - Set to 1 to skip the daily update check");
+    println!("  GIT_AI_RELEASES_URL - Release feed used by the update check and self-update");
     println!();
     println!("Configuration files:");
     let global_path = ConfigManager::get_global_config_path()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|_| "<unavailable>".to_string());
     println!("  Global: {}", global_path);
+    println!("  Repo:   .git/git-ai.json (private to this clone and shared by its worktrees, never committed)");
     println!("  Local:  .git-ai.json (in project root)");
+    println!();
+    println!("Priority: env > local > repo > global");
 
     Ok(())
 }
 
+/// Wizard entry for endpoints without a preset (vLLM, LiteLLM, TGI, ...)
+const CUSTOM_PROVIDER_LABEL: &str = "other / custom";
+
+fn prompt_api_key() -> Result<String> {
+    Input::new()
+        .with_prompt("Enter API key")
+        .interact()
+        .map_err(|e| crate::error::GitAiError::prompt("Input", e))
+}
+
+/// Walk through base URL and auth for a self-hosted or unlisted endpoint
+fn custom_provider_config() -> Result<AIConfig> {
+    let compatible = Confirm::new()
+        .with_prompt("Does the endpoint implement the OpenAI chat completions API?")
+        .default(true)
+        .interact()
+        .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
+    if !compatible {
+        return Err(crate::error::GitAiError::Config(
+            "git-ai needs an OpenAI-compatible endpoint. Put a proxy such as LiteLLM in front of it and use the proxy URL."
+                .to_string(),
+        ));
+    }
+
+    let base_url: String = Input::new()
+        .with_prompt("Base URL (e.g. http://localhost:8000/v1)")
+        .validate_with(|url: &String| {
+            if url.starts_with("http://") || url.starts_with("https://") {
+                Ok(())
+            } else {
+                Err("URL must start with http:// or https://")
+            }
+        })
+        .interact()
+        .map_err(|e| crate::error::GitAiError::prompt("Input", e))?;
+
+    let auth_labels = [
+        "Authorization: Bearer <key>",
+        "api-key: <key>",
+        "No authentication",
+    ];
+    let auth_idx = Select::new()
+        .with_prompt("How is the API key sent?")
+        .items(&auth_labels)
+        .default(0)
+        .interact()
+        .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
+    let auth_header = AUTH_HEADER_STYLES[auth_idx];
+
+    let api_key = if auth_header == "none" {
+        String::new()
+    } else {
+        prompt_api_key()?
+    };
+
+    Ok(AIConfig {
+        provider: "custom".to_string(),
+        api_key,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        auth_header: Some(auth_header.to_string()),
+        ..Default::default()
+    })
+}
+
+/// How long the wizard waits for a provider's `/models` list
+const MODEL_LIST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fetch models from the provider, if it answers quickly
+async fn fetch_models(config: &AIConfig) -> Option<Vec<String>> {
+    let client = AIClient::new(config.clone()).ok()?;
+    let spinner = ci::spinner();
+    spinner.set_message("Fetching available models...");
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    let result = tokio::time::timeout(MODEL_LIST_TIMEOUT, client.list_models()).await;
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(Ok(models)) if !models.is_empty() => Some(models),
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => {
+            println!("⚠️  Could not list models: {}", e);
+            None
+        }
+        Err(_) => {
+            println!("⚠️  Listing models timed out");
+            None
+        }
+    }
+}
+
+/// Pick a model from the provider's list, falling back to free text
+async fn choose_model(config: &AIConfig, default_model: &str) -> Result<String> {
+    if let Some(models) = fetch_models(config).await {
+        let mut items = models.clone();
+        items.push("✏️  Enter another model name".to_string());
+        let default_idx = models.iter().position(|m| m == default_model).unwrap_or(0);
+
+        let idx = Select::new()
+            .with_prompt("Select model")
+            .items(&items)
+            .default(default_idx)
+            .max_length(15)
+            .interact()
+            .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
+        if idx < models.len() {
+            return Ok(models[idx].clone());
+        }
+    }
+
+    if default_model.is_empty() {
+        return Input::new()
+            .with_prompt("Enter model name")
+            .interact()
+            .map_err(|e| crate::error::GitAiError::prompt("Input", e));
+    }
+
+    Input::new()
+        .with_prompt(format!("Enter model name (default: {})", default_model))
+        .default(default_model.to_string())
+        .interact()
+        .map_err(|e| crate::error::GitAiError::prompt("Input", e))
+}
+
 pub async fn run_wizard(local: bool) -> Result<()> {
+    ci::require_input(
+        "Configuration wizard",
+        "use 'git-ai config set <key> <value>'",
+    )?;
     println!("\n🔧 Git-AI Configuration Wizard\n");
 
     let presets = get_provider_presets();
     let mut provider_names: Vec<&str> = presets.keys().copied().collect();
     provider_names.sort();
+    provider_names.push(CUSTOM_PROVIDER_LABEL);
 
     // Select provider
     println!("Select AI provider:");
@@ -121,38 +1149,41 @@ pub async fn run_wizard(local: bool) -> Result<()> {
         .items(&provider_names)
         .default(0)
         .interact()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Selection failed: {}", e)))?;
+        .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
 
     let provider_key = provider_names[provider_idx];
-    let preset = &presets[provider_key];
-
-    let mut config = AIConfig {
-        provider: provider_key.to_string(),
-        ..Default::default()
-    };
+    let mut config = if provider_key == CUSTOM_PROVIDER_LABEL {
+        let mut config = custom_provider_config()?;
+        config.model = choose_model(&config, "").await?;
+        config
+    } else {
+        let preset = &presets[provider_key];
+        let mut config = AIConfig {
+            provider: provider_key.to_string(),
+            ..Default::default()
+        };
 
-    // Get API key if required
-    if preset.requires_key {
-        let api_key: String = Input::new()
-            .with_prompt("Enter API key")
-            .interact()
-            .map_err(|e| crate::error::GitAiError::Other(format!("Input failed: {}", e)))?;
-        config.api_key = api_key;
-    }
+        // Get API key if required; one already in the provider's env var is
+        // picked up at run time and not written to the config
+        let key_var = preset
+            .key_env
+            .iter()
+            .find(|var| std::env::var(var).is_ok_and(|v| !v.is_empty()));
+        match key_var {
+            Some(var) if preset.requires_key => {
+                println!("ℹ️  Using the API key from {}", var)
+            }
+            _ if preset.requires_key => config.api_key = prompt_api_key()?,
+            _ => {}
+        }
 
-    // Set base URL
-    config.base_url = preset.base_url.clone();
+        // Set base URL
+        config.base_url = preset.base_url.clone();
 
-    // Get model
-    let model: String = Input::new()
-        .with_prompt(format!(
-            "Enter model name (default: {})",
-            preset.default_model
-        ))
-        .default(preset.default_model.clone())
-        .interact()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Input failed: {}", e)))?;
-    config.model = model;
+        // Get model
+        config.model = choose_model(&config, &preset.default_model).await?;
+        config
+    };
 
     // Get locale
     let locale_options = vec!["English", "中文"];
@@ -161,7 +1192,7 @@ pub async fn run_wizard(local: bool) -> Result<()> {
         .items(&locale_options)
         .default(0)
         .interact()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Selection failed: {}", e)))?;
+        .map_err(|e| crate::error::GitAiError::prompt("Selection", e))?;
 
     config.locale = if locale_idx == 0 { "en" } else { "zh" }.to_string();
 
@@ -170,29 +1201,41 @@ pub async fn run_wizard(local: bool) -> Result<()> {
         .with_prompt("Use custom system prompt?")
         .default(false)
         .interact()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Confirmation failed: {}", e)))?;
+        .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
 
     if use_custom {
         let custom_prompt: String = Input::new()
             .with_prompt("Enter custom system prompt")
             .interact()
-            .map_err(|e| crate::error::GitAiError::Other(format!("Input failed: {}", e)))?;
+            .map_err(|e| crate::error::GitAiError::prompt("Input", e))?;
         config.custom_prompt = Some(custom_prompt);
     }
 
     // Ask for footer
     let enable_footer = Confirm::new()
         .with_prompt("Add footer to commit messages?")
-        .default(true)
+        .default(false)
         .interact()
-        .map_err(|e| crate::error::GitAiError::Other(format!("Confirmation failed: {}", e)))?;
+        .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
 
     config.enable_footer = Some(enable_footer);
 
     // Save configuration
     if local {
+        // Keys stay out of the committed file; keep them in the per-clone config
+        let api_key = std::mem::take(&mut config.api_key);
         ConfigManager::write_local_config(&config)?;
         println!("\n✅ Local configuration saved to .git-ai.json");
+        if !api_key.is_empty() {
+            match ConfigManager::read_repo_config() {
+                Ok(mut repo_config) => {
+                    repo_config.api_key = api_key;
+                    ConfigManager::write_repo_config(&repo_config)?;
+                    println!("🔑 API key saved to .git/git-ai.json (never committed)");
+                }
+                Err(_) => println!("⚠️  API key not saved; set GIT_AI_API_KEY to provide it"),
+            }
+        }
     } else {
         ConfigManager::write_global_config(&config)?;
         println!("\n✅ Global configuration saved");
@@ -202,3 +1245,89 @@ pub async fn run_wizard(local: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_opencommit_config_file() {
+        let content = "# comment\nOCO_API_KEY=sk-abc\nOCO_MODEL=gpt-4o-mini\nOCO_LANGUAGE=zh_CN\nOCO_EMOJI=false\n";
+        let mapped: Vec<_> = parse_opencommit_config(content)
+            .into_iter()
+            .filter_map(|(k, v)| map_opencommit_setting(&k, &v))
+            .collect();
+        assert_eq!(
+            mapped,
+            vec![
+                ("api_key", "sk-abc".to_string()),
+                ("model", "gpt-4o-mini".to_string()),
+                ("locale", "zh".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_toml() {
+        let mut values = serde_json::Map::new();
+        values.insert("model".to_string(), "gpt-4o".into());
+        values.insert("api_key".to_string(), "sk-secret".into());
+        values.insert("slack_webhook".to_string(), "https://hooks/secret".into());
+        values.insert("default_num".to_string(), 3.into());
+
+        let toml = render_snapshot(values, SnapshotFormat::Toml, true).unwrap();
+        assert!(!toml.contains("secret"));
+
+        let parsed = parse_snapshot(&toml, std::path::Path::new("team.toml")).unwrap();
+        assert_eq!(parsed["model"], "gpt-4o");
+        assert_eq!(parsed["default_num"], 3);
+        assert!(!parsed.contains_key("api_key"));
+    }
+
+    #[test]
+    fn snapshot_keys_are_normalized() {
+        let parsed = parse_snapshot(
+            r#"{"baseUrl": "https://x/v1", "agentModel": "m"}"#,
+            std::path::Path::new("team.json"),
+        )
+        .unwrap();
+        assert_eq!(parsed["base_url"], "https://x/v1");
+        assert_eq!(parsed["agent_model"], "m");
+    }
+
+    #[test]
+    fn edit_template_is_valid() {
+        for scope in [ConfigScope::Global, ConfigScope::Local] {
+            let template = config_template(scope);
+            assert!(ConfigManager::validate_config_text(&template, scope).is_ok());
+        }
+        assert!(!config_template(ConfigScope::Local).contains("api_key"));
+    }
+
+    #[test]
+    fn imports_each_key_once_from_the_last_source() {
+        let found = vec![
+            ("v1".to_string(), "model".to_string(), "a".into()),
+            ("v1".to_string(), "locale".to_string(), "zh".into()),
+            ("env".to_string(), "model".to_string(), "b".into()),
+        ];
+        let resolved = last_per_key(found);
+        assert_eq!(
+            resolved,
+            vec![
+                ("env".to_string(), "model".to_string(), "b".into()),
+                ("v1".to_string(), "locale".to_string(), "zh".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_unknown_opencommit_values() {
+        assert_eq!(map_opencommit_setting("OCO_AI_PROVIDER", "azure"), None);
+        assert_eq!(map_opencommit_setting("OCO_API_KEY", "undefined"), None);
+        assert_eq!(
+            map_opencommit_setting("OCO_AI_PROVIDER", "Ollama"),
+            Some(("provider", "ollama".to_string()))
+        );
+    }
+}
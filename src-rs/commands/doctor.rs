@@ -0,0 +1,276 @@
+use crate::commands::hook::{inspect_local_hook, HookKind, HookState};
+use crate::error::{GitAiError, Result};
+use crate::types::get_provider_presets;
+use crate::utils::ai::AIClient;
+use crate::utils::{ConfigManager, GitManager};
+use std::time::Duration;
+
+/// Locales with built-in prompt templates
+const SUPPORTED_LOCALES: &[&str] = &["en", "zh"];
+
+/// How long the `/models` probe may take; the client's own timeout is sized
+/// for generation and would leave doctor hanging on a dead endpoint
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+struct Check {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn print(&self) {
+        let icon = match self.status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        };
+        println!("  {} {:<18} {}", icon, self.name, self.detail);
+        if let Some(hint) = &self.hint {
+            println!("     → {}", hint);
+        }
+    }
+}
+
+pub async fn run() -> Result<()> {
+    println!("\n🩺 git-ai doctor\n");
+
+    let mut checks: Vec<Check> = Vec::new();
+
+    // Git and repository
+    let git_ok = match GitManager::get_git_version() {
+        Ok(version) => {
+            checks.push(Check::pass("Git installed", version));
+            true
+        }
+        Err(_) => {
+            checks.push(Check::fail(
+                "Git installed",
+                "git not found on PATH",
+                "Install git from https://git-scm.com/downloads",
+            ));
+            false
+        }
+    };
+
+    let in_repo = git_ok && GitManager::is_in_git_repo().unwrap_or(false);
+    if in_repo {
+        let root = GitManager::get_repo_root()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        checks.push(Check::pass("Git repository", root));
+        checks.push(check_hook());
+    } else if git_ok {
+        checks.push(Check::warn(
+            "Git repository",
+            "not inside a git repository",
+            "Run git-ai from a repository to check hooks and staged changes",
+        ));
+    }
+
+    // Configuration
+    let config = match ConfigManager::get_merged_config() {
+        Ok(config) => config,
+        Err(e) => {
+            checks.push(Check::fail(
+                "Config",
+                e.to_string(),
+                "Fix the JSON file or run 'git-ai config reset'",
+            ));
+            return finish(&checks);
+        }
+    };
+
+    let presets = get_provider_presets();
+    let requires_key = presets
+        .get(config.provider.as_str())
+        .map(|p| p.requires_key)
+        .unwrap_or(config.auth_header.as_deref() != Some("none"));
+
+    if config.provider.is_empty() {
+        checks.push(Check::fail(
+            "Provider",
+            "not configured",
+            "Run 'git-ai config' to choose a provider",
+        ));
+    } else {
+        checks.push(Check::pass("Provider", config.provider.clone()));
+    }
+
+    if config.base_url.is_empty() {
+        checks.push(Check::fail(
+            "Base URL",
+            "not configured",
+            "Run 'git-ai config set base_url <url>'",
+        ));
+    } else {
+        checks.push(Check::pass("Base URL", config.base_url.clone()));
+    }
+
+    if config.model.is_empty() {
+        checks.push(Check::fail(
+            "Model",
+            "not configured",
+            "Run 'git-ai config set model <name>'",
+        ));
+    } else {
+        checks.push(Check::pass("Model", config.model.clone()));
+    }
+
+    if config.api_key.is_empty() && requires_key {
+        checks.push(Check::fail(
+            "API key",
+            "missing",
+            "Run 'git-ai config set api_key <key>' or export GIT_AI_API_KEY",
+        ));
+    }
+
+    if SUPPORTED_LOCALES.contains(&config.locale.as_str()) {
+        checks.push(Check::pass("Locale", config.locale.clone()));
+    } else {
+        checks.push(Check::warn(
+            "Locale",
+            format!("'{}' has no built-in prompt", config.locale),
+            "Run 'git-ai config set locale en' (or zh)",
+        ));
+    }
+
+    // Provider reachability and key validity
+    if !config.provider.is_empty() && !config.base_url.is_empty() {
+        match AIClient::new(config.clone()) {
+            Ok(client) => checks.extend(check_provider(&client, requires_key).await),
+            Err(e) => checks.push(Check::fail("Provider reachable", "skipped", e.to_string())),
+        }
+    }
+
+    finish(&checks)
+}
+
+fn check_hook() -> Check {
+    match inspect_local_hook(HookKind::PrepareCommitMsg) {
+        Ok(HookState::Installed { path, executable }) => {
+            if executable {
+                Check::pass("Hook", path.display().to_string())
+            } else {
+                Check::fail(
+                    "Hook",
+                    format!("{} is not executable", path.display()),
+                    format!("Run 'chmod +x {}'", path.display()),
+                )
+            }
+        }
+        Ok(HookState::Managed { manager, target }) => {
+            Check::pass("Hook", format!("{} via {}", target, manager))
+        }
+        Ok(HookState::Foreign(path)) => Check::warn(
+            "Hook",
+            format!("{} exists but is not git-ai's", path.display()),
+            "Run 'git-ai hook install' (the existing hook is backed up)",
+        ),
+        Ok(HookState::NotInstalled) => Check::warn(
+            "Hook",
+            "not installed (optional)",
+            "Run 'git-ai hook install' to generate messages on 'git commit'",
+        ),
+        Err(e) => Check::warn(
+            "Hook",
+            e.to_string(),
+            "Check the repository's hooks directory",
+        ),
+    }
+}
+
+async fn check_provider(client: &AIClient, requires_key: bool) -> Vec<Check> {
+    let models = tokio::time::timeout(PROBE_TIMEOUT, client.list_models())
+        .await
+        .unwrap_or_else(|_| {
+            Err(GitAiError::Http(format!(
+                "no response within {}s",
+                PROBE_TIMEOUT.as_secs()
+            )))
+        });
+    match models {
+        Ok(models) => {
+            let mut checks = vec![Check::pass(
+                "Provider reachable",
+                format!("{} model(s) listed", models.len()),
+            )];
+            if requires_key {
+                checks.push(Check::pass("API key", "accepted"));
+            }
+            checks
+        }
+        Err(GitAiError::Config(e)) => vec![
+            Check::pass("Provider reachable", "responded"),
+            Check::fail(
+                "API key",
+                e,
+                "Check the key with your provider and re-set it",
+            ),
+        ],
+        Err(GitAiError::Http(e)) => vec![Check::fail(
+            "Provider reachable",
+            e,
+            "Check base_url, your network/proxy, or that the local server is running",
+        )],
+        // Some providers do not implement /models; reaching them is enough.
+        Err(e) => vec![Check::warn(
+            "Provider reachable",
+            e.to_string(),
+            "Run 'git-ai config test' to verify generation end-to-end",
+        )],
+    }
+}
+
+fn finish(checks: &[Check]) -> Result<()> {
+    for check in checks {
+        check.print();
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    println!();
+    if failed > 0 {
+        return Err(GitAiError::Other(format!("{} check(s) failed", failed)));
+    }
+
+    println!("✅ All checks passed");
+    Ok(())
+}
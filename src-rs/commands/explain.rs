@@ -0,0 +1,175 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ci, truncate_at_char_boundary, ConfigManager, GitManager};
+
+/// Flags of `git-ai explain`
+#[derive(Debug, Default)]
+pub struct ExplainOptions {
+    /// Commit or `from..to` range (default: HEAD)
+    pub target: Option<String>,
+    /// Explain the staged changes instead
+    pub staged: bool,
+}
+
+/// What `git-ai explain` looks at
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExplainTarget {
+    Staged,
+    Commit(String),
+    /// `from..to` or `from...to`, both read as the commits on `to` since it
+    /// forked from `from`: they are listed with `git log from..to` and
+    /// diffed with `git diff from...to`, so the diff matches the commits.
+    /// An empty side means HEAD
+    Range {
+        from: String,
+        to: String,
+    },
+}
+
+impl ExplainTarget {
+    pub fn parse(target: Option<&str>, staged: bool) -> Self {
+        if staged {
+            return Self::Staged;
+        }
+        let target = target.unwrap_or("HEAD");
+        match target.split_once("...").or_else(|| target.split_once("..")) {
+            Some((from, to)) => {
+                let head = |rev: &str| {
+                    if rev.is_empty() {
+                        "HEAD".to_string()
+                    } else {
+                        rev.to_string()
+                    }
+                };
+                Self::Range {
+                    from: head(from),
+                    to: head(to),
+                }
+            }
+            None => Self::Commit(target.to_string()),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Staged => "staged changes".to_string(),
+            Self::Commit(rev) => rev.clone(),
+            Self::Range { from, to } => format!("{}..{}", from, to),
+        }
+    }
+}
+
+pub async fn run(options: ExplainOptions) -> Result<()> {
+    let target = ExplainTarget::parse(options.target.as_deref(), options.staged);
+
+    let content = match &target {
+        ExplainTarget::Staged => {
+            let diff = GitManager::get_staged_diff(&[], false)?;
+            if diff.trim().is_empty() {
+                return Err(GitAiError::NoStagedChanges);
+            }
+            format!("Staged changes (not committed yet):\n\n{}", diff)
+        }
+        ExplainTarget::Commit(rev) => {
+            format!("Commit {}:\n\n{}", rev, GitManager::get_commit_patch(rev)?)
+        }
+        ExplainTarget::Range { from, to } => {
+            let commits = GitManager::get_commits_between_refs(from, to)?;
+            if commits.is_empty() {
+                println!("ℹ️  No commits in {}", target.label());
+                return Ok(());
+            }
+            format!(
+                "Commits {}..{}:\n{}\n\nCombined diff since the merge base:\n\n{}",
+                from,
+                to,
+                commits.join("\n"),
+                GitManager::get_range_diff(&format!("{}...{}", from, to))?
+            )
+        }
+    };
+
+    let max_diff_chars = std::env::var("GIT_AI_MAX_DIFF_CHARS")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(5000);
+    let content = if content.len() > max_diff_chars {
+        format!(
+            "{}\n... (diff truncated)",
+            truncate_at_char_boundary(&content, max_diff_chars)
+        )
+    } else {
+        content
+    };
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let pb = ci::spinner();
+    pb.set_message(format!("🧭 Explaining {}...", target.label()));
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let explanation = ai_client
+        .generate_review(&get_explain_system_prompt(&config.locale), &content)
+        .await;
+    pb.finish_and_clear();
+
+    println!("🧭 {}\n", target.label());
+    println!("{}\n", explanation?.trim());
+    if config.show_usage.unwrap_or(false) {
+        println!("{}", ai_client.usage().summary());
+    }
+    Ok(())
+}
+
+fn get_explain_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => r#"你负责向不熟悉这段代码的开发者（代码审查者或新成员）解释一次提交或一段 diff。用通俗的中文输出 Markdown，依次包含：
+## 做了什么
+用 2-5 个要点说明行为上的变化，而不是逐行复述代码。
+## 为什么
+根据提交信息和代码推断最可能的动机；属于推测时明确说明。
+## 需要注意
+值得审查或后续关注的地方：边界情况、兼容性、缺少的测试、隐含假设；没有则写"无明显问题"。
+只依据给出的内容，不要编造。"#
+            .to_string(),
+        _ => r#"You explain a commit or diff to a developer who does not know this code (a reviewer or a new team member). Write plain-language Markdown with these sections, in order:
+## What it does
+2-5 bullets on the change in behavior, not a line-by-line retelling of the code.
+## Why
+The most likely motivation, from the commit message and the code; say so when you are guessing.
+## Watch out for
+What deserves a closer look or follow-up: edge cases, compatibility, missing tests, hidden assumptions; write "Nothing notable" if there is nothing.
+Stick to what is shown; do not invent details."#
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExplainTarget;
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!(
+            ExplainTarget::parse(None, false),
+            ExplainTarget::Commit("HEAD".to_string())
+        );
+        assert_eq!(
+            ExplainTarget::parse(Some("abc123"), true),
+            ExplainTarget::Staged
+        );
+        assert_eq!(
+            ExplainTarget::parse(Some("v1.0..v1.1"), false),
+            ExplainTarget::Range {
+                from: "v1.0".to_string(),
+                to: "v1.1".to_string()
+            }
+        );
+        assert_eq!(
+            ExplainTarget::parse(Some("origin/main..."), false),
+            ExplainTarget::Range {
+                from: "origin/main".to_string(),
+                to: "HEAD".to_string()
+            }
+        );
+    }
+}
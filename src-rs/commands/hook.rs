@@ -1,12 +1,397 @@
 use crate::error::Result;
+use crate::utils::{ci, GitManager};
+use clap::ValueEnum;
+use dialoguer::Confirm;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub async fn run(action: String, global: bool) -> Result<()> {
+/// Markers of the git-ai block written before they carried the hook name
+const LEGACY_BLOCK_START: &str = "# >>> git-ai >>>";
+const LEGACY_BLOCK_END: &str = "# <<< git-ai <<<";
+
+/// Lefthook script name used for the git-ai hook
+const LEFTHOOK_SCRIPT: &str = "git-ai.sh";
+
+/// Global git config key remembering the core.hooksPath that `hook install
+/// --global` replaced, restored by `hook remove --global`
+const PREVIOUS_HOOKS_PATH_KEY: &str = "git-ai.previousHooksPath";
+
+/// Git hooks that git-ai knows how to install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    /// Generate the commit message before the editor opens
+    PrepareCommitMsg,
+    /// Summarize outgoing commits and block risky pushes
+    PrePush,
+    /// Review staged changes and block commits at the `pre_commit_fail_on` risk level
+    PreCommit,
+    /// Attach audit notes to commits whose message the prepare-commit-msg hook wrote
+    PostCommit,
+}
+
+impl HookKind {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            HookKind::PrepareCommitMsg => "prepare-commit-msg",
+            HookKind::PrePush => "pre-push",
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PostCommit => "post-commit",
+        }
+    }
+}
+
+/// Script language of an installed hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookShell {
+    /// POSIX shell script (default outside Windows)
+    Bash,
+    /// Batch file
+    Cmd,
+    /// `sh` launcher running a `<hook>.ps1` script (default on Windows)
+    Powershell,
+}
+
+impl HookShell {
+    fn resolve(shell: Option<HookShell>) -> HookShell {
+        shell.unwrap_or(if cfg!(windows) {
+            HookShell::Powershell
+        } else {
+            HookShell::Bash
+        })
+    }
+}
+
+/// Installation state of a git-ai hook in the current repository
+#[derive(Debug, Clone)]
+pub enum HookState {
+    NotInstalled,
+    /// A hook file exists but was not written by git-ai
+    Foreign(PathBuf),
+    Installed {
+        path: PathBuf,
+        executable: bool,
+    },
+    /// Registered through husky or lefthook
+    Managed {
+        manager: &'static str,
+        target: String,
+    },
+}
+
+/// Inspect how (and whether) the git-ai hook is installed in the current repository
+pub fn inspect_local_hook(kind: HookKind) -> Result<HookState> {
+    if let Some(manager) = detect_hook_manager()? {
+        if manager_has_git_ai(&manager, kind) {
+            return Ok(HookState::Managed {
+                manager: manager.name(),
+                target: manager.target_display(kind),
+            });
+        }
+    }
+
+    let hook_path = get_local_hook_path(kind)?;
+    if !hook_path.exists() {
+        return Ok(HookState::NotInstalled);
+    }
+
+    let content = read_optional(&hook_path)?;
+    if !is_git_ai_hook(&content) {
+        return Ok(HookState::Foreign(hook_path));
+    }
+
+    Ok(HookState::Installed {
+        executable: is_executable(&hook_path),
+        path: hook_path,
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.exists()
+}
+
+/// What `hook install` did in a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InstallOutcome {
+    Installed,
+    Updated,
+    Unchanged,
+}
+
+impl InstallOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            InstallOutcome::Installed => "✅ installed",
+            InstallOutcome::Updated => "🔄 updated",
+            InstallOutcome::Unchanged => "✅ already installed",
+        }
+    }
+}
+
+/// Third-party hook managers that own the repository's hooks
+#[derive(Debug, Clone, PartialEq)]
+enum HookManager {
+    /// husky: hooks are shell snippets in `.husky/<hook>`
+    Husky(PathBuf),
+    /// lefthook: hooks are declared in `lefthook.yml`
+    Lefthook(PathBuf),
+}
+
+impl HookManager {
+    fn name(&self) -> &'static str {
+        match self {
+            HookManager::Husky(_) => "husky",
+            HookManager::Lefthook(_) => "lefthook",
+        }
+    }
+
+    fn target_display(&self, kind: HookKind) -> String {
+        match self {
+            HookManager::Husky(dir) => dir.join(kind.file_name()).display().to_string(),
+            HookManager::Lefthook(config) => config.display().to_string(),
+        }
+    }
+}
+
+/// Overrides baked into the generated hook script at install time
+#[derive(Debug, Clone, Default)]
+pub struct HookOptions {
+    pub locale: Option<String>,
+    pub num: Option<usize>,
+    pub timeout: Option<u64>,
+    pub model: Option<String>,
+    /// Script language; picked from the platform when not given
+    pub shell: Option<HookShell>,
+    /// git-ai executable the script runs; looked up on PATH when not set
+    pub binary: Option<String>,
+    /// Global install: run the repository's own hook (and the one in
+    /// `previous_hooks_path`) first, since core.hooksPath replaces `.git/hooks`
+    pub chain: bool,
+    /// core.hooksPath the global install replaced
+    pub previous_hooks_path: Option<String>,
+}
+
+impl HookOptions {
+    /// Options an installed hook was generated with, read back from its
+    /// file (`main`) and, for PowerShell hooks, the `.ps1` script (`script`)
+    fn from_script(main: &str, script: &str) -> Self {
+        let shell = if main.starts_with("@echo off") {
+            HookShell::Cmd
+        } else if main.contains(".ps1\"") {
+            HookShell::Powershell
+        } else {
+            HookShell::Bash
+        };
+        let mut options = HookOptions {
+            shell: Some(shell),
+            ..Default::default()
+        };
+
+        for line in script.lines().map(str::trim) {
+            let assignment = if let Some(rest) = line.strip_prefix("export ") {
+                rest.split_once('=')
+                    .map(|(name, value)| (name, unquote(value, "'\\''")))
+            } else if let Some(rest) = line.strip_prefix("$env:") {
+                rest.split_once(" = ")
+                    .map(|(name, value)| (name, unquote(value, "''")))
+            } else if let Some(rest) = line.strip_prefix("set \"") {
+                // Written unescaped by earlier versions
+                rest.strip_suffix('"')
+                    .and_then(|rest| rest.split_once('='))
+                    .map(|(name, value)| (name, value.to_string()))
+            } else {
+                line.strip_prefix("set ")
+                    .and_then(|rest| rest.split_once('='))
+                    .map(|(name, value)| (name, cmd_unescape(value)))
+            };
+            match assignment {
+                Some(("GIT_AI_LOCALE", value)) => options.locale = Some(value),
+                Some(("GIT_AI_MODEL", value)) => options.model = Some(value),
+                Some(("GIT_AI_TIMEOUT", value)) => options.timeout = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        options.num = ["--num ", "'--num', '"].iter().find_map(|flag| {
+            let rest = &script[script.find(flag)? + flag.len()..];
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        });
+        options
+    }
+
+    /// Environment overrides as (name, value) pairs
+    fn env_overrides(&self) -> Vec<(&'static str, String)> {
+        let mut vars = Vec::new();
+        if let Some(locale) = &self.locale {
+            vars.push(("GIT_AI_LOCALE", locale.clone()));
+        }
+        if let Some(model) = &self.model {
+            vars.push(("GIT_AI_MODEL", model.clone()));
+        }
+        if let Some(timeout) = self.timeout {
+            vars.push(("GIT_AI_TIMEOUT", timeout.to_string()));
+        }
+        vars
+    }
+
+    fn env_block_bash(&self) -> String {
+        self.env_overrides()
+            .into_iter()
+            .map(|(name, value)| format!("export {}={}\n", name, shell_quote(&value)))
+            .collect()
+    }
+
+    fn env_block_windows(&self) -> String {
+        self.env_overrides()
+            .into_iter()
+            .map(|(name, value)| format!("set {}={}\r\n", name, cmd_escape(&value)))
+            .collect()
+    }
+
+    fn env_block_powershell(&self) -> String {
+        self.env_overrides()
+            .into_iter()
+            .map(|(name, value)| format!("$env:{} = {}\n", name, powershell_quote(&value)))
+            .collect()
+    }
+
+    /// How the script invokes git-ai in `shell`
+    fn binary_for(&self, shell: HookShell) -> String {
+        match (&self.binary, shell) {
+            (None, _) => "git-ai".to_string(),
+            (Some(binary), HookShell::Bash) => shell_quote(binary),
+            (Some(binary), HookShell::Cmd) => format!("\"{}\"", binary.replace('%', "%%")),
+            (Some(binary), HookShell::Powershell) => powershell_quote(binary),
+        }
+    }
+
+    /// cmd line exiting the hook when the git-ai binary is gone
+    fn binary_guard_cmd(&self) -> String {
+        match &self.binary {
+            None => "where git-ai >nul 2>&1 || exit /b 0\r\n".to_string(),
+            Some(_) => format!(
+                "if not exist {} exit /b 0\r\n",
+                self.binary_for(HookShell::Cmd)
+            ),
+        }
+    }
+
+    fn msg_args(&self) -> String {
+        match self.num {
+            Some(num) => format!(" --num {}", num),
+            None => String::new(),
+        }
+    }
+}
+
+/// Value of a single-quoted shell or PowerShell string whose quotes are
+/// escaped as `escaped`
+fn unquote(value: &str, escaped: &str) -> String {
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .map(|v| v.replace(escaped, "'"))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Binary a hook script runs git-ai with; `None` for a plain `git-ai` PATH lookup
+fn referenced_binary(script: &str) -> Option<String> {
+    script.lines().find_map(|line| {
+        let end = [
+            " msg --quiet",
+            " @msgArgs",
+            " push-summary ",
+            " review --pre-commit",
+            " audit --attach-pending",
+        ]
+        .iter()
+        .find_map(|marker| line.find(marker))?;
+        let command = line[..end].trim_end();
+        let binary = match command.chars().last()? {
+            quote @ ('\'' | '"') => {
+                let inner = &command[..command.len() - 1];
+                let start = inner.rfind(quote)?;
+                inner[start + 1..].to_string()
+            }
+            _ => command
+                .rsplit(|c: char| c.is_whitespace() || c == '&' || c == '|')
+                .next()?
+                .to_string(),
+        };
+        (binary != "git-ai").then_some(binary)
+    })
+}
+
+/// Whether a hook file was written by `hook install`: every generated
+/// script names git-ai-cli in its header comment
+fn is_git_ai_hook(content: &str) -> bool {
+    content.contains("hook for git-ai-cli")
+}
+
+/// Quote a value for POSIX shells
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Escape a value for an unquoted `set NAME=value` in a cmd script
+fn cmd_escape(value: &str) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '%' => escaped.push_str("%%"),
+            '^' | '&' | '|' | '<' | '>' | '"' => {
+                escaped.push('^');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Value of a `set NAME=value` escaped by `cmd_escape`
+fn cmd_unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '^' => unescaped.extend(chars.next()),
+            '%' => {
+                unescaped.push('%');
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Quote a value as a PowerShell verbatim string
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// `yes` sets the global core.hooksPath without asking
+pub async fn run(
+    action: String,
+    global: bool,
+    kind: HookKind,
+    options: HookOptions,
+    yes: bool,
+) -> Result<()> {
     match action.as_str() {
-        "install" => run_install(global).await,
-        "remove" => run_remove(global).await,
-        "status" => run_status(global).await,
+        "install" => run_install(global, kind, &options, yes).await.map(|_| ()),
+        "remove" => run_remove(global, kind).await,
+        "status" => run_status(global, Some(kind)).await,
         _ => Err(crate::error::GitAiError::InvalidArgument(format!(
             "Unknown hook action: {}",
             action
@@ -14,11 +399,163 @@ pub async fn run(action: String, global: bool) -> Result<()> {
     }
 }
 
-async fn run_install(global: bool) -> Result<()> {
+/// Install the hook into every repository under `root` and/or listed in `repos_file`
+pub async fn run_batch_install(
+    root: Option<PathBuf>,
+    repos_file: Option<PathBuf>,
+    kind: HookKind,
+    options: HookOptions,
+) -> Result<()> {
+    let mut repos: Vec<PathBuf> = Vec::new();
+    if let Some(root) = root {
+        repos.extend(discover_repos(&root));
+    }
+    if let Some(file) = repos_file {
+        let content = fs::read_to_string(&file).map_err(|e| {
+            crate::error::GitAiError::Other(format!(
+                "Failed to read repository list {}: {}",
+                file.display(),
+                e
+            ))
+        })?;
+        repos.extend(parse_repo_list(&content));
+    }
+
+    if repos.is_empty() {
+        println!("ℹ️  No git repositories found");
+        return Ok(());
+    }
+
+    let original_dir = std::env::current_dir()?;
+    let mut results: Vec<(String, String)> = Vec::new();
+
+    for repo in &repos {
+        let name = repo.display().to_string();
+        println!("\n📁 {}", name);
+
+        let outcome = match std::env::set_current_dir(original_dir.join(repo)) {
+            Ok(()) if GitManager::is_in_git_repo().unwrap_or(false) => {
+                run_install(false, kind, &options, false).await
+            }
+            Ok(()) => Err(crate::error::GitAiError::NotInGitRepo),
+            Err(e) => Err(e.into()),
+        };
+
+        let label = match outcome {
+            Ok(outcome) => outcome.label().to_string(),
+            Err(e) => format!("❌ {}", e),
+        };
+        results.push((name, label));
+    }
+
+    std::env::set_current_dir(&original_dir)?;
+
+    let width = results
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(10)
+        .max(10);
+    println!("\n📋 Hook install summary ({}):\n", kind.file_name());
+    println!("  {:<width$}  Result", "Repository", width = width);
+    println!("  {:─<width$}  {:─<6}", "", "", width = width);
+    for (name, label) in &results {
+        println!("  {:<width$}  {}", name, label, width = width);
+    }
+
+    let failed = results.iter().filter(|(_, l)| l.starts_with('❌')).count();
+    if failed > 0 {
+        return Err(crate::error::GitAiError::Other(format!(
+            "Hook install failed in {} of {} repositories",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Directories that never contain repositories worth touching
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor"];
+
+/// Find git repositories under `root` without descending into them
+fn discover_repos(root: &Path) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        if dir.join(".git").exists() {
+            repos.push(dir);
+            continue;
+        }
+
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            pending.push(entry.path());
+        }
+    }
+
+    repos.sort();
+    repos
+}
+
+/// Parse a repository list file: one path per line, `#` starts a comment
+fn parse_repo_list(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+async fn run_install(
+    global: bool,
+    kind: HookKind,
+    options: &HookOptions,
+    yes: bool,
+) -> Result<InstallOutcome> {
+    if !global {
+        if let Some(manager) = detect_hook_manager()? {
+            // Without a terminal (scripts, CI) take the manager path by default.
+            let use_manager = !ci::interactive()
+                || Confirm::new()
+                    .with_prompt(format!(
+                        "Detected {}. Add git-ai to {} instead of .git/hooks?",
+                        manager.name(),
+                        manager.target_display(kind)
+                    ))
+                    .default(true)
+                    .interact()
+                    .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
+
+            if use_manager {
+                return install_with_manager(&manager, kind, options);
+            }
+
+            println!(
+                "⚠️  {} manages hooks in this repository; a hook in .git/hooks may never run",
+                manager.name()
+            );
+        }
+    }
+
     let hook_path = if global {
-        get_global_hook_path()?
+        take_over_global_hooks_path(yes)?.join(kind.file_name())
     } else {
-        get_local_hook_path()?
+        get_local_hook_path(kind)?
     };
 
     // Create hook directory if needed
@@ -28,35 +565,57 @@ async fn run_install(global: bool) -> Result<()> {
         })?;
     }
 
-    // Generate hook script (platform-specific)
-    let hook_script = if cfg!(windows) {
-        generate_hook_script_windows()
-    } else {
-        generate_hook_script_bash()
-    };
+    let options = with_placement(options.clone(), &hook_path, global)?;
+    if global && HookShell::resolve(options.shell) == HookShell::Cmd {
+        println!("⚠️  cmd hooks cannot run the repository's own hooks; use --shell bash or powershell to keep them");
+    }
+    let (hook_script, companion) = generate_hook_files(kind, &options);
+    let companion = companion.map(|script| (powershell_script_path(&hook_path), script));
 
     // Check if hook already exists
+    let mut outcome = InstallOutcome::Installed;
     if hook_path.exists() {
         let existing = fs::read_to_string(&hook_path).map_err(|e| {
             crate::error::GitAiError::Other(format!("Failed to read existing hook: {}", e))
         })?;
+        let companion_current = companion
+            .as_ref()
+            .is_none_or(|(path, script)| read_optional(path).is_ok_and(|c| &c == script));
 
-        if existing.contains("git-ai") {
+        if existing == hook_script && companion_current {
             println!("✅ Git hook already installed at {}", hook_path.display());
-            return Ok(());
+            return Ok(InstallOutcome::Unchanged);
         }
 
-        // Backup existing hook
-        let backup_path = format!("{}.original", hook_path.display());
-        fs::copy(&hook_path, &backup_path).map_err(|e| {
-            crate::error::GitAiError::Other(format!("Failed to backup hook: {}", e))
-        })?;
-        println!("📦 Backed up existing hook to {}", backup_path);
+        // Our own hook with different options is rewritten in place; any
+        // other hook (even one calling git-ai) is kept, without replacing an
+        // earlier backup
+        if is_git_ai_hook(&existing) {
+            outcome = InstallOutcome::Updated;
+        } else {
+            let backup_path = (0..)
+                .map(|n| match n {
+                    0 => format!("{}.original", hook_path.display()),
+                    n => format!("{}.original.{}", hook_path.display(), n),
+                })
+                .find(|path| !Path::new(path).exists())
+                .expect("a free backup name");
+            fs::copy(&hook_path, &backup_path).map_err(|e| {
+                crate::error::GitAiError::Other(format!("Failed to backup hook: {}", e))
+            })?;
+            println!("📦 Backed up existing hook to {}", backup_path);
+        }
     }
 
     // Write hook script
     fs::write(&hook_path, hook_script)
         .map_err(|e| crate::error::GitAiError::Other(format!("Failed to write hook: {}", e)))?;
+    match &companion {
+        Some((path, script)) => fs::write(path, script)
+            .map_err(|e| crate::error::GitAiError::Other(format!("Failed to write hook: {}", e)))?,
+        // Switching away from PowerShell leaves no stale script behind
+        None => remove_powershell_script(&hook_path)?,
+    }
 
     // Make hook executable (Unix-like systems)
     #[cfg(unix)]
@@ -72,33 +631,73 @@ async fn run_install(global: bool) -> Result<()> {
         "✅ Git hook installed successfully at {}",
         hook_path.display()
     );
-    println!("   Hook will run before each commit to generate messages");
+    match kind {
+        HookKind::PrepareCommitMsg => {
+            println!("   Hook will run before each commit to generate messages");
+            println!("   With audit_notes on, also run 'git-ai hook install --type post-commit' to record them");
+        }
+        HookKind::PrePush => {
+            println!("   Hook will summarize outgoing commits before each push")
+        }
+        HookKind::PreCommit => {
+            println!("   Hook will review staged changes and block commits at the preCommitFailOn risk level (default: high)")
+        }
+        HookKind::PostCommit => {
+            println!(
+                "   Hook will attach audit notes to commits written by the prepare-commit-msg hook"
+            )
+        }
+    }
 
-    Ok(())
+    Ok(outcome)
 }
 
-async fn run_remove(global: bool) -> Result<()> {
+async fn run_remove(global: bool, kind: HookKind) -> Result<()> {
+    if !global {
+        if let Some(manager) = detect_hook_manager()? {
+            if remove_from_manager(&manager, kind)? {
+                println!(
+                    "✅ git-ai removed from {} ({})",
+                    manager.name(),
+                    manager.target_display(kind)
+                );
+                return Ok(());
+            }
+        }
+    }
+
     let hook_path = if global {
-        get_global_hook_path()?
+        get_global_hook_path(kind)?
     } else {
-        get_local_hook_path()?
+        get_local_hook_path(kind)?
     };
 
     if !hook_path.exists() {
         println!("ℹ️  Git hook not found at {}", hook_path.display());
         return Ok(());
     }
+    // Never delete a hook git-ai did not write, e.g. the user's own pre-push
+    if !is_git_ai_hook(&read_optional(&hook_path)?) {
+        return Err(crate::error::GitAiError::InvalidArgument(format!(
+            "{} exists but doesn't contain git-ai; leaving it untouched",
+            hook_path.display()
+        )));
+    }
+
+    remove_powershell_script(&hook_path)?;
 
     // Check if there's a backup
-    let backup_path = format!("{}.original", hook_path.display());
-    if PathBuf::from(&backup_path).exists() {
+    if let Some(backup_path) = latest_backup(&hook_path) {
         fs::copy(&backup_path, &hook_path).map_err(|e| {
             crate::error::GitAiError::Other(format!("Failed to restore backup: {}", e))
         })?;
         fs::remove_file(&backup_path).map_err(|e| {
             crate::error::GitAiError::Other(format!("Failed to remove backup: {}", e))
         })?;
-        println!("✅ Git hook removed and original hook restored");
+        println!(
+            "✅ Git hook removed and original hook restored from {}",
+            backup_path.display()
+        );
     } else {
         fs::remove_file(&hook_path).map_err(|e| {
             crate::error::GitAiError::Other(format!("Failed to remove hook: {}", e))
@@ -106,79 +705,757 @@ async fn run_remove(global: bool) -> Result<()> {
         println!("✅ Git hook removed successfully");
     }
 
+    if global {
+        release_global_hooks_path()?;
+    }
+
     Ok(())
 }
 
-async fn run_status(global: bool) -> Result<()> {
+/// Health of every git-ai hook (or just `kind`)
+pub async fn run_status(global: bool, kind: Option<HookKind>) -> Result<()> {
+    let kinds: Vec<HookKind> = match kind {
+        Some(kind) => vec![kind],
+        None => HookKind::value_variants().to_vec(),
+    };
+    let mut stale = false;
+    for kind in kinds {
+        match inspect_hook(global, kind)? {
+            HookCheck::NotInstalled => {
+                println!("❌ {}: not installed", kind.file_name());
+                println!(
+                    "   Run 'git-ai hook install --type {}' to install it",
+                    kind.file_name()
+                );
+            }
+            HookCheck::Foreign(path) => {
+                println!(
+                    "⚠️  {}: {} exists but doesn't contain git-ai",
+                    kind.file_name(),
+                    path.display()
+                );
+            }
+            HookCheck::Installed(hook) => {
+                let binary = hook.binary_status();
+                let healthy = hook.executable && binary.is_ok() && hook.is_current();
+                stale |= !hook.is_current() || binary.is_err();
+                println!(
+                    "{} {}: installed {}",
+                    if healthy { "✅" } else { "⚠️ " },
+                    kind.file_name(),
+                    hook.location
+                );
+                println!(
+                    "   Executable: {}",
+                    if hook.executable { "yes" } else { "no" }
+                );
+                match &hook.backup {
+                    Some(backup) => println!("   Backup:     {}", backup.display()),
+                    None => println!("   Backup:     none"),
+                }
+                match binary {
+                    Ok(found) => println!("   Binary:     {}", found),
+                    Err(missing) => println!("   Binary:     ⚠️  {}", missing),
+                }
+                println!(
+                    "   Script:     {}",
+                    if hook.is_current() {
+                        "up to date"
+                    } else {
+                        "outdated"
+                    }
+                );
+            }
+        }
+    }
+    if stale {
+        println!("\n💡 Run 'git-ai hook upgrade' to rewrite outdated hooks");
+    }
+
+    Ok(())
+}
+
+/// Rewrite git-ai hooks whose script no longer matches what this version
+/// installs, e.g. after the git-ai binary moved. Options baked in at install
+/// time (locale, model, ...) are kept.
+pub async fn run_upgrade(global: bool, kind: Option<HookKind>) -> Result<()> {
+    let kinds: Vec<HookKind> = match kind {
+        Some(kind) => vec![kind],
+        None => HookKind::value_variants().to_vec(),
+    };
+    let mut installed = 0;
+    for kind in kinds {
+        let HookCheck::Installed(hook) = inspect_hook(global, kind)? else {
+            continue;
+        };
+        installed += 1;
+        if hook.is_current() {
+            println!("✅ {}: up to date", kind.file_name());
+            continue;
+        }
+        write_executable(&hook.path, &hook.expected)?;
+        if let Some((path, _, expected)) = &hook.companion {
+            fs::write(path, expected).map_err(|e| {
+                crate::error::GitAiError::Other(format!("Failed to write hook: {}", e))
+            })?;
+        }
+        println!("🔄 {}: rewritten {}", kind.file_name(), hook.location);
+    }
+    if installed == 0 {
+        println!("ℹ️  No git-ai hooks installed");
+    }
+
+    Ok(())
+}
+
+/// What `hook status` found for one hook type
+enum HookCheck {
+    NotInstalled,
+    /// A hook file exists but was not written by git-ai
+    Foreign(PathBuf),
+    Installed(InstalledHook),
+}
+
+/// A git-ai hook read back from disk, with what `hook upgrade` would write
+struct InstalledHook {
+    /// "at <path>" or "via <manager> at <path>"
+    location: String,
+    /// File holding the hook (the husky hook, lefthook script or hook file)
+    path: PathBuf,
+    content: String,
+    expected: String,
+    /// `.ps1` script of a PowerShell hook: path, content, expected content
+    companion: Option<(PathBuf, String, String)>,
+    executable: bool,
+    backup: Option<PathBuf>,
+    /// Binary the script runs; `None` when it looks git-ai up on PATH
+    binary: Option<String>,
+}
+
+impl InstalledHook {
+    fn is_current(&self) -> bool {
+        self.content == self.expected
+            && self
+                .companion
+                .as_ref()
+                .is_none_or(|(_, content, expected)| content == expected)
+    }
+
+    /// Where the referenced binary resolves, or why it does not
+    fn binary_status(&self) -> std::result::Result<String, String> {
+        match &self.binary {
+            Some(binary) if Path::new(binary).is_file() => Ok(binary.clone()),
+            Some(binary) => Err(format!("{} no longer exists", binary)),
+            None => match crate::commands::plugin::find_on_path("git-ai") {
+                Some(found) => Ok(format!("git-ai from PATH ({})", found.display())),
+                None => Err("git-ai is not on PATH".to_string()),
+            },
+        }
+    }
+}
+
+fn inspect_hook(global: bool, kind: HookKind) -> Result<HookCheck> {
+    if !global {
+        if let Some(manager) = detect_hook_manager()? {
+            if manager_has_git_ai(&manager, kind) {
+                return inspect_managed_hook(&manager, kind);
+            }
+        }
+    }
+
     let hook_path = if global {
-        get_global_hook_path()?
+        get_global_hook_path(kind)?
     } else {
-        get_local_hook_path()?
+        get_local_hook_path(kind)?
     };
+    if !hook_path.exists() {
+        return Ok(HookCheck::NotInstalled);
+    }
+    let content = read_optional(&hook_path)?;
+    if !is_git_ai_hook(&content) {
+        return Ok(HookCheck::Foreign(hook_path));
+    }
 
-    if hook_path.exists() {
-        let content = fs::read_to_string(&hook_path)
-            .map_err(|e| crate::error::GitAiError::Other(format!("Failed to read hook: {}", e)))?;
+    let script_path = powershell_script_path(&hook_path);
+    let is_launcher = content.contains(&format!("/{}.ps1\"", kind.file_name()));
+    let script = if is_launcher {
+        read_optional(&script_path)?
+    } else {
+        content.clone()
+    };
+    let options = with_placement(
+        HookOptions::from_script(&content, &script),
+        &hook_path,
+        global,
+    )?;
+    let (expected, expected_companion) = generate_hook_files(kind, &options);
+    let backup = latest_backup(&hook_path);
 
-        if content.contains("git-ai") {
-            println!("✅ Git hook is installed at {}", hook_path.display());
-            println!("   Type: prepare-commit-msg");
-            println!("   Status: Active");
-        } else {
-            println!("⚠️  Hook exists but doesn't contain git-ai");
+    Ok(HookCheck::Installed(InstalledHook {
+        location: format!("at {}", hook_path.display()),
+        binary: referenced_binary(&script),
+        companion: expected_companion.map(|expected| (script_path, script, expected)),
+        executable: is_executable(&hook_path),
+        backup,
+        path: hook_path,
+        content,
+        expected,
+    }))
+}
+
+/// husky and lefthook hooks are committed, so they always run git-ai from PATH
+fn inspect_managed_hook(manager: &HookManager, kind: HookKind) -> Result<HookCheck> {
+    let location = format!("via {} at {}", manager.name(), manager.target_display(kind));
+    let (path, content, expected) = match manager {
+        HookManager::Husky(dir) => {
+            let path = dir.join(kind.file_name());
+            let content = read_optional(&path)?;
+            let options = HookOptions::from_script(&content, &content);
+            let expected =
+                replace_managed_block(&content, kind, &managed_hook_body(kind, &options))
+                    .unwrap_or_else(|| content.clone());
+            (path, content, expected)
+        }
+        HookManager::Lefthook(config_path) => {
+            let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+            let path = root
+                .join(".lefthook")
+                .join(kind.file_name())
+                .join(LEFTHOOK_SCRIPT);
+            let content = read_optional(&path)?;
+            let options = HookOptions::from_script(&content, &content);
+            let expected = generate_hook_script_bash_for(kind, &options);
+            (path, content, expected)
+        }
+    };
+
+    Ok(HookCheck::Installed(InstalledHook {
+        location,
+        executable: is_executable(&path),
+        binary: None,
+        companion: None,
+        backup: None,
+        path,
+        content,
+        expected,
+    }))
+}
+
+fn detect_hook_manager() -> Result<Option<HookManager>> {
+    let root = GitManager::get_repo_root()?;
+
+    let husky_dir = root.join(".husky");
+    if husky_dir.is_dir() {
+        return Ok(Some(HookManager::Husky(husky_dir)));
+    }
+
+    for name in [
+        "lefthook.yml",
+        ".lefthook.yml",
+        "lefthook.yaml",
+        ".lefthook.yaml",
+    ] {
+        let config = root.join(name);
+        if config.is_file() {
+            return Ok(Some(HookManager::Lefthook(config)));
+        }
+    }
+
+    Ok(None)
+}
+
+fn install_with_manager(
+    manager: &HookManager,
+    kind: HookKind,
+    options: &HookOptions,
+) -> Result<InstallOutcome> {
+    match manager {
+        HookManager::Husky(dir) => {
+            let hook_path = dir.join(kind.file_name());
+            let existing = read_optional(&hook_path)?;
+            // Re-installing replaces the previous block so new options take effect
+            let (existing, outcome) = match strip_managed_block(&existing, kind) {
+                Some(stripped) => (stripped, InstallOutcome::Updated),
+                None => (existing, InstallOutcome::Installed),
+            };
+            let updated = append_managed_block(&existing, kind, &managed_hook_body(kind, options));
+            write_executable(&hook_path, &updated)?;
+            println!("✅ git-ai added to {}", hook_path.display());
+            Ok(outcome)
+        }
+        HookManager::Lefthook(config_path) => {
+            let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+            let script_path = root
+                .join(".lefthook")
+                .join(kind.file_name())
+                .join(LEFTHOOK_SCRIPT);
+            if let Some(parent) = script_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    crate::error::GitAiError::Other(format!(
+                        "Failed to create lefthook script directory: {}",
+                        e
+                    ))
+                })?;
+            }
+            write_executable(&script_path, &generate_hook_script_bash_for(kind, options))?;
+
+            let config = read_optional(config_path)?;
+            let entry = lefthook_entry(kind);
+            if find_managed_block(&config, kind).is_some() {
+                println!(
+                    "✅ Git hook already registered in {}",
+                    config_path.display()
+                );
+            } else if has_top_level_key(&config, kind.file_name()) {
+                println!(
+                    "⚠️  {} already defines '{}'. Add this under it manually:",
+                    config_path.display(),
+                    kind.file_name()
+                );
+                println!(
+                    "  scripts:\n    \"{}\":\n      runner: bash",
+                    LEFTHOOK_SCRIPT
+                );
+            } else {
+                let updated = append_managed_block(&config, kind, &entry);
+                fs::write(config_path, updated).map_err(|e| {
+                    crate::error::GitAiError::Other(format!(
+                        "Failed to update lefthook config: {}",
+                        e
+                    ))
+                })?;
+                println!("✅ git-ai registered in {}", config_path.display());
+            }
+            println!("   Script written to {}", script_path.display());
+            println!("   Run 'lefthook install' to activate the change");
+            Ok(InstallOutcome::Installed)
         }
-    } else {
-        println!("❌ Git hook is not installed");
-        println!("   Run 'git-ai hook install' to install it");
+    }
+}
+
+fn remove_from_manager(manager: &HookManager, kind: HookKind) -> Result<bool> {
+    match manager {
+        HookManager::Husky(dir) => {
+            let hook_path = dir.join(kind.file_name());
+            let existing = read_optional(&hook_path)?;
+            let Some(stripped) = strip_managed_block(&existing, kind) else {
+                return Ok(false);
+            };
+
+            if stripped.trim().is_empty() {
+                fs::remove_file(&hook_path).map_err(|e| {
+                    crate::error::GitAiError::Other(format!("Failed to remove hook: {}", e))
+                })?;
+            } else {
+                fs::write(&hook_path, stripped).map_err(|e| {
+                    crate::error::GitAiError::Other(format!("Failed to write hook: {}", e))
+                })?;
+            }
+            Ok(true)
+        }
+        HookManager::Lefthook(config_path) => {
+            let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+            let script_path = root
+                .join(".lefthook")
+                .join(kind.file_name())
+                .join(LEFTHOOK_SCRIPT);
+            let script_removed = script_path.exists() && fs::remove_file(&script_path).is_ok();
+
+            let config = read_optional(config_path)?;
+            let config_updated = match strip_managed_block(&config, kind) {
+                Some(stripped) => {
+                    fs::write(config_path, stripped).map_err(|e| {
+                        crate::error::GitAiError::Other(format!(
+                            "Failed to update lefthook config: {}",
+                            e
+                        ))
+                    })?;
+                    true
+                }
+                None => false,
+            };
+
+            Ok(script_removed || config_updated)
+        }
+    }
+}
+
+fn manager_has_git_ai(manager: &HookManager, kind: HookKind) -> bool {
+    match manager {
+        HookManager::Husky(dir) => read_optional(&dir.join(kind.file_name()))
+            .map(|content| find_managed_block(&content, kind).is_some())
+            .unwrap_or(false),
+        HookManager::Lefthook(config_path) => {
+            let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+            root.join(".lefthook")
+                .join(kind.file_name())
+                .join(LEFTHOOK_SCRIPT)
+                .exists()
+        }
+    }
+}
+
+fn read_optional(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).map_err(|e| {
+        crate::error::GitAiError::Other(format!("Failed to read {}: {}", path.display(), e))
+    })
+}
+
+fn write_executable(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)
+        .map_err(|e| crate::error::GitAiError::Other(format!("Failed to write hook: {}", e)))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let perms = fs::Permissions::from_mode(0o755);
+        fs::set_permissions(path, perms).map_err(|e| {
+            crate::error::GitAiError::Other(format!("Failed to make hook executable: {}", e))
+        })?;
     }
 
     Ok(())
 }
 
-fn get_local_hook_path() -> Result<PathBuf> {
-    let git_dir = std::process::Command::new("git")
-        .arg("rev-parse")
-        .arg("--git-dir")
-        .output()
-        .map_err(|e| crate::error::GitAiError::Git(format!("Failed to get git dir: {}", e)))?;
+/// Markers delimiting the git-ai block of `kind` inside files owned by a
+/// hook manager; a lefthook config holds one block per hook kind
+fn block_markers(kind: HookKind) -> (String, String) {
+    (
+        format!("# >>> git-ai {} >>>", kind.file_name()),
+        format!("# <<< git-ai {} <<<", kind.file_name()),
+    )
+}
+
+/// Byte range of the git-ai block of `kind`. A legacy unnamed block is
+/// taken as well, unless it registers another hook kind (a lefthook entry).
+fn find_managed_block(content: &str, kind: HookKind) -> Option<(usize, usize)> {
+    let (start, end) = block_markers(kind);
+    if let Some(range) = block_range(content, &start, &end) {
+        return Some(range);
+    }
+    let (start, end) = block_range(content, LEGACY_BLOCK_START, LEGACY_BLOCK_END)?;
+    let other_kind = HookKind::value_variants()
+        .iter()
+        .filter(|other| **other != kind)
+        .any(|other| has_top_level_key(&content[start..end], other.file_name()));
+    (!other_kind).then_some((start, end))
+}
+
+fn block_range(content: &str, start_marker: &str, end_marker: &str) -> Option<(usize, usize)> {
+    let start = content.find(start_marker)?;
+    let end = content[start..].find(end_marker)? + start + end_marker.len();
+    Some((start, end))
+}
 
-    if !git_dir.status.success() {
-        return Err(crate::error::GitAiError::NotInGitRepo);
+/// Append `body` wrapped in the markers of `kind`, keeping existing content intact
+fn append_managed_block(existing: &str, kind: HookKind, body: &str) -> String {
+    let (start, end) = block_markers(kind);
+    let mut result = existing.to_string();
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
     }
+    result.push_str(&start);
+    result.push('\n');
+    result.push_str(body.trim_end());
+    result.push('\n');
+    result.push_str(&end);
+    result.push('\n');
+    result
+}
+
+/// Remove the git-ai block of `kind`; returns None when no block is present
+fn strip_managed_block(content: &str, kind: HookKind) -> Option<String> {
+    let (start, end) = find_managed_block(content, kind)?;
+    let end = if content[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+    Some(format!("{}{}", &content[..start], &content[end..]))
+}
+
+/// Swap the body of the git-ai block of `kind` in place (rewriting legacy
+/// markers); None when no block is present
+fn replace_managed_block(content: &str, kind: HookKind, body: &str) -> Option<String> {
+    let (start, end) = find_managed_block(content, kind)?;
+    let (start_marker, end_marker) = block_markers(kind);
+    Some(format!(
+        "{}{}\n{}\n{}{}",
+        &content[..start],
+        start_marker,
+        body.trim_end(),
+        end_marker,
+        &content[end..]
+    ))
+}
+
+fn has_top_level_key(yaml: &str, key: &str) -> bool {
+    yaml.lines()
+        .any(|line| line.trim_end() == format!("{}:", key))
+}
+
+/// Hook body for husky: the bash hook run in a subshell so its `exit`
+/// does not end the rest of the user's hook file
+fn managed_hook_body(kind: HookKind, options: &HookOptions) -> String {
+    let script = generate_hook_script_bash_for(kind, options);
+    let body: Vec<&str> = script.lines().filter(|l| !l.starts_with("#!")).collect();
+    format!("(\n{}\n)", body.join("\n").trim())
+}
 
-    let git_dir_str = String::from_utf8_lossy(&git_dir.stdout).trim().to_string();
-    Ok(PathBuf::from(git_dir_str)
-        .join("hooks")
-        .join("prepare-commit-msg"))
+fn lefthook_entry(kind: HookKind) -> String {
+    format!(
+        "{}:\n  scripts:\n    \"{}\":\n      runner: bash",
+        kind.file_name(),
+        LEFTHOOK_SCRIPT
+    )
 }
 
-fn get_global_hook_path() -> Result<PathBuf> {
-    // Get git config core.hooksPath
-    let output = std::process::Command::new("git")
-        .arg("config")
-        .arg("--global")
-        .arg("core.hooksPath")
-        .output()
-        .map_err(|e| crate::error::GitAiError::Git(format!("Failed to get hooks path: {}", e)))?;
+/// Hook file for `kind`, plus the `.ps1` script PowerShell hooks hand over to
+fn generate_hook_files(kind: HookKind, options: &HookOptions) -> (String, Option<String>) {
+    let (main, companion) = generate_unchained_hook_files(kind, options);
+    // Bash hooks and PowerShell launchers are sh scripts: chain after the shebang
+    match main.split_once('\n') {
+        Some((shebang, rest)) if options.chain && shebang.starts_with("#!") => (
+            format!(
+                "{}\n{}{}",
+                shebang,
+                chain_prelude(kind, options.previous_hooks_path.as_deref()),
+                rest
+            ),
+            companion,
+        ),
+        _ => (main, companion),
+    }
+}
+
+/// Shell lines a global hook starts with: the same hook from the repository's
+/// own hooks directory and from the replaced core.hooksPath runs first
+/// (git-ai's own hooks are skipped), and a failing one stops the hook.
+/// pre-push input is read once and replayed to each hook.
+fn chain_prelude(kind: HookKind, previous_hooks_path: Option<&str>) -> String {
+    let name = kind.file_name();
+    let mut hooks = vec![format!(
+        "\"$(git rev-parse --git-common-dir)/hooks/{}\"",
+        name
+    )];
+    if let Some(previous) = previous_hooks_path {
+        hooks.push(shell_quote(&format!(
+            "{}/{}",
+            previous.trim_end_matches(['/', '\\']),
+            name
+        )));
+    }
+    let (read_stdin, feed_stdin, restore_stdin) = match kind {
+        HookKind::PrePush => (
+            "stdin=$(cat)\n",
+            "printf '%s\\n' \"$stdin\" | ",
+            "exec <<GIT_AI_STDIN\n$stdin\nGIT_AI_STDIN\n",
+        ),
+        HookKind::PrepareCommitMsg | HookKind::PreCommit | HookKind::PostCommit => ("", "", ""),
+    };
+    format!(
+        r#"
+# Hooks that git-ai's global core.hooksPath would otherwise hide run first
+{read_stdin}for hook in {hooks}; do
+    if [ -x "$hook" ] && ! grep -q git-ai-cli "$hook"; then
+        {feed_stdin}"$hook" "$@" || exit $?
+    fi
+done
+{restore_stdin}"#,
+        hooks = hooks.join(" "),
+    )
+}
 
-    if output.status.success() {
-        let hooks_path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if !hooks_path.is_empty() {
-            return Ok(PathBuf::from(hooks_path).join("prepare-commit-msg"));
+fn generate_unchained_hook_files(
+    kind: HookKind,
+    options: &HookOptions,
+) -> (String, Option<String>) {
+    match (kind, HookShell::resolve(options.shell)) {
+        (HookKind::PrepareCommitMsg, HookShell::Cmd) => {
+            (generate_hook_script_windows(options), None)
+        }
+        (HookKind::PrePush, HookShell::Cmd) => (generate_pre_push_script_windows(options), None),
+        (HookKind::PreCommit, HookShell::Cmd) => {
+            (generate_pre_commit_script_windows(options), None)
+        }
+        (HookKind::PostCommit, HookShell::Cmd) => {
+            (generate_post_commit_script_windows(options), None)
         }
+        (_, HookShell::Powershell) => (
+            generate_powershell_launcher(kind),
+            Some(generate_powershell_script(kind, options)),
+        ),
+        (_, HookShell::Bash) => (generate_hook_script_bash_for(kind, options), None),
     }
+}
+
+/// `options` completed with what depends on where the hook is written: the
+/// binary it runs and, for global installs, the hooks it chains
+fn with_placement(options: HookOptions, hook_path: &Path, global: bool) -> Result<HookOptions> {
+    Ok(HookOptions {
+        binary: install_binary(hook_path),
+        chain: global,
+        previous_hooks_path: if global {
+            GitManager::get_global_config_path(PREVIOUS_HOOKS_PATH_KEY)?
+        } else {
+            None
+        },
+        ..options
+    })
+}
+
+/// git-ai binary baked into a hook written to `hook_path`: this executable,
+/// so hooks still run from GUI clients without git-ai on PATH. Hook
+/// directories inside the worktree (`core.hooksPath = .githooks`) are usually
+/// committed and shared, so they keep the PATH lookup.
+fn install_binary(hook_path: &Path) -> Option<String> {
+    let in_worktree = GitManager::get_repo_root().is_ok_and(|root| hook_path.starts_with(root))
+        && !GitManager::get_git_common_dir().is_ok_and(|dir| hook_path.starts_with(dir));
+    if in_worktree {
+        return None;
+    }
+    std::env::current_exe()
+        .ok()
+        .map(|exe| exe.display().to_string())
+}
 
-    // Fallback to ~/.config/git-ai-cli/hooks
+fn generate_hook_script_bash_for(kind: HookKind, options: &HookOptions) -> String {
+    match kind {
+        HookKind::PrepareCommitMsg => generate_hook_script_bash(options),
+        HookKind::PrePush => generate_pre_push_script_bash(options),
+        HookKind::PreCommit => generate_pre_commit_script_bash(options),
+        HookKind::PostCommit => generate_post_commit_script_bash(options),
+    }
+}
+
+fn get_local_hook_path(kind: HookKind) -> Result<PathBuf> {
+    let hooks_path = match GitManager::get_config_path("core.hooksPath")? {
+        Some(hooks_path) => {
+            let hooks_dir = PathBuf::from(hooks_path);
+            Some(if hooks_dir.is_relative() {
+                GitManager::get_repo_root()?.join(hooks_dir)
+            } else {
+                hooks_dir
+            })
+        }
+        None => None,
+    };
+    let hooks_dir = local_hooks_dir(
+        hooks_path,
+        global_hooks_dir().ok().as_deref(),
+        GitManager::get_git_common_dir()?,
+    );
+    Ok(hooks_dir.join(kind.file_name()))
+}
+
+/// Where a repository's own hooks live: core.hooksPath (local, then global)
+/// replaces $GIT_DIR/hooks entirely, except git-ai's global directory, whose
+/// hooks run the repository's `hooks` first; local installs, removals and
+/// status checks must never touch those shared hooks. Linked worktrees have
+/// a `.git` file and their own git dir, but hooks always live in the common
+/// dir shared with the main checkout.
+fn local_hooks_dir(
+    hooks_path: Option<PathBuf>,
+    global_dir: Option<&Path>,
+    common_dir: PathBuf,
+) -> PathBuf {
+    match hooks_path {
+        Some(dir) if global_dir != Some(dir.as_path()) => dir,
+        _ => common_dir.join("hooks"),
+    }
+}
+
+fn get_global_hook_path(kind: HookKind) -> Result<PathBuf> {
+    let dir = match GitManager::get_global_config_path("core.hooksPath")? {
+        Some(hooks_path) => PathBuf::from(hooks_path),
+        None => global_hooks_dir()?,
+    };
+    Ok(dir.join(kind.file_name()))
+}
+
+/// `~/.config/git-ai-cli/hooks`, where `hook install --global` writes
+fn global_hooks_dir() -> Result<PathBuf> {
     let config_dir = dirs::config_dir().ok_or_else(|| {
         crate::error::GitAiError::Config("Cannot determine config directory".to_string())
     })?;
-    Ok(config_dir
-        .join("git-ai-cli")
-        .join("hooks")
-        .join("prepare-commit-msg"))
+    Ok(config_dir.join("git-ai-cli").join("hooks"))
 }
 
-fn generate_hook_script_bash() -> String {
+/// Point the global core.hooksPath at git-ai's hooks directory (after
+/// confirmation, unless `yes`), remembering the previous value so its hooks
+/// keep running and `hook remove --global` can restore it
+fn take_over_global_hooks_path(yes: bool) -> Result<PathBuf> {
+    let dir = global_hooks_dir()?;
+    let current = GitManager::get_global_config_path("core.hooksPath")?;
+    if current
+        .as_deref()
+        .is_some_and(|path| Path::new(path) == dir)
+    {
+        return Ok(dir);
+    }
+
+    // It changes every repository, so scripts must opt in with --yes
+    if !yes {
+        ci::require_input("Set the global core.hooksPath", "pass --yes")?;
+    }
+    let confirmed = yes
+        || Confirm::new()
+            .with_prompt(format!(
+                "Set the global core.hooksPath to {}? Git then runs hooks from there in every repository (their own hooks keep running)",
+                dir.display()
+            ))
+            .default(true)
+            .interact()
+            .map_err(|e| crate::error::GitAiError::prompt("Confirmation", e))?;
+    if !confirmed {
+        println!("❌ Global hook not installed: git only runs hooks from core.hooksPath");
+        return Err(crate::error::GitAiError::UserCancelled);
+    }
+
+    match &current {
+        Some(previous) => GitManager::set_global_config(PREVIOUS_HOOKS_PATH_KEY, previous)?,
+        None => GitManager::unset_global_config(PREVIOUS_HOOKS_PATH_KEY)?,
+    }
+    GitManager::set_global_config("core.hooksPath", &dir.display().to_string())?;
+    println!("🔗 Global core.hooksPath set to {}", dir.display());
+    if let Some(previous) = current {
+        println!("   Hooks in {} run before git-ai's", previous);
+    }
+    Ok(dir)
+}
+
+/// Undo [`take_over_global_hooks_path`] once no git-ai hook is left in its
+/// directory
+fn release_global_hooks_path() -> Result<()> {
+    let dir = global_hooks_dir()?;
+    if HookKind::value_variants()
+        .iter()
+        .any(|kind| dir.join(kind.file_name()).exists())
+    {
+        return Ok(());
+    }
+    let current = GitManager::get_global_config_path("core.hooksPath")?;
+    if current.is_none_or(|path| Path::new(&path) != dir) {
+        return Ok(());
+    }
+
+    match GitManager::get_global_config_path(PREVIOUS_HOOKS_PATH_KEY)? {
+        Some(previous) => {
+            GitManager::set_global_config("core.hooksPath", &previous)?;
+            println!("🔗 Global core.hooksPath restored to {}", previous);
+        }
+        None => {
+            GitManager::unset_global_config("core.hooksPath")?;
+            println!("🔗 Global core.hooksPath unset");
+        }
+    }
+    GitManager::unset_global_config(PREVIOUS_HOOKS_PATH_KEY)
+}
+
+fn generate_hook_script_bash(options: &HookOptions) -> String {
     r#"#!/bin/bash
 # Git hook for git-ai-cli
 # This hook automatically generates commit messages using AI
@@ -193,94 +1470,585 @@ if [ "$GIT_AI_RUNNING" = "1" ]; then
     exit 0
 fi
 
-# Skip for merge commits
-if grep -q "^Merge " "$1"; then
-    exit 0
-fi
+# Generate message: git-ai reads the commit file for template context and
+# skips merge, squash, amend and -m commits by itself
+export GIT_AI_RUNNING=1
+__GIT_AI_ENV____GIT_AI_BIN__ msg --quiet__GIT_AI_ARGS__ --commit-file "$1" --source "$2" >/dev/null 2>&1
+
+exit 0
+"#
+    .replace("__GIT_AI_ENV__", &options.env_block_bash())
+    .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Bash))
+    .replace("__GIT_AI_ARGS__", &options.msg_args())
+}
+
+fn generate_hook_script_windows(options: &HookOptions) -> String {
+    // Using concat! to avoid raw string issues with special characters
+    [
+        "@echo off\r\n",
+        "REM Git hook for git-ai-cli\r\n",
+        "REM This hook automatically generates commit messages using AI\r\n",
+        "\r\n",
+        "REM Skip if disabled\r\n",
+        "if \"%GIT_AI_DISABLED%\"==\"1\" exit /b 0\r\n",
+        "\r\n",
+        "REM Skip if already running (recursion guard)\r\n",
+        "if \"%GIT_AI_RUNNING%\"==\"1\" exit /b 0\r\n",
+        "\r\n",
+        "REM Generate message: git-ai reads the commit file for template context and\r\n",
+        "REM skips merge, squash, amend and -m commits by itself\r\n",
+        "set GIT_AI_RUNNING=1\r\n",
+        &options.env_block_windows(),
+        &format!(
+            "{} msg --quiet{} --commit-file \"%~1\" --source \"%~2\" >nul 2>&1\r\n",
+            options.binary_for(HookShell::Cmd),
+            options.msg_args()
+        ),
+        "\r\n",
+        "exit /b 0\r\n",
+    ]
+    .concat()
+    .to_string()
+}
+
+/// `<hook>.ps1` next to the hook file
+fn powershell_script_path(hook_path: &Path) -> PathBuf {
+    let mut path = hook_path.as_os_str().to_owned();
+    path.push(".ps1");
+    PathBuf::from(path)
+}
+
+/// Newest backup `run_install` made of a foreign hook at `hook_path`
+/// (`<hook>.original` or `<hook>.original.N`)
+fn latest_backup(hook_path: &Path) -> Option<PathBuf> {
+    let dir = hook_path.parent()?;
+    let original = format!("{}.original", hook_path.file_name()?.to_string_lossy());
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name == original
+                || name
+                    .strip_prefix(&format!("{}.", original))
+                    .is_some_and(|n| n.parse::<u32>().is_ok())
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Remove the `.ps1` script next to a git-ai hook. A committed hooks
+/// directory (`core.hooksPath = .githooks`) may hold a team script of that
+/// name, so only one git-ai wrote is deleted.
+fn remove_powershell_script(hook_path: &Path) -> Result<()> {
+    let path = powershell_script_path(hook_path);
+    if path.exists() && is_git_ai_hook(&read_optional(&path)?) {
+        fs::remove_file(&path).map_err(|e| {
+            crate::error::GitAiError::Other(format!("Failed to remove hook: {}", e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Git for Windows runs hooks through its bundled `sh`, so the hook itself
+/// stays a shell script that hands over to PowerShell (pwsh when installed)
+fn generate_powershell_launcher(kind: HookKind) -> String {
+    format!(
+        r#"#!/bin/sh
+# Git hook for git-ai-cli: runs {name}.ps1 with PowerShell
+ps=powershell.exe
+command -v pwsh >/dev/null 2>&1 && ps=pwsh
+exec "$ps" -NoProfile -NonInteractive -ExecutionPolicy Bypass -File "$(dirname "$0")/{name}.ps1" "$@"
+"#,
+        name = kind.file_name()
+    )
+}
+
+fn generate_powershell_script(kind: HookKind, options: &HookOptions) -> String {
+    match kind {
+        HookKind::PrepareCommitMsg => {
+            let num = match options.num {
+                Some(num) => format!(", '--num', '{}'", num),
+                None => String::new(),
+            };
+            r#"# Git hook for git-ai-cli
+# This hook automatically generates commit messages using AI
+param([string]$CommitFile, [string]$Source)
+
+# Skip if disabled
+if ($env:GIT_AI_DISABLED -eq '1') { exit 0 }
+
+# Skip if already running (recursion guard)
+if ($env:GIT_AI_RUNNING -eq '1') { exit 0 }
+
+# Generate message: git-ai reads the commit file for template context and
+# skips merge, squash, amend and -m commits by itself. Empty arguments are
+# dropped when calling native commands, so --source is only passed when set.
+$env:GIT_AI_RUNNING = '1'
+__GIT_AI_ENV__$msgArgs = @('msg', '--quiet'__GIT_AI_ARGS__, '--commit-file', $CommitFile)
+if ($Source) { $msgArgs += @('--source', $Source) }
+& __GIT_AI_BIN__ @msgArgs *> $null
+
+exit 0
+"#
+            .replace("__GIT_AI_ENV__", &options.env_block_powershell())
+            .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Powershell))
+            .replace("__GIT_AI_ARGS__", &num)
+        }
+        HookKind::PrePush => r#"# Git pre-push hook for git-ai-cli
+# This hook summarizes outgoing commits and blocks risky pushes
+param([string]$Remote, [string]$Url)
+
+# Skip if disabled
+if ($env:GIT_AI_DISABLED -eq '1') { exit 0 }
 
-# Skip for squash commits
-if grep -q "^# This is a combination of" "$1"; then
+# Skip if git-ai was uninstalled or moved
+if (-not (Get-Command __GIT_AI_BIN__ -ErrorAction SilentlyContinue)) { exit 0 }
+
+# Git passes "<local ref> <local sha> <remote ref> <remote sha>" lines on stdin
+__GIT_AI_ENV__$input | & __GIT_AI_BIN__ push-summary --remote $Remote --stdin
+exit $LASTEXITCODE
+"#
+        .replace("__GIT_AI_ENV__", &options.env_block_powershell())
+        .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Powershell)),
+        HookKind::PreCommit => r#"# Git pre-commit hook for git-ai-cli
+# This hook reviews staged changes and blocks commits at the configured risk level
+
+# Skip if disabled
+if ($env:GIT_AI_DISABLED -eq '1') { exit 0 }
+
+# Skip if git-ai itself is committing
+if ($env:GIT_AI_RUNNING -eq '1') { exit 0 }
+
+# Skip if git-ai was uninstalled or moved
+if (-not (Get-Command __GIT_AI_BIN__ -ErrorAction SilentlyContinue)) { exit 0 }
+
+# The threshold is preCommitFailOn in .git-ai.json (default: high)
+__GIT_AI_ENV__& __GIT_AI_BIN__ review --pre-commit
+exit $LASTEXITCODE
+"#
+        .replace("__GIT_AI_ENV__", &options.env_block_powershell())
+        .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Powershell)),
+        HookKind::PostCommit => r#"# Git post-commit hook for git-ai-cli
+# This hook attaches audit notes to commits whose message git-ai generated
+
+# Skip if disabled
+if ($env:GIT_AI_DISABLED -eq '1') { exit 0 }
+
+# The commit already exists, so a failure here only loses the note
+__GIT_AI_ENV__& __GIT_AI_BIN__ audit --attach-pending
+exit 0
+"#
+        .replace("__GIT_AI_ENV__", &options.env_block_powershell())
+        .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Powershell)),
+    }
+}
+
+fn generate_pre_push_script_bash(options: &HookOptions) -> String {
+    r#"#!/bin/bash
+# Git pre-push hook for git-ai-cli
+# This hook summarizes outgoing commits and blocks risky pushes
+
+# Skip if disabled
+if [ "$GIT_AI_DISABLED" = "1" ]; then
     exit 0
 fi
 
-# Skip for amend commits
-if grep -q "^# Please enter the commit message for your changes" "$1"; then
+# Skip if git-ai was uninstalled or moved
+command -v __GIT_AI_BIN__ >/dev/null 2>&1 || exit 0
+
+# Git passes "<local ref> <local sha> <remote ref> <remote sha>" lines on stdin
+__GIT_AI_ENV____GIT_AI_BIN__ push-summary --remote "$1" --stdin
+"#
+    .replace("__GIT_AI_ENV__", &options.env_block_bash())
+    .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Bash))
+}
+
+fn generate_pre_push_script_windows(options: &HookOptions) -> String {
+    [
+        "@echo off\r\n",
+        "REM Git pre-push hook for git-ai-cli\r\n",
+        "REM This hook summarizes outgoing commits and blocks risky pushes\r\n",
+        "\r\n",
+        "REM Skip if disabled\r\n",
+        "if \"%GIT_AI_DISABLED%\"==\"1\" exit /b 0\r\n",
+        "\r\n",
+        "REM Skip if git-ai was uninstalled or moved\r\n",
+        &options.binary_guard_cmd(),
+        "\r\n",
+        &options.env_block_windows(),
+        &format!(
+            "{} push-summary --remote \"%~1\" --stdin\r\n",
+            options.binary_for(HookShell::Cmd)
+        ),
+        "exit /b %errorlevel%\r\n",
+    ]
+    .concat()
+    .to_string()
+}
+
+fn generate_pre_commit_script_bash(options: &HookOptions) -> String {
+    r#"#!/bin/bash
+# Git pre-commit hook for git-ai-cli
+# This hook reviews staged changes and blocks commits at the configured risk level
+
+# Skip if disabled
+if [ "$GIT_AI_DISABLED" = "1" ]; then
     exit 0
 fi
 
-# Skip if message already exists
-if [ -s "$1" ] && ! grep -q "^# Please enter the commit message" "$1"; then
+# Skip if git-ai itself is committing
+if [ "$GIT_AI_RUNNING" = "1" ]; then
     exit 0
 fi
 
-# Generate message
-export GIT_AI_RUNNING=1
-MESSAGE=$(git-ai msg --quiet 2>/dev/null)
+# Skip if git-ai was uninstalled or moved
+command -v __GIT_AI_BIN__ >/dev/null 2>&1 || exit 0
 
-if [ -n "$MESSAGE" ]; then
-    # Prepend generated message to commit file
-    {
-        echo "$MESSAGE"
-        echo ""
-        cat "$1"
-    } > "$1.tmp"
-    mv "$1.tmp" "$1"
-fi
-
-exit 0
+# The threshold is preCommitFailOn in .git-ai.json (default: high)
+__GIT_AI_ENV____GIT_AI_BIN__ review --pre-commit
 "#
-    .to_string()
+    .replace("__GIT_AI_ENV__", &options.env_block_bash())
+    .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Bash))
 }
 
-fn generate_hook_script_windows() -> String {
-    // Using concat! to avoid raw string issues with special characters
+fn generate_pre_commit_script_windows(options: &HookOptions) -> String {
     [
         "@echo off\r\n",
-        "REM Git hook for git-ai-cli\r\n",
-        "REM This hook automatically generates commit messages using AI\r\n",
+        "REM Git pre-commit hook for git-ai-cli\r\n",
+        "REM This hook reviews staged changes and blocks commits at the configured risk level\r\n",
         "\r\n",
         "REM Skip if disabled\r\n",
         "if \"%GIT_AI_DISABLED%\"==\"1\" exit /b 0\r\n",
         "\r\n",
-        "REM Skip if already running (recursion guard)\r\n",
+        "REM Skip if git-ai itself is committing\r\n",
         "if \"%GIT_AI_RUNNING%\"==\"1\" exit /b 0\r\n",
         "\r\n",
-        "REM Skip for merge commits\r\n",
-        "findstr /B /C:\"Merge \" \"%~1\" >nul 2>&1\r\n",
-        "if %errorlevel%==0 exit /b 0\r\n",
+        "REM Skip if git-ai was uninstalled or moved\r\n",
+        &options.binary_guard_cmd(),
         "\r\n",
-        "REM Skip for squash commits\r\n",
-        "findstr /B \"# This is a combination\" \"%~1\" >nul 2>&1\r\n",
-        "if %errorlevel%==0 exit /b 0\r\n",
-        "\r\n",
-        "REM Skip for amend commits\r\n",
-        "findstr /B \"# Please enter the commit message\" \"%~1\" >nul 2>&1\r\n",
-        "if %errorlevel%==0 exit /b 0\r\n",
-        "\r\n",
-        "REM Check if message already exists\r\n",
-        "for %%A in (\"%~1\") do set size=%%~zA\r\n",
-        "if %size% gtr 0 (\r\n",
-        "    findstr /B \"# Please enter the commit message\" \"%~1\" >nul 2>&1\r\n",
-        "    if %errorlevel% neq 0 exit /b 0\r\n",
-        ")\r\n",
-        "\r\n",
-        "REM Generate message\r\n",
-        "set GIT_AI_RUNNING=1\r\n",
-        "set TMPMSG=%~1.git-ai-msg.tmp\r\n",
-        "git-ai msg --quiet > \"%TMPMSG%\" 2>nul\r\n",
-        "for %%A in (\"%TMPMSG%\") do set msg_size=%%~zA\r\n",
+        "REM The threshold is preCommitFailOn in .git-ai.json (default: high)\r\n",
+        &options.env_block_windows(),
+        &format!(
+            "{} review --pre-commit\r\n",
+            options.binary_for(HookShell::Cmd)
+        ),
+        "exit /b %errorlevel%\r\n",
+    ]
+    .concat()
+    .to_string()
+}
+
+fn generate_post_commit_script_bash(options: &HookOptions) -> String {
+    r#"#!/bin/bash
+# Git post-commit hook for git-ai-cli
+# This hook attaches audit notes to commits whose message git-ai generated
+
+# Skip if disabled
+if [ "$GIT_AI_DISABLED" = "1" ]; then
+    exit 0
+fi
+
+# The commit already exists, so a failure here only loses the note
+__GIT_AI_ENV____GIT_AI_BIN__ audit --attach-pending || true
+"#
+    .replace("__GIT_AI_ENV__", &options.env_block_bash())
+    .replace("__GIT_AI_BIN__", &options.binary_for(HookShell::Bash))
+}
+
+fn generate_post_commit_script_windows(options: &HookOptions) -> String {
+    [
+        "@echo off\r\n",
+        "REM Git post-commit hook for git-ai-cli\r\n",
+        "REM This hook attaches audit notes to commits whose message git-ai generated\r\n",
         "\r\n",
-        "if %msg_size% gtr 0 (\r\n",
-        "    REM Prepend generated message to commit file\r\n",
-        "    type \"%TMPMSG%\" > \"%~1.tmp\"\r\n",
-        "    echo.>> \"%~1.tmp\"\r\n",
-        "    type \"%~1\" >> \"%~1.tmp\"\r\n",
-        "    move /y \"%~1.tmp\" \"%~1\" >nul\r\n",
-        ")\r\n",
-        "del /q \"%TMPMSG%\" >nul 2>&1\r\n",
+        "REM Skip if disabled\r\n",
+        "if \"%GIT_AI_DISABLED%\"==\"1\" exit /b 0\r\n",
         "\r\n",
+        "REM The commit already exists, so a failure here only loses the note\r\n",
+        &options.env_block_windows(),
+        &format!(
+            "{} audit --attach-pending\r\n",
+            options.binary_for(HookShell::Cmd)
+        ),
         "exit /b 0\r\n",
     ]
     .concat()
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn managed_block_round_trip() {
+        let original = "npx lint-staged\n";
+        let with_block = append_managed_block(original, HookKind::PrepareCommitMsg, "git-ai msg");
+        assert!(with_block.starts_with(original));
+        assert!(with_block.contains("# >>> git-ai prepare-commit-msg >>>"));
+
+        let stripped =
+            strip_managed_block(&with_block, HookKind::PrepareCommitMsg).expect("block present");
+        assert_eq!(stripped, original);
+    }
+
+    #[test]
+    fn managed_blocks_are_kept_per_hook_kind() {
+        let config = append_managed_block(
+            "",
+            HookKind::PrepareCommitMsg,
+            &lefthook_entry(HookKind::PrepareCommitMsg),
+        );
+        assert!(find_managed_block(&config, HookKind::PrePush).is_none());
+        let config = append_managed_block(
+            &config,
+            HookKind::PrePush,
+            &lefthook_entry(HookKind::PrePush),
+        );
+
+        let stripped = strip_managed_block(&config, HookKind::PrePush).expect("pre-push block");
+        assert!(find_managed_block(&stripped, HookKind::PrepareCommitMsg).is_some());
+        assert!(find_managed_block(&stripped, HookKind::PrePush).is_none());
+
+        // An unnamed block from an earlier version belongs to the kind it registers
+        let legacy = format!(
+            "{}\n{}\n{}\n",
+            LEGACY_BLOCK_START,
+            lefthook_entry(HookKind::PrepareCommitMsg),
+            LEGACY_BLOCK_END
+        );
+        assert!(strip_managed_block(&legacy, HookKind::PrePush).is_none());
+        assert_eq!(
+            strip_managed_block(&legacy, HookKind::PrepareCommitMsg).as_deref(),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn strip_without_block_is_none() {
+        assert!(strip_managed_block("npx lint-staged\n", HookKind::PrePush).is_none());
+    }
+
+    #[test]
+    fn hook_options_are_baked_into_script() {
+        let options = HookOptions {
+            locale: Some("zh".to_string()),
+            num: Some(1),
+            timeout: Some(20),
+            model: Some("it's-cheap".to_string()),
+            shell: None,
+            ..Default::default()
+        };
+        let script = generate_hook_script_bash(&options);
+        assert!(script.contains("export GIT_AI_LOCALE='zh'\n"));
+        assert!(script.contains("export GIT_AI_TIMEOUT='20'\n"));
+        assert!(script.contains("export GIT_AI_MODEL='it'\\''s-cheap'\n"));
+        assert!(script.contains("git-ai msg --quiet --num 1 --commit-file \"$1\""));
+        assert!(!script.contains("__GIT_AI_"));
+    }
+
+    #[test]
+    fn default_hook_script_has_no_overrides() {
+        let script = generate_hook_script_bash(&HookOptions::default());
+        assert!(script.contains("git-ai msg --quiet --commit-file \"$1\" --source \"$2\""));
+        assert!(!script.contains("__GIT_AI_"));
+    }
+
+    #[test]
+    fn powershell_hook_quotes_overrides_and_skips_empty_source() {
+        let options = HookOptions {
+            num: Some(2),
+            model: Some("it's-cheap".to_string()),
+            ..Default::default()
+        };
+        let script = generate_powershell_script(HookKind::PrepareCommitMsg, &options);
+        assert!(script.contains("$env:GIT_AI_MODEL = 'it''s-cheap'\n"));
+        assert!(script.contains("@('msg', '--quiet', '--num', '2', '--commit-file', $CommitFile)"));
+        assert!(script.contains("if ($Source) { $msgArgs += @('--source', $Source) }"));
+        assert!(!script.contains("__GIT_AI_"));
+
+        let launcher = generate_powershell_launcher(HookKind::PrepareCommitMsg);
+        assert!(launcher.starts_with("#!/bin/sh\n"));
+        assert!(launcher.contains("/prepare-commit-msg.ps1\" \"$@\""));
+    }
+
+    #[test]
+    fn installed_options_and_binary_are_read_back() {
+        for shell in [HookShell::Bash, HookShell::Cmd, HookShell::Powershell] {
+            for kind in [
+                HookKind::PrepareCommitMsg,
+                HookKind::PrePush,
+                HookKind::PreCommit,
+                HookKind::PostCommit,
+            ] {
+                let options = HookOptions {
+                    locale: Some("zh".to_string()),
+                    num: (kind == HookKind::PrepareCommitMsg).then_some(2),
+                    timeout: Some(20),
+                    model: Some("it's-cheap&50%^".to_string()),
+                    shell: Some(shell),
+                    binary: Some("/opt/my tools/git-ai".to_string()),
+                    ..Default::default()
+                };
+                let (main, companion) = generate_hook_files(kind, &options);
+                let script = companion.unwrap_or_else(|| main.clone());
+                let parsed = HookOptions::from_script(&main, &script);
+                assert_eq!(
+                    generate_hook_files(
+                        kind,
+                        &HookOptions {
+                            binary: options.binary.clone(),
+                            ..parsed
+                        }
+                    ),
+                    generate_hook_files(kind, &options),
+                    "{:?} {:?}",
+                    shell,
+                    kind
+                );
+                assert_eq!(referenced_binary(&script), options.binary);
+                assert!(is_git_ai_hook(&main));
+            }
+        }
+        let script = generate_hook_script_bash(&HookOptions::default());
+        assert_eq!(referenced_binary(&script), None);
+        assert!(!is_git_ai_hook("#!/bin/sh\nnpx git-ai msg --quiet\n"));
+
+        let script = generate_hook_script_windows(&HookOptions {
+            model: Some("it's-cheap&50%^".to_string()),
+            ..Default::default()
+        });
+        assert!(script.contains("set GIT_AI_MODEL=it's-cheap^&50%%^^\r\n"));
+    }
+
+    #[test]
+    fn hooks_skip_without_binary_or_inside_git_ai() {
+        let options = HookOptions {
+            binary: Some("/opt/my tools/git-ai".to_string()),
+            ..Default::default()
+        };
+        let script = generate_pre_commit_script_bash(&options);
+        assert!(script.contains("if [ \"$GIT_AI_RUNNING\" = \"1\" ]; then"));
+        assert!(script.contains("command -v '/opt/my tools/git-ai' >/dev/null 2>&1 || exit 0\n"));
+
+        let script = generate_pre_commit_script_windows(&options);
+        assert!(script.contains("if not exist \"/opt/my tools/git-ai\" exit /b 0\r\n"));
+        let script = generate_pre_commit_script_windows(&HookOptions::default());
+        assert!(script.contains("where git-ai >nul 2>&1 || exit /b 0\r\n"));
+
+        let script = generate_pre_push_script_bash(&options);
+        assert!(script.contains("command -v '/opt/my tools/git-ai' >/dev/null 2>&1 || exit 0\n"));
+        let script = generate_powershell_script(HookKind::PrePush, &options);
+        assert!(script.contains("if (-not (Get-Command '/opt/my tools/git-ai' -ErrorAction"));
+    }
+
+    #[test]
+    fn global_hooks_chain_the_hooks_they_replace() {
+        let options = HookOptions {
+            chain: true,
+            previous_hooks_path: Some("/home/me/.githooks/".to_string()),
+            ..Default::default()
+        };
+        let (script, _) = generate_hook_files(HookKind::PrePush, &options);
+        assert!(script.starts_with("#!/bin/bash\n\n# Hooks that git-ai's"));
+        assert!(script.contains(
+            "for hook in \"$(git rev-parse --git-common-dir)/hooks/pre-push\" '/home/me/.githooks/pre-push'; do"
+        ));
+        assert!(script.contains("printf '%s\\n' \"$stdin\" | \"$hook\" \"$@\" || exit $?"));
+        assert!(script.contains("exec <<GIT_AI_STDIN\n$stdin\nGIT_AI_STDIN\n"));
+
+        let (launcher, _) = generate_hook_files(
+            HookKind::PrepareCommitMsg,
+            &HookOptions {
+                shell: Some(HookShell::Powershell),
+                ..options.clone()
+            },
+        );
+        assert!(launcher.starts_with("#!/bin/sh\n"));
+        assert!(launcher.contains("    \"$hook\" \"$@\" || exit $?"));
+        assert!(!launcher.contains("stdin"));
+
+        let (cmd, _) = generate_hook_files(
+            HookKind::PrepareCommitMsg,
+            &HookOptions {
+                shell: Some(HookShell::Cmd),
+                ..options
+            },
+        );
+        assert!(!cmd.contains("for hook in"));
+    }
+
+    #[test]
+    fn local_hooks_ignore_the_global_takeover() {
+        let global = Path::new("/home/me/.config/git-ai-cli/hooks");
+        let common = PathBuf::from("/work/repo/.git");
+        // `hook install --global` set core.hooksPath: local hooks stay in .git/hooks
+        assert_eq!(
+            local_hooks_dir(Some(global.to_path_buf()), Some(global), common.clone()),
+            Path::new("/work/repo/.git/hooks")
+        );
+        assert_eq!(
+            local_hooks_dir(
+                Some(PathBuf::from("/work/repo/.githooks")),
+                Some(global),
+                common.clone()
+            ),
+            Path::new("/work/repo/.githooks")
+        );
+        assert_eq!(
+            local_hooks_dir(None, Some(global), common),
+            Path::new("/work/repo/.git/hooks")
+        );
+    }
+
+    #[test]
+    fn newest_backup_is_restored() {
+        let dir = std::env::temp_dir().join(format!("git-ai-backups-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let hook = dir.join("pre-push");
+        assert_eq!(latest_backup(&hook), None);
+        for name in [
+            "pre-push.original",
+            "pre-push.original.1",
+            "pre-push.originally",
+        ] {
+            fs::write(dir.join(name), name).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(latest_backup(&hook), Some(dir.join("pre-push.original.1")));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn foreign_powershell_scripts_are_kept() {
+        let dir = std::env::temp_dir().join(format!("git-ai-ps1-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let hook = dir.join("pre-commit");
+        fs::write(dir.join("pre-commit.ps1"), "Write-Host 'team lint'\n").unwrap();
+        remove_powershell_script(&hook).unwrap();
+        assert!(dir.join("pre-commit.ps1").exists());
+
+        fs::write(dir.join("pre-commit.ps1"), "# Git hook for git-ai-cli\n").unwrap();
+        remove_powershell_script(&hook).unwrap();
+        assert!(!dir.join("pre-commit.ps1").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn repo_list_skips_blanks_and_comments() {
+        let repos = parse_repo_list("# platform repos\nservices/api\n\n  web # frontend\n");
+        assert_eq!(
+            repos,
+            vec![PathBuf::from("services/api"), PathBuf::from("web")]
+        );
+    }
+
+    #[test]
+    fn detects_top_level_yaml_key() {
+        let yaml = "pre-commit:\n  commands:\n    lint:\n      run: npm run lint\n";
+        assert!(has_top_level_key(yaml, "pre-commit"));
+        assert!(!has_top_level_key(yaml, "prepare-commit-msg"));
+    }
+}
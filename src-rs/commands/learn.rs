@@ -0,0 +1,82 @@
+use crate::error::Result;
+use crate::types::Conventions;
+use crate::utils::{ConfigManager, GitManager};
+
+/// Flags of `git-ai learn`
+#[derive(Debug, Default)]
+pub struct LearnOptions {
+    /// Commits to learn from
+    pub count: usize,
+    /// Print what was learned without saving it
+    pub dry_run: bool,
+}
+
+pub async fn run(options: LearnOptions) -> Result<()> {
+    let LearnOptions { count, dry_run } = options;
+
+    let messages = GitManager::get_commit_messages(count)?;
+    let conventions = Conventions::learn(&messages);
+    if conventions.sample_size == 0 {
+        println!("ℹ️  No commits to learn from yet");
+        return Ok(());
+    }
+
+    println!(
+        "📚 Learned from the last {} commits:",
+        conventions.sample_size
+    );
+    let list = |values: &[String]| {
+        if values.is_empty() {
+            "-".to_string()
+        } else {
+            values.join(", ")
+        }
+    };
+    println!(
+        "   Conventional Commits: {}",
+        if conventions.conventional {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    println!("   Types:    {}", list(&conventions.types));
+    println!("   Scopes:   {}", list(&conventions.scopes));
+    println!(
+        "   Tense:    {}",
+        conventions.tense.as_deref().unwrap_or("mixed")
+    );
+    println!(
+        "   Language: {}",
+        conventions.language.as_deref().unwrap_or("-")
+    );
+    match (&conventions.ticket_format, &conventions.ticket_placement) {
+        (Some(format), Some(placement)) => println!("   Tickets:  {} ({})", format, placement),
+        _ => println!("   Tickets:  -"),
+    }
+    println!();
+
+    if dry_run {
+        println!("{}\n", conventions.prompt_section());
+        println!("ℹ️  Dry run: nothing was saved");
+        return Ok(());
+    }
+
+    // Repository language wins over a personal default, but not over a team setting
+    let mut local = ConfigManager::read_local_config()?;
+    if local.locale.is_empty() {
+        let effective = ConfigManager::get_merged_config()?.locale;
+        if let Some(language) = conventions.language.as_deref().filter(|l| *l != effective) {
+            local.locale = language.to_string();
+            println!("🌐 Set locale = {} to match the history", language);
+        }
+    }
+    local.conventions = Some(conventions);
+    ConfigManager::write_local_config(&local)?;
+
+    println!(
+        "✅ Saved conventions to {}; generated messages now follow them",
+        ConfigManager::get_local_config_path().display()
+    );
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! `git-ai logs`: read the debug logs written with `--debug`.
+
+use crate::error::Result;
+use crate::utils::debug_log;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+/// Print the last `lines` lines of the newest log; with `follow`, keep
+/// printing what is appended until interrupted
+pub async fn run_tail(lines: usize, follow: bool) -> Result<()> {
+    let Some(path) = debug_log::latest_log()? else {
+        println!(
+            "ℹ️  No debug logs in {} yet. Run a command with --debug (or GIT_AI_DEBUG=1) to record one.",
+            debug_log::log_dir()?.display()
+        );
+        return Ok(());
+    };
+
+    eprintln!("📄 {}", path.display());
+    let content = fs::read_to_string(&path)?;
+    let all: Vec<&str> = content.lines().collect();
+    for line in &all[all.len().saturating_sub(lines)..] {
+        println!("{}", line);
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut file = fs::File::open(&path)?;
+    let mut position = file.seek(SeekFrom::End(0))?;
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let length = file.metadata()?.len();
+        if length < position {
+            // Truncated or replaced: start over
+            position = 0;
+        }
+        if length == position {
+            continue;
+        }
+        file.seek(SeekFrom::Start(position))?;
+        let mut appended = String::new();
+        position += file.read_to_string(&mut appended)? as u64;
+        print!("{}", appended);
+    }
+}
@@ -1,5 +1,30 @@
+pub mod audit;
+pub mod bisect_hint;
+pub mod changelog;
 pub mod commit;
 pub mod config;
+pub mod doctor;
+pub mod explain;
 pub mod hook;
+pub mod learn;
+pub mod logs;
 pub mod msg;
+pub mod plugin;
+pub mod pr;
+pub mod push_summary;
+pub mod release;
 pub mod report;
+pub mod review;
+pub mod search;
+pub mod self_update;
+pub mod serve;
+pub mod skills;
+pub mod standup;
+pub mod stash_list;
+pub mod stats;
+pub mod summarize_repo;
+pub mod tag;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod undo;
+pub mod why;
@@ -1,30 +1,312 @@
 use crate::error::Result;
-use crate::types::CommitMessageOutput;
-use crate::utils::ai::{AIClient, PromptTemplates};
-use crate::utils::{ConfigManager, GitManager};
-
-pub async fn run(
-    num: usize,
-    json_output: bool,
-    quiet: bool,
-    locale_override: Option<String>,
-) -> Result<()> {
-    // Get staged files
-    let staged_files = GitManager::get_staged_files()?;
+use crate::types::{split_locale, AIConfig, CommitMessageOutput, FollowUp};
+use crate::utils::agent_lite::AgentLite;
+use crate::utils::ai::{AIClient, PromptContext, PromptTemplates, TokenUsage};
+use crate::utils::audit::{AuditRecord, PendingAudit};
+use crate::utils::codeowners;
+use crate::utils::config::resolve_config_path;
+use crate::utils::conventional::MessageRules;
+use crate::utils::diff_compact;
+use crate::utils::follow_ups;
+use crate::utils::footer;
+use crate::utils::issues;
+use crate::utils::repo_state::RepoState;
+use crate::utils::{truncate_at_char_boundary, workspace, ConfigManager, GitManager};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Deadline for generation, or a review, when running from a git hook
+pub(crate) const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 15;
+
+/// The generated hooks export GIT_AI_RUNNING=1 before calling `git-ai msg`
+fn is_hook_invocation() -> bool {
+    std::env::var("GIT_AI_RUNNING")
+        .map(|v| v == "1")
+        .unwrap_or(false)
+}
+
+/// Commit sources (prepare-commit-msg `$2`) where git already supplies a
+/// message; merges are decided by `CommitFile::should_skip`
+const SKIPPED_SOURCES: &[&str] = &["squash", "commit", "message"];
+
+/// Comment git adds to MERGE_MSG above the files that had conflicts
+const CONFLICTS_MARKER: &str = "# Conflicts:";
+
+/// Line git places above the verbose diff; nothing below it is part of the message
+const SCISSORS_MARKER: &str = "------------------------ >8 ------------------------";
+
+/// Commit message file handed over by the prepare-commit-msg hook
+pub struct CommitFile {
+    pub path: PathBuf,
+    pub source: Option<String>,
+}
+
+impl CommitFile {
+    /// `content` is the file as git prepared it. A merge that stopped on
+    /// conflicts (git lists them under `# Conflicts:`) is concluded by hand,
+    /// so it gets a merge-style message; clean merges keep git's title.
+    fn should_skip(&self, content: &str) -> bool {
+        match self.source.as_deref() {
+            Some("merge") => !content.lines().any(|line| line.trim() == CONFLICTS_MARKER),
+            Some(source) => SKIPPED_SOURCES.contains(&source),
+            None => false,
+        }
+    }
+}
+
+/// Split a commit message file into template text and `#` comment lines
+fn parse_commit_file(content: &str) -> (String, Vec<String>) {
+    let mut template = Vec::new();
+    let mut comments = Vec::new();
+
+    for line in content.lines() {
+        if let Some(comment) = line.strip_prefix('#') {
+            if comment.contains(SCISSORS_MARKER) {
+                break;
+            }
+            let comment = comment.trim();
+            if !comment.is_empty() {
+                comments.push(comment.to_string());
+            }
+        } else {
+            template.push(line);
+        }
+    }
+
+    (template.join("\n").trim().to_string(), comments)
+}
+
+/// Build the commit file content: generated message first, then the
+/// template when the message does not follow it, then git's comments.
+/// Alternative messages are kept as comments so they can be swapped in.
+fn render_commit_file(original: &str, messages: &[String]) -> String {
+    let (template, _) = parse_commit_file(original);
+    let comment_block: String = original
+        .lines()
+        .skip_while(|line| !line.starts_with('#'))
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    let message = messages[0].trim();
+    let mut content = format!("{}\n", message);
+    if !follows_template(message, &template) {
+        content.push_str(&format!("\n{}\n", template));
+    }
+    for (i, alternative) in messages.iter().enumerate().skip(1) {
+        content.push_str(&format!("\n# git-ai alternative {}:\n", i));
+        for line in alternative.trim().lines() {
+            content.push_str(&format!("# {}\n", line));
+        }
+    }
+    if !comment_block.is_empty() {
+        content.push('\n');
+        content.push_str(&comment_block);
+    }
+    content
+}
+
+/// Whether every line of `template` starts a line of `message`, i.e. the
+/// model filled in the template's fields instead of replacing them
+fn follows_template(message: &str, template: &str) -> bool {
+    template
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .all(|field| {
+            message
+                .lines()
+                .any(|line| line.trim_start().starts_with(field))
+        })
+}
+
+/// Flags of `git-ai msg`
+#[derive(Default)]
+pub struct MsgOptions {
+    pub num: Option<usize>,
+    pub json: bool,
+    pub quiet: bool,
+    pub locale: Option<String>,
+    pub commit_file: Option<CommitFile>,
+    pub working_tree: bool,
+    pub style: Option<String>,
+    /// `None` falls back to `ignore_whitespace` from config
+    pub ignore_whitespace: Option<bool>,
+    /// Expand context around the most important files (staged mode only);
+    /// `None` falls back to `expand_context` from config
+    pub expand_context: Option<bool>,
+    /// Only describe changes under these pathspecs (`--path`)
+    pub paths: Vec<String>,
+    /// Extra request from the user, added to the prompt (`git-ai tui` refine)
+    pub instruction: Option<String>,
+}
+
+/// Messages generated by `generate`
+pub struct Generation {
+    /// Empty when a hook invocation ran out of time
+    pub messages: Vec<String>,
+    pub staged_files: Vec<String>,
+    pub truncated: bool,
+    /// Added `TODO`/`FIXME`/`HACK` comments, already listed in the messages
+    pub follow_ups: Vec<FollowUp>,
+    pub config: AIConfig,
+    pub usage: TokenUsage,
+    /// Prompts the messages were generated from, for audit notes
+    pub system_prompt: String,
+    pub user_prompt: String,
+}
+
+pub async fn run(mut options: MsgOptions) -> Result<()> {
+    let commit_file = options.commit_file.take();
+    let commit_file_content = match &commit_file {
+        Some(file) => Some(fs::read_to_string(&file.path)?),
+        None => None,
+    };
+    if let (Some(file), Some(content)) = (&commit_file, &commit_file_content) {
+        // A record from an aborted commit must not land on this one
+        if let Err(e) = PendingAudit::clear() {
+            eprintln!("⚠️  Failed to clear pending audit note: {}", e);
+        }
+        if file.should_skip(content) {
+            return Ok(());
+        }
+    }
+    let json_output = options.json;
+    let quiet = options.quiet;
+
+    let Generation {
+        messages,
+        staged_files,
+        truncated,
+        follow_ups,
+        config,
+        usage,
+        system_prompt,
+        user_prompt,
+    } = generate(options, commit_file_content.as_deref()).await?;
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    // Usage goes to stderr so stdout stays a clean message for scripts
+    if config.show_usage.unwrap_or(false) && !quiet && !json_output {
+        eprintln!("{}", usage.summary());
+    }
+
+    // Output results
+    if let (Some(file), Some(content)) = (&commit_file, &commit_file_content) {
+        // The hook's message is what gets committed, so it carries the footer
+        let mut messages = messages;
+        let footer = footer::join([
+            codeowners::owners_trailer(&config, &staged_files),
+            footer::render(&config),
+        ]);
+        messages[0] = footer::append(&messages[0], footer.as_deref());
+        fs::write(&file.path, render_commit_file(content, &messages))?;
+        if config.audit_notes.unwrap_or(false) {
+            let pending = PendingAudit {
+                record: AuditRecord::new(&config, &system_prompt, &user_prompt, false),
+                message: messages[0].clone(),
+            };
+            if let Err(e) = pending.save() {
+                eprintln!("⚠️  Failed to record audit note: {}", e);
+            }
+        }
+    } else if json_output {
+        let output = CommitMessageOutput {
+            messages,
+            staged_files,
+            truncated,
+            ignored_files: vec![],
+            follow_ups,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !quiet {
+        for (i, msg) in messages.iter().enumerate() {
+            if i > 0 {
+                println!("---END---");
+            }
+            println!("{}", msg);
+        }
+    } else {
+        for msg in messages {
+            println!("{}", msg);
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate messages for the staged (or working-tree) changes without
+/// printing them; `commit_file_content` is the prepare-commit-msg template
+pub async fn generate(
+    options: MsgOptions,
+    commit_file_content: Option<&str>,
+) -> Result<Generation> {
+    let MsgOptions {
+        num,
+        locale: locale_override,
+        working_tree,
+        style,
+        ignore_whitespace,
+        expand_context,
+        paths,
+        instruction,
+        ..
+    } = options;
+
+    let repo_state = RepoState::detect()?;
+    repo_state.ensure_can_generate()?;
+
+    // Get staged files (or every changed tracked file in working-tree mode)
+    let no_changes = || {
+        if working_tree {
+            crate::error::GitAiError::NoChanges
+        } else {
+            crate::error::GitAiError::NoStagedChanges
+        }
+    };
+    let staged_files = if working_tree {
+        GitManager::get_working_tree_files(&paths)?
+    } else {
+        GitManager::get_staged_files(&paths)?
+    };
     if staged_files.is_empty() {
-        return Err(crate::error::GitAiError::NoStagedChanges);
+        return Err(no_changes());
     }
 
     // Get config
     let config = ConfigManager::get_merged_config()?;
+    let num = num.or(config.default_num).unwrap_or(1).max(1);
+    let style = style.or_else(|| config.style.clone());
+    let ignore_whitespace = ignore_whitespace
+        .or(config.ignore_whitespace)
+        .unwrap_or(false);
+    let expand_context = expand_context.or(config.expand_context).unwrap_or(false);
 
     // Determine locale
-    let locale = locale_override.unwrap_or(config.locale.clone());
+    let (locale, second_locale) = match locale_override {
+        Some(locale) => split_locale(&locale),
+        None => (config.locale.clone(), config.second_locale.clone()),
+    };
 
     // Get diff
-    let diff = GitManager::get_staged_diff()?;
+    let (mut diff, formatting_only) =
+        GitManager::get_prompt_diff(&paths, working_tree, ignore_whitespace)?;
+    if expand_context && !working_tree && !formatting_only && !diff.is_empty() {
+        if let Ok(expanded) = AgentLite::build_expanded_diff(&paths, ignore_whitespace) {
+            if !expanded.is_empty() {
+                diff = expanded;
+            }
+        }
+    }
     if diff.is_empty() {
-        return Err(crate::error::GitAiError::NoStagedChanges);
+        return Err(no_changes());
+    }
+
+    // Expanded context is asked for on purpose; keep it whole
+    if !expand_context && config.compact_diff.unwrap_or(false) {
+        diff = diff_compact::compact(&diff);
     }
 
     // Truncate diff if needed
@@ -34,70 +316,209 @@ pub async fn run(
         .unwrap_or(5000);
 
     let (truncated_diff, truncated) = if diff.len() > max_diff_chars {
-        // Find a valid UTF-8 char boundary to avoid panicking on multi-byte chars
-        let mut end = max_diff_chars;
-        while !diff.is_char_boundary(end) {
-            end -= 1;
-        }
-        (diff[..end].to_string(), true)
+        (
+            truncate_at_char_boundary(&diff, max_diff_chars).to_string(),
+            true,
+        )
     } else {
         (diff, false)
     };
 
-    // Get branch name and recent commits
-    let branch_name = GitManager::get_current_branch().ok();
+    // Get branch name and recent commits; a detached HEAD has no branch and
+    // a fresh repository no history, both are simply left out of the prompt
+    let is_initial_commit = !GitManager::has_commits();
+    let branch_name = GitManager::get_current_branch().ok().flatten();
     let recent_commits = GitManager::get_recent_commits(10).ok();
 
     // Create AI client
     let ai_client = AIClient::new(config.clone())?;
 
     // Generate system and user prompts
-    let system_prompt = PromptTemplates::get_system_prompt(
-        &locale,
-        &config.provider,
-        config.custom_prompt.as_deref(),
+    let system_prompt = PromptTemplates::apply_style(
+        PromptTemplates::get_system_prompt(
+            &locale,
+            &config.provider,
+            config.custom_prompt.as_deref(),
+            PromptTemplates::preset_for_style(config.prompt_preset.as_deref(), style.as_deref()),
+        ),
+        style.as_deref(),
     );
+    let system_prompt =
+        PromptTemplates::apply_body_style(system_prompt, config.body_style.as_deref());
+    let system_prompt = match &second_locale {
+        Some(second) => format!(
+            "{}\n\n{}",
+            system_prompt,
+            PromptTemplates::bilingual_instruction(&locale, second)
+        ),
+        None => system_prompt,
+    };
+    let system_prompt = match repo_state.system_instruction(&locale) {
+        Some(instruction) => format!("{}\n\n{}", system_prompt, instruction),
+        None => system_prompt,
+    };
+    let system_prompt = match &config.conventions {
+        Some(conventions) => format!("{}\n\n{}", system_prompt, conventions.prompt_section()),
+        None => system_prompt,
+    };
 
-    let user_prompt = PromptTemplates::get_user_prompt(
-        &truncated_diff,
-        branch_name.as_deref(),
-        recent_commits.as_deref(),
-    );
+    let prompt_file = config.prompt_file.as_deref().map(resolve_config_path);
+    let mut user_prompt = PromptTemplates::build_user_prompt(
+        prompt_file.as_deref(),
+        &PromptContext {
+            diff: &truncated_diff,
+            branch: branch_name.as_deref(),
+            recent_commits: recent_commits.as_deref(),
+            files: &staged_files,
+            locale: &locale,
+        },
+    )?;
+    if formatting_only {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(PromptTemplates::formatting_only_note(&locale));
+    }
+    if is_initial_commit {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(PromptTemplates::initial_commit_note(&locale));
+    }
+    if let Some(context) = repo_state.prompt_context() {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(&context);
+    }
+    if let Some(hint) = workspace::scope_hint(&staged_files, config.allowed_scopes.as_deref()) {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(&hint);
+    }
+    match issues::fetch_for_branch(&config, branch_name.as_deref()).await {
+        Ok(Some(issue)) => {
+            user_prompt.push_str("\n\n");
+            user_prompt.push_str(&issue.prompt_section());
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("⚠️  Issue context unavailable: {}", e),
+    }
+
+    if let Some(content) = commit_file_content {
+        let (template, comments) = parse_commit_file(content);
+        if !template.is_empty() {
+            user_prompt.push_str(&format!(
+                "\n\nCommit template (follow its structure):\n{}",
+                template
+            ));
+        }
+        if !comments.is_empty() {
+            user_prompt.push_str("\n\nNotes from git:\n");
+            for comment in comments.iter().take(30) {
+                user_prompt.push_str(&format!("- {}\n", comment));
+            }
+        }
+    }
+
+    if let Some(instruction) = instruction {
+        user_prompt.push_str(&format!("\n\nRequest from the user:\n{}", instruction));
+    }
 
     // Generate messages
-    let messages = if num > 1 {
-        ai_client
-            .generate_multiple_messages(&system_prompt, &user_prompt, num)
-            .await?
+    // Merge titles are git's, not conventional headers
+    let rules = if repo_state.is_merge() {
+        MessageRules::default()
     } else {
-        vec![
-            ai_client
-                .generate_commit_message(&system_prompt, &user_prompt)
-                .await?,
-        ]
+        MessageRules::from_config(&config).with_format(&config, &locale, style.as_deref())
     };
+    let generation = ai_client.generate_messages(&system_prompt, &user_prompt, num, &rules);
 
-    // Output results
-    if json_output {
-        let output = CommitMessageOutput {
-            messages,
-            staged_files,
-            truncated,
-            ignored_files: vec![],
-        };
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else if !quiet {
-        for (i, msg) in messages.iter().enumerate() {
-            if i > 0 {
-                println!("---END---");
+    let messages = if is_hook_invocation() {
+        // A hung provider must never freeze `git commit`: give up quietly and
+        // let the hook fall through to the normal editor flow.
+        let secs = config
+            .hook_timeout_secs
+            .unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS);
+        match tokio::time::timeout(Duration::from_secs(secs), generation).await {
+            Ok(result) => result?,
+            Err(_) => {
+                eprintln!(
+                    "⚠️  git-ai timed out after {}s; continuing without a message",
+                    secs
+                );
+                Vec::new()
             }
-            println!("{}", msg);
         }
     } else {
-        for msg in messages {
-            println!("{}", msg);
-        }
+        generation.await?
+    };
+
+    let follow_ups = follow_ups::collect(&config, &paths, working_tree);
+    let messages = messages
+        .iter()
+        .map(|message| follow_ups::append(message, &follow_ups))
+        .collect();
+
+    Ok(Generation {
+        messages,
+        staged_files,
+        truncated,
+        follow_ups,
+        config,
+        usage: ai_client.usage(),
+        system_prompt,
+        user_prompt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commit_file_separates_template_and_comments() {
+        let content = "Summary:\n\nWhy:\n# Please enter the commit message\n#\n# On branch main\n# ------------------------ >8 ------------------------\n# Do not modify\ndiff --git a/x b/x\n";
+        let (template, comments) = parse_commit_file(content);
+        assert_eq!(template, "Summary:\n\nWhy:");
+        assert_eq!(
+            comments,
+            vec!["Please enter the commit message", "On branch main"]
+        );
     }
 
-    Ok(())
+    #[test]
+    fn render_keeps_git_comments_and_alternatives() {
+        let original = "\n# Please enter the commit message\n# On branch main\n";
+        let rendered = render_commit_file(
+            original,
+            &["feat: add x".to_string(), "feat: introduce x".to_string()],
+        );
+        assert!(rendered.starts_with("feat: add x\n"));
+        assert!(rendered.contains("# git-ai alternative 1:\n# feat: introduce x\n"));
+        assert!(rendered.ends_with("# Please enter the commit message\n# On branch main\n"));
+
+        // A template the message did not follow stays in the file
+        let original = "Summary:\n\nTicket:\n# Please enter the commit message\n";
+        let rendered = render_commit_file(original, &["feat: add x".to_string()]);
+        assert_eq!(
+            rendered,
+            "feat: add x\n\nSummary:\n\nTicket:\n\n# Please enter the commit message\n"
+        );
+        let filled = "Summary: add x\n\nTicket: GA-1".to_string();
+        let rendered = render_commit_file(original, &[filled]);
+        assert_eq!(
+            rendered,
+            "Summary: add x\n\nTicket: GA-1\n\n# Please enter the commit message\n"
+        );
+    }
+
+    #[test]
+    fn skips_sources_with_existing_message() {
+        let file = |source: Option<&str>| CommitFile {
+            path: PathBuf::from("COMMIT_EDITMSG"),
+            source: source.map(str::to_string),
+        };
+        assert!(file(Some("merge")).should_skip("Merge branch 'x'\n"));
+        assert!(file(Some("message")).should_skip(""));
+        assert!(!file(Some("template")).should_skip(""));
+        assert!(!file(None).should_skip(""));
+
+        // A conflicted merge is concluded by hand and gets a merge message
+        let conflicted = "Merge branch 'x'\n\n# Conflicts:\n#\tsrc/lib.rs\n";
+        assert!(!file(Some("merge")).should_skip(conflicted));
+    }
 }
@@ -0,0 +1,140 @@
+//! External plugins: `git-ai <name> [args]` runs an executable called
+//! `git-ai-<name>` from PATH, the way git runs `git-<name>`. The plugin gets
+//! its arguments as given, and a JSON context on stdin:
+//!
+//! ```json
+//! {"version": 1, "git_ai_version": "2.0.5", "command": "<name>",
+//!  "args": [...], "cwd": "...", "repo": "/path/or/null",
+//!  "branch": "main", "config": { ... }}
+//! ```
+//!
+//! `config` is the merged config with secrets redacted. GIT_AI_BIN (this
+//! executable) and GIT_AI_REPO are also set, so a plugin can call back into
+//! `git-ai msg --json` and friends.
+
+use crate::error::{GitAiError, Result};
+use crate::utils::config::redact_secret;
+use crate::utils::{ConfigManager, GitManager};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Version of the stdin context; bumped when fields change meaning
+const CONTEXT_VERSION: u32 = 1;
+
+/// Run the `git-ai-<name>` plugin named by `args[0]`; returns its exit code
+pub fn run(args: Vec<String>) -> Result<i32> {
+    let Some((name, plugin_args)) = args.split_first() else {
+        return Err(GitAiError::InvalidArgument("Missing command".to_string()));
+    };
+    let plugin = find_plugin(name).ok_or_else(|| {
+        GitAiError::InvalidArgument(format!(
+            "Unknown command '{}'. No git-ai-{} plugin on PATH; run 'git-ai --help' for the built-in commands.",
+            name, name
+        ))
+    })?;
+
+    let repo = GitManager::get_repo_root().ok();
+    let context = serde_json::json!({
+        "version": CONTEXT_VERSION,
+        "git_ai_version": env!("CARGO_PKG_VERSION"),
+        "command": name,
+        "args": plugin_args,
+        "cwd": std::env::current_dir()?,
+        "repo": repo,
+        "branch": repo.as_ref().and_then(|_| GitManager::get_current_branch().ok().flatten()),
+        "config": redacted_config(),
+    });
+
+    let mut command = Command::new(&plugin);
+    command
+        .args(plugin_args)
+        .stdin(Stdio::piped())
+        .env("GIT_AI_BIN", std::env::current_exe()?);
+    if let Some(repo) = &repo {
+        command.env("GIT_AI_REPO", repo);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| GitAiError::Other(format!("Failed to run {}: {}", plugin.display(), e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that never reads its context closes stdin early
+        let _ = stdin.write_all(context.to_string().as_bytes());
+    }
+    let status = child.wait()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Merged config with secrets masked, or `null` when there is none yet
+fn redacted_config() -> serde_json::Value {
+    let Ok(mut config) = ConfigManager::get_merged_config() else {
+        return serde_json::Value::Null;
+    };
+    config.api_key = redact_secret(&config.api_key);
+    config.slack_webhook = config.slack_webhook.as_deref().map(redact_secret);
+    serde_json::to_value(config).unwrap_or_default()
+}
+
+/// `git-ai-<name>` from PATH (with PATHEXT extensions on Windows)
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    if !is_plugin_name(name) {
+        return None;
+    }
+    find_on_path(&format!("git-ai-{}", name))
+}
+
+/// First executable `file` on PATH (with PATHEXT extensions on Windows)
+pub fn find_on_path(file: &str) -> Option<PathBuf> {
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|ext| ext.to_lowercase())
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", file, ext)))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+/// Plugin names are plain words, never paths or options
+pub fn is_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_plugin_name;
+
+    #[test]
+    fn plugin_names_are_plain_words() {
+        assert!(is_plugin_name("jira"));
+        assert!(is_plugin_name("release-notes_v2"));
+        assert!(!is_plugin_name(""));
+        assert!(!is_plugin_name("--help"));
+        assert!(!is_plugin_name("../evil"));
+        assert!(!is_plugin_name("a/b"));
+    }
+}
@@ -0,0 +1,366 @@
+use crate::commands::release::confirm;
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::github::{self, GitHubApi};
+use crate::utils::{ci, truncate_at_char_boundary, ConfigManager, GitManager};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Flags of `git-ai pr`
+#[derive(Debug, Default)]
+pub struct PrOptions {
+    /// Branch the PR targets (default: the remote's main/master)
+    pub base: Option<String>,
+    /// Create the PR, or update the open one for this branch
+    pub create: bool,
+    /// Copy the title and body to the clipboard
+    pub copy: bool,
+    pub remote: Option<String>,
+    pub yes: bool,
+}
+
+/// Generated pull request text
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrDescription {
+    pub title: String,
+    pub body: String,
+}
+
+pub async fn run(options: PrOptions) -> Result<()> {
+    let PrOptions {
+        base,
+        create,
+        copy,
+        remote,
+        yes,
+    } = options;
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+
+    let branch = GitManager::get_current_branch()?.ok_or_else(|| {
+        GitAiError::InvalidArgument("HEAD is detached; check out the PR branch first".to_string())
+    })?;
+    let base = match base {
+        Some(base) => base,
+        None => default_base(&remote)?,
+    };
+    let base_branch = base
+        .strip_prefix(&format!("{}/", remote))
+        .unwrap_or(&base)
+        .to_string();
+    if base_branch == branch {
+        return Err(GitAiError::InvalidArgument(format!(
+            "{} is the base branch; run this from a feature branch or pass --base",
+            branch
+        )));
+    }
+
+    let commits = GitManager::get_commits_between_refs(&base, "HEAD")?;
+    if commits.is_empty() {
+        println!("ℹ️  No commits on {} since {}", branch, base);
+        return Ok(());
+    }
+    let (diff, files) = GitManager::get_branch_diff(&base)?;
+
+    let max_diff_chars = std::env::var("GIT_AI_MAX_DIFF_CHARS")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(5000);
+    let diff = if diff.len() > max_diff_chars {
+        format!(
+            "{}\n... (diff truncated)",
+            truncate_at_char_boundary(&diff, max_diff_chars)
+        )
+    } else {
+        diff
+    };
+
+    println!(
+        "🔀 {} → {}: {} commits, {} files\n",
+        branch,
+        base_branch,
+        commits.len(),
+        files.len()
+    );
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let pb = ci::spinner();
+    pb.set_message("🤖 Writing pull request description...");
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let user_prompt = format!(
+        "Branch: {}\nBase: {}\n\nCommits:\n{}\n\nChanged files:\n{}\n\nDiff:\n{}",
+        branch,
+        base_branch,
+        commits.join("\n"),
+        files.join("\n"),
+        diff
+    );
+    let response = ai_client
+        .generate_review(&get_pr_system_prompt(&config.locale), &user_prompt)
+        .await;
+    pb.finish_and_clear();
+    let description = parse_pr_description(&response?)
+        .ok_or_else(|| GitAiError::Ai("Empty pull request description".to_string()))?;
+
+    println!("📝 {}\n", description.title);
+    println!("{}\n", description.body);
+    if config.show_usage.unwrap_or(false) {
+        println!("{}", ai_client.usage().summary());
+    }
+
+    if copy {
+        copy_to_clipboard(&format!("{}\n\n{}", description.title, description.body))?;
+        println!("📋 Copied to clipboard");
+    }
+    if !create {
+        return Ok(());
+    }
+
+    if !confirm(yes, &format!("Push {} to {}?", branch, remote))? {
+        println!("\n❌ Pull request not created");
+        return Err(GitAiError::UserCancelled);
+    }
+    GitManager::push_ref(&remote, &branch)?;
+
+    let url = if github::gh_available() {
+        publish_with_gh(&branch, &base_branch, &description)?
+    } else {
+        publish_with_api(&remote, &branch, &base_branch, &description).await?
+    };
+    println!("🚀 {}", url);
+
+    Ok(())
+}
+
+/// `<remote>/main` (or master) when fetched, otherwise the local branch
+fn default_base(remote: &str) -> Result<String> {
+    let branch = GitManager::get_default_branch()?.ok_or_else(|| {
+        GitAiError::InvalidArgument(
+            "No main or master branch found. Use --base <branch>.".to_string(),
+        )
+    })?;
+    let tracking = format!("{}/{}", remote, branch);
+    if GitManager::rev_parse(&format!("refs/remotes/{}", tracking)).is_ok() {
+        Ok(tracking)
+    } else {
+        Ok(branch)
+    }
+}
+
+/// Title on the first line, body after it; code fences and a leading
+/// `#`/`Title:` are dropped
+pub fn parse_pr_description(text: &str) -> Option<PrDescription> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("```markdown")
+        .or_else(|| text.strip_prefix("```"))
+        .map(|inner| inner.trim_end().trim_end_matches("```"))
+        .unwrap_or(text)
+        .trim();
+
+    let (title, body) = text.split_once('\n').unwrap_or((text, ""));
+    let title = title.trim().trim_start_matches('#').trim();
+    let title = title
+        .strip_prefix("Title:")
+        .or_else(|| title.strip_prefix("标题："))
+        .or_else(|| title.strip_prefix("标题:"))
+        .unwrap_or(title)
+        .trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some(PrDescription {
+        title: title.to_string(),
+        body: body.trim().to_string(),
+    })
+}
+
+/// Edit the open PR of `branch` if there is one, otherwise create it
+fn publish_with_gh(branch: &str, base: &str, description: &PrDescription) -> Result<String> {
+    let existing = Command::new("gh")
+        .args(["pr", "view", branch, "--json", "url,state"])
+        .args(["--jq", r#"select(.state == "OPEN") | .url"#])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|url| !url.is_empty());
+
+    match existing {
+        Some(url) => {
+            github::run_gh(
+                &[
+                    "pr",
+                    "edit",
+                    branch,
+                    "--title",
+                    &description.title,
+                    "--body-file",
+                    "-",
+                ],
+                &description.body,
+            )?;
+            println!("✅ Updated pull request");
+            Ok(url)
+        }
+        None => {
+            let url = github::run_gh(
+                &[
+                    "pr",
+                    "create",
+                    "--head",
+                    branch,
+                    "--base",
+                    base,
+                    "--title",
+                    &description.title,
+                    "--body-file",
+                    "-",
+                ],
+                &description.body,
+            )?;
+            println!("✅ Created pull request");
+            Ok(url)
+        }
+    }
+}
+
+async fn publish_with_api(
+    remote: &str,
+    branch: &str,
+    base: &str,
+    description: &PrDescription,
+) -> Result<String> {
+    let api = GitHubApi::for_remote(remote, "create pull requests")?;
+    let open = api
+        .request(
+            reqwest::Method::GET,
+            &format!("pulls?state=open&head={}:{}", api.owner, branch),
+            None,
+        )
+        .await?;
+
+    let pr = match open.as_array().and_then(|prs| prs.first()) {
+        Some(pr) => {
+            let number = pr["number"].as_u64().unwrap_or_default();
+            let pr = api
+                .request(
+                    reqwest::Method::PATCH,
+                    &format!("pulls/{}", number),
+                    Some(serde_json::json!({
+                        "title": description.title,
+                        "body": description.body,
+                    })),
+                )
+                .await?;
+            println!("✅ Updated pull request #{}", number);
+            pr
+        }
+        None => {
+            let pr = api
+                .request(
+                    reqwest::Method::POST,
+                    "pulls",
+                    Some(serde_json::json!({
+                        "title": description.title,
+                        "body": description.body,
+                        "head": branch,
+                        "base": base,
+                    })),
+                )
+                .await?;
+            println!(
+                "✅ Created pull request #{}",
+                pr["number"].as_u64().unwrap_or_default()
+            );
+            pr
+        }
+    };
+    Ok(pr["html_url"].as_str().unwrap_or_default().to_string())
+}
+
+/// Pipe `text` into the first clipboard tool found
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    let candidates: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else if cfg!(windows) {
+        &[&["clip"]]
+    } else {
+        &[
+            &["wl-copy"],
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+
+    for candidate in candidates {
+        let Ok(mut child) = Command::new(candidate[0])
+            .args(&candidate[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    Err(GitAiError::Other(
+        "No clipboard tool found (pbcopy, clip, wl-copy, xclip or xsel)".to_string(),
+    ))
+}
+
+fn get_pr_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => r##"你负责根据分支的提交和 diff 撰写 Pull Request。输出 Markdown，不要代码块包裹：
+第一行：PR 标题（不超过 72 个字符，不加 # 或 "标题："前缀；若提交遵循 Conventional Commits，标题也遵循）
+空一行后依次输出以下小节：
+## 概述
+1-3 句话说明这个 PR 做了什么、为什么。
+## 变更
+以 "- " 开头的要点，按功能归纳，不要逐条照抄提交。
+## 测试计划
+审阅者如何验证这些改动，以 "- " 开头。
+## 破坏性变更
+列出对接口、配置或行为的不兼容修改；没有则写"无"。
+不要编造 diff 和提交中没有的内容。"##
+            .to_string(),
+        _ => r##"You write pull requests from a branch's commits and diff. Output Markdown, not wrapped in a code fence:
+First line: the PR title (72 characters at most, no "#" or "Title:" prefix; follow Conventional Commits if the commits do)
+Then a blank line and these sections, in order:
+## Summary
+1-3 sentences on what the PR does and why.
+## Changes
+Bullets starting with "- ", grouped by feature rather than copied commit by commit.
+## Test plan
+How a reviewer can verify the change, as "- " bullets.
+## Breaking changes
+Incompatible changes to APIs, configuration or behavior; write "None" if there are none.
+Do not invent anything the diff and commits do not show."##
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_title_and_body() {
+        let description = parse_pr_description(
+            "```markdown\n# feat(pr): add pull request descriptions\n\n## Summary\nAdds `git-ai pr`.\n```",
+        )
+        .unwrap();
+        assert_eq!(description.title, "feat(pr): add pull request descriptions");
+        assert_eq!(description.body, "## Summary\nAdds `git-ai pr`.");
+
+        let description = parse_pr_description("Title: Fix login\n\n## Summary\nx").unwrap();
+        assert_eq!(description.title, "Fix login");
+        assert!(parse_pr_description("  \n").is_none());
+    }
+}
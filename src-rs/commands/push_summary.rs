@@ -0,0 +1,291 @@
+use crate::commands::msg::DEFAULT_HOOK_TIMEOUT_SECS;
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ConfigManager, GitManager};
+use std::io::BufRead;
+use std::time::Duration;
+
+/// Object id git uses for "no commit" in pre-push ref updates
+const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// Branches where force-pushes and deletions are blocked
+const PROTECTED_BRANCHES: &[&str] = &["main", "master"];
+
+/// Commits listed per pushed ref. Pushing to a URL, or to a remote without
+/// remote-tracking refs, would otherwise list the whole history.
+const MAX_LISTED_COMMITS: usize = 50;
+
+/// A single ref update as reported by git to the pre-push hook
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushUpdate {
+    pub local_ref: String,
+    pub local_sha: String,
+    pub remote_ref: String,
+    pub remote_sha: String,
+}
+
+impl PushUpdate {
+    /// Parse a `<local ref> <local sha> <remote ref> <remote sha>` line
+    pub fn parse(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() != 4 {
+            return None;
+        }
+
+        Some(Self {
+            local_ref: parts[0].to_string(),
+            local_sha: parts[1].to_string(),
+            remote_ref: parts[2].to_string(),
+            remote_sha: parts[3].to_string(),
+        })
+    }
+
+    pub fn is_delete(&self) -> bool {
+        is_zero_sha(&self.local_sha)
+    }
+
+    pub fn is_new_ref(&self) -> bool {
+        is_zero_sha(&self.remote_sha)
+    }
+
+    /// Branch name on the remote side (refs/heads/main -> main)
+    pub fn remote_branch(&self) -> &str {
+        self.remote_ref
+            .strip_prefix("refs/heads/")
+            .unwrap_or(&self.remote_ref)
+    }
+
+    pub fn targets_protected_branch(&self) -> bool {
+        PROTECTED_BRANCHES.contains(&self.remote_branch())
+    }
+}
+
+fn is_zero_sha(sha: &str) -> bool {
+    !sha.is_empty() && sha.chars().all(|c| c == '0')
+}
+
+pub async fn run(remote: Option<String>, from_stdin: bool) -> Result<()> {
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+
+    let updates = if from_stdin {
+        read_updates_from_stdin()?
+    } else {
+        updates_from_upstream()?
+    };
+
+    if updates.is_empty() {
+        println!("ℹ️  Nothing to push");
+        return Ok(());
+    }
+
+    let mut risks: Vec<String> = Vec::new();
+    let mut sections: Vec<String> = Vec::new();
+    let mut total_commits = 0usize;
+    let mut capped = false;
+
+    for update in &updates {
+        if update.is_delete() {
+            if update.targets_protected_branch() {
+                risks.push(format!(
+                    "Deleting protected branch '{}' on {}",
+                    update.remote_branch(),
+                    remote
+                ));
+            }
+            continue;
+        }
+
+        let mut commits = if update.is_new_ref() {
+            outgoing_commits(update, &remote)
+        } else {
+            let fast_forward = GitManager::is_ancestor(&update.remote_sha, &update.local_sha)
+                // The remote commit may not exist locally; treat it as a force-push.
+                .unwrap_or(false);
+            if !fast_forward && update.targets_protected_branch() {
+                risks.push(format!(
+                    "Force-push to protected branch '{}' on {}",
+                    update.remote_branch(),
+                    remote
+                ));
+            }
+            match GitManager::get_commits_between_refs(&update.remote_sha, &update.local_sha) {
+                Ok(commits) => commits,
+                Err(e) => {
+                    // Typically the remote commit was never fetched: list what no
+                    // remote ref has instead of reporting nothing to push
+                    eprintln!(
+                        "⚠️  Cannot compare {} with {}/{} ({}); listing commits not on {}",
+                        update.local_ref,
+                        remote,
+                        update.remote_branch(),
+                        e,
+                        remote
+                    );
+                    outgoing_commits(update, &remote)
+                }
+            }
+        };
+
+        if commits.is_empty() {
+            continue;
+        }
+
+        total_commits += commits.len().min(MAX_LISTED_COMMITS);
+        if commits.len() > MAX_LISTED_COMMITS {
+            commits.truncate(MAX_LISTED_COMMITS);
+            commits.push("...".to_string());
+            capped = true;
+        }
+        sections.push(format!(
+            "{} -> {}/{}:\n{}",
+            update.local_ref,
+            remote,
+            update.remote_branch(),
+            commits.join("\n")
+        ));
+    }
+
+    if !risks.is_empty() {
+        eprintln!("🚫 Risky push detected:");
+        for risk in &risks {
+            eprintln!("   • {}", risk);
+        }
+        eprintln!("   Use 'git push --no-verify' if this is intentional.");
+        return Err(GitAiError::Other("Push blocked by git-ai".to_string()));
+    }
+
+    if total_commits == 0 {
+        println!("ℹ️  No new commits to push");
+        return Ok(());
+    }
+
+    let more = if capped { "more than " } else { "" };
+    println!(
+        "📤 Pushing {}{} commit(s) to {}\n",
+        more, total_commits, remote
+    );
+
+    // The summary is informational: never block a push because the AI is
+    // unavailable or slow.
+    match generate_summary(&sections, total_commits, from_stdin).await {
+        Ok(summary) => println!("{}\n", summary.trim()),
+        Err(e) => eprintln!("⚠️  Push summary unavailable: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Commits of `update` that no ref of `remote` has, one more than are
+/// listed so the caller can tell the list was cut. A git error only loses
+/// the listing: the protected-branch checks alone may block a push.
+fn outgoing_commits(update: &PushUpdate, remote: &str) -> Vec<String> {
+    GitManager::get_commits_not_on_remote(&update.local_sha, remote, MAX_LISTED_COMMITS + 1)
+        .unwrap_or_else(|e| {
+            eprintln!("⚠️  Cannot list the commits of {}: {}", update.local_ref, e);
+            Vec::new()
+        })
+}
+
+fn read_updates_from_stdin() -> Result<Vec<PushUpdate>> {
+    let stdin = std::io::stdin();
+    let mut updates = Vec::new();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if let Some(update) = PushUpdate::parse(&line) {
+            updates.push(update);
+        }
+    }
+    Ok(updates)
+}
+
+fn updates_from_upstream() -> Result<Vec<PushUpdate>> {
+    let upstream = GitManager::get_upstream_branch()?.ok_or_else(|| {
+        GitAiError::InvalidArgument(
+            "Current branch has no upstream. Use --stdin from a pre-push hook.".to_string(),
+        )
+    })?;
+
+    let local_branch = GitManager::get_current_branch()?.ok_or_else(|| {
+        GitAiError::InvalidArgument(
+            "HEAD is detached. Check out a branch or use --stdin from a pre-push hook.".to_string(),
+        )
+    })?;
+    let remote_branch = upstream
+        .split_once('/')
+        .map(|(_, branch)| branch.to_string())
+        .unwrap_or_else(|| upstream.clone());
+
+    Ok(vec![PushUpdate {
+        local_ref: format!("refs/heads/{}", local_branch),
+        local_sha: GitManager::rev_parse("HEAD")?,
+        remote_ref: format!("refs/heads/{}", remote_branch),
+        remote_sha: GitManager::rev_parse(&upstream).unwrap_or_else(|_| ZERO_SHA.to_string()),
+    }])
+}
+
+/// Summarize the push; from the hook, within `hook_timeout_secs`
+async fn generate_summary(sections: &[String], total_commits: usize, hook: bool) -> Result<String> {
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+
+    let system_prompt = get_push_summary_system_prompt(&config.locale);
+    let user_prompt = format!(
+        "Total outgoing commits: {}\n\nSummarize the following push:\n\n{}",
+        total_commits,
+        sections.join("\n\n")
+    );
+
+    let generation = ai_client.generate_commit_message(&system_prompt, &user_prompt);
+    if !hook {
+        return generation.await;
+    }
+    let secs = config
+        .hook_timeout_secs
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS);
+    tokio::time::timeout(Duration::from_secs(secs), generation)
+        .await
+        .map_err(|_| GitAiError::Other(format!("timed out after {}s", secs)))?
+}
+
+fn get_push_summary_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => "你是一个代码推送摘要助手。根据即将推送的提交列表，用一段话（不超过 80 字）概括本次推送的主要内容和影响。不要使用列表，不要编造提交中没有的信息。"
+            .to_string(),
+        _ => "You summarize an outgoing git push. Based on the commit list, write a single paragraph (at most 60 words) describing what the push delivers and its likely impact. Do not use bullet points and do not invent facts beyond the commits."
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushUpdate;
+
+    #[test]
+    fn parse_pre_push_line() {
+        let update = PushUpdate::parse(
+            "refs/heads/main 1111111111111111111111111111111111111111 refs/heads/main 0000000000000000000000000000000000000000",
+        )
+        .expect("valid update line");
+
+        assert_eq!(update.remote_branch(), "main");
+        assert!(update.is_new_ref());
+        assert!(!update.is_delete());
+        assert!(update.targets_protected_branch());
+    }
+
+    #[test]
+    fn parse_rejects_malformed_line() {
+        assert!(PushUpdate::parse("refs/heads/main abc").is_none());
+    }
+
+    #[test]
+    fn delete_is_detected() {
+        let update = PushUpdate::parse(
+            "(delete) 0000000000000000000000000000000000000000 refs/heads/feature/x 2222222222222222222222222222222222222222",
+        )
+        .expect("valid update line");
+
+        assert!(update.is_delete());
+        assert!(!update.targets_protected_branch());
+    }
+}
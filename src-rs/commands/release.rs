@@ -0,0 +1,251 @@
+use crate::commands::report::{get_release_notes_system_prompt, service_line};
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::conventional::ConventionalHeader;
+use crate::utils::github::{self, GitHubApi};
+use crate::utils::{ci, ConfigManager, GitManager};
+use dialoguer::{Confirm, Input};
+
+/// Flags of `git-ai release`
+#[derive(Debug, Default)]
+pub struct ReleaseOptions {
+    pub tag: Option<String>,
+    pub from: Option<String>,
+    pub github: bool,
+    pub remote: Option<String>,
+    pub yes: bool,
+}
+
+pub async fn run(options: ReleaseOptions) -> Result<()> {
+    let ReleaseOptions {
+        tag,
+        from,
+        github,
+        remote,
+        yes,
+    } = options;
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+
+    let latest_tag = GitManager::get_latest_tag()?;
+    let from = from.or_else(|| latest_tag.clone()).ok_or_else(|| {
+        GitAiError::InvalidArgument(
+            "No previous tag found. Use --from <ref> to choose where the release starts."
+                .to_string(),
+        )
+    })?;
+
+    let commits = GitManager::get_commits_between_refs(&from, "HEAD")?;
+    if commits.is_empty() {
+        println!("ℹ️  Nothing to release: no commits since {}", from);
+        return Ok(());
+    }
+    println!("📦 {} commits since {}\n", commits.len(), from);
+
+    let suggested = suggest_next_tag(latest_tag.as_deref(), &commits);
+    let tag = match tag {
+        Some(tag) => tag,
+        None if yes => suggested,
+        None => {
+            ci::require_input("Release tag", "pass a tag or --yes")?;
+            Input::new()
+                .with_prompt("Release tag")
+                .default(suggested)
+                .interact_text()
+                .map_err(|e| GitAiError::prompt("Input", e))?
+        }
+    };
+    if GitManager::is_tag(&tag) {
+        return Err(GitAiError::InvalidArgument(format!(
+            "Tag {} already exists",
+            tag
+        )));
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+
+    println!("🤖 Writing release notes for {}...\n", tag);
+    let user_prompt = format!(
+        "{}Release: {}\nCommit range: {}..HEAD\nTotal commits in range: {}\n\nPlease generate release notes focused on functional changes and service impact:\n\n{}",
+        service_line(),
+        tag,
+        from,
+        commits.len(),
+        commits.join("\n")
+    );
+    let notes = ai_client
+        .generate_commit_message(
+            &get_release_notes_system_prompt(&config.locale),
+            &user_prompt,
+        )
+        .await?;
+    println!("{}\n", notes.trim());
+
+    let tag_message = ai_client
+        .generate_commit_message(
+            &get_tag_message_system_prompt(&config.locale),
+            &format!("Tag: {}\n\nRelease notes:\n{}", tag, notes),
+        )
+        .await?;
+    let tag_message = tag_message.trim().trim_matches('`').trim().to_string();
+    println!("🏷️  Tag message:\n\n{}\n", tag_message);
+
+    if !confirm(yes, &format!("Create annotated tag {}?", tag))? {
+        println!("\n❌ Release cancelled");
+        return Err(GitAiError::UserCancelled);
+    }
+    GitManager::create_annotated_tag(&tag, &tag_message, false)?;
+    println!("✅ Created tag {}", tag);
+
+    if !github {
+        println!("\nPublish with: git push {} {}", remote, tag);
+        return Ok(());
+    }
+
+    if !confirm(yes, &format!("Push {} to {}?", tag, remote))? {
+        println!(
+            "\nℹ️  Tag kept locally. Publish with: git push {} {}",
+            remote, tag
+        );
+        return Ok(());
+    }
+    GitManager::push_ref(&remote, &tag)?;
+    println!("✅ Pushed {} to {}", tag, remote);
+
+    if !confirm(yes, "Create GitHub Release?")? {
+        return Ok(());
+    }
+    let url = publish_github_release(&remote, &tag, &notes).await?;
+    println!("🚀 GitHub Release created: {}", url);
+
+    Ok(())
+}
+
+/// Ask `prompt` unless `yes` was given
+pub fn confirm(yes: bool, prompt: &str) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    ci::require_input(prompt, "pass --yes")?;
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(true)
+        .interact()
+        .map_err(|e| GitAiError::prompt("Confirmation", e))
+}
+
+/// Bump the latest semver tag from the Conventional Commits in the range:
+/// breaking changes bump major, `feat` minor, anything else patch
+pub fn suggest_next_tag(latest: Option<&str>, commits: &[String]) -> String {
+    let Some(latest) = latest else {
+        return "v0.1.0".to_string();
+    };
+    let (prefix, version) = match latest.strip_prefix('v') {
+        Some(version) => ("v", version),
+        None => ("", latest),
+    };
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<std::result::Result<_, _>>()
+        .unwrap_or_default();
+    let [major, minor, patch] = parts[..] else {
+        // Not semver: let the user pick
+        return latest.to_string();
+    };
+
+    let headers: Vec<ConventionalHeader> = commits
+        .iter()
+        .filter_map(|line| line.splitn(3, ' ').nth(2))
+        .filter_map(ConventionalHeader::parse)
+        .collect();
+    let (major, minor, patch) = if headers.iter().any(|h| h.breaking) {
+        (major + 1, 0, 0)
+    } else if headers.iter().any(|h| h.commit_type == "feat") {
+        (major, minor + 1, 0)
+    } else {
+        (major, minor, patch + 1)
+    };
+
+    format!("{}{}.{}.{}", prefix, major, minor, patch)
+}
+
+/// Create the release through `gh` when installed, otherwise the REST API
+/// with GITHUB_TOKEN / GH_TOKEN. Returns the release URL.
+async fn publish_github_release(remote: &str, tag: &str, notes: &str) -> Result<String> {
+    if github::gh_available() {
+        return github::run_gh(
+            &[
+                "release",
+                "create",
+                tag,
+                "--verify-tag",
+                "--title",
+                tag,
+                "--notes-file",
+                "-",
+            ],
+            notes,
+        );
+    }
+
+    let api = GitHubApi::for_remote(remote, "create releases")?;
+    let body = api
+        .request(
+            reqwest::Method::POST,
+            "releases",
+            Some(serde_json::json!({ "tag_name": tag, "name": tag, "body": notes })),
+        )
+        .await?;
+    Ok(body["html_url"].as_str().unwrap_or_default().to_string())
+}
+
+/// Shared by `release` (from its notes) and `tag` (from the commit list)
+pub fn get_tag_message_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => {
+            r#"你负责为 git 附注标签撰写标签说明。根据发布说明或提交列表输出纯文本（不要 Markdown 标题或代码块）：
+第一行：<标签名>: 一句话概括本次发布
+空一行后列出 3-6 条以 "- " 开头的要点。
+不要编造其中没有的内容。"#
+                .to_string()
+        }
+        _ => {
+            r#"You write the message of an annotated git tag. Based on the release notes or commit list, output plain text (no Markdown headings or code fences):
+First line: <tag>: one-sentence summary of the release
+Then a blank line and 3-6 highlights, each starting with "- ".
+Do not invent anything they do not say."#
+                .to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest_next_tag;
+
+    fn commits(subjects: &[&str]) -> Vec<String> {
+        subjects
+            .iter()
+            .map(|s| format!("abc1234 2024-05-01 {}", s))
+            .collect()
+    }
+
+    #[test]
+    fn suggests_semver_bump_from_commits() {
+        assert_eq!(
+            suggest_next_tag(Some("v1.2.3"), &commits(&["fix: crash"])),
+            "v1.2.4"
+        );
+        assert_eq!(
+            suggest_next_tag(Some("v1.2.3"), &commits(&["fix: crash", "feat(api): add"])),
+            "v1.3.0"
+        );
+        assert_eq!(
+            suggest_next_tag(Some("1.2.3"), &commits(&["feat!: drop v1"])),
+            "2.0.0"
+        );
+        assert_eq!(suggest_next_tag(None, &commits(&["init"])), "v0.1.0");
+        assert_eq!(suggest_next_tag(Some("nightly"), &[]), "nightly");
+    }
+}
@@ -1,14 +1,141 @@
 use crate::error::{GitAiError, Result};
+use crate::types::CommitStat;
 use crate::utils::ai::AIClient;
+use crate::utils::config::resolve_config_path;
+use crate::utils::git::{CommitFilter, LogScope};
+use crate::utils::summary_cache::SummaryCache;
+use crate::utils::webhook;
 use crate::utils::ConfigManager;
 use crate::utils::GitManager;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Chunk summaries requested at the same time for large ranges
+const MAX_CONCURRENT_CHUNKS: usize = 4;
+
+/// Characters of a commit's patch sent when summarizing it
+const MAX_COMMIT_PATCH_CHARS: usize = 6000;
+
+/// Flags of `git-ai report`
+#[derive(Debug, Default)]
+pub struct ReportOptions {
+    pub days: usize,
+    pub from_last_tag: bool,
+    pub from_tag: Option<String>,
+    pub to_ref: Option<String>,
+    pub by_author: bool,
+    pub stats: bool,
+    /// `<base>..<head>`: what each branch has that the other does not
+    pub compare: Option<String>,
+    pub no_merges: bool,
+    /// Markdown skeleton replacing the built-in report layout
+    pub template: Option<PathBuf>,
+    /// Send the report to a webhook; `Some(None)` uses `slack_webhook`
+    pub post: Option<Option<String>>,
+    /// Summarize every commit's diff first (cached by SHA)
+    pub summarize_commits: bool,
+    /// `<from> <to>` range; same as `from_tag` + `to_ref` unless `per_tag`
+    pub between: Option<(String, String)>,
+    /// One release-notes section per tag inside `between`
+    pub per_tag: bool,
+}
+
+pub async fn run(options: ReportOptions) -> Result<()> {
+    let ReportOptions {
+        days,
+        from_last_tag,
+        mut from_tag,
+        mut to_ref,
+        by_author,
+        stats,
+        compare,
+        no_merges,
+        template,
+        post,
+        summarize_commits,
+        between,
+        per_tag,
+    } = options;
+
+    let webhook = resolve_webhook(post)?;
+    if let Some(compare) = compare {
+        if from_last_tag || from_tag.is_some() || to_ref.is_some() || by_author {
+            return Err(GitAiError::InvalidArgument(
+                "--compare cannot be combined with tag ranges or --by-author".to_string(),
+            ));
+        }
+        return run_compare(&compare, webhook.as_deref()).await;
+    }
+
+    match between {
+        Some(_) if from_last_tag || from_tag.is_some() || to_ref.is_some() => {
+            return Err(GitAiError::InvalidArgument(
+                "--between cannot be combined with --from-last-tag, --from-tag or --to-ref"
+                    .to_string(),
+            ));
+        }
+        Some((from, to)) if per_tag => {
+            return run_per_tag(&from, &to, no_merges, webhook.as_deref()).await;
+        }
+        Some((from, to)) => {
+            from_tag = Some(from);
+            to_ref = Some(to);
+        }
+        None if per_tag => {
+            return Err(GitAiError::InvalidArgument(
+                "--per-tag requires --between <from> <to>".to_string(),
+            ));
+        }
+        None => {}
+    }
+
+    let generated = generate(ReportOptions {
+        days,
+        from_last_tag,
+        from_tag,
+        to_ref,
+        by_author,
+        stats,
+        no_merges,
+        template,
+        summarize_commits,
+        ..Default::default()
+    })
+    .await?;
+    let Some((scope, report)) = generated else {
+        return Ok(());
+    };
+
+    println!("{}", report);
+    if let Some(url) = &webhook {
+        deliver(url, &format!("git-ai report: {}", scope), &report).await?;
+    }
+
+    Ok(())
+}
+
+/// Generate the report or release notes for a day or tag range, returning
+/// its scope label and text; `None` when the scope has no commits.
+/// `compare`, `post`, `between` and `per_tag` are handled by `run`.
+pub async fn generate(options: ReportOptions) -> Result<Option<(String, String)>> {
+    let ReportOptions {
+        days,
+        from_last_tag,
+        from_tag,
+        to_ref,
+        by_author,
+        stats,
+        no_merges,
+        template,
+        summarize_commits,
+        ..
+    } = options;
 
-pub async fn run(
-    days: usize,
-    from_last_tag: bool,
-    from_tag: Option<String>,
-    to_ref: Option<String>,
-) -> Result<()> {
     if from_last_tag && from_tag.is_some() {
         return Err(GitAiError::InvalidArgument(
             "--from-last-tag cannot be used together with --from-tag".to_string(),
@@ -23,23 +150,74 @@ pub async fn run(
 
     let target_ref = to_ref.unwrap_or_else(|| "HEAD".to_string());
 
-    let (commits, scope, range_mode) = if from_last_tag {
+    let log_scope = if from_last_tag {
         let latest_tag = GitManager::get_latest_tag()?.ok_or_else(|| {
             GitAiError::InvalidArgument(
                 "No git tag found. Use --from-tag <tag> or fall back to --days.".to_string(),
             )
         })?;
-        let commits = GitManager::get_commits_between_refs(&latest_tag, &target_ref)?;
-        (commits, format!("{}..{}", latest_tag, target_ref), true)
+        LogScope::Range {
+            from: latest_tag,
+            to: target_ref,
+        }
     } else if let Some(from_tag) = from_tag {
-        let commits = GitManager::get_commits_between_refs(&from_tag, &target_ref)?;
-        (commits, format!("{}..{}", from_tag, target_ref), true)
+        LogScope::Range {
+            from: from_tag,
+            to: target_ref,
+        }
+    } else {
+        LogScope::Days(days)
+    };
+
+    let (scope, range_mode) = match &log_scope {
+        LogScope::Range { from, to } => (format!("{}..{}", from, to), true),
+        LogScope::Days(days) => (format!("last {} days", days), false),
+    };
+
+    let config = ConfigManager::get_merged_config()?;
+    let filter = CommitFilter::from_config(&config, no_merges);
+    let template = match template {
+        Some(path) => Some(path),
+        None => config.report_template.as_deref().map(resolve_config_path),
+    }
+    .map(|path| {
+        fs::read_to_string(&path).map_err(|e| {
+            GitAiError::Config(format!(
+                "Failed to read report template {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    })
+    .transpose()?;
+
+    // Per-person reports and exclusions need the author of every commit
+    let mut authored = if by_author || filter.is_active() {
+        let all = GitManager::get_authored_commits(&log_scope, filter.no_merges)?;
+        let total = all.len();
+        let kept: Vec<(String, String)> = all
+            .into_iter()
+            .filter(|(author, line)| !filter.excludes(author, line))
+            .collect();
+        if kept.len() < total {
+            println!(
+                "🧹 Skipped {} commits matching exclude_authors / exclude_patterns\n",
+                total - kept.len()
+            );
+        }
+        Some(kept)
     } else {
-        let commits = GitManager::get_commits_by_days(days)?;
-        (commits, format!("last {} days", days), false)
+        None
+    };
+    let mut commits: Vec<String> = match (&authored, &log_scope) {
+        (Some(authored), _) => authored.iter().map(|(_, line)| line.clone()).collect(),
+        (None, LogScope::Range { from, to }) => GitManager::get_commits_between_refs(from, to)?,
+        (None, LogScope::Days(days)) => GitManager::get_commits_by_days(*days)?,
     };
 
-    if range_mode {
+    if by_author {
+        println!("👥 Generating team report for {}...\n", scope);
+    } else if range_mode {
         println!("📦 Generating release notes for {}...\n", scope);
     } else {
         println!("📊 Generating report for {}...\n", scope);
@@ -47,67 +225,641 @@ pub async fn run(
 
     if commits.is_empty() {
         println!("No commits found in {}", scope);
-        return Ok(());
+        return Ok(None);
     }
 
     println!("Found {} commits\n", commits.len());
 
+    // Real size/area signal so the model does not have to guess from subjects
+    let mut areas_section = String::new();
+    if stats {
+        let mut commit_stats = GitManager::get_commit_stats(&log_scope)?;
+        // Excluded commits should not count towards the touched areas either
+        if filter.is_active() {
+            commit_stats.retain(|stat| {
+                commits.iter().any(|line| {
+                    line.split(' ')
+                        .next()
+                        .is_some_and(|h| stat.hash.starts_with(h))
+                })
+            });
+        }
+        for line in commits.iter_mut() {
+            *line = annotate_commit(line, &commit_stats);
+        }
+        for (_, line) in authored.iter_mut().flatten() {
+            *line = annotate_commit(line, &commit_stats);
+        }
+        areas_section = format_areas(&commit_stats);
+    }
+
     let max_commits = std::env::var("GIT_AI_REPORT_MAX_COMMITS")
         .ok()
         .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
         .unwrap_or(200);
     let total_commits = commits.len();
-    let commits_for_prompt: Vec<String> = commits.into_iter().take(max_commits).collect();
 
-    if total_commits > commits_for_prompt.len() {
-        println!(
-            "⚠️  Commit list truncated for AI context: using {} of {} commits\n",
-            commits_for_prompt.len(),
-            total_commits
-        );
-    }
+    // Create AI clients; intermediate summaries go to the cheaper agent model
+    let ai_client = AIClient::new(config.clone())?;
+    let summary_client = Arc::new(AIClient::for_agent(&config)?);
 
-    // Get config
-    let config = ConfigManager::get_merged_config()?;
+    if summarize_commits {
+        let summaries = summarize_each_commit(&summary_client, &config.locale, &commits).await?;
+        let append = |line: &mut String| {
+            let short_hash = line.split_whitespace().next().unwrap_or_default();
+            if let Some(summary) = summaries.get(short_hash) {
+                line.push_str(&format!(" — {}", summary));
+            }
+        };
+        commits.iter_mut().for_each(append);
+        authored
+            .iter_mut()
+            .flatten()
+            .for_each(|(_, line)| append(line));
+    }
 
-    // Create AI client
-    let ai_client = AIClient::new(config.clone())?;
+    // Ranges that do not fit one prompt are summarized chunk by chunk first
+    let (commit_block, context_note) = if total_commits > max_commits {
+        let lines: Vec<String> = match authored.as_ref().filter(|_| by_author) {
+            Some(authored) => authored
+                .iter()
+                .map(|(author, line)| format!("[{}] {}", author, line))
+                .collect(),
+            None => commits,
+        };
+        let chunks = chunk_commits(&lines, max_commits);
+        println!(
+            "🧩 {} commits exceed the single-prompt limit of {}; summarizing {} chunks...\n",
+            total_commits,
+            max_commits,
+            chunks.len()
+        );
+        let note = format!(
+            "Commits included in context: all {}, as {} chunk summaries (newest first)",
+            total_commits,
+            chunks.len()
+        );
+        (
+            summarize_chunks(&summary_client, &config.locale, chunks).await?,
+            note,
+        )
+    } else {
+        let block = match authored.as_ref().filter(|_| by_author) {
+            Some(authored) => group_by_author(authored),
+            None => commits.join("\n"),
+        };
+        (
+            block,
+            format!("Commits included in context: {}", total_commits),
+        )
+    };
 
     // Generate report using AI
-    let system_prompt = if range_mode {
+    let system_prompt = if let Some(template) = &template {
+        let filled = fill_known_placeholders(
+            template,
+            &[
+                ("scope", scope.clone()),
+                ("total_commits", total_commits.to_string()),
+                ("date", Local::now().date_naive().to_string()),
+            ],
+        );
+        get_template_system_prompt(&config.locale, &filled)
+    } else if by_author {
+        get_team_report_system_prompt(&config.locale)
+    } else if range_mode {
         get_release_notes_system_prompt(&config.locale)
     } else {
         get_report_system_prompt(&config.locale)
     };
-    let user_prompt = if range_mode {
+    let mut user_prompt = if by_author {
         format!(
-            "Current service: git-ai-cli (Rust 2.x).\nCommit range: {}\nTotal commits in range: {}\nCommits included in context: {}\n\nPlease generate release notes focused on functional changes and service impact:\n\n{}",
-            scope,
-            total_commits,
-            commits_for_prompt.len(),
-            commits_for_prompt.join("\n")
+            "Scope: {}\nTotal commits in scope: {}\n{}\n\nGenerate a per-person team report for the following commits, grouped by author:\n\n{}",
+            scope, total_commits, context_note, commit_block
+        )
+    } else if range_mode {
+        format!(
+            "{}Commit range: {}\nTotal commits in range: {}\n{}\n\nPlease generate release notes focused on functional changes and service impact:\n\n{}",
+            service_line(), scope, total_commits, context_note, commit_block
         )
     } else {
         format!(
-            "Total commits in scope: {}\nCommits included in context: {}\n\nGenerate a structured report for the following commits:\n\n{}",
-            total_commits,
-            commits_for_prompt.len(),
-            commits_for_prompt.join("\n")
+            "Total commits in scope: {}\n{}\n\nGenerate a structured report for the following commits:\n\n{}",
+            total_commits, context_note, commit_block
         )
     };
+    user_prompt.push_str(&areas_section);
 
     println!("🤖 Analyzing commits...\n");
 
     let report = ai_client
         .generate_commit_message(&system_prompt, &user_prompt)
         .await?;
+    Ok(Some((scope, report)))
+}
+
+/// `report --between <from> <to> --per-tag`: release notes for every
+/// release in the interval, newest first, e.g. to back-fill a changelog
+async fn run_per_tag(from: &str, to: &str, no_merges: bool, webhook: Option<&str>) -> Result<()> {
+    let mut releases = vec![from.to_string()];
+    releases.extend(GitManager::get_tags_between(from, to)?);
+    releases.push(to.to_string());
+    println!(
+        "🏷️  {} releases between {} and {}\n",
+        releases.len() - 1,
+        from,
+        to
+    );
+
+    let config = ConfigManager::get_merged_config()?;
+    let filter = CommitFilter::from_config(&config, no_merges);
+    let ai_client = AIClient::new(config.clone())?;
+    let system_prompt = get_release_notes_system_prompt(&config.locale);
+
+    let mut sections = Vec::new();
+    for pair in releases.windows(2).rev() {
+        let (previous, release) = (&pair[0], &pair[1]);
+        let commits: Vec<String> = if filter.is_active() {
+            let scope = LogScope::Range {
+                from: previous.clone(),
+                to: release.clone(),
+            };
+            GitManager::get_authored_commits(&scope, filter.no_merges)?
+                .into_iter()
+                .filter(|(author, line)| !filter.excludes(author, line))
+                .map(|(_, line)| line)
+                .collect()
+        } else {
+            GitManager::get_commits_between_refs(previous, release)?
+        };
+        if commits.is_empty() {
+            println!("⏭️  {}: no commits since {}", release, previous);
+            continue;
+        }
 
+        println!(
+            "🤖 Writing notes for {} ({} commits since {})...",
+            release,
+            commits.len(),
+            previous
+        );
+        let user_prompt = format!(
+            "{}Release: {}\nCommit range: {}..{}\nTotal commits in range: {}\n\nPlease generate release notes focused on functional changes and service impact:\n\n{}",
+            service_line(),
+            release,
+            previous,
+            release,
+            commits.len(),
+            commits.join("\n")
+        );
+        let notes = ai_client
+            .generate_commit_message(&system_prompt, &user_prompt)
+            .await?;
+        // A range ending at a branch or HEAD documents work not released yet
+        let heading = if GitManager::is_tag(release) {
+            release.as_str()
+        } else {
+            "Unreleased"
+        };
+        sections.push(format!(
+            "## {} ({})\n\n{}",
+            heading,
+            GitManager::get_commit_date(release)?,
+            notes.trim()
+        ));
+    }
+
+    if sections.is_empty() {
+        println!("No commits found between {} and {}", from, to);
+        return Ok(());
+    }
+    let report = sections.join("\n\n");
+    println!("\n{}", report);
+    if let Some(url) = webhook {
+        deliver(
+            url,
+            &format!("git-ai release notes: {}..{}", from, to),
+            &report,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// `--post <url>` wins over the configured `slack_webhook`
+fn resolve_webhook(post: Option<Option<String>>) -> Result<Option<String>> {
+    match post {
+        None => Ok(None),
+        Some(Some(url)) => Ok(Some(url)),
+        Some(None) => ConfigManager::get_merged_config()?
+            .slack_webhook
+            .map(Some)
+            .ok_or_else(|| {
+                GitAiError::Config(
+                    "--post needs a webhook URL or the slack_webhook config".to_string(),
+                )
+            }),
+    }
+}
+
+async fn deliver(url: &str, title: &str, report: &str) -> Result<()> {
+    webhook::post_report(url, title, report).await?;
+    println!("\n📨 Report posted to webhook");
+    Ok(())
+}
+
+/// `report --compare <base>..<head>`: release-readiness / branch sync review
+async fn run_compare(spec: &str, webhook: Option<&str>) -> Result<()> {
+    let (base, head) = parse_compare_spec(spec).ok_or_else(|| {
+        GitAiError::InvalidArgument(format!(
+            "--compare expects <base>..<head> (e.g. main..release/2.1), got '{}'",
+            spec
+        ))
+    })?;
+
+    println!("🔀 Comparing {} with {}...\n", base, head);
+    let comparison = GitManager::compare_branches(base, head)?;
+    if comparison.only_base.is_empty() && comparison.only_head.is_empty() {
+        println!(
+            "✅ {} and {} contain the same changes ({} cherry-picked on both sides)",
+            base, head, comparison.equivalent
+        );
+        return Ok(());
+    }
+    println!(
+        "Only in {}: {} commits, only in {}: {} commits, cherry-picked on both: {}\n",
+        head,
+        comparison.only_head.len(),
+        base,
+        comparison.only_base.len(),
+        comparison.equivalent
+    );
+
+    let max_commits = std::env::var("GIT_AI_REPORT_MAX_COMMITS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(200);
+    let side = |commits: &[String]| {
+        if commits.is_empty() {
+            return "(none)".to_string();
+        }
+        let mut text = commits
+            .iter()
+            .take(max_commits)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        if commits.len() > max_commits {
+            text.push_str(&format!("\n... and {} more", commits.len() - max_commits));
+        }
+        text
+    };
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let user_prompt = format!(
+        "Base: {}\nHead: {}\nCommits already on both sides via cherry-pick: {}\n\nOnly in {} ({} commits):\n{}\n\nOnly in {} ({} commits):\n{}",
+        base,
+        head,
+        comparison.equivalent,
+        head,
+        comparison.only_head.len(),
+        side(&comparison.only_head),
+        base,
+        comparison.only_base.len(),
+        side(&comparison.only_base)
+    );
+
+    println!("🤖 Analyzing commits...\n");
+    let report = ai_client
+        .generate_commit_message(&get_compare_system_prompt(&config.locale), &user_prompt)
+        .await?;
     println!("{}", report);
+    if let Some(url) = webhook {
+        deliver(
+            url,
+            &format!("git-ai report: {} vs {}", head, base),
+            &report,
+        )
+        .await?;
+    }
 
     Ok(())
 }
 
-fn get_release_notes_system_prompt(locale: &str) -> String {
+/// Split `base..head` (or `base...head`)
+fn parse_compare_spec(spec: &str) -> Option<(&str, &str)> {
+    let (base, head) = spec.split_once("...").or_else(|| spec.split_once(".."))?;
+    if base.is_empty() || head.is_empty() {
+        return None;
+    }
+    Some((base, head))
+}
+
+/// Substitute the `{{name}}` placeholders we know; the rest are left for the model
+fn fill_known_placeholders(template: &str, values: &[(&str, String)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+        output.push_str(&rest[..start]);
+        match values.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => output.push_str(value),
+            None => output.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &rest[start + end + 4..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn get_template_system_prompt(locale: &str, template: &str) -> String {
+    let instructions = match locale {
+        "zh" => "你是一个 Git 提交报告生成器。请严格按照下面的 Markdown 模板输出报告：保留所有标题、顺序和固定文字，把每个 {{...}} 占位符替换为根据提交记录写出的内容（占位符名称说明了应填写什么）。没有可填写的内容时写“无”。不要添加模板以外的章节，不要编造提交中没有的事实。",
+        _ => "You are a Git commit report generator. Produce the report by filling in the Markdown template below exactly: keep every heading, its order and all fixed text, and replace each {{...}} placeholder with content written from the commits (the placeholder name says what belongs there). Write \"None\" when there is nothing to fill in. Do not add sections the template does not have and do not invent facts.",
+    };
+    format!("{}\n\nTemplate:\n\n{}", instructions, template.trim())
+}
+
+/// Split commits (newest first) into calendar weeks, further cut into
+/// pieces of at most `max_per_chunk` commits
+fn chunk_commits(lines: &[String], max_per_chunk: usize) -> Vec<(String, Vec<String>)> {
+    let mut weeks: Vec<(String, Vec<String>)> = Vec::new();
+    for line in lines {
+        let week = line
+            .split_whitespace()
+            .find_map(|token| NaiveDate::parse_from_str(token, "%Y-%m-%d").ok())
+            .map(|date| {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                format!("Week of {}", monday)
+            });
+        match (week, weeks.last_mut()) {
+            (Some(week), Some((label, commits))) if *label == week => commits.push(line.clone()),
+            (None, Some((_, commits))) => commits.push(line.clone()),
+            (week, _) => weeks.push((
+                week.unwrap_or_else(|| "Undated".to_string()),
+                vec![line.clone()],
+            )),
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for (label, commits) in weeks {
+        let parts = commits.len().div_ceil(max_per_chunk.max(1));
+        for (i, part) in commits.chunks(max_per_chunk.max(1)).enumerate() {
+            let label = if parts > 1 {
+                format!("{} (part {}/{})", label, i + 1, parts)
+            } else {
+                label.clone()
+            };
+            chunks.push((label, part.to_vec()));
+        }
+    }
+    chunks
+}
+
+/// Summarize every chunk concurrently and join the summaries in chunk order
+async fn summarize_chunks(
+    ai_client: &Arc<AIClient>,
+    locale: &str,
+    chunks: Vec<(String, Vec<String>)>,
+) -> Result<String> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CHUNKS));
+    let system_prompt = get_chunk_system_prompt(locale);
+    let total = chunks.len();
+    let mut tasks = JoinSet::new();
+
+    for (index, (label, commits)) in chunks.into_iter().enumerate() {
+        let ai_client = Arc::clone(ai_client);
+        let semaphore = Arc::clone(&semaphore);
+        let system_prompt = system_prompt.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let user_prompt = format!(
+                "Chunk {}/{}: {} ({} commits)\n\n{}",
+                index + 1,
+                total,
+                label,
+                commits.len(),
+                commits.join("\n")
+            );
+            let summary = ai_client
+                .generate_commit_message(&system_prompt, &user_prompt)
+                .await;
+            (index, label, commits.len(), summary)
+        });
+    }
+
+    let mut summaries = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        let (index, label, count, summary) =
+            joined.map_err(|e| GitAiError::Other(format!("Chunk summary task failed: {}", e)))?;
+        summaries.push((
+            index,
+            format!("### {} ({} commits)\n{}", label, count, summary?.trim()),
+        ));
+    }
+    summaries.sort_by_key(|(index, _)| *index);
+
+    Ok(summaries
+        .into_iter()
+        .map(|(_, summary)| summary)
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+/// One-line summary of each commit's diff, keyed by short hash. Summaries
+/// are cached by SHA, so only commits never seen before reach the model.
+async fn summarize_each_commit(
+    ai_client: &Arc<AIClient>,
+    locale: &str,
+    lines: &[String],
+) -> Result<HashMap<String, String>> {
+    let mut cache = SummaryCache::open()?;
+    let mut summaries = HashMap::new();
+    let mut missing = Vec::new();
+    for line in lines {
+        let Some(short_hash) = line.split_whitespace().next() else {
+            continue;
+        };
+        let sha = GitManager::rev_parse(short_hash)?;
+        match cache.get(locale, &sha) {
+            Some(summary) => {
+                summaries.insert(short_hash.to_string(), summary.to_string());
+            }
+            None => missing.push((short_hash.to_string(), sha)),
+        }
+    }
+    println!(
+        "🗃️  Commit summaries: {} cached, {} to generate\n",
+        summaries.len(),
+        missing.len()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CHUNKS));
+    let system_prompt = get_commit_summary_system_prompt(locale);
+    let mut tasks = JoinSet::new();
+    for (short_hash, sha) in missing {
+        let patch: String = GitManager::get_commit_patch(&sha)?
+            .chars()
+            .take(MAX_COMMIT_PATCH_CHARS)
+            .collect();
+        let ai_client = Arc::clone(ai_client);
+        let semaphore = Arc::clone(&semaphore);
+        let system_prompt = system_prompt.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let summary = ai_client
+                .generate_commit_message(&system_prompt, &format!("Commit {}\n\n{}", sha, patch))
+                .await;
+            (short_hash, sha, summary)
+        });
+    }
+
+    // Keep whatever finished even if one request failed
+    let mut first_error = None;
+    while let Some(joined) = tasks.join_next().await {
+        let (short_hash, sha, summary) =
+            joined.map_err(|e| GitAiError::Other(format!("Commit summary task failed: {}", e)))?;
+        match summary {
+            Ok(summary) => {
+                let summary = summary
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                cache.insert(locale, &sha, summary.clone());
+                summaries.insert(short_hash, summary);
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    cache.save()?;
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(summaries),
+    }
+}
+
+fn get_commit_summary_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => "你在为提交报告逐条概括提交。根据提交信息和 diff，用一句话（不超过 30 个字）说明这个提交实际改了什么、影响是什么。只输出这一句，不要提交哈希，不要编造。".to_string(),
+        _ => "You are summarizing a single commit for a later report. From its message and diff, write one sentence (at most 20 words) saying what actually changed and its effect. Output only that sentence, without the commit hash, and do not invent anything.".to_string(),
+    }
+}
+
+fn get_chunk_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => "你在为一份大型提交报告做分段摘要。把本段提交压缩成 3-8 条要点，按 功能 / 修复 / 改进 / 其他 归类，合并同类提交，保留 [作者] 标注和关键数字，不要编造内容，只输出要点。".to_string(),
+        _ => "You are summarizing one slice of a large commit history for a later report. Condense these commits into 3-8 bullet points grouped as Features / Fixes / Improvements / Other, merging related commits. Keep [author] tags and concrete numbers, do not invent anything, and output only the bullets.".to_string(),
+    }
+}
+
+/// Append `[+ins -del, N files: areas]` to a `%h %cd %s` line
+fn annotate_commit(line: &str, stats: &[CommitStat]) -> String {
+    let short_hash = line.split_whitespace().next().unwrap_or_default();
+    let Some(stat) = stats
+        .iter()
+        .find(|s| !short_hash.is_empty() && s.hash.starts_with(short_hash))
+    else {
+        return line.to_string();
+    };
+    if stat.files == 0 {
+        return line.to_string();
+    }
+
+    format!(
+        "{} [+{} -{}, {} file{}: {}]",
+        line,
+        stat.insertions,
+        stat.deletions,
+        stat.files,
+        if stat.files == 1 { "" } else { "s" },
+        stat.areas.join(", ")
+    )
+}
+
+/// Areas ranked by lines changed across the whole scope
+fn format_areas(stats: &[CommitStat]) -> String {
+    let mut areas: Vec<(&str, u32, usize)> = Vec::new();
+    for stat in stats {
+        let lines = stat.insertions + stat.deletions;
+        for area in &stat.areas {
+            match areas.iter_mut().find(|(name, _, _)| name == area) {
+                Some((_, total, commits)) => {
+                    *total += lines;
+                    *commits += 1;
+                }
+                None => areas.push((area, lines, 1)),
+            }
+        }
+    }
+    if areas.is_empty() {
+        return String::new();
+    }
+    areas.sort_by_key(|(_, lines, _)| std::cmp::Reverse(*lines));
+
+    let mut section = String::from("\n\nTouched areas (lines changed in commits touching them):");
+    for (area, lines, commits) in areas.iter().take(15) {
+        section.push_str(&format!(
+            "\n- {} ({} lines, {} commits)",
+            area, lines, commits
+        ));
+    }
+    section
+}
+
+/// Commit lines grouped under each author, most active first
+fn group_by_author(authored: &[(String, String)]) -> String {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for (author, commit) in authored {
+        match groups.iter_mut().find(|(name, _)| name == author) {
+            Some((_, commits)) => commits.push(commit),
+            None => groups.push((author, vec![commit])),
+        }
+    }
+    // Stable sort keeps first-seen (most recent) order among equal counts
+    groups.sort_by_key(|(_, commits)| std::cmp::Reverse(commits.len()));
+
+    groups
+        .iter()
+        .map(|(author, commits)| {
+            let lines: Vec<String> = commits.iter().map(|c| format!("- {}", c)).collect();
+            format!(
+                "### {} ({} commits)\n{}",
+                author,
+                commits.len(),
+                lines.join("\n")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// `Current service: <repo>.` line for release-note prompts, named after the
+/// repository's directory
+pub fn service_line() -> String {
+    GitManager::get_repo_root()
+        .ok()
+        .and_then(|root| {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .map(|name| format!("Current service: {}.\n", name))
+        .unwrap_or_default()
+}
+
+pub fn get_release_notes_system_prompt(locale: &str) -> String {
     match locale {
         "zh" => {
             r#"你是一个专业的软件版本发布说明生成器。请根据提交记录输出清晰、可直接发布的功能描述。
@@ -164,6 +916,98 @@ Requirements:
     }
 }
 
+fn get_team_report_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => {
+            r#"你是一个团队迭代回顾报告生成器。提交已按作者分组，请为每位成员写一个小节，适合在迭代评审会上展示。
+
+请按以下结构输出：
+
+## 👥 团队概览
+- 总提交数：X，参与人数：Y
+- 一句话总结本周期团队的主要成果
+
+## 按成员
+### 姓名
+- 交付：概括此人完成的功能（合并同类提交，不要逐条抄写）
+- 修复：概括此人修复的问题
+- 其他：重构、文档、CI 等（没有则省略）
+
+要求：
+1) 每位作者都要出现，顺序与输入一致。
+2) 只根据提交记录描述，不要编造或评价个人表现。
+3) 保持简洁，每人 1-4 条。"#
+                .to_string()
+        }
+        _ => {
+            r#"You are a team report generator for sprint reviews. The commits are grouped by author; write one short section per person.
+
+Use this structure:
+
+## 👥 Team Overview
+- Total commits: X, contributors: Y
+- One sentence on what the team achieved in this period
+
+## By Contributor
+### Name
+- Shipped: features this person delivered (merge related commits, do not copy them one by one)
+- Fixed: problems this person fixed
+- Other: refactoring, docs, CI, ... (omit when empty)
+
+Requirements:
+1) Include every author, in the order given.
+2) Only describe what the commits show; do not invent facts or judge individual performance.
+3) Keep it brief: 1-4 bullets per person."#
+                .to_string()
+        }
+    }
+}
+
+fn get_compare_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => {
+            r#"你是一个分支对比分析助手，用于发版就绪评审和长期分支同步检查。输入给出了两个分支各自独有的提交。
+
+请按以下结构输出：
+
+## 🔀 对比概览
+- 一句话说明两个分支的差异程度
+
+## ➡️ 仅在 head 分支
+- 按功能归纳 head 独有的改动，指出哪些是发版需要的
+
+## ⬅️ 仅在 base 分支
+- 归纳 base 独有的改动，特别标出尚未同步到 head 的修复（可能需要 cherry-pick 或合并）
+
+## ✅ 结论与建议
+- 是否可以发版 / 合并，需要先同步哪些提交，潜在冲突风险
+
+不要编造提交中没有的内容。"#
+                .to_string()
+        }
+        _ => {
+            r#"You compare two branches for release-readiness reviews and long-lived branch sync checks. The input lists the commits unique to each side.
+
+Use this structure:
+
+## 🔀 Comparison Overview
+- One sentence on how far the branches have diverged
+
+## ➡️ Only on Head
+- Group head-only changes by capability and point out what the release depends on
+
+## ⬅️ Only on Base
+- Summarize base-only changes, flagging fixes not yet synced to head (candidates for cherry-pick or merge)
+
+## ✅ Verdict
+- Whether the branch is ready to release / merge, what to sync first and likely conflict risks
+
+Do not invent anything the commits do not show."#
+                .to_string()
+        }
+    }
+}
+
 fn get_report_system_prompt(locale: &str) -> String {
     match locale {
         "zh" => {
@@ -228,3 +1072,98 @@ Ensure the report is clear, professional, and easy to understand."#
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        annotate_commit, chunk_commits, fill_known_placeholders, group_by_author,
+        parse_compare_spec,
+    };
+    use crate::types::CommitStat;
+
+    #[test]
+    fn groups_commits_by_author() {
+        let authored: Vec<(String, String)> = [
+            ("Bob", "c3 2024-05-03 fix: crash"),
+            ("Alice", "c2 2024-05-02 feat: export"),
+            ("Alice", "c1 2024-05-01 feat: import"),
+        ]
+        .iter()
+        .map(|(a, c)| (a.to_string(), c.to_string()))
+        .collect();
+
+        assert_eq!(
+            group_by_author(&authored),
+            "### Alice (2 commits)\n- c2 2024-05-02 feat: export\n- c1 2024-05-01 feat: import\n\n### Bob (1 commits)\n- c3 2024-05-03 fix: crash"
+        );
+    }
+
+    #[test]
+    fn annotates_commit_with_stats() {
+        let stats = vec![CommitStat {
+            hash: "abc1234def".to_string(),
+            files: 2,
+            insertions: 10,
+            deletions: 3,
+            areas: vec!["src/".to_string(), "README.md".to_string()],
+        }];
+        assert_eq!(
+            annotate_commit("abc1234 2024-05-01 feat: x", &stats),
+            "abc1234 2024-05-01 feat: x [+10 -3, 2 files: src/, README.md]"
+        );
+        assert_eq!(
+            annotate_commit("fff0000 2024-05-01 chore: y", &stats),
+            "fff0000 2024-05-01 chore: y"
+        );
+    }
+
+    #[test]
+    fn chunks_commits_by_week_and_size() {
+        let lines: Vec<String> = [
+            "a1 2024-05-08 feat: c",
+            "a2 2024-05-07 feat: b",
+            "a3 2024-05-06 fix: a",
+            "a4 2024-05-03 chore: z",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let chunks = chunk_commits(&lines, 2);
+        let labels: Vec<&str> = chunks.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Week of 2024-05-06 (part 1/2)",
+                "Week of 2024-05-06 (part 2/2)",
+                "Week of 2024-04-29"
+            ]
+        );
+        assert_eq!(chunks[1].1, vec!["a3 2024-05-06 fix: a".to_string()]);
+    }
+
+    #[test]
+    fn parses_compare_spec() {
+        assert_eq!(
+            parse_compare_spec("main..release/2.1"),
+            Some(("main", "release/2.1"))
+        );
+        assert_eq!(parse_compare_spec("main...dev"), Some(("main", "dev")));
+        assert_eq!(parse_compare_spec("main"), None);
+        assert_eq!(parse_compare_spec("..dev"), None);
+    }
+
+    #[test]
+    fn fills_known_placeholders_only() {
+        assert_eq!(
+            fill_known_placeholders(
+                "# Report {{ scope }}\n{{highlights}}\n{{total_commits}} commits {{",
+                &[
+                    ("scope", "v1..v2".to_string()),
+                    ("total_commits", "3".to_string())
+                ]
+            ),
+            "# Report v1..v2\n{{highlights}}\n3 commits {{"
+        );
+    }
+}
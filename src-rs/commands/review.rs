@@ -0,0 +1,338 @@
+use crate::commands::msg::DEFAULT_HOOK_TIMEOUT_SECS;
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::{AIClient, TokenUsage};
+use crate::utils::codeowners;
+use crate::utils::copilot::{CodeAnalysis, Severity, ANALYSIS_ROLE};
+use crate::utils::{ci, truncate_at_char_boundary, ConfigManager, CopilotCLI, GitManager};
+use std::time::Duration;
+
+/// Flags of `git-ai review`
+#[derive(Debug, Default)]
+pub struct ReviewOptions {
+    /// Review the branch since it forked from this ref instead of the staged changes
+    pub base: Option<String>,
+    /// Exit non-zero when a risk at this severity or above is found
+    pub fail_on: Option<Severity>,
+    /// Analyze with GitHub Copilot CLI instead of the configured provider
+    pub copilot: bool,
+    /// Print the analysis as a JSON document
+    pub json: bool,
+    /// Run as the pre-commit hook: block at the `pre_commit_fail_on` level
+    /// and let the commit through when the review itself cannot run
+    pub pre_commit: bool,
+}
+
+pub async fn run(options: ReviewOptions) -> Result<()> {
+    let ReviewOptions {
+        base,
+        fail_on,
+        copilot,
+        json,
+        pre_commit,
+    } = options;
+    if pre_commit {
+        return run_pre_commit(copilot).await;
+    }
+
+    let Some((analysis, usage)) = analyze(base.as_deref(), copilot, json).await? else {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&empty_analysis())?);
+        } else {
+            println!("ℹ️  No changes since {}", base.unwrap_or_default());
+        }
+        return Ok(());
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&to_json(&analysis))?);
+    } else {
+        print_analysis(&analysis);
+        if let Some(usage) = usage {
+            println!("{}", usage.summary());
+        }
+    }
+
+    check_risk(&analysis, fail_on)
+}
+
+/// The pre-commit hook: quiet when the staged changes stay below the
+/// policy, the analysis and a block otherwise
+async fn run_pre_commit(copilot: bool) -> Result<()> {
+    // A broken config must not stop every commit either
+    let config = match ConfigManager::get_merged_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("⚠️  git-ai review skipped: {}", e);
+            return Ok(());
+        }
+    };
+    let Some(threshold) = pre_commit_threshold(config.pre_commit_fail_on.as_deref()) else {
+        return Ok(());
+    };
+    // A hung provider must not freeze `git commit` either
+    let secs = config
+        .hook_timeout_secs
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS);
+    let review = tokio::time::timeout(Duration::from_secs(secs), analyze(None, copilot, false));
+    let analysis = match review.await {
+        Ok(Ok(Some((analysis, _)))) => analysis,
+        Ok(Ok(None)) | Ok(Err(GitAiError::NoStagedChanges)) => return Ok(()),
+        // A missing key or an unreachable provider must not stop every commit
+        Ok(Err(e)) => {
+            eprintln!("⚠️  git-ai review skipped: {}", e);
+            return Ok(());
+        }
+        Err(_) => {
+            eprintln!("⚠️  git-ai review skipped: timed out after {}s", secs);
+            return Ok(());
+        }
+    };
+    // Like a provider failure: the hook lets the commit through
+    if !analysis.parsed {
+        eprintln!("⚠️  git-ai review skipped: the analysis could not be parsed");
+        return Ok(());
+    }
+    if analysis.issues_at_least(threshold) == 0 {
+        println!(
+            "✅ git-ai review: no risk at {} severity or above",
+            threshold.as_str()
+        );
+        return Ok(());
+    }
+
+    print_analysis(&analysis);
+    eprintln!(
+        "🚫 Commit blocked by git-ai review (preCommitFailOn: {})",
+        threshold.as_str()
+    );
+    eprintln!("   Fix the risks above, or use 'git commit --no-verify' if this is intentional.");
+    check_risk(&analysis, Some(threshold))
+}
+
+/// `pre_commit_fail_on` as a severity; `None` when set to off
+fn pre_commit_threshold(level: Option<&str>) -> Option<Severity> {
+    match level.map(|level| level.trim().to_lowercase()) {
+        Some(level) if level == "off" => None,
+        Some(level) => Some(Severity::parse(&level).unwrap_or_else(|| {
+            eprintln!(
+                "⚠️  Unknown preCommitFailOn '{}' (expected high, medium, low or off); using high",
+                level
+            );
+            Severity::High
+        })),
+        None => Some(Severity::High),
+    }
+}
+
+/// Analyze the staged changes, or the branch since `base`; `None` when the
+/// branch has no changes. `json` requests the analysis as a JSON document
+/// rather than parsing the text layout. Token usage is returned when
+/// `show_usage` is on.
+pub async fn analyze(
+    base: Option<&str>,
+    copilot: bool,
+    json: bool,
+) -> Result<Option<(CodeAnalysis, Option<TokenUsage>)>> {
+    let (diff, files) = match base {
+        Some(base) => GitManager::get_branch_diff(base)?,
+        None => (
+            GitManager::get_staged_diff(&[], false)?,
+            GitManager::get_staged_files(&[])?,
+        ),
+    };
+    if diff.trim().is_empty() {
+        return match base {
+            Some(_) => Ok(None),
+            None => Err(GitAiError::NoStagedChanges),
+        };
+    }
+
+    let max_diff_chars = std::env::var("GIT_AI_MAX_DIFF_CHARS")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(5000);
+    let diff = if diff.len() > max_diff_chars {
+        truncate_at_char_boundary(&diff, max_diff_chars).to_string()
+    } else {
+        diff
+    };
+
+    if copilot && !CopilotCLI::is_available() {
+        return Err(GitAiError::Config(
+            "GitHub Copilot CLI not available. Install with: gh auth login".to_string(),
+        ));
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let pb = ci::spinner();
+    pb.set_message(format!("🔍 Reviewing {} files...", files.len()));
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let (mut analysis, usage) = if copilot {
+        let analysis = CopilotCLI::analyze_code_impact_cached(&diff, &files, json).await;
+        pb.finish_and_clear();
+        (analysis?, None)
+    } else {
+        let ai_client = AIClient::new(config.clone())?;
+        let system_prompt = get_review_system_prompt(&config.locale);
+        let user_prompt = CopilotCLI::analysis_prompt(&diff, &files, json);
+        let response = if json {
+            ai_client
+                .generate_review_json(&system_prompt, &user_prompt)
+                .await
+        } else {
+            ai_client
+                .generate_review(&system_prompt, &user_prompt)
+                .await
+        };
+        pb.finish_and_clear();
+        (
+            CopilotCLI::parse_response(&response?, json),
+            Some(ai_client.usage()),
+        )
+    };
+
+    analysis.owners = codeowners::owners_to_notify(&files);
+    let usage = usage.filter(|_| config.show_usage.unwrap_or(false));
+    Ok(Some((analysis, usage)))
+}
+
+/// `CodeAnalysis` plus its risk score
+pub fn to_json(analysis: &CodeAnalysis) -> serde_json::Value {
+    let mut value = serde_json::to_value(analysis).unwrap_or_default();
+    value["risk_score"] = analysis.risk_score().into();
+    value
+}
+
+pub fn empty_analysis() -> serde_json::Value {
+    to_json(&CodeAnalysis {
+        impact_summary: String::new(),
+        potential_issues: Vec::new(),
+        affected_areas: Vec::new(),
+        test_recommendations: Vec::new(),
+        owners: Vec::new(),
+        parsed: true,
+    })
+}
+
+/// Fail when `fail_on` is reached, or cannot be checked because the
+/// analysis did not follow the requested format
+pub fn check_risk(analysis: &CodeAnalysis, fail_on: Option<Severity>) -> Result<()> {
+    let Some(threshold) = fail_on else {
+        return Ok(());
+    };
+    if !analysis.parsed {
+        return Err(GitAiError::Other(format!(
+            "The analysis has no RISKS section, so risks at {} severity cannot be ruled out",
+            threshold.as_str()
+        )));
+    }
+    match analysis.issues_at_least(threshold) {
+        0 => Ok(()),
+        count => Err(GitAiError::RiskThreshold(format!(
+            "{} risk(s) at {} severity or above",
+            count,
+            threshold.as_str()
+        ))),
+    }
+}
+
+/// Print an impact analysis, risks grouped by file and most severe first
+pub fn print_analysis(analysis: &CodeAnalysis) {
+    println!("📊 Impact Analysis:");
+    println!("   {}\n", analysis.impact_summary);
+
+    if !analysis.potential_issues.is_empty() {
+        let mut issues: Vec<_> = analysis.potential_issues.iter().collect();
+        // Files in the order they are first mentioned, general risks last
+        let mut files: Vec<Option<&str>> = Vec::new();
+        for issue in &issues {
+            if !files.contains(&issue.file.as_deref()) {
+                files.push(issue.file.as_deref());
+            }
+        }
+        files.sort_by_key(|file| file.is_none());
+        issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+
+        println!("⚠️  Potential Risks:");
+        for file in files {
+            println!("   {}", file.unwrap_or("(general)"));
+            for issue in issues.iter().filter(|issue| issue.file.as_deref() == file) {
+                let icon = match issue.severity {
+                    Severity::High => "🔴",
+                    Severity::Medium => "🟡",
+                    Severity::Low => "🔵",
+                };
+                let line = issue
+                    .line
+                    .map(|line| format!("line {}: ", line))
+                    .unwrap_or_default();
+                println!(
+                    "     {} {:<6} {}{}",
+                    icon,
+                    issue.severity.as_str(),
+                    line,
+                    issue.message
+                );
+            }
+        }
+        println!();
+    }
+
+    if !analysis.affected_areas.is_empty() {
+        println!("🔗 Affected Areas:");
+        for area in &analysis.affected_areas {
+            println!("   • {}", area);
+        }
+        println!();
+    }
+
+    if !analysis.test_recommendations.is_empty() {
+        println!("✅ Test Recommendations:");
+        for test in &analysis.test_recommendations {
+            println!("   • {}", test);
+        }
+        println!();
+    }
+
+    if !analysis.owners.is_empty() {
+        println!("👥 Owners to notify: {}\n", analysis.owners.join(", "));
+    }
+
+    println!("🎯 Risk score: {}/100\n", analysis.risk_score());
+}
+
+fn get_review_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => format!(
+            "{} 请用中文撰写各条内容，但保持 IMPACT/RISKS/AFFECTED/TESTS 标题、`[high|medium|low]` 严重级别标记和文件路径原样不变。只报告 diff 中能看出的问题。",
+            ANALYSIS_ROLE
+        ),
+        _ => format!(
+            "{} Report only problems the diff actually shows, and follow the requested format exactly.",
+            ANALYSIS_ROLE
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pre_commit_policy_defaults_to_high() {
+        assert_eq!(pre_commit_threshold(None), Some(Severity::High));
+        assert_eq!(pre_commit_threshold(Some("Medium")), Some(Severity::Medium));
+        assert_eq!(pre_commit_threshold(Some("low")), Some(Severity::Low));
+        assert_eq!(pre_commit_threshold(Some("off")), None);
+    }
+
+    #[test]
+    fn fail_on_needs_a_parsed_analysis() {
+        let free_text = CopilotCLI::parse_analysis("Looks fine to me.");
+        assert!(check_risk(&free_text, None).is_ok());
+        assert!(check_risk(&free_text, Some(Severity::High)).is_err());
+
+        let clean = CopilotCLI::parse_analysis("IMPACT: x\nRISKS:\nAFFECTED:\nTESTS:");
+        assert!(check_risk(&clean, Some(Severity::High)).is_ok());
+    }
+}
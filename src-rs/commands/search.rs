@@ -0,0 +1,221 @@
+//! `git-ai search`: rank commits by how close their message, touched paths
+//! and diff are to a query in embedding space, then have the model say in
+//! one line why each match is relevant.
+
+use crate::error::Result;
+use crate::utils::ai::{language_name, AIClient};
+use crate::utils::commit_index::{self, CommitIndex};
+use crate::utils::{ci, ConfigManager, GitManager};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Commits embedded per `/embeddings` request
+const EMBED_BATCH: usize = 64;
+
+/// Flags of `git-ai search`
+#[derive(Debug, Default)]
+pub struct SearchOptions {
+    pub query: String,
+    /// Results to show
+    pub num: usize,
+    /// Newest commits searched (and indexed)
+    pub limit: usize,
+    pub json: bool,
+}
+
+/// One ranked commit
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    hash: String,
+    date: String,
+    subject: String,
+    score: f32,
+    explanation: Option<String>,
+}
+
+pub async fn run(options: SearchOptions) -> Result<()> {
+    let SearchOptions {
+        query,
+        num,
+        limit,
+        json,
+    } = options;
+    let commits = GitManager::get_commit_records(limit)?;
+    if commits.is_empty() {
+        println!("ℹ️  No commits to search");
+        return Ok(());
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let mut index = CommitIndex::open(&ai_client.embedding_model()?)?;
+
+    let missing: Vec<_> = commits
+        .iter()
+        .filter(|commit| index.get(&commit.hash).is_none())
+        .collect();
+    if !missing.is_empty() {
+        let hashes: Vec<&str> = missing.iter().map(|c| c.hash.as_str()).collect();
+        let patches = GitManager::get_commit_patches(&hashes)?;
+        let documents: Vec<String> = missing
+            .iter()
+            .map(|c| {
+                let patch = patches.get(&c.hash).map(String::as_str).unwrap_or("");
+                commit_index::document(c, patch)
+            })
+            .collect();
+        // One question for the whole history rather than one per batch
+        ai_client.confirm_embedding_size(&documents)?;
+
+        let pb = ci::spinner();
+        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        let indexed: Result<()> = async {
+            for (done, batch) in documents.chunks(EMBED_BATCH).enumerate() {
+                pb.set_message(format!(
+                    "📚 Indexing commits ({}/{})...",
+                    done * EMBED_BATCH,
+                    missing.len()
+                ));
+                let embeddings = ai_client.embed(batch).await?;
+                let commits = &missing[done * EMBED_BATCH..];
+                for (commit, embedding) in commits.iter().zip(embeddings) {
+                    index.insert(&commit.hash, embedding);
+                }
+            }
+            Ok(())
+        }
+        .await;
+        pb.finish_and_clear();
+        // Written once, keeping what was indexed even if a later batch failed
+        index.save()?;
+        indexed?;
+    }
+
+    let query_embedding = ai_client
+        .embed(std::slice::from_ref(&query))
+        .await?
+        .pop()
+        .unwrap_or_default();
+    let mut ranked: Vec<SearchResult> = commits
+        .iter()
+        .filter_map(|commit| {
+            let score = commit_index::cosine(&query_embedding, index.get(&commit.hash)?);
+            Some(SearchResult {
+                hash: commit.hash.clone(),
+                date: commit.date.clone(),
+                subject: commit
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                score,
+                explanation: None,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(num);
+
+    let pb = ci::spinner();
+    pb.set_message("🔎 Explaining matches...");
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let messages: HashMap<&str, &str> = commits
+        .iter()
+        .map(|c| (c.hash.as_str(), c.message.as_str()))
+        .collect();
+    let listing: Vec<String> = ranked
+        .iter()
+        .map(|result| {
+            format!(
+                "[{}]\n{}",
+                &result.hash[..7],
+                messages.get(result.hash.as_str()).unwrap_or(&"")
+            )
+        })
+        .collect();
+    let explanations = ai_client
+        .generate_review(
+            &get_search_system_prompt(&config.locale),
+            &format!("Query: {}\n\nCommits:\n{}", query, listing.join("\n\n")),
+        )
+        .await;
+    pb.finish_and_clear();
+    // Ranking stands on its own; explanations are a bonus
+    match explanations {
+        Ok(text) => {
+            let explanations = parse_explanations(&text);
+            for result in &mut ranked {
+                result.explanation = explanations.get(&result.hash[..7]).cloned();
+            }
+        }
+        Err(e) => eprintln!("⚠️  Could not explain the matches: {}", e),
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&ranked)?);
+        return Ok(());
+    }
+    println!("🔎 Commits matching \"{}\":\n", query);
+    for (rank, result) in ranked.iter().enumerate() {
+        println!(
+            "{:>2}. {} {}  {}  ({:.2})",
+            rank + 1,
+            &result.hash[..7],
+            result.date,
+            result.subject,
+            result.score
+        );
+        if let Some(explanation) = &result.explanation {
+            println!("    {}", explanation);
+        }
+    }
+    if config.show_usage.unwrap_or(false) {
+        println!("\n{}", ai_client.usage().summary());
+    }
+    Ok(())
+}
+
+fn get_search_system_prompt(locale: &str) -> String {
+    format!(
+        "You explain why commits match a search over a repository's history. \
+         For every commit listed, reply with exactly one line: `<hash>: <why it is relevant to the query>`, \
+         at most 20 words, using the 7-character hash shown in brackets. \
+         Say plainly when a commit is only loosely related. Write in {}.",
+        language_name(locale)
+    )
+}
+
+/// `<hash>: <explanation>` lines by hash (brackets and list markers are tolerated)
+fn parse_explanations(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim().trim_start_matches(['-', '*', ' ']);
+            let (hash, explanation) = line.split_once(':')?;
+            let hash = hash.trim().trim_matches(['[', ']', '`']);
+            let explanation = explanation.trim();
+            (hash.len() == 7
+                && hash.chars().all(|c| c.is_ascii_hexdigit())
+                && !explanation.is_empty())
+            .then(|| (hash.to_string(), explanation.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_explanations;
+
+    #[test]
+    fn reads_explanations_by_hash() {
+        let explanations = parse_explanations(
+            "a1b2c3d: Adds the retry loop the query asks about\n- [0f0f0f0]: Loosely related\nnote: ignored\n`deadbee`:\n",
+        );
+        assert_eq!(explanations.len(), 2);
+        assert_eq!(
+            explanations["a1b2c3d"],
+            "Adds the retry loop the query asks about"
+        );
+        assert_eq!(explanations["0f0f0f0"], "Loosely related");
+    }
+}
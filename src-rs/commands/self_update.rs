@@ -0,0 +1,51 @@
+use crate::commands::release::confirm;
+use crate::error::Result;
+use crate::utils::update::{self, CURRENT_VERSION};
+
+/// Flags of `git-ai self-update`
+#[derive(Debug, Default)]
+pub struct SelfUpdateOptions {
+    /// Only report whether a newer version exists
+    pub check: bool,
+    pub yes: bool,
+}
+
+pub async fn run(options: SelfUpdateOptions) -> Result<()> {
+    println!("🔍 Checking for updates...");
+    let release = update::fetch_latest_release().await?;
+    let latest = release.version().to_string();
+    if !update::is_newer(&latest, CURRENT_VERSION) {
+        println!("✅ git-ai {} is the latest version", CURRENT_VERSION);
+        return Ok(());
+    }
+    println!(
+        "🆕 git-ai {} is available (you have {})",
+        latest, CURRENT_VERSION
+    );
+    if options.check {
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe()?;
+    if exe.components().any(|c| c.as_os_str() == "node_modules") {
+        println!("⚠️  This copy was installed with npm; upgrade it with:");
+        println!("   npm install -g @dongowu/git-ai-cli@latest");
+        return Ok(());
+    }
+    if !confirm(
+        options.yes,
+        &format!("Replace {} with version {}?", exe.display(), latest),
+    )? {
+        println!("👋 Update cancelled");
+        return Ok(());
+    }
+
+    println!("⬇️  Downloading git-ai {}...", latest);
+    let path = update::install(&release).await?;
+    println!(
+        "✅ Updated {} to {} (checksum verified)",
+        path.display(),
+        latest
+    );
+    Ok(())
+}
@@ -0,0 +1,488 @@
+//! `git-ai serve`: a local HTTP API for editor plugins and internal tools,
+//! so they can generate messages, reviews and reports without starting a
+//! process (and reading config and keychain) for every request.
+//!
+//! Every request names the repository it works on. Commands run in the
+//! repository's directory, so requests are handled one at a time.
+//!
+//! Any local process can reach the port, so every endpoint but `/health`
+//! needs `Authorization: Bearer <token>` with the token printed at startup,
+//! and a `Host` other than the loopback address (DNS rebinding) is refused.
+
+use crate::commands::{msg, report, review};
+use crate::error::{GitAiError, Result};
+use crate::types::CommitMessageOutput;
+use crate::utils::{crypto, GitManager};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+/// Largest request accepted (headers and body)
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+/// A client that stops sending must not hold a connection open
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Flags of `git-ai serve`
+#[derive(Debug, Default)]
+pub struct ServeOptions {
+    pub port: u16,
+    /// Bearer token clients must send; `None` uses `GIT_AI_SERVE_TOKEN`,
+    /// or a random one
+    pub token: Option<String>,
+}
+
+/// What every request is checked against
+#[derive(Debug)]
+struct Access {
+    port: u16,
+    token: String,
+}
+
+impl Access {
+    /// `Host` names this server: the loopback address or `localhost`, on
+    /// our port when one is given
+    fn allows_host(&self, host: &str) -> bool {
+        let (name, port) = match host.rsplit_once(':') {
+            Some((name, port)) if !port.contains(']') => (name, Some(port)),
+            _ => (host, None),
+        };
+        matches!(name, "127.0.0.1" | "localhost" | "[::1]")
+            && port.is_none_or(|port| port == self.port.to_string())
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), self.token.as_bytes()))
+    }
+}
+
+/// Compare without returning early, so timing does not reveal the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Parsed HTTP request
+#[derive(Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// Lower-cased names
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageRequest {
+    repo: PathBuf,
+    num: Option<usize>,
+    locale: Option<String>,
+    style: Option<String>,
+    #[serde(default)]
+    working_tree: bool,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewRequest {
+    repo: PathBuf,
+    base: Option<String>,
+    #[serde(default)]
+    copilot: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportRequest {
+    repo: PathBuf,
+    days: Option<usize>,
+    #[serde(default)]
+    from_last_tag: bool,
+    from_tag: Option<String>,
+    to_ref: Option<String>,
+    #[serde(default)]
+    by_author: bool,
+    #[serde(default)]
+    stats: bool,
+    #[serde(default)]
+    no_merges: bool,
+}
+
+pub async fn run(options: ServeOptions) -> Result<()> {
+    let token = options
+        .token
+        .or_else(|| std::env::var("GIT_AI_SERVE_TOKEN").ok())
+        .filter(|token| !token.trim().is_empty())
+        .unwrap_or_else(crypto::random_token);
+    let access = Arc::new(Access {
+        port: options.port,
+        token: token.trim().to_string(),
+    });
+
+    let listener = TcpListener::bind(("127.0.0.1", options.port))
+        .await
+        .map_err(|e| GitAiError::Other(format!("Cannot listen on port {}: {}", options.port, e)))?;
+    println!(
+        "🛰️  git-ai API listening on http://127.0.0.1:{}",
+        options.port
+    );
+    println!("   Authorization: Bearer {}", access.token);
+    println!("   GET  /health");
+    println!("   POST /message  {{\"repo\": \"/path/to/repo\", \"num\": 3}}");
+    println!("   POST /review   {{\"repo\": \"/path/to/repo\", \"base\": \"main\"}}");
+    println!("   POST /report   {{\"repo\": \"/path/to/repo\", \"days\": 7}}");
+    println!("   Press Ctrl+C to stop\n");
+
+    let busy = Arc::new(Mutex::new(()));
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let busy = Arc::clone(&busy);
+        let access = Arc::clone(&access);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, busy, &access).await {
+                eprintln!("⚠️  Connection failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    busy: Arc<Mutex<()>>,
+    access: &Access,
+) -> Result<()> {
+    let (status, body) = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => {
+            let (status, body) = match reject(&request, access) {
+                Some(rejection) => rejection,
+                None => {
+                    let _guard = busy.lock().await;
+                    respond(&request).await
+                }
+            };
+            println!("{} {} -> {}", request.method, request.path, status);
+            (status, body)
+        }
+        Ok(Err(e)) => (400, e.to_json()),
+        Err(_) => (
+            408,
+            GitAiError::InvalidArgument("Timed out reading the request".to_string()).to_json(),
+        ),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        if let Some(request) = parse_request(&buffer)? {
+            return Ok(request);
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err(GitAiError::InvalidArgument("Request too large".to_string()));
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(GitAiError::InvalidArgument(
+                "Connection closed before the request was complete".to_string(),
+            ));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// The request in `buffer`, or `None` while its headers or body are incomplete
+pub fn parse_request(buffer: &[u8]) -> Result<Option<Request>> {
+    let Some(header_end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return Ok(None);
+    };
+    let head = String::from_utf8_lossy(&buffer[..header_end]);
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(GitAiError::InvalidArgument(
+            "Malformed request line".to_string(),
+        ));
+    };
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .map(|(_, value)| {
+            value
+                .parse::<usize>()
+                .map_err(|_| GitAiError::InvalidArgument("Invalid Content-Length".to_string()))
+        })
+        .transpose()?
+        .unwrap_or(0);
+    if content_length > MAX_REQUEST_BYTES {
+        return Err(GitAiError::InvalidArgument("Request too large".to_string()));
+    }
+    let body_start = header_end + 4;
+    if buffer.len() < body_start + content_length {
+        return Ok(None);
+    }
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        // The query string is not used
+        path: path.split('?').next().unwrap_or_default().to_string(),
+        headers,
+        body: buffer[body_start..body_start + content_length].to_vec(),
+    }))
+}
+
+/// Status and error for a request that must not be served
+fn reject(request: &Request, access: &Access) -> Option<(u16, serde_json::Value)> {
+    let forbidden = |message: &str| {
+        Some((
+            403,
+            GitAiError::InvalidArgument(message.to_string()).to_json(),
+        ))
+    };
+    // Browsers send Origin; a web page must not drive a local git tool
+    if request.header("origin").is_some() {
+        return forbidden("Cross-origin requests are not allowed");
+    }
+    if !request
+        .header("host")
+        .is_some_and(|host| access.allows_host(host))
+    {
+        return forbidden("Host must be 127.0.0.1 or localhost");
+    }
+    if request.path != "/health" && !access.authorized(request) {
+        return Some((
+            401,
+            GitAiError::InvalidArgument(
+                "Send the token printed by git-ai serve as Authorization: Bearer <token>"
+                    .to_string(),
+            )
+            .to_json(),
+        ));
+    }
+    None
+}
+
+async fn respond(request: &Request) -> (u16, serde_json::Value) {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => (
+            200,
+            serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+        ),
+        ("POST", "/message" | "/review" | "/report") => {
+            let is_json = request
+                .header("content-type")
+                .is_some_and(|value| value.starts_with("application/json"));
+            if !is_json {
+                return (
+                    415,
+                    GitAiError::InvalidArgument(
+                        "Send the request body as Content-Type: application/json".to_string(),
+                    )
+                    .to_json(),
+                );
+            }
+            match route(&request.path, &request.body).await {
+                Ok(value) => (200, value),
+                Err(e) => (status_of(&e), e.to_json()),
+            }
+        }
+        (_, "/health" | "/message" | "/review" | "/report") => (
+            405,
+            GitAiError::InvalidArgument(format!("{} is not allowed here", request.method))
+                .to_json(),
+        ),
+        _ => (
+            404,
+            GitAiError::InvalidArgument(format!("Unknown endpoint {}", request.path)).to_json(),
+        ),
+    }
+}
+
+async fn route(path: &str, body: &[u8]) -> Result<serde_json::Value> {
+    match path {
+        "/message" => {
+            let request: MessageRequest = serde_json::from_slice(body)?;
+            enter_repo(&request.repo)?;
+            let generation = msg::generate(
+                msg::MsgOptions {
+                    num: request.num,
+                    locale: request.locale,
+                    style: request.style,
+                    working_tree: request.working_tree,
+                    paths: request.paths,
+                    ..Default::default()
+                },
+                None,
+            )
+            .await?;
+            Ok(serde_json::to_value(CommitMessageOutput {
+                messages: generation.messages,
+                staged_files: generation.staged_files,
+                truncated: generation.truncated,
+                ignored_files: vec![],
+                follow_ups: generation.follow_ups,
+            })?)
+        }
+        "/review" => {
+            let request: ReviewRequest = serde_json::from_slice(body)?;
+            enter_repo(&request.repo)?;
+            Ok(
+                match review::analyze(request.base.as_deref(), request.copilot, true).await? {
+                    Some((analysis, _)) => review::to_json(&analysis),
+                    None => review::empty_analysis(),
+                },
+            )
+        }
+        _ => {
+            let request: ReportRequest = serde_json::from_slice(body)?;
+            enter_repo(&request.repo)?;
+            let generated = report::generate(report::ReportOptions {
+                days: request.days.unwrap_or(7),
+                from_last_tag: request.from_last_tag,
+                from_tag: request.from_tag,
+                to_ref: request.to_ref,
+                by_author: request.by_author,
+                stats: request.stats,
+                no_merges: request.no_merges,
+                ..Default::default()
+            })
+            .await?;
+            let (scope, report) = generated.unzip();
+            Ok(serde_json::json!({ "scope": scope, "report": report }))
+        }
+    }
+}
+
+/// Work in `repo` for the rest of the request
+fn enter_repo(repo: &Path) -> Result<()> {
+    // Relative paths would depend on the previous request's repository
+    if !repo.is_absolute() {
+        return Err(GitAiError::InvalidArgument(format!(
+            "repo must be an absolute path, got {}",
+            repo.display()
+        )));
+    }
+    GitManager::change_dir(repo)?;
+    if !GitManager::is_in_git_repo()? {
+        return Err(GitAiError::NotInGitRepo);
+    }
+    Ok(())
+}
+
+fn status_of(error: &GitAiError) -> u16 {
+    match error {
+        GitAiError::InvalidArgument(_)
+        | GitAiError::Json(_)
+        | GitAiError::NotInGitRepo
+        | GitAiError::NoStagedChanges
+        | GitAiError::NoChanges => 400,
+        GitAiError::Ai(_) | GitAiError::Http(_) => 502,
+        _ => 500,
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        415 => "Unsupported Media Type",
+        502 => "Bad Gateway",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_request, reject, Access};
+
+    #[test]
+    fn parses_requests_once_complete() {
+        let raw = b"POST /message?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: 12\r\n\r\n{\"repo\":\".\"}";
+        assert!(parse_request(&raw[..40]).unwrap().is_none());
+        assert!(parse_request(&raw[..raw.len() - 1]).unwrap().is_none());
+
+        let request = parse_request(raw).unwrap().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/message");
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.body, b"{\"repo\":\".\"}");
+
+        assert!(parse_request(b"\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn requires_loopback_host_and_token() {
+        let access = Access {
+            port: 7878,
+            token: "secret".to_string(),
+        };
+        let status = |raw: &str| {
+            let request = parse_request(raw.as_bytes()).unwrap().unwrap();
+            reject(&request, &access).map(|(status, _)| status)
+        };
+
+        assert_eq!(
+            status("GET /health HTTP/1.1\r\nHost: 127.0.0.1:7878\r\n\r\n"),
+            None
+        );
+        assert_eq!(
+            status("POST /message HTTP/1.1\r\nHost: localhost:7878\r\n\r\n"),
+            Some(401)
+        );
+        assert_eq!(
+            status("POST /message HTTP/1.1\r\nHost: localhost:7878\r\nAuthorization: Bearer nope\r\n\r\n"),
+            Some(401)
+        );
+        assert_eq!(
+            status("POST /message HTTP/1.1\r\nHost: localhost:7878\r\nAuthorization: Bearer secret\r\n\r\n"),
+            None
+        );
+        // DNS rebinding: a page served from attacker.example resolves to us
+        assert_eq!(
+            status("GET /health HTTP/1.1\r\nHost: attacker.example:7878\r\n\r\n"),
+            Some(403)
+        );
+        assert_eq!(
+            status("GET /health HTTP/1.1\r\nHost: 127.0.0.1:9999\r\n\r\n"),
+            Some(403)
+        );
+        assert_eq!(status("GET /health HTTP/1.1\r\n\r\n"), Some(403));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::agent_skills::{self, Skill, SkillSource, SkillStatus};
+use crate::utils::ConfigManager;
+
+pub async fn run_list(json: bool) -> Result<()> {
+    let skills = agent_skills::discover()?;
+    let config = ConfigManager::read_global_config()?;
+
+    if json {
+        let list: Vec<serde_json::Value> = skills
+            .iter()
+            .map(|skill| {
+                serde_json::json!({
+                    "name": skill.name,
+                    "source": skill.source,
+                    "status": skill.status(&config),
+                    "description": skill.descriptor.description,
+                    "command": skill.descriptor.command,
+                    "path": skill.path,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&list)?);
+        return Ok(());
+    }
+
+    if skills.is_empty() {
+        println!("ℹ️  No skills found. Add TOML descriptors to:");
+        println!("   {}", agent_skills::global_skills_dir()?.display());
+        if let Ok(dir) = agent_skills::repo_skills_dir() {
+            println!("   {}", dir.display());
+        }
+        return Ok(());
+    }
+
+    println!("🧩 Agent skills:\n");
+    for skill in &skills {
+        let status = match skill.status(&config) {
+            SkillStatus::Enabled => "✅ enabled",
+            SkillStatus::Disabled => "⏸️  disabled",
+            SkillStatus::Untrusted => "🔒 not trusted (run 'git-ai skills enable')",
+            SkillStatus::Unsupported => "⚠️  unsupported (WASM skills are not available yet)",
+        };
+        let source = match skill.source {
+            SkillSource::Global => "global",
+            SkillSource::Repo => "repo",
+        };
+        println!("  {} [{}] {}", skill.name, source, status);
+        if !skill.descriptor.description.is_empty() {
+            println!("    {}", skill.descriptor.description);
+        }
+        if let Some(command) = &skill.descriptor.command {
+            println!("    $ {}", command);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_enable(name: &str) -> Result<()> {
+    let skill = find(name)?;
+    if skill.descriptor.command.is_none() {
+        return Err(GitAiError::InvalidArgument(format!(
+            "Skill '{}' has no command; WASM skills are not available yet",
+            name
+        )));
+    }
+
+    let mut config = ConfigManager::read_global_config()?;
+    let mut disabled = config.disabled_skills.take().unwrap_or_default();
+    disabled.retain(|entry| entry != name);
+    config.disabled_skills = Some(disabled).filter(|list| !list.is_empty());
+    if skill.source == SkillSource::Repo {
+        // Trust this exact descriptor and its scripts; a later edit needs a new `enable`
+        let mut enabled = config.enabled_skills.take().unwrap_or_default();
+        let prefix = format!("{}@", name);
+        enabled.retain(|entry| !entry.starts_with(&prefix));
+        enabled.push(skill.fingerprint());
+        config.enabled_skills = Some(enabled);
+        println!(
+            "⚠️  This runs on every agent analysis:\n    $ {}",
+            skill.descriptor.command.as_deref().unwrap_or_default()
+        );
+    }
+    ConfigManager::write_global_config(&config)?;
+
+    println!("✅ Enabled skill {}", name);
+    Ok(())
+}
+
+pub async fn run_disable(name: &str) -> Result<()> {
+    find(name)?;
+
+    let mut config = ConfigManager::read_global_config()?;
+    let mut disabled = config.disabled_skills.take().unwrap_or_default();
+    if !disabled.iter().any(|entry| entry == name) {
+        disabled.push(name.to_string());
+    }
+    config.disabled_skills = Some(disabled);
+    if let Some(enabled) = config.enabled_skills.as_mut() {
+        let prefix = format!("{}@", name);
+        enabled.retain(|entry| !entry.starts_with(&prefix));
+    }
+    config.enabled_skills = config.enabled_skills.take().filter(|list| !list.is_empty());
+    ConfigManager::write_global_config(&config)?;
+
+    println!("⏸️  Disabled skill {}", name);
+    Ok(())
+}
+
+fn find(name: &str) -> Result<Skill> {
+    agent_skills::discover()?
+        .into_iter()
+        .find(|skill| skill.name == name)
+        .ok_or_else(|| {
+            GitAiError::InvalidArgument(format!(
+                "Unknown skill '{}'. Run 'git-ai skills list' to see available skills.",
+                name
+            ))
+        })
+}
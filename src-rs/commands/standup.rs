@@ -0,0 +1,164 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ConfigManager, GitManager};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+
+/// Flags of `git-ai standup`
+#[derive(Debug, Default)]
+pub struct StandupOptions {
+    /// Look back this many days instead of "since the previous working day"
+    pub days: Option<usize>,
+    /// Also list local branches with commits not yet on the base branch
+    pub branches: bool,
+    /// Base branch for `branches` (default: main, or master)
+    pub base: Option<String>,
+}
+
+pub async fn run(options: StandupOptions) -> Result<()> {
+    let StandupOptions {
+        days,
+        branches,
+        base,
+    } = options;
+
+    let email = GitManager::get_config_value("user.email")?.ok_or_else(|| {
+        GitAiError::Config(
+            "git user.email is not set; standup only lists your own commits".to_string(),
+        )
+    })?;
+
+    let today = Local::now().date_naive();
+    let since = match days {
+        Some(days) => today - Duration::days(days as i64),
+        None => previous_working_day(today),
+    };
+    let commits = GitManager::get_commits_by_author_since(&email, &start_of_day(since))?;
+
+    let open_branches = if branches {
+        let base = match base {
+            Some(base) => base,
+            None => GitManager::get_default_branch()?.ok_or_else(|| {
+                GitAiError::InvalidArgument(
+                    "Cannot find a main or master branch. Use --base <branch>.".to_string(),
+                )
+            })?,
+        };
+        GitManager::get_branches_ahead_of(&base)?
+            .into_iter()
+            .map(|(branch, ahead)| format!("- {} ({} commits ahead of {})", branch, ahead, base))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if commits.is_empty() && open_branches.is_empty() {
+        println!("ℹ️  No commits by {} since {}", email, since);
+        return Ok(());
+    }
+
+    println!(
+        "☕ Preparing standup from {} commits since {}...\n",
+        commits.len(),
+        since
+    );
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+
+    let mut user_prompt = format!(
+        "Today: {} ({})\nMy commits since {} (all local branches):\n{}",
+        today,
+        today.weekday(),
+        since,
+        if commits.is_empty() {
+            "(none)".to_string()
+        } else {
+            commits.join("\n")
+        }
+    );
+    if !open_branches.is_empty() {
+        user_prompt.push_str(&format!(
+            "\n\nOpen branches not merged yet:\n{}",
+            open_branches.join("\n")
+        ));
+    }
+
+    let note = ai_client
+        .generate_commit_message(&get_standup_system_prompt(&config.locale), &user_prompt)
+        .await?;
+    println!("{}", note.trim());
+
+    Ok(())
+}
+
+/// Start of the window: yesterday, or Friday when today is Monday (or the weekend)
+fn previous_working_day(today: NaiveDate) -> NaiveDate {
+    let back = match today.weekday() {
+        Weekday::Mon => 3,
+        Weekday::Sun => 2,
+        _ => 1,
+    };
+    today - Duration::days(back)
+}
+
+/// `--since` value covering the whole day: git reads a bare date as that day
+/// at the current time of day
+fn start_of_day(date: NaiveDate) -> String {
+    format!("{} 00:00", date)
+}
+
+fn get_standup_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => {
+            r#"你帮开发者准备每日站会发言。根据他本人的提交（以及尚未合并的分支）输出一段简短的中文笔记：
+
+**昨天**
+- 完成了什么（合并同类提交，用一句话描述结果，不要抄提交哈希）
+
+**今天**
+- 根据未完成的分支或进行中的工作推测接下来要做的事，用"计划"语气；无法推测时写"待定"
+
+**阻碍**
+- 只有在提交中能看出问题（如回滚、反复修复）时才写，否则写"无"
+
+保持口语化，总共不超过 8 行。"#
+                .to_string()
+        }
+        _ => {
+            r#"You help a developer prepare their daily standup. From their own commits (and unmerged branches) write a short note:
+
+**Yesterday**
+- What got done (merge related commits into one outcome; no commit hashes)
+
+**Today**
+- Likely next steps inferred from open branches or unfinished work, phrased as a plan; say "TBD" when unclear
+
+**Blockers**
+- Only when the commits hint at trouble (reverts, repeated fixes); otherwise "None"
+
+Keep it conversational and under 8 lines in total."#
+                .to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{previous_working_day, start_of_day};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn monday_looks_back_to_friday() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        // 2024-05-06 is a Monday
+        assert_eq!(previous_working_day(day(6)), day(3));
+        assert_eq!(previous_working_day(day(7)), day(6));
+        assert_eq!(previous_working_day(day(5)), day(3));
+    }
+
+    #[test]
+    fn window_starts_at_midnight() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 3).unwrap();
+        assert_eq!(start_of_day(day), "2024-05-03 00:00");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::Result;
+use crate::utils::ai::AIClient;
+use crate::utils::{truncate_at_char_boundary, ConfigManager, GitManager};
+use serde::Serialize;
+
+/// Per-stash diff budget; one line of description needs far less than a commit
+const MAX_STASH_DIFF_CHARS: usize = 3000;
+
+#[derive(Debug, Serialize)]
+struct StashSummary {
+    stash: String,
+    subject: String,
+    description: String,
+}
+
+pub async fn run(json_output: bool) -> Result<()> {
+    let entries = GitManager::get_stash_entries()?;
+    if entries.is_empty() {
+        if json_output {
+            println!("[]");
+        } else {
+            println!("ℹ️  No stash entries");
+        }
+        return Ok(());
+    }
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let system_prompt = get_stash_system_prompt(&config.locale);
+
+    let mut summaries = Vec::new();
+    for (stash, subject) in entries {
+        let diff = GitManager::get_stash_diff(&stash)?;
+        let description = if diff.trim().is_empty() {
+            "(empty stash)".to_string()
+        } else {
+            let user_prompt = format!(
+                "Stash message: {}\n\nDescribe this stashed change:\n\n{}",
+                subject,
+                truncate_at_char_boundary(&diff, MAX_STASH_DIFF_CHARS)
+            );
+            // One failing entry should not hide the rest of the list
+            match ai_client
+                .generate_commit_message(&system_prompt, &user_prompt)
+                .await
+            {
+                Ok(text) => first_line(&text),
+                Err(e) => format!("(description unavailable: {})", e),
+            }
+        };
+
+        if !json_output {
+            println!("{}  {}", stash, description);
+            println!("   {}", subject);
+        }
+        summaries.push(StashSummary {
+            stash,
+            subject,
+            description,
+        });
+    }
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+    }
+
+    Ok(())
+}
+
+/// Models sometimes add a body or quotes despite the prompt; keep one line
+fn first_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+        .trim_matches(|c| c == '"' || c == '`')
+        .to_string()
+}
+
+fn get_stash_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => "你是一个 git stash 描述助手。根据 stash 的 diff，用一句话（不超过 20 字）说明其中保存了什么改动，方便用户在恢复前辨认。只输出这一句话，不要加引号或前缀。"
+            .to_string(),
+        _ => "You describe git stash entries. Based on the stashed diff, write ONE line (at most 12 words) saying what work it contains, so the user can recognize it before popping. Output only that line, without quotes or prefixes."
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::first_line;
+
+    #[test]
+    fn first_line_skips_blank_lines_and_quotes() {
+        assert_eq!(
+            first_line("\n\"Half-done login form validation\"\n\nmore"),
+            "Half-done login form validation"
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! `git-ai stats`: how history splits into Conventional Commits types and
+//! scopes, and how many messages miss the convention, over time.
+
+use crate::error::Result;
+use crate::utils::conventional::{ConventionalHeader, MessageRules};
+use crate::utils::git::{CommitFilter, LogScope};
+use crate::utils::{ConfigManager, GitManager};
+use chrono::{Duration, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Scopes listed in the breakdown
+const MAX_SCOPES: usize = 10;
+
+/// Windows up to this many days are bucketed by week, longer ones by month
+const WEEKLY_UP_TO_DAYS: usize = 84;
+
+/// Width of the bars in the text output
+const BAR_WIDTH: usize = 20;
+
+/// Flags of `git-ai stats`
+#[derive(Debug, Default)]
+pub struct StatsOptions {
+    pub days: usize,
+    pub json: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Stats {
+    days: usize,
+    commits: usize,
+    conventional: usize,
+    non_conforming: usize,
+    non_conforming_percent: f64,
+    breaking: usize,
+    types: Vec<Count>,
+    scopes: Vec<Count>,
+    trend: Vec<Period>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Count {
+    name: String,
+    count: usize,
+    percent: f64,
+}
+
+/// Commits of one week or month of the window, oldest period first
+#[derive(Debug, PartialEq, Serialize)]
+struct Period {
+    start: String,
+    commits: usize,
+    conventional: usize,
+}
+
+pub async fn run(options: StatsOptions) -> Result<()> {
+    let StatsOptions { days, json } = options;
+    let config = ConfigManager::get_merged_config().unwrap_or_default();
+    let filter = CommitFilter::from_config(&config, true);
+    let commits: Vec<(NaiveDate, String)> =
+        GitManager::get_authored_commits(&LogScope::Days(days), true)?
+            .into_iter()
+            .filter(|(author, line)| !filter.excludes(author, line))
+            .filter_map(|(_, line)| {
+                // <hash> <date> <subject>
+                let mut parts = line.splitn(3, ' ');
+                let date = parts.nth(1)?.parse().ok()?;
+                Some((date, parts.next().unwrap_or_default().to_string()))
+            })
+            .collect();
+
+    let stats = compute(
+        &commits,
+        &MessageRules::from_config(&config),
+        Local::now().date_naive(),
+        days,
+    );
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.commits == 0 {
+        println!("ℹ️  No commits in the last {} days", days);
+        return Ok(());
+    }
+    print(&stats);
+    Ok(())
+}
+
+fn print(stats: &Stats) {
+    println!(
+        "📊 {} commits in the last {} days: {} conventional, {} non-conforming ({:.1}%)",
+        stats.commits,
+        stats.days,
+        stats.conventional,
+        stats.non_conforming,
+        stats.non_conforming_percent
+    );
+    if stats.breaking > 0 {
+        println!("   💥 {} breaking changes", stats.breaking);
+    }
+
+    if !stats.types.is_empty() {
+        println!("\n🏷️  Types:");
+        for count in &stats.types {
+            println!(
+                "   {:<10} {:>5}  {:>5.1}%  {}",
+                count.name,
+                count.count,
+                count.percent,
+                bar(count.percent / 100.0)
+            );
+        }
+    }
+    if !stats.scopes.is_empty() {
+        println!("\n📦 Top scopes:");
+        for count in &stats.scopes {
+            println!(
+                "   {:<16} {:>5}  {:>5.1}%",
+                count.name, count.count, count.percent
+            );
+        }
+    }
+
+    println!("\n📈 Conventional share over time:");
+    for period in &stats.trend {
+        if period.commits == 0 {
+            println!("   {}  {}     -  (no commits)", period.start, bar(0.0));
+            continue;
+        }
+        let share = period.conventional as f64 / period.commits as f64;
+        println!(
+            "   {}  {}  {:>3.0}%  ({} commits)",
+            period.start,
+            bar(share),
+            share * 100.0,
+            period.commits
+        );
+    }
+}
+
+fn bar(share: f64) -> String {
+    let filled = (share.clamp(0.0, 1.0) * BAR_WIDTH as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+/// Break `commits` (date, subject) down for the `days` before `today`. With
+/// `allowed_types`/`allowed_scopes` configured, headers outside them count
+/// as non-conforming too.
+fn compute(
+    commits: &[(NaiveDate, String)],
+    rules: &MessageRules,
+    today: NaiveDate,
+    days: usize,
+) -> Stats {
+    let period_days = if days <= WEEKLY_UP_TO_DAYS { 7 } else { 30 };
+    let periods = days.div_ceil(period_days).max(1);
+    let mut trend: Vec<Period> = (0..periods)
+        .rev()
+        .map(|index| Period {
+            start: (today - Duration::days(((index + 1) * period_days) as i64 - 1)).to_string(),
+            commits: 0,
+            conventional: 0,
+        })
+        .collect();
+
+    let mut stats = Stats {
+        days,
+        commits: commits.len(),
+        ..Default::default()
+    };
+    let mut types: HashMap<String, usize> = HashMap::new();
+    let mut scopes: HashMap<String, usize> = HashMap::new();
+    for (date, subject) in commits {
+        let header = ConventionalHeader::parse(subject).filter(|_| rules.check(subject).is_ok());
+        // Commits older than the window (clock skew, rebases) only miss the trend
+        let age = (today - *date).num_days().max(0) as usize;
+        let period = trend
+            .len()
+            .checked_sub(1 + age / period_days)
+            .and_then(|index| trend.get_mut(index));
+        if let Some(period) = period {
+            period.commits += 1;
+            period.conventional += usize::from(header.is_some());
+        }
+        let Some(header) = header else {
+            stats.non_conforming += 1;
+            continue;
+        };
+        stats.conventional += 1;
+        if header.breaking {
+            stats.breaking += 1;
+        }
+        *types.entry(header.commit_type.to_lowercase()).or_default() += 1;
+        if let Some(scope) = header.scope {
+            *scopes.entry(scope).or_default() += 1;
+        }
+    }
+
+    stats.non_conforming_percent = percent(stats.non_conforming, stats.commits);
+    stats.types = ranked(types, usize::MAX, stats.commits);
+    stats.scopes = ranked(scopes, MAX_SCOPES, stats.commits);
+    stats.trend = trend;
+    stats
+}
+
+/// Share of `total` with one decimal
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (count as f64 * 1000.0 / total as f64).round() / 10.0
+}
+
+/// Most frequent first, ties by name
+fn ranked(counts: HashMap<String, usize>, max: usize, total: usize) -> Vec<Count> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .take(max)
+        .map(|(name, count)| Count {
+            name,
+            count,
+            percent: percent(count, total),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaks_history_down_by_type_scope_and_week() {
+        let today: NaiveDate = "2026-10-16".parse().unwrap();
+        let commits: Vec<(NaiveDate, String)> = [
+            ("2026-10-16", "feat(api): add search"),
+            ("2026-10-15", "fix(api)!: drop v1 route"),
+            ("2026-10-14", "Update readme"),
+            ("2026-10-05", "feat(ui): dark mode"),
+            ("2026-10-04", "wip: stuff"),
+        ]
+        .iter()
+        .map(|(date, subject)| (date.parse().unwrap(), subject.to_string()))
+        .collect();
+
+        let stats = compute(&commits, &MessageRules::default(), today, 14);
+        assert_eq!(stats.commits, 5);
+        assert_eq!(stats.conventional, 4);
+        assert_eq!(stats.non_conforming, 1);
+        assert_eq!(stats.non_conforming_percent, 20.0);
+        assert_eq!(stats.breaking, 1);
+        let types: Vec<(&str, usize)> = stats
+            .types
+            .iter()
+            .map(|c| (c.name.as_str(), c.count))
+            .collect();
+        assert_eq!(types, vec![("feat", 2), ("fix", 1), ("wip", 1)]);
+        assert_eq!(stats.scopes[0].name, "api");
+        assert_eq!(stats.scopes[0].count, 2);
+        assert_eq!(
+            stats.trend,
+            vec![
+                Period {
+                    start: "2026-10-03".to_string(),
+                    commits: 2,
+                    conventional: 2,
+                },
+                Period {
+                    start: "2026-10-10".to_string(),
+                    commits: 3,
+                    conventional: 2,
+                },
+            ]
+        );
+
+        // Types outside allowed_types miss the convention as well
+        let rules = MessageRules {
+            allowed_types: vec!["feat".to_string(), "fix".to_string()],
+            ..Default::default()
+        };
+        let stats = compute(&commits, &rules, today, 14);
+        assert_eq!(stats.non_conforming, 2);
+        assert_eq!(stats.non_conforming_percent, 40.0);
+    }
+}
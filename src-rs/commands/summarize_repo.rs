@@ -0,0 +1,263 @@
+//! `git-ai summarize-repo`: an orientation document for new team members,
+//! written from the file tree, the top-level READMEs and manifests, and the
+//! recent history (hot spots and active contributors).
+
+use crate::error::Result;
+use crate::utils::ai::{language_name, AIClient};
+use crate::utils::git::LogScope;
+use crate::utils::{ci, ConfigManager, GitManager};
+use chrono::{Duration, Local};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+/// Root files read as they are: READMEs, contributor guides and manifests
+const ROOT_DOCUMENTS: &[&str] = &[
+    "README.md",
+    "README",
+    "README_EN.md",
+    "CONTRIBUTING.md",
+    "ARCHITECTURE.md",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+    "Makefile",
+    "Dockerfile",
+];
+
+/// Characters kept of each root document
+const MAX_DOCUMENT_CHARS: usize = 4000;
+
+/// Directory levels shown in the tree outline
+const TREE_DEPTH: usize = 2;
+
+/// Directories listed per level of the outline
+const MAX_TREE_ENTRIES: usize = 40;
+
+/// Commit subjects of the period sent to the model
+const MAX_SUBJECTS: usize = 150;
+
+/// Newest commits read for hot spots
+const MAX_HISTORY_COMMITS: usize = 2000;
+
+/// Files listed as hot spots, and contributors listed as active
+const MAX_RANKED: usize = 15;
+
+/// Flags of `git-ai summarize-repo`
+#[derive(Debug, Default)]
+pub struct SummarizeRepoOptions {
+    /// Days of history counted as recent
+    pub days: usize,
+    /// Write the document to this file instead of printing it
+    pub output: Option<String>,
+}
+
+pub async fn run(options: SummarizeRepoOptions) -> Result<()> {
+    let SummarizeRepoOptions { days, output } = options;
+    let root = GitManager::get_repo_root()?;
+    let files: Vec<String> = GitManager::get_index_entries()?
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect();
+
+    let documents: Vec<String> = ROOT_DOCUMENTS
+        .iter()
+        .filter_map(|name| {
+            let content = fs::read_to_string(root.join(name)).ok()?;
+            let content = if content.chars().count() > MAX_DOCUMENT_CHARS {
+                content.chars().take(MAX_DOCUMENT_CHARS).collect::<String>() + "\n... (truncated)"
+            } else {
+                content
+            };
+            Some(format!("--- {} ---\n{}", name, content.trim()))
+        })
+        .collect();
+
+    let since = (Local::now().date_naive() - Duration::days(days as i64)).to_string();
+    let commits = GitManager::get_authored_commits(&LogScope::Days(days), true)?;
+    let recent_files: Vec<String> = GitManager::get_commit_records(MAX_HISTORY_COMMITS)?
+        .into_iter()
+        .filter(|record| record.date >= since)
+        .flat_map(|record| record.files)
+        .collect();
+    let hot_spots = rank(recent_files.iter().map(String::as_str));
+    let contributors = rank(commits.iter().map(|(author, _)| author.as_str()));
+
+    println!(
+        "🧭 Reading {} files, {} root documents and {} commits from the last {} days...\n",
+        files.len(),
+        documents.len(),
+        commits.len(),
+        days
+    );
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let pb = ci::spinner();
+    pb.set_message("📝 Writing the orientation document...");
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let subjects: Vec<&str> = commits
+        .iter()
+        .take(MAX_SUBJECTS)
+        .map(|(_, commit)| commit.as_str())
+        .collect();
+    let user_prompt = format!(
+        "Repository: {}\n\nFile tree ({} tracked files):\n{}\n\nTop-level documents:\n{}\n\n\
+         Most changed files in the last {} days (changes):\n{}\n\n\
+         Active contributors in the last {} days (commits):\n{}\n\n\
+         Recent commits, newest first:\n{}",
+        root.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        files.len(),
+        tree_outline(&files),
+        if documents.is_empty() {
+            "(none)".to_string()
+        } else {
+            documents.join("\n\n")
+        },
+        days,
+        format_ranked(&hot_spots),
+        days,
+        format_ranked(&contributors),
+        if subjects.is_empty() {
+            "(none)".to_string()
+        } else {
+            subjects.join("\n")
+        }
+    );
+    let document = ai_client
+        .generate_review(
+            &get_summarize_repo_system_prompt(&config.locale),
+            &user_prompt,
+        )
+        .await;
+    pb.finish_and_clear();
+    let document = document?.trim().to_string();
+
+    match output {
+        Some(path) => {
+            fs::write(&path, format!("{}\n", document))?;
+            println!("✅ Wrote the orientation document to {}", path);
+        }
+        None => println!("{}\n", document),
+    }
+    if config.show_usage.unwrap_or(false) {
+        println!("{}", ai_client.usage().summary());
+    }
+    Ok(())
+}
+
+fn get_summarize_repo_system_prompt(locale: &str) -> String {
+    format!(
+        "You write an orientation document for engineers joining a software project. \
+         Using only the file tree, documents and history provided, write Markdown with these sections: \
+         `## Overview` (what the project is and who uses it), `## Architecture` (how the pieces fit together \
+         and how a request or command flows through them), `## Main modules` (a bullet per important directory \
+         or file with its responsibility), `## Hot spots` (areas changing most right now and what that suggests), \
+         `## Active contributors` (who to ask about what, based on the history), and `## Getting started` \
+         (build, test and run commands found in the documents). Prefer concrete paths over generalities, \
+         and say so when something cannot be told from the input. Write in {}.",
+        language_name(locale)
+    )
+}
+
+/// Directories of the tracked files up to `TREE_DEPTH` levels, each with
+/// its file count, plus the files at the root
+fn tree_outline(files: &[String]) -> String {
+    let mut dirs: BTreeMap<String, usize> = BTreeMap::new();
+    let mut root_files = Vec::new();
+    for file in files {
+        let parts: Vec<&str> = file.split('/').collect();
+        if parts.len() == 1 {
+            root_files.push(file.as_str());
+            continue;
+        }
+        for depth in 1..=TREE_DEPTH.min(parts.len() - 1) {
+            *dirs.entry(parts[..depth].join("/")).or_default() += 1;
+        }
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut shown_per_depth: HashMap<usize, usize> = HashMap::new();
+    for (dir, count) in &dirs {
+        let depth = dir.matches('/').count();
+        let shown = shown_per_depth.entry(depth).or_default();
+        *shown += 1;
+        if *shown > MAX_TREE_ENTRIES {
+            continue;
+        }
+        let name = dir.rsplit('/').next().unwrap_or(dir);
+        lines.push(format!("{}{}/ ({})", "  ".repeat(depth), name, count));
+    }
+    lines.extend(root_files.iter().map(|file| file.to_string()));
+    lines.join("\n")
+}
+
+/// Occurrences of each value, most frequent first (ties by name), cut to
+/// `MAX_RANKED`
+fn rank<'a>(values: impl Iterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut ranked: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(MAX_RANKED);
+    ranked
+}
+
+fn format_ranked(ranked: &[(String, usize)]) -> String {
+    if ranked.is_empty() {
+        return "(none)".to_string();
+    }
+    ranked
+        .iter()
+        .map(|(value, count)| format!("- {} ({})", value, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_tree_and_ranks_history() {
+        let files: Vec<String> = [
+            "Cargo.toml",
+            "src/main.rs",
+            "src/commands/commit.rs",
+            "src/commands/hook.rs",
+            "src/commands/deep/nested.rs",
+            "docs/guide.md",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+        assert_eq!(
+            tree_outline(&files),
+            "docs/ (1)\nsrc/ (4)\n  commands/ (3)\nCargo.toml"
+        );
+
+        let ranked = rank(["b.rs", "a.rs", "b.rs", "c.rs", "a.rs", "b.rs"].into_iter());
+        assert_eq!(
+            ranked,
+            vec![
+                ("b.rs".to_string(), 3),
+                ("a.rs".to_string(), 2),
+                ("c.rs".to_string(), 1)
+            ]
+        );
+        assert_eq!(format_ranked(&ranked[..1]), "- b.rs (3)");
+        assert_eq!(format_ranked(&[]), "(none)");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::commands::release::{confirm, get_tag_message_system_prompt};
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ConfigManager, GitManager};
+
+/// Flags of `git-ai tag`
+#[derive(Debug, Default)]
+pub struct TagOptions {
+    pub name: String,
+    /// Start of the range (default: latest tag, or the whole history when
+    /// there is none)
+    pub from: Option<String>,
+    /// GPG-sign the tag (`git tag -s`)
+    pub sign: bool,
+    pub yes: bool,
+}
+
+pub async fn run(options: TagOptions) -> Result<()> {
+    let TagOptions {
+        name,
+        from,
+        sign,
+        yes,
+    } = options;
+
+    if !GitManager::is_valid_tag_name(&name) {
+        return Err(GitAiError::InvalidArgument(format!(
+            "{} is not a valid tag name",
+            name
+        )));
+    }
+    if GitManager::is_tag(&name) {
+        return Err(GitAiError::InvalidArgument(format!(
+            "Tag {} already exists",
+            name
+        )));
+    }
+    // The first tag covers everything from the root commit
+    let from = match from {
+        Some(from) => Some(from),
+        None => GitManager::get_latest_tag()?,
+    };
+    let (commits, since, range) = match &from {
+        Some(from) => (
+            GitManager::get_commits_between_refs(from, "HEAD")?,
+            from.clone(),
+            format!("{}..HEAD", from),
+        ),
+        None => (
+            GitManager::get_commits_up_to("HEAD")?,
+            "the first commit".to_string(),
+            "all commits up to HEAD (first tag)".to_string(),
+        ),
+    };
+    if commits.is_empty() {
+        println!("ℹ️  No commits since {}", since);
+        return Ok(());
+    }
+    println!("🏷️  {} commits since {}\n", commits.len(), since);
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let message = ai_client
+        .generate_commit_message(
+            &get_tag_message_system_prompt(&config.locale),
+            &format!(
+                "Tag: {}\nCommit range: {}\n\nCommits:\n{}",
+                name,
+                range,
+                commits.join("\n")
+            ),
+        )
+        .await?;
+    let message = message.trim().trim_matches('`').trim().to_string();
+    println!("{}\n", message);
+
+    let kind = if sign { "signed" } else { "annotated" };
+    if !confirm(yes, &format!("Create {} tag {}?", kind, name))? {
+        println!("\n❌ Tag cancelled");
+        return Err(GitAiError::UserCancelled);
+    }
+    GitManager::create_annotated_tag(&name, &message, sign)?;
+    println!("✅ Created {} tag {}", kind, name);
+    println!("\nPublish with: git push origin {}", name);
+
+    Ok(())
+}
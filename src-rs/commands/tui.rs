@@ -0,0 +1,377 @@
+//! `git-ai tui`: staged files, the diff and the candidate messages in one
+//! full-screen view, with commit / edit / regenerate / refine as single keys
+//! instead of the prompt sequence of `git-ai commit`.
+
+use crate::commands::commit::edit_message;
+use crate::commands::msg::{self, Generation, MsgOptions};
+use crate::error::{GitAiError, Result};
+use crate::utils::audit::AuditRecord;
+use crate::utils::{ci, codeowners, commit_summary, footer, GitManager};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Flags of `git-ai tui`
+#[derive(Debug, Default)]
+pub struct TuiOptions {
+    pub num: Option<usize>,
+    pub paths: Vec<String>,
+}
+
+enum Mode {
+    Browse,
+    /// Typing a refine request for the selected candidate
+    Refine(String),
+}
+
+/// What the user asked for when the event loop returns
+enum Action {
+    Commit { message: String, edited: bool },
+    Quit,
+}
+
+struct App {
+    files: Vec<String>,
+    diff: Vec<String>,
+    /// Index in `diff` where each file's section starts
+    file_starts: Vec<usize>,
+    current_file: usize,
+    scroll: usize,
+    generation: Generation,
+    candidates: ListState,
+    mode: Mode,
+    status: String,
+}
+
+pub async fn run(options: TuiOptions) -> Result<()> {
+    ci::require_input("Interactive review", "use 'git-ai commit --yes' instead")?;
+    let diff = GitManager::get_staged_diff(&options.paths, false)?;
+    if diff.trim().is_empty() {
+        return Err(GitAiError::NoStagedChanges);
+    }
+    println!("🤖 Generating commit message(s)...");
+    let generation = generate(&options, None).await?;
+
+    let diff: Vec<String> = diff.lines().map(str::to_string).collect();
+    let mut app = App {
+        files: generation.staged_files.clone(),
+        file_starts: file_starts(&diff),
+        diff,
+        current_file: 0,
+        scroll: 0,
+        generation,
+        candidates: ListState::default().with_selected(Some(0)),
+        mode: Mode::Browse,
+        status: String::new(),
+    };
+
+    let mut terminal = ratatui::init();
+    let action = event_loop(&mut terminal, &mut app, &options).await;
+    ratatui::restore();
+
+    match action? {
+        Action::Commit { message, edited } => {
+            let Generation {
+                config,
+                staged_files,
+                system_prompt,
+                user_prompt,
+                ..
+            } = &app.generation;
+            let footer = footer::join([
+                codeowners::owners_trailer(config, staged_files),
+                footer::render(config),
+            ]);
+            GitManager::commit(&footer::append(&message, footer.as_deref()), &options.paths)?;
+            if config.audit_notes.unwrap_or(false) {
+                let record = AuditRecord::new(config, system_prompt, user_prompt, edited);
+                if let Err(e) = record.attach_to_head() {
+                    eprintln!("⚠️  Failed to record audit note: {}", e);
+                }
+            }
+            println!("✅ Commit created successfully!");
+            commit_summary::print(config, &app.generation.usage);
+            Ok(())
+        }
+        Action::Quit => {
+            println!("❌ Commit cancelled");
+            Err(GitAiError::UserCancelled)
+        }
+    }
+}
+
+async fn generate(options: &TuiOptions, instruction: Option<String>) -> Result<Generation> {
+    let generation = msg::generate(
+        MsgOptions {
+            num: options.num,
+            paths: options.paths.clone(),
+            instruction,
+            ..Default::default()
+        },
+        None,
+    )
+    .await?;
+    if generation.messages.is_empty() {
+        return Err(GitAiError::Ai("No commit message generated".to_string()));
+    }
+    Ok(generation)
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    options: &TuiOptions,
+) -> Result<Action> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Ok(Action::Quit);
+        }
+
+        if let Mode::Refine(request) = &mut app.mode {
+            match key.code {
+                KeyCode::Esc => app.mode = Mode::Browse,
+                KeyCode::Backspace => {
+                    request.pop();
+                }
+                KeyCode::Char(c) => request.push(c),
+                KeyCode::Enter => {
+                    let request = std::mem::take(request);
+                    app.mode = Mode::Browse;
+                    if !request.trim().is_empty() {
+                        let instruction = format!(
+                            "Revise this commit message:\n{}\n\nChange requested: {}",
+                            app.selected_message(),
+                            request.trim()
+                        );
+                        app.regenerate(terminal, options, Some(instruction)).await?;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Action::Quit),
+            KeyCode::Char('c') | KeyCode::Enter => {
+                return Ok(Action::Commit {
+                    message: app.selected_message().to_string(),
+                    edited: false,
+                })
+            }
+            KeyCode::Char('e') => {
+                ratatui::restore();
+                let edited = edit_message(app.selected_message());
+                *terminal = ratatui::init();
+                let edited = edited?;
+                if edited.trim().is_empty() {
+                    app.status = "Empty message, nothing committed".to_string();
+                    continue;
+                }
+                return Ok(Action::Commit {
+                    edited: edited.trim() != app.selected_message().trim(),
+                    message: edited,
+                });
+            }
+            KeyCode::Char('r') => app.regenerate(terminal, options, None).await?,
+            KeyCode::Char('f') => app.mode = Mode::Refine(String::new()),
+            KeyCode::Up | KeyCode::Char('k') => app.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => app.select(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_by(20),
+            KeyCode::PageUp | KeyCode::Char('b') => app.scroll_by(-20),
+            KeyCode::Char('J') => app.scroll_by(1),
+            KeyCode::Char('K') => app.scroll_by(-1),
+            KeyCode::Tab | KeyCode::Char(']') => app.jump_file(1),
+            KeyCode::BackTab | KeyCode::Char('[') => app.jump_file(-1),
+            _ => {}
+        }
+    }
+}
+
+impl App {
+    fn selected_message(&self) -> &str {
+        let index = self.candidates.selected().unwrap_or(0);
+        &self.generation.messages[index.min(self.generation.messages.len() - 1)]
+    }
+
+    fn select(&mut self, delta: isize) {
+        let count = self.generation.messages.len();
+        let index = self.candidates.selected().unwrap_or(0) as isize + delta;
+        self.candidates
+            .select(Some(index.clamp(0, count as isize - 1) as usize));
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let last = self.diff.len().saturating_sub(1);
+        self.scroll = (self.scroll as isize + delta).clamp(0, last as isize) as usize;
+    }
+
+    fn jump_file(&mut self, delta: isize) {
+        if self.file_starts.is_empty() {
+            return;
+        }
+        let last = self.file_starts.len() as isize - 1;
+        self.current_file = (self.current_file as isize + delta).clamp(0, last) as usize;
+        self.scroll = self.file_starts[self.current_file];
+    }
+
+    async fn regenerate(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        options: &TuiOptions,
+        instruction: Option<String>,
+    ) -> Result<()> {
+        self.status = "⏳ Generating...".to_string();
+        terminal.draw(|frame| draw(frame, self))?;
+        match generate(options, instruction).await {
+            Ok(generation) => {
+                self.generation = generation;
+                self.candidates.select(Some(0));
+                self.status.clear();
+            }
+            Err(e) => self.status = format!("❌ {}", e),
+        }
+        // Warnings printed during generation land on the screen
+        terminal.clear()?;
+        Ok(())
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [top, candidates_area, input_area, help_area] = Layout::vertical([
+        Constraint::Percentage(60),
+        Constraint::Min(6),
+        Constraint::Length(if matches!(app.mode, Mode::Refine(_)) {
+            3
+        } else {
+            0
+        }),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [files_area, diff_area] =
+        Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)]).areas(top);
+
+    let files: Vec<ListItem> = app
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let style = if index == app.current_file {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(file.as_str()).style(style)
+        })
+        .collect();
+    frame.render_widget(
+        List::new(files).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Staged ({}) ", app.files.len())),
+        ),
+        files_area,
+    );
+
+    let diff: Vec<Line> = app.diff.iter().map(|line| diff_line(line)).collect();
+    frame.render_widget(
+        Paragraph::new(diff)
+            .block(Block::default().borders(Borders::ALL).title(" Diff "))
+            .scroll((app.scroll.min(u16::MAX as usize) as u16, 0)),
+        diff_area,
+    );
+
+    let candidates: Vec<ListItem> = app
+        .generation
+        .messages
+        .iter()
+        .map(|message| ListItem::new(message.as_str()))
+        .collect();
+    frame.render_stateful_widget(
+        List::new(candidates)
+            .block(Block::default().borders(Borders::ALL).title(" Messages "))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> "),
+        candidates_area,
+        &mut app.candidates,
+    );
+
+    if let Mode::Refine(request) = &app.mode {
+        frame.render_widget(
+            Paragraph::new(request.as_str())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Refine: what should change? (Enter to send, Esc to cancel) "),
+                ),
+            input_area,
+        );
+    }
+
+    let help = if app.status.is_empty() {
+        "↑/↓ select · c/Enter commit · e edit · r regenerate · f refine · Space/b scroll · Tab next file · q quit"
+    } else {
+        app.status.as_str()
+    };
+    frame.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        help_area,
+    );
+}
+
+fn diff_line(line: &str) -> Line<'_> {
+    let color = if line.starts_with("diff --git") {
+        Some(Color::Yellow)
+    } else if line.starts_with("@@") {
+        Some(Color::Cyan)
+    } else if line.starts_with('+') && !line.starts_with("+++") {
+        Some(Color::Green)
+    } else if line.starts_with('-') && !line.starts_with("---") {
+        Some(Color::Red)
+    } else {
+        None
+    };
+    match color {
+        Some(color) => Line::from(Span::styled(line, Style::default().fg(color))),
+        None => Line::from(line),
+    }
+}
+
+/// Line index of each `diff --git` header
+fn file_starts(diff: &[String]) -> Vec<usize> {
+    diff.iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("diff --git "))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::file_starts;
+
+    #[test]
+    fn finds_file_sections_in_diff() {
+        let diff: Vec<String> = "diff --git a/a b/a\n@@ -1 +1 @@\n-x\n+y\ndiff --git a/b b/b\n+z"
+            .lines()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(file_starts(&diff), vec![0, 4]);
+    }
+}
@@ -0,0 +1,113 @@
+//! `git-ai undo`: take back the last commit git-ai created, leaving its
+//! changes staged so the message can be regenerated or edited.
+
+use crate::commands::release::confirm;
+use crate::error::{GitAiError, Result};
+use crate::utils::audit::AuditRecord;
+use crate::utils::footer::DEFAULT_FOOTER_TEMPLATE;
+use crate::utils::{footer, ConfigManager, GitManager};
+
+/// Flags of `git-ai undo`
+#[derive(Debug, Default)]
+pub struct UndoOptions {
+    pub yes: bool,
+}
+
+pub async fn run(options: UndoOptions) -> Result<()> {
+    let head = GitManager::rev_parse("HEAD").map_err(|_| {
+        GitAiError::InvalidArgument("Nothing to undo: the branch has no commits".to_string())
+    })?;
+    let (message, parents) = GitManager::get_commit_info(&head)?;
+    let short = &head[..head.len().min(7)];
+    let subject = message.lines().next().unwrap_or_default();
+
+    let config = ConfigManager::get_merged_config().unwrap_or_default();
+    let has_note = AuditRecord::read(&head)?.is_some();
+    if !created_by_git_ai(&message, has_note, footer::render(&config).as_deref()) {
+        return Err(GitAiError::InvalidArgument(format!(
+            "HEAD ({} {}) was not created by git-ai (no audit note or Generated-by trailer); \
+             use 'git reset --soft HEAD~1' if you really mean it",
+            short, subject
+        )));
+    }
+    match parents.len() {
+        0 => {
+            return Err(GitAiError::InvalidArgument(
+                "HEAD is the root commit; there is no previous commit to return to".to_string(),
+            ))
+        }
+        1 => {}
+        _ => {
+            return Err(GitAiError::InvalidArgument(
+                "HEAD is a merge commit; undo only resets ordinary commits".to_string(),
+            ))
+        }
+    }
+    if let Some(upstream) = GitManager::get_upstream_branch()? {
+        if GitManager::is_ancestor(&head, &upstream)? {
+            return Err(GitAiError::InvalidArgument(format!(
+                "{} is already on {}; use 'git revert' instead of rewriting pushed history",
+                short, upstream
+            )));
+        }
+    }
+
+    println!("↩️  {} {}", short, subject);
+    if !confirm(
+        options.yes,
+        "Undo this commit? Its changes will stay staged",
+    )? {
+        println!("❌ Undo cancelled");
+        return Err(GitAiError::UserCancelled);
+    }
+
+    GitManager::reset_soft(&parents[0])?;
+    println!("✅ Commit undone, changes are staged again");
+    println!("   Run 'git-ai' to generate a new message, or 'git commit' to write one yourself");
+    Ok(())
+}
+
+/// The commit carries an audit note, the configured footer, or the default
+/// `Generated-by: git-ai/` trailer
+fn created_by_git_ai(message: &str, has_note: bool, footer: Option<&str>) -> bool {
+    let default_prefix = DEFAULT_FOOTER_TEMPLATE
+        .split("{version}")
+        .next()
+        .unwrap_or_default();
+    has_note
+        || message
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .any(|line| {
+                line.starts_with(default_prefix)
+                    || footer.is_some_and(|footer| footer.lines().any(|f| f.trim() == line))
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::created_by_git_ai;
+
+    #[test]
+    fn recognises_git_ai_commits() {
+        let generated = "feat: add x\n\nGenerated-by: git-ai/2.1.0 (deepseek-chat)";
+        assert!(created_by_git_ai(generated, false, None));
+        assert!(created_by_git_ai("feat: add x", true, None));
+        assert!(created_by_git_ai(
+            "feat: add x\n\nAssisted-by: bot",
+            false,
+            Some("Assisted-by: bot")
+        ));
+        assert!(!created_by_git_ai(
+            "feat: add x",
+            false,
+            Some("Assisted-by: bot")
+        ));
+        assert!(!created_by_git_ai(
+            "fix: mention Generated-by: git-ai/ in docs",
+            false,
+            None
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::ai::AIClient;
+use crate::utils::{ci, ConfigManager, GitManager};
+use std::fs;
+
+/// History sent to the model; the newest commits come first, so truncation
+/// drops the oldest ones
+const MAX_HISTORY_CHARS: usize = 12000;
+
+/// Lines of the current code region shown alongside the history
+const MAX_REGION_LINES: usize = 80;
+
+/// Flags of `git-ai why`
+#[derive(Debug, Default)]
+pub struct WhyOptions {
+    pub file: String,
+    /// `-L` range as given (`start,end`, `start,+count` or a single line)
+    pub lines: Option<String>,
+    /// Number of commits to walk back
+    pub count: usize,
+}
+
+pub async fn run(options: WhyOptions) -> Result<()> {
+    let WhyOptions { file, lines, count } = options;
+    let lines = lines.as_deref().map(parse_line_range).transpose()?;
+    let region = match lines {
+        Some((start, end)) => format!("{}:{}-{}", file, start, end),
+        None => file.clone(),
+    };
+
+    let history = GitManager::get_line_history(&file, lines, count)?;
+    let commits: Vec<&str> = history
+        .split('\x1e')
+        .filter_map(|record| record.lines().next())
+        .filter(|header| !header.trim().is_empty())
+        .collect();
+    if commits.is_empty() {
+        println!("ℹ️  No commits touched {}", region);
+        return Ok(());
+    }
+    println!("📜 {} commits touched {}:", commits.len(), region);
+    for commit in &commits {
+        println!("   {}", commit);
+    }
+    println!();
+
+    let history = if history.chars().count() > MAX_HISTORY_CHARS {
+        history.chars().take(MAX_HISTORY_CHARS).collect::<String>()
+            + "\n... (older history truncated)"
+    } else {
+        history
+    };
+    let current = current_region(&file, lines).unwrap_or_default();
+
+    let config = ConfigManager::get_merged_config()?;
+    let ai_client = AIClient::new(config.clone())?;
+    let pb = ci::spinner();
+    pb.set_message(format!("🔎 Tracing the history of {}...", region));
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let user_prompt = format!(
+        "Code region: {}\n\nCurrent code:\n{}\n\nHistory, newest commit first (each commit starts with hash, date, author and subject, followed by its message and patch):\n{}",
+        region, current, history
+    );
+    let explanation = ai_client
+        .generate_review(&get_why_system_prompt(&config.locale), &user_prompt)
+        .await;
+    pb.finish_and_clear();
+
+    println!("{}\n", explanation?.trim());
+    if config.show_usage.unwrap_or(false) {
+        println!("{}", ai_client.usage().summary());
+    }
+    Ok(())
+}
+
+/// `start,end`, `start,+count` or `start` as an inclusive, 1-based range
+pub fn parse_line_range(value: &str) -> Result<(usize, usize)> {
+    let invalid = || {
+        GitAiError::InvalidArgument(format!(
+            "Invalid line range '{}'. Use -L start,end (e.g. -L 10,40) or -L start,+count",
+            value
+        ))
+    };
+    let number = |text: &str| text.trim().parse::<usize>().ok().filter(|n| *n > 0);
+
+    let (start, end) = match value.split_once(',') {
+        Some((start, end)) => {
+            let start = number(start).ok_or_else(invalid)?;
+            let end = match end.trim().strip_prefix('+') {
+                Some(count) => start + number(count).ok_or_else(invalid)? - 1,
+                None => number(end).ok_or_else(invalid)?,
+            };
+            (start, end)
+        }
+        None => {
+            let line = number(value).ok_or_else(invalid)?;
+            (line, line)
+        }
+    };
+    if end < start {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+/// The selected lines as they are in the working tree now
+fn current_region(file: &str, lines: Option<(usize, usize)>) -> Option<String> {
+    let content = fs::read_to_string(file).ok()?;
+    let (start, end) = lines.unwrap_or((1, MAX_REGION_LINES));
+    let end = end.min(start + MAX_REGION_LINES - 1);
+    Some(
+        content
+            .lines()
+            .enumerate()
+            .skip(start - 1)
+            .take(end + 1 - start)
+            .map(|(index, line)| format!("{:>5} | {}", index + 1, line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn get_why_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => r#"你负责解释一段代码为什么是现在这个样子。根据给出的当前代码和触及它的提交历史（最新的在前），用中文输出 Markdown：
+## 演变过程
+按时间从早到晚，用要点说明关键提交各自改了什么（引用短哈希）。
+## 原因
+这段代码当前写法背后的原因：修复过的问题、满足的需求、做过的取舍。依据提交信息和补丁，属于推测时明确说明。
+## 修改前请注意
+改动这段代码时需要保留的行为或约束；没有则写"无"。
+不要编造历史中没有的内容。"#
+            .to_string(),
+        _ => r#"You explain why a piece of code looks the way it does. From the current code and the commits that touched it (newest first), write Markdown with these sections:
+## Evolution
+Oldest to newest, bullets on what each significant commit changed (cite short hashes).
+## Rationale
+Why the code is written this way now: bugs it fixed, requirements it meets, trade-offs made. Base it on the commit messages and patches, and say so when you are guessing.
+## Before you change it
+Behavior or constraints a change must keep; write "None" if there are none.
+Do not invent anything the history does not show."#
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_line_range;
+
+    #[test]
+    fn parses_line_ranges() {
+        assert_eq!(parse_line_range("10,40").unwrap(), (10, 40));
+        assert_eq!(parse_line_range("10,+5").unwrap(), (10, 14));
+        assert_eq!(parse_line_range("7").unwrap(), (7, 7));
+        assert!(parse_line_range("40,10").is_err());
+        assert!(parse_line_range("0,3").is_err());
+        assert!(parse_line_range("a,b").is_err());
+    }
+}
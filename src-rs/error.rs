@@ -32,11 +32,107 @@ pub enum GitAiError {
     #[error("No staged changes")]
     NoStagedChanges,
 
+    #[error("No changes in working tree")]
+    NoChanges,
+
     #[error("User cancelled operation")]
     UserCancelled,
 
+    #[error("Risk threshold exceeded: {0}")]
+    RiskThreshold(String),
+
+    #[error("Input required: {0}")]
+    InputRequired(String),
+
     #[error("{0}")]
     Other(String),
 }
 
+impl GitAiError {
+    /// Stable process exit code, so hooks and scripts can branch on the cause.
+    /// These values are part of the CLI contract: never renumber them.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GitAiError::Other(_) => 1,
+            GitAiError::InvalidArgument(_) => 2,
+            GitAiError::Config(_) => 3,
+            GitAiError::NotInGitRepo => 4,
+            GitAiError::GitNotInstalled => 5,
+            GitAiError::Git(_) => 6,
+            GitAiError::NoStagedChanges => 10,
+            GitAiError::NoChanges => 11,
+            GitAiError::Ai(_) => 20,
+            GitAiError::Http(_) => 21,
+            GitAiError::UserCancelled => 30,
+            GitAiError::RiskThreshold(_) => 31,
+            GitAiError::InputRequired(_) => 32,
+            GitAiError::Io(_) => 40,
+            GitAiError::Json(_) => 41,
+        }
+    }
+
+    /// Stable machine-readable error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GitAiError::Other(_) => "other",
+            GitAiError::InvalidArgument(_) => "invalid_argument",
+            GitAiError::Config(_) => "config",
+            GitAiError::NotInGitRepo => "not_in_git_repo",
+            GitAiError::GitNotInstalled => "git_not_installed",
+            GitAiError::Git(_) => "git",
+            GitAiError::NoStagedChanges => "no_staged_changes",
+            GitAiError::NoChanges => "no_changes",
+            GitAiError::Ai(_) => "provider",
+            GitAiError::Http(_) => "http",
+            GitAiError::UserCancelled => "user_cancelled",
+            GitAiError::RiskThreshold(_) => "risk_threshold",
+            GitAiError::InputRequired(_) => "input_required",
+            GitAiError::Io(_) => "io",
+            GitAiError::Json(_) => "json",
+        }
+    }
+
+    /// A dialoguer prompt that failed; Ctrl-C while it is open cancels
+    pub fn prompt(action: &str, error: dialoguer::Error) -> Self {
+        match error {
+            dialoguer::Error::IO(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                GitAiError::UserCancelled
+            }
+            e => GitAiError::Other(format!("{} failed: {}", action, e)),
+        }
+    }
+
+    /// `{code, kind, message}` payload for `--json-errors`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.exit_code(),
+            "kind": self.kind(),
+            "message": self.to_string(),
+        })
+    }
+}
+
 pub type Result<T> = std::result::Result<T, GitAiError>;
+
+#[cfg(test)]
+mod tests {
+    use super::GitAiError;
+
+    #[test]
+    fn exit_codes_are_stable() {
+        assert_eq!(GitAiError::Config("x".to_string()).exit_code(), 3);
+        assert_eq!(GitAiError::NoStagedChanges.exit_code(), 10);
+        assert_eq!(GitAiError::Ai("x".to_string()).exit_code(), 20);
+        assert_eq!(GitAiError::UserCancelled.exit_code(), 30);
+        assert_eq!(GitAiError::RiskThreshold("x".to_string()).exit_code(), 31);
+        assert_eq!(GitAiError::InputRequired("x".to_string()).exit_code(), 32);
+    }
+
+    #[test]
+    fn json_payload_has_code_kind_message() {
+        let value = GitAiError::NoStagedChanges.to_json();
+        assert_eq!(value["code"], 10);
+        assert_eq!(value["kind"], "no_staged_changes");
+        assert_eq!(value["message"], "No staged changes");
+    }
+}
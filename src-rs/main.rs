@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use std::process;
 
 mod commands;
@@ -6,7 +7,11 @@ mod error;
 mod types;
 mod utils;
 
+use commands::config::SnapshotFormat;
+use commands::hook::{HookKind, HookOptions, HookShell};
 use error::Result;
+use utils::config::ConfigScope;
+use utils::copilot::Severity;
 
 #[derive(Parser)]
 #[command(name = "git-ai")]
@@ -20,26 +25,66 @@ struct Cli {
     #[arg(long)]
     no_update_check: bool,
 
+    /// Never prompt: commit implies --yes and questions fail with exit code 32 (automatic without a terminal)
+    #[arg(long, global = true)]
+    ci: bool,
+
     /// Auto-commit without prompting
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_yes")]
     yes: bool,
 
-    /// Number of message options to generate
-    #[arg(short, long, default_value = "1")]
-    num: usize,
+    /// Ask before committing even when `auto_yes` is set
+    #[arg(long, overrides_with = "yes")]
+    no_yes: bool,
+
+    /// Number of message options to generate (default: 1, or `default_num`)
+    #[arg(short, long)]
+    num: Option<usize>,
 
     /// Override locale (zh/en)
     #[arg(short, long)]
     locale: Option<String>,
 
     /// Force agent mode
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_agent")]
     agent: bool,
 
+    /// Skip agent mode even when `agent_by_default` is set
+    #[arg(long, overrides_with = "agent")]
+    no_agent: bool,
+
     /// Use GitHub Copilot CLI for enhancement
-    #[arg(long)]
+    #[arg(long, overrides_with = "no_copilot")]
     copilot: bool,
 
+    /// Skip Copilot even when `copilot_by_default` is set
+    #[arg(long, overrides_with = "copilot")]
+    no_copilot: bool,
+
+    /// With --copilot: abort the commit when a risk at this severity or above is found
+    #[arg(long, value_parser = ["high", "medium"])]
+    fail_on: Option<String>,
+
+    /// Commit message style (overrides the `style` config key)
+    #[arg(long, value_parser = style_parser())]
+    style: Option<String>,
+
+    /// Leave whitespace-only changes out of the prompt diff (also: `ignore_whitespace`)
+    #[arg(long, overrides_with = "no_ignore_whitespace")]
+    ignore_whitespace: bool,
+
+    /// Keep whitespace-only changes even when `ignore_whitespace` is set
+    #[arg(long, overrides_with = "ignore_whitespace")]
+    no_ignore_whitespace: bool,
+
+    /// Show whole functions for the most important files (also: `expand_context`)
+    #[arg(long, overrides_with = "no_expand_context")]
+    expand_context: bool,
+
+    /// Keep minimal context even when `expand_context` is set
+    #[arg(long, overrides_with = "expand_context")]
+    no_expand_context: bool,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -55,32 +100,95 @@ struct Cli {
     /// Use global config only
     #[arg(long)]
     global: bool,
+
+    /// Run as if git-ai was started in <PATH> (like `git -C`)
+    #[arg(short = 'C', global = true, value_name = "PATH")]
+    directory: Option<PathBuf>,
+
+    /// Use this file instead of the global config (also: GIT_AI_CONFIG_PATH)
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Print errors as JSON ({code, kind, message}) on stderr
+    #[arg(long, global = true)]
+    json_errors: bool,
+
+    /// Print the system and user prompts (API keys masked) to stderr before sending
+    #[arg(long, short = 'v', global = true)]
+    show_prompt: bool,
+
+    /// Log provider requests, responses, timings and retries (secrets redacted) to the logs dir; also GIT_AI_DEBUG=1
+    #[arg(long, global = true)]
+    debug: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Generate commit message (default)
     Commit {
-        #[arg(short, long)]
+        #[arg(short, long, overrides_with = "no_yes")]
         yes: bool,
 
-        #[arg(short, long, default_value = "1")]
-        num: usize,
+        /// Ask before committing even when `auto_yes` is set
+        #[arg(long, overrides_with = "yes")]
+        no_yes: bool,
 
         #[arg(short, long)]
-        locale: Option<String>,
+        num: Option<usize>,
 
         #[arg(short, long)]
+        locale: Option<String>,
+
+        #[arg(short, long, overrides_with = "no_agent")]
         agent: bool,
 
-        #[arg(long)]
+        /// Skip agent mode even when `agent_by_default` is set
+        #[arg(long, overrides_with = "agent")]
+        no_agent: bool,
+
+        #[arg(long, overrides_with = "no_copilot")]
         copilot: bool,
+
+        /// Skip Copilot even when `copilot_by_default` is set
+        #[arg(long, overrides_with = "copilot")]
+        no_copilot: bool,
+
+        /// With --copilot: abort the commit when a risk at this severity or above is found
+        #[arg(long, value_parser = ["high", "medium"])]
+        fail_on: Option<String>,
+
+        /// With --copilot: print the impact analysis as JSON
+        #[arg(long)]
+        json: bool,
+
+        #[arg(long, value_parser = style_parser())]
+        style: Option<String>,
+
+        /// Leave whitespace-only changes out of the prompt diff
+        #[arg(long, overrides_with = "no_ignore_whitespace")]
+        ignore_whitespace: bool,
+
+        /// Keep whitespace-only changes even when `ignore_whitespace` is set
+        #[arg(long, overrides_with = "ignore_whitespace")]
+        no_ignore_whitespace: bool,
+
+        /// Show whole functions for the most important files
+        #[arg(long, overrides_with = "no_expand_context")]
+        expand_context: bool,
+
+        /// Keep minimal context even when `expand_context` is set
+        #[arg(long, overrides_with = "expand_context")]
+        no_expand_context: bool,
+
+        /// Only describe and commit staged files matching these pathspecs
+        #[arg(last = true, value_name = "PATHSPEC")]
+        paths: Vec<String>,
     },
 
     /// Generate message only (for hooks/scripts)
     Msg {
-        #[arg(short, long, default_value = "1")]
-        num: usize,
+        #[arg(short, long)]
+        num: Option<usize>,
 
         #[arg(long)]
         json: bool,
@@ -90,6 +198,41 @@ enum Commands {
 
         #[arg(short, long)]
         locale: Option<String>,
+
+        /// Commit message file to fill in (prepare-commit-msg `$1`)
+        #[arg(long, value_name = "PATH")]
+        commit_file: Option<PathBuf>,
+
+        /// Commit message source (prepare-commit-msg `$2`)
+        #[arg(long, requires = "commit_file")]
+        source: Option<String>,
+
+        /// Describe working-tree changes instead of the index (for `git commit -a`)
+        #[arg(long, conflicts_with = "commit_file")]
+        working_tree: bool,
+
+        #[arg(long, value_parser = style_parser())]
+        style: Option<String>,
+
+        /// Leave whitespace-only changes out of the prompt diff
+        #[arg(long, overrides_with = "no_ignore_whitespace")]
+        ignore_whitespace: bool,
+
+        /// Keep whitespace-only changes even when `ignore_whitespace` is set
+        #[arg(long, overrides_with = "ignore_whitespace")]
+        no_ignore_whitespace: bool,
+
+        /// Show whole functions for the most important files
+        #[arg(long, overrides_with = "no_expand_context")]
+        expand_context: bool,
+
+        /// Keep minimal context even when `expand_context` is set
+        #[arg(long, overrides_with = "expand_context")]
+        no_expand_context: bool,
+
+        /// Only describe changes matching this pathspec or glob (repeatable)
+        #[arg(long = "path", value_name = "PATHSPEC")]
+        paths: Vec<String>,
     },
 
     /// Configure AI provider
@@ -104,6 +247,9 @@ enum Commands {
         global: bool,
     },
 
+    /// Diagnose installation, configuration and provider connectivity
+    Doctor,
+
     /// Manage git hooks
     Hook {
         #[command(subcommand)]
@@ -113,6 +259,319 @@ enum Commands {
         global: bool,
     },
 
+    /// Manage agent skills (commands whose output feeds agent analysis)
+    Skills {
+        #[command(subcommand)]
+        subcommand: SkillsSubcommand,
+    },
+
+    /// Read the debug logs recorded with --debug
+    Logs {
+        #[command(subcommand)]
+        subcommand: LogsSubcommand,
+    },
+
+    /// Summarize commits about to be pushed (used by the pre-push hook)
+    PushSummary {
+        /// Remote being pushed to
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Read ref updates from stdin in pre-push hook format
+        #[arg(long)]
+        stdin: bool,
+    },
+
+    /// Generate a Keep-a-Changelog section and optionally update CHANGELOG.md
+    Changelog {
+        /// Start of the range (default: latest tag)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End of the range (default: HEAD)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Section version (default: the --to tag, otherwise "Unreleased")
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Insert or update the section in the changelog file instead of printing it
+        #[arg(short, long)]
+        write: bool,
+
+        /// Changelog file to update (default: CHANGELOG.md)
+        #[arg(long)]
+        file: Option<String>,
+    },
+
+    /// Write release notes, create an annotated tag and optionally a GitHub Release
+    Release {
+        /// Tag to create (default: semver bump suggested from the commits)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Start of the range (default: latest tag)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Push the tag and create a GitHub Release (gh CLI or GITHUB_TOKEN)
+        #[arg(long)]
+        github: bool,
+
+        /// Remote to push the tag to (default: origin)
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Skip every confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Explain in plain language what a commit, range or the staged diff does
+    Explain {
+        /// Commit or range to explain (default: HEAD), e.g. abc123 or v1.0..v1.1
+        #[arg(value_name = "REV|RANGE")]
+        target: Option<String>,
+
+        /// Explain the staged changes instead
+        #[arg(long, conflicts_with = "target")]
+        staged: bool,
+    },
+
+    /// Full-screen view of staged files, diff and candidate messages
+    Tui {
+        /// Number of candidate messages
+        #[arg(short, long)]
+        num: Option<usize>,
+
+        /// Only commit changes under these paths
+        #[arg(long = "path")]
+        paths: Vec<String>,
+    },
+
+    /// Explain how and why a file or line range got its current shape
+    Why {
+        /// File to trace
+        file: String,
+
+        /// Lines to trace: start,end or start,+count (like `git log -L`)
+        #[arg(short = 'L', value_name = "START,END")]
+        lines: Option<String>,
+
+        /// Number of commits to walk back
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+    },
+
+    /// Write a pull request title and description for the current branch
+    Pr {
+        /// Branch the PR targets (default: <remote>/main or master)
+        #[arg(long, value_name = "REF")]
+        base: Option<String>,
+
+        /// Push the branch and create the PR, or update its open PR (gh CLI or GITHUB_TOKEN)
+        #[arg(long)]
+        create: bool,
+
+        /// Copy the title and description to the clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Remote to push to (default: origin)
+        #[arg(long)]
+        remote: Option<String>,
+
+        /// Skip every confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Create an annotated tag with an AI-written message for the changes since the last tag
+    Tag {
+        /// Tag to create, e.g. v1.4.0
+        name: String,
+
+        /// Start of the range (default: latest tag, or the whole history)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// GPG-sign the tag (git tag -s)
+        #[arg(short, long)]
+        sign: bool,
+
+        /// Create the tag without confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Serve a local HTTP API (POST /message, /review, /report) for editor plugins and tools
+    Serve {
+        /// Port to listen on (127.0.0.1 only)
+        #[arg(long, default_value = "7878")]
+        port: u16,
+
+        /// Bearer token clients must send (default: GIT_AI_SERVE_TOKEN or a random one)
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Download the latest release and replace this binary (checksum verified)
+    SelfUpdate {
+        /// Only check whether a newer version exists
+        #[arg(long)]
+        check: bool,
+
+        /// Update without confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Learn commit conventions from history and save them to .git-ai.json
+    Learn {
+        /// Number of recent commits to analyze
+        #[arg(long, default_value = "300")]
+        count: usize,
+
+        /// Show the learned conventions without saving them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Review staged changes for risks with the configured provider
+    Review {
+        /// Review the current branch since it forked from this ref instead
+        #[arg(long, value_name = "REF")]
+        base: Option<String>,
+
+        /// Exit with code 31 when a risk at this severity or above is found
+        #[arg(long, value_parser = ["high", "medium"])]
+        fail_on: Option<String>,
+
+        /// Analyze with GitHub Copilot CLI instead of the configured provider
+        #[arg(long)]
+        copilot: bool,
+
+        /// Output the analysis as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Run as the pre-commit hook: block at the preCommitFailOn level (default: high)
+        #[arg(long, conflicts_with_all = ["base", "fail_on", "json"])]
+        pre_commit: bool,
+    },
+
+    /// Summarize your own commits since the previous working day for a standup
+    Standup {
+        /// Look back this many days instead of the previous working day
+        #[arg(long)]
+        days: Option<usize>,
+
+        /// Also mention local branches not merged into the base branch
+        #[arg(long)]
+        branches: bool,
+
+        /// Base branch for --branches (default: main or master)
+        #[arg(long)]
+        base: Option<String>,
+    },
+
+    /// Search history by meaning, ranking commits against a natural-language query
+    Search {
+        /// What to look for, e.g. "where did we add retry logic"
+        query: String,
+
+        /// Number of results to show
+        #[arg(short, long, default_value = "5")]
+        num: usize,
+
+        /// Search (and index) only the newest N commits
+        #[arg(long, default_value = "1000")]
+        limit: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rank the commits between a good and a bad revision by how likely they caused a bug
+    BisectHint {
+        /// Last revision known to work
+        good: String,
+
+        /// First revision known to be broken
+        bad: String,
+
+        /// What goes wrong, e.g. "login returns 500 for SSO users"
+        symptom: String,
+
+        /// Number of suspects to list
+        #[arg(short = 'n', long, default_value = "5")]
+        top: usize,
+
+        /// Start (or reuse) a git bisect session and skip the commits judged unrelated
+        #[arg(long)]
+        skip: bool,
+
+        /// Skip the confirmation prompt of --skip
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Write an orientation document for new team members from the tree, docs and history
+    SummarizeRepo {
+        /// Days of history counted as recent (hot spots, contributors)
+        #[arg(long, default_value = "90")]
+        days: usize,
+
+        /// Write the document to this file instead of printing it
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Break history down by Conventional Commits type and scope, with the non-conforming share
+    Stats {
+        /// Look back this many days
+        #[arg(long, default_value = "90")]
+        days: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Undo the last commit if git-ai created it, keeping its changes staged
+    Undo {
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show the provenance note recorded for a commit (audit_notes)
+    Audit {
+        /// Commit to inspect (default: HEAD)
+        rev: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Attach the note the prepare-commit-msg hook left for HEAD (post-commit hook)
+        #[arg(long, hide = true, conflicts_with_all = ["rev", "json"])]
+        attach_pending: bool,
+    },
+
+    /// Describe each stash entry in one line
+    StashList {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Generate reports from git history
     Report {
         /// Generate report by recent days (default mode)
@@ -130,18 +589,68 @@ enum Commands {
         /// End ref/tag for range mode (default: HEAD)
         #[arg(long)]
         to_ref: Option<String>,
+
+        /// Group commits per contributor (team report for sprint reviews)
+        #[arg(long)]
+        by_author: bool,
+
+        /// Include per-commit diffstat and touched top-level directories
+        #[arg(long)]
+        stats: bool,
+
+        /// Compare two branches: <base>..<head> (e.g. main..release/2.1)
+        #[arg(long, value_name = "BASE..HEAD")]
+        compare: Option<String>,
+
+        /// Leave merge commits out (see also exclude_authors / exclude_patterns)
+        #[arg(long)]
+        no_merges: bool,
+
+        /// Markdown skeleton the report must follow (overrides report_template)
+        #[arg(long)]
+        template: Option<std::path::PathBuf>,
+
+        /// Post the report to a Slack/Teams incoming webhook (default: slack_webhook config)
+        #[arg(long, num_args = 0..=1, value_name = "WEBHOOK_URL")]
+        post: Option<Option<String>>,
+
+        /// Summarize each commit's diff first; summaries are cached by SHA so reruns only pay for new commits
+        #[arg(long)]
+        summarize_commits: bool,
+
+        /// Release range given as two refs (e.g. --between v1.0.0 v1.3.0)
+        #[arg(long, num_args = 2, value_names = ["FROM", "TO"])]
+        between: Option<Vec<String>>,
+
+        /// With --between: one release-notes section per tag in the range
+        #[arg(long, requires = "between", conflicts_with_all = ["by_author", "stats", "template", "summarize_commits"])]
+        per_tag: bool,
     },
+
+    /// Any other command runs the `git-ai-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 #[derive(Subcommand)]
 enum ConfigSubcommand {
-    /// Get current configuration
+    /// Get current configuration (or a single key)
     Get {
+        /// Print only this key (e.g. model, locale)
+        key: Option<String>,
+
         #[arg(long)]
         json: bool,
 
         #[arg(long)]
         local: bool,
+
+        /// Read the repo-private config (.git/git-ai.json)
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+
+        #[arg(long)]
+        global: bool,
     },
 
     /// Set configuration value
@@ -151,8 +660,114 @@ enum ConfigSubcommand {
 
         #[arg(long)]
         local: bool,
+
+        /// Write to .git/git-ai.json, which is never committed
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+    },
+
+    /// Remove a single key from the config file
+    Unset {
+        key: String,
+
+        #[arg(long)]
+        local: bool,
+
+        /// Edit the repo-private config (.git/git-ai.json)
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+    },
+
+    /// Delete the global (default), repo or local config file
+    Reset {
+        #[arg(long)]
+        local: bool,
+
+        /// Delete the repo-private config (.git/git-ai.json)
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+
+        #[arg(long, conflicts_with_all = ["local", "repo"])]
+        global: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Import settings from git-ai v1 (Node.js) and opencommit
+    Import {
+        /// Snapshot written by `config export`; without it, git-ai v1 and
+        /// opencommit settings are detected and migrated
+        file: Option<PathBuf>,
+
+        /// Show what would be imported without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Overwrite values already present in the global config (migration only)
+        #[arg(long)]
+        force: bool,
+
+        /// Import the snapshot into the local config (.git-ai.json)
+        #[arg(long, requires = "file")]
+        local: bool,
+
+        /// Import the snapshot into the repo-private config (.git/git-ai.json)
+        #[arg(long, requires = "file", conflicts_with = "local")]
+        repo: bool,
+    },
+
+    /// Write the effective (or one file's) config as a shareable snapshot
+    Export {
+        /// Leave the API key out of the snapshot
+        #[arg(long)]
+        redact: bool,
+
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::Json)]
+        format: SnapshotFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        #[arg(long)]
+        local: bool,
+
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+
+        #[arg(long, conflicts_with_all = ["local", "repo"])]
+        global: bool,
+    },
+
+    /// Open the global (default), repo or local config file in $EDITOR
+    Edit {
+        #[arg(long)]
+        local: bool,
+
+        #[arg(long, conflicts_with = "local")]
+        repo: bool,
+
+        #[arg(long, conflicts_with_all = ["local", "repo"])]
+        global: bool,
+    },
+
+    /// Encrypt the global config with a passphrase
+    Encrypt,
+
+    /// Store the global config as plaintext again
+    Decrypt,
+
+    /// Show where each effective config value comes from
+    Origin {
+        #[arg(long)]
+        json: bool,
     },
 
+    /// Send a tiny prompt to verify provider, key and model
+    Test,
+
     /// Describe all configuration keys
     Describe,
 }
@@ -160,85 +775,695 @@ enum ConfigSubcommand {
 #[derive(Subcommand)]
 enum HookSubcommand {
     /// Install git hook
-    Install,
+    Install {
+        /// Hook to install
+        #[arg(long = "type", value_enum, default_value_t = HookKind::PrepareCommitMsg)]
+        hook_type: HookKind,
+
+        /// Locale used by the hook (overrides config)
+        #[arg(long)]
+        locale: Option<String>,
+
+        /// Number of messages the hook generates
+        #[arg(long)]
+        num: Option<usize>,
+
+        /// Request timeout in seconds for hook-driven generation
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Model used by the hook (overrides config)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Script language of the hook (default: powershell on Windows, bash elsewhere)
+        #[arg(long, value_enum)]
+        shell: Option<HookShell>,
+
+        /// Install into every git repository found under this directory
+        #[arg(long, value_name = "DIR")]
+        recursive: Option<PathBuf>,
+
+        /// Install into the repositories listed in this file (one path per line)
+        #[arg(long, value_name = "FILE")]
+        repos: Option<PathBuf>,
+
+        /// With --global: set the global core.hooksPath without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
 
     /// Remove git hook
-    Remove,
+    Remove {
+        /// Hook to remove
+        #[arg(long = "type", value_enum, default_value_t = HookKind::PrepareCommitMsg)]
+        hook_type: HookKind,
+    },
 
-    /// Check hook status
-    Status,
+    /// Check every git-ai hook: executable bit, backup, binary and script version
+    Status {
+        /// Hook to check (default: all)
+        #[arg(long = "type", value_enum)]
+        hook_type: Option<HookKind>,
+    },
+
+    /// Rewrite outdated hook scripts, e.g. after the git-ai binary moved
+    Upgrade {
+        /// Hook to upgrade (default: all)
+        #[arg(long = "type", value_enum)]
+        hook_type: Option<HookKind>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SkillsSubcommand {
+    /// List skills from the global and repository skills directories
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Enable (and for repository skills, trust) a skill
+    Enable { name: String },
+
+    /// Disable a skill
+    Disable { name: String },
+}
+
+#[derive(Subcommand)]
+enum LogsSubcommand {
+    /// Print the end of the newest debug log
+    Tail {
+        /// Number of lines to print
+        #[arg(short = 'n', long, default_value_t = 50)]
+        lines: usize,
+
+        /// Keep printing entries as they are written
+        #[arg(short, long)]
+        follow: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let json_errors = cli.json_errors;
+    if let Some(path) = cli.config.clone() {
+        utils::ConfigManager::set_config_path_override(path);
+    }
+    utils::ai::set_show_prompt(cli.show_prompt);
+    utils::ai::set_assume_yes(
+        cli.yes
+            || matches!(
+                cli.command,
+                Some(
+                    Commands::Commit { yes: true, .. }
+                        | Commands::Release { yes: true, .. }
+                        | Commands::Pr { yes: true, .. }
+                        | Commands::Tag { yes: true, .. }
+                        // API clients cannot answer a prompt on the server's terminal
+                        | Commands::Serve { .. }
+                )
+            ),
+    );
+    if cli.ci {
+        utils::ci::enable();
+    }
+    if cli.debug || utils::debug_log::env_requested() {
+        utils::debug_log::enable();
+    }
+
+    // Serve is long-running and self-update checks on its own
+    let update_check = utils::update::enabled(cli.no_update_check || cli.ci)
+        && !matches!(
+            cli.command,
+            Some(Commands::Serve { .. } | Commands::SelfUpdate { .. })
+        );
+    let pending_check = update_check.then(utils::update::spawn_check).flatten();
 
-    if let Err(e) = run(cli).await {
-        eprintln!("❌ Error: {}", e);
-        process::exit(1);
+    let serving = matches!(cli.command, Some(Commands::Serve { .. }));
+    let following = matches!(
+        cli.command,
+        Some(Commands::Logs {
+            subcommand: LogsSubcommand::Tail { follow: true, .. }
+        })
+    );
+    let result = tokio::select! {
+        result = run(cli) => result,
+        // Dropping `run` aborts the requests it has in flight
+        _ = tokio::signal::ctrl_c() => {
+            restore_terminal();
+            eprintln!();
+            if serving {
+                eprintln!("👋 Server stopped");
+                Ok(())
+            } else if following {
+                Ok(())
+            } else {
+                Err(error::GitAiError::UserCancelled)
+            }
+        }
+    };
+
+    if let Err(e) = result {
+        restore_terminal();
+        if json_errors {
+            eprintln!("{}", e.to_json());
+        } else {
+            eprintln!("❌ Error: {}", e);
+        }
+        process::exit(e.exit_code());
+    }
+    if let Some(check) = pending_check {
+        utils::update::finish_check(check).await;
+    }
+    if update_check {
+        if let Some(notice) = utils::update::notice() {
+            eprintln!("\n{}", notice);
+        }
     }
 }
 
 async fn run(cli: Cli) -> Result<()> {
+    if let Some(dir) = &cli.directory {
+        utils::GitManager::change_dir(dir)?;
+    }
+
     match cli.command {
         Some(Commands::Commit {
             yes,
+            no_yes,
             num,
             locale,
             agent,
+            no_agent,
             copilot,
+            no_copilot,
+            fail_on,
+            json,
+            style,
+            ignore_whitespace,
+            no_ignore_whitespace,
+            expand_context,
+            no_expand_context,
+            paths,
         }) => {
             ensure_git_ready()?;
-            commands::commit::run(yes, num, locale, agent, copilot).await
+            commands::commit::run(commands::commit::CommitOptions {
+                yes: flag_override(yes, no_yes),
+                num,
+                locale,
+                agent: flag_override(agent, no_agent),
+                copilot: flag_override(copilot, no_copilot),
+                fail_on: fail_on.as_deref().and_then(Severity::parse),
+                json: json || cli.json,
+                style,
+                ignore_whitespace: flag_override(ignore_whitespace, no_ignore_whitespace),
+                expand_context: flag_override(expand_context, no_expand_context),
+                paths,
+            })
+            .await
         }
         Some(Commands::Msg {
             num,
             json,
             quiet,
             locale,
+            commit_file,
+            source,
+            working_tree,
+            style,
+            ignore_whitespace,
+            no_ignore_whitespace,
+            expand_context,
+            no_expand_context,
+            paths,
         }) => {
             ensure_git_ready()?;
-            commands::msg::run(num, json, quiet, locale).await
+            let commit_file = commit_file.map(|path| commands::msg::CommitFile {
+                path,
+                source: source.filter(|s| !s.is_empty()),
+            });
+            commands::msg::run(commands::msg::MsgOptions {
+                num,
+                json,
+                quiet,
+                locale,
+                commit_file,
+                working_tree,
+                style,
+                ignore_whitespace: flag_override(ignore_whitespace, no_ignore_whitespace),
+                expand_context: flag_override(expand_context, no_expand_context),
+                paths,
+                instruction: None,
+            })
+            .await
         }
         Some(Commands::Config {
             subcommand,
             local,
-            global: _,
+            global,
         }) => match subcommand {
-            Some(ConfigSubcommand::Get { json, local }) => {
-                commands::config::run_get(local, json).await
+            Some(ConfigSubcommand::Get {
+                key,
+                json,
+                local: get_local,
+                repo,
+                global: get_global,
+            }) => {
+                let scope = read_scope(local || get_local, repo, global || get_global)?;
+                commands::config::run_get(key, scope, json).await
+            }
+            Some(ConfigSubcommand::Set {
+                key,
+                value,
+                local: set_local,
+                repo,
+            }) => {
+                let scope = write_scope(local || set_local, repo)?;
+                commands::config::run_set(&key, &value, scope).await
             }
-            Some(ConfigSubcommand::Set { key, value, local }) => {
-                commands::config::run_set(&key, &value, local).await
+            Some(ConfigSubcommand::Unset {
+                key,
+                local: unset_local,
+                repo,
+            }) => {
+                let scope = write_scope(local || unset_local, repo)?;
+                commands::config::run_unset(&key, scope).await
             }
+            Some(ConfigSubcommand::Reset {
+                local: reset_local,
+                repo,
+                global: _,
+                yes,
+            }) => {
+                let scope = write_scope(local || reset_local, repo)?;
+                commands::config::run_reset(scope, yes).await
+            }
+            Some(ConfigSubcommand::Import {
+                file,
+                dry_run,
+                force,
+                local: import_local,
+                repo,
+            }) => match file {
+                Some(file) => {
+                    let scope = write_scope(local || import_local, repo)?;
+                    commands::config::run_import_file(&file, scope, dry_run).await
+                }
+                None => commands::config::run_import(dry_run, force).await,
+            },
+            Some(ConfigSubcommand::Export {
+                redact,
+                format,
+                output,
+                local: export_local,
+                repo,
+                global: export_global,
+            }) => {
+                let scope = read_scope(local || export_local, repo, global || export_global)?;
+                commands::config::run_export(scope, format, redact, output).await
+            }
+            Some(ConfigSubcommand::Edit {
+                local: edit_local,
+                repo,
+                global: _,
+            }) => {
+                let scope = write_scope(local || edit_local, repo)?;
+                commands::config::run_edit(scope).await
+            }
+            Some(ConfigSubcommand::Encrypt) => commands::config::run_encrypt().await,
+            Some(ConfigSubcommand::Decrypt) => commands::config::run_decrypt().await,
+            Some(ConfigSubcommand::Origin { json }) => commands::config::run_origin(json).await,
+            Some(ConfigSubcommand::Test) => commands::config::run_test().await,
             Some(ConfigSubcommand::Describe) => commands::config::run_describe().await,
             None => commands::config::run_wizard(local).await,
         },
         Some(Commands::Hook { subcommand, global }) => {
             ensure_git_installed()?;
-            if !global {
+            let batch = matches!(
+                &subcommand,
+                HookSubcommand::Install {
+                    recursive: Some(_),
+                    ..
+                } | HookSubcommand::Install { repos: Some(_), .. }
+            );
+            if !global && !batch {
                 ensure_in_git_repo()?;
             }
 
             match subcommand {
-                HookSubcommand::Install => commands::hook::run("install".to_string(), global).await,
-                HookSubcommand::Remove => commands::hook::run("remove".to_string(), global).await,
-                HookSubcommand::Status => commands::hook::run("status".to_string(), global).await,
+                HookSubcommand::Install {
+                    hook_type,
+                    locale,
+                    num,
+                    timeout,
+                    model,
+                    shell,
+                    recursive,
+                    repos,
+                    yes,
+                } => {
+                    let options = HookOptions {
+                        locale,
+                        num,
+                        timeout,
+                        model,
+                        shell,
+                        ..Default::default()
+                    };
+                    if batch {
+                        if global {
+                            return Err(error::GitAiError::InvalidArgument(
+                                "--global cannot be combined with --recursive or --repos"
+                                    .to_string(),
+                            ));
+                        }
+                        return commands::hook::run_batch_install(
+                            recursive, repos, hook_type, options,
+                        )
+                        .await;
+                    }
+                    commands::hook::run(
+                        "install".to_string(),
+                        global,
+                        hook_type,
+                        options,
+                        yes || cli.yes,
+                    )
+                    .await
+                }
+                HookSubcommand::Remove { hook_type } => {
+                    commands::hook::run(
+                        "remove".to_string(),
+                        global,
+                        hook_type,
+                        HookOptions::default(),
+                        false,
+                    )
+                    .await
+                }
+                HookSubcommand::Status { hook_type } => {
+                    commands::hook::run_status(global, hook_type).await
+                }
+                HookSubcommand::Upgrade { hook_type } => {
+                    commands::hook::run_upgrade(global, hook_type).await
+                }
+            }
+        }
+        Some(Commands::Doctor) => commands::doctor::run().await,
+        Some(Commands::PushSummary { remote, stdin }) => {
+            ensure_git_ready()?;
+            commands::push_summary::run(remote, stdin).await
+        }
+        Some(Commands::Changelog {
+            from,
+            to,
+            version,
+            write,
+            file,
+        }) => {
+            ensure_git_ready()?;
+            commands::changelog::run(commands::changelog::ChangelogOptions {
+                from,
+                to,
+                version,
+                write,
+                file,
+            })
+            .await
+        }
+        Some(Commands::Release {
+            tag,
+            from,
+            github,
+            remote,
+            yes,
+        }) => {
+            ensure_git_ready()?;
+            commands::release::run(commands::release::ReleaseOptions {
+                tag,
+                from,
+                github,
+                remote,
+                yes: yes || cli.yes,
+            })
+            .await
+        }
+        Some(Commands::Explain { target, staged }) => {
+            ensure_git_ready()?;
+            commands::explain::run(commands::explain::ExplainOptions { target, staged }).await
+        }
+        Some(Commands::Tui { num, paths }) => {
+            ensure_git_ready()?;
+            #[cfg(feature = "tui")]
+            {
+                commands::tui::run(commands::tui::TuiOptions {
+                    num: num.or(cli.num),
+                    paths,
+                })
+                .await
+            }
+            #[cfg(not(feature = "tui"))]
+            {
+                let _ = (num, paths);
+                Err(error::GitAiError::Config(
+                    "This build of git-ai has no TUI; rebuild with --features tui".to_string(),
+                ))
             }
         }
+        Some(Commands::Why { file, lines, count }) => {
+            ensure_git_ready()?;
+            commands::why::run(commands::why::WhyOptions { file, lines, count }).await
+        }
+        Some(Commands::Pr {
+            base,
+            create,
+            copy,
+            remote,
+            yes,
+        }) => {
+            ensure_git_ready()?;
+            commands::pr::run(commands::pr::PrOptions {
+                base,
+                create,
+                copy,
+                remote,
+                yes: yes || cli.yes,
+            })
+            .await
+        }
+        Some(Commands::Tag {
+            name,
+            from,
+            sign,
+            yes,
+        }) => {
+            ensure_git_ready()?;
+            commands::tag::run(commands::tag::TagOptions {
+                name,
+                from,
+                sign,
+                yes: yes || cli.yes,
+            })
+            .await
+        }
+        Some(Commands::Serve { port, token }) => {
+            ensure_git_installed()?;
+            commands::serve::run(commands::serve::ServeOptions { port, token }).await
+        }
+        Some(Commands::SelfUpdate { check, yes }) => {
+            commands::self_update::run(commands::self_update::SelfUpdateOptions {
+                check,
+                yes: yes || cli.yes,
+            })
+            .await
+        }
+        Some(Commands::Learn { count, dry_run }) => {
+            ensure_git_ready()?;
+            commands::learn::run(commands::learn::LearnOptions { count, dry_run }).await
+        }
+        Some(Commands::Review {
+            base,
+            fail_on,
+            copilot,
+            json,
+            pre_commit,
+        }) => {
+            ensure_git_ready()?;
+            commands::review::run(commands::review::ReviewOptions {
+                base,
+                fail_on: fail_on.as_deref().and_then(Severity::parse),
+                copilot,
+                json: json || cli.json,
+                pre_commit,
+            })
+            .await
+        }
+        Some(Commands::Standup {
+            days,
+            branches,
+            base,
+        }) => {
+            ensure_git_ready()?;
+            commands::standup::run(commands::standup::StandupOptions {
+                days,
+                branches,
+                base,
+            })
+            .await
+        }
+        Some(Commands::Skills { subcommand }) => match subcommand {
+            SkillsSubcommand::List { json } => commands::skills::run_list(json || cli.json).await,
+            SkillsSubcommand::Enable { name } => commands::skills::run_enable(&name).await,
+            SkillsSubcommand::Disable { name } => commands::skills::run_disable(&name).await,
+        },
+        Some(Commands::Logs { subcommand }) => match subcommand {
+            LogsSubcommand::Tail { lines, follow } => commands::logs::run_tail(lines, follow).await,
+        },
+        Some(Commands::Search {
+            query,
+            num,
+            limit,
+            json,
+        }) => {
+            ensure_git_ready()?;
+            commands::search::run(commands::search::SearchOptions {
+                query,
+                num,
+                limit,
+                json: json || cli.json,
+            })
+            .await
+        }
+        Some(Commands::BisectHint {
+            good,
+            bad,
+            symptom,
+            top,
+            skip,
+            yes,
+            json,
+        }) => {
+            ensure_git_ready()?;
+            commands::bisect_hint::run(commands::bisect_hint::BisectHintOptions {
+                good,
+                bad,
+                symptom,
+                top,
+                skip,
+                yes: yes || cli.yes,
+                json: json || cli.json,
+            })
+            .await
+        }
+        Some(Commands::SummarizeRepo { days, output }) => {
+            ensure_git_ready()?;
+            commands::summarize_repo::run(commands::summarize_repo::SummarizeRepoOptions {
+                days,
+                output,
+            })
+            .await
+        }
+        Some(Commands::Stats { days, json }) => {
+            ensure_git_ready()?;
+            commands::stats::run(commands::stats::StatsOptions {
+                days,
+                json: json || cli.json,
+            })
+            .await
+        }
+        Some(Commands::Undo { yes }) => {
+            ensure_git_ready()?;
+            commands::undo::run(commands::undo::UndoOptions {
+                yes: yes || cli.yes,
+            })
+            .await
+        }
+        Some(Commands::Audit {
+            rev,
+            json,
+            attach_pending,
+        }) => {
+            ensure_git_ready()?;
+            if attach_pending {
+                return commands::audit::attach_pending();
+            }
+            commands::audit::run(rev, json || cli.json).await
+        }
+        Some(Commands::StashList { json }) => {
+            ensure_git_ready()?;
+            commands::stash_list::run(json || cli.json).await
+        }
         Some(Commands::Report {
             days,
             from_last_tag,
             from_tag,
             to_ref,
+            by_author,
+            stats,
+            compare,
+            no_merges,
+            template,
+            post,
+            summarize_commits,
+            between,
+            per_tag,
         }) => {
             ensure_git_ready()?;
-            commands::report::run(days, from_last_tag, from_tag, to_ref).await
+            commands::report::run(commands::report::ReportOptions {
+                days,
+                from_last_tag,
+                from_tag,
+                to_ref,
+                by_author,
+                stats,
+                compare,
+                no_merges,
+                template,
+                post,
+                summarize_commits,
+                between: between.map(|refs| (refs[0].clone(), refs[1].clone())),
+                per_tag,
+            })
+            .await
+        }
+        Some(Commands::External(args)) => {
+            let code = commands::plugin::run(args)?;
+            if code != 0 {
+                process::exit(code);
+            }
+            Ok(())
         }
         None => {
             // Default: interactive commit
             ensure_git_ready()?;
-            commands::commit::run(cli.yes, cli.num, cli.locale, cli.agent, cli.copilot).await
+            commands::commit::run(commands::commit::CommitOptions {
+                yes: flag_override(cli.yes, cli.no_yes),
+                num: cli.num,
+                locale: cli.locale,
+                agent: flag_override(cli.agent, cli.no_agent),
+                copilot: flag_override(cli.copilot, cli.no_copilot),
+                fail_on: cli.fail_on.as_deref().and_then(Severity::parse),
+                json: cli.json,
+                style: cli.style,
+                ignore_whitespace: flag_override(cli.ignore_whitespace, cli.no_ignore_whitespace),
+                expand_context: flag_override(cli.expand_context, cli.no_expand_context),
+                paths: Vec::new(),
+            })
+            .await
+        }
+    }
+}
+
+/// Show the cursor and clear a half-drawn spinner line that an interrupted
+/// prompt or progress bar left behind
+fn restore_terminal() {
+    for term in [console::Term::stdout(), console::Term::stderr()] {
+        if term.is_term() {
+            let _ = term.clear_line();
+            let _ = term.show_cursor();
         }
     }
 }
@@ -258,6 +1483,48 @@ fn ensure_in_git_repo() -> Result<()> {
 }
 
 fn ensure_git_ready() -> Result<()> {
-    ensure_git_installed()?;
-    ensure_in_git_repo()
+    // A single rev-parse answers both: it only fails to start without git
+    match utils::GitManager::is_in_git_repo() {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(error::GitAiError::NotInGitRepo),
+        Err(_) => Err(error::GitAiError::GitNotInstalled),
+    }
+}
+
+/// Config file to read from (`None` means the merged, effective config)
+fn read_scope(local: bool, repo: bool, global: bool) -> Result<Option<ConfigScope>> {
+    if global && !local && !repo {
+        return Ok(Some(ConfigScope::Global));
+    }
+    if local || repo {
+        return write_scope(local, repo).map(Some);
+    }
+    Ok(None)
+}
+
+/// Config file targeted by set/unset/reset (global unless --local/--repo)
+fn write_scope(local: bool, repo: bool) -> Result<ConfigScope> {
+    if local {
+        Ok(ConfigScope::Local)
+    } else if repo {
+        ensure_git_ready()?;
+        Ok(ConfigScope::Repo)
+    } else {
+        Ok(ConfigScope::Global)
+    }
+}
+
+/// A `--flag`/`--no-flag` pair: `None` when neither is given, so the
+/// config default applies
+fn flag_override(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// Accepts only the styles in `COMMIT_STYLES`
+fn style_parser() -> clap::builder::PossibleValuesParser {
+    clap::builder::PossibleValuesParser::new(utils::ai::COMMIT_STYLES.iter().copied())
 }
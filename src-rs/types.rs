@@ -1,11 +1,14 @@
-use serde::{Deserialize, Serialize};
+use crate::error::GitAiError;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
     #[serde(default)]
     pub provider: String,
-    #[serde(default, alias = "apiKey")]
+    /// One key, or several separated by commas (a JSON list is accepted too)
+    /// that requests rotate through
+    #[serde(default, alias = "apiKey", deserialize_with = "key_or_keys")]
     pub api_key: String,
     #[serde(default, alias = "baseUrl")]
     pub base_url: String,
@@ -13,12 +16,153 @@ pub struct AIConfig {
     pub model: String,
     #[serde(default, alias = "agentModel")]
     pub agent_model: Option<String>,
+    /// Model of the `/embeddings` endpoint used by `git-ai search`
+    #[serde(default, alias = "embeddingModel")]
+    pub embedding_model: Option<String>,
     #[serde(default)]
     pub locale: String,
+    /// Language the body is repeated in, split off a bilingual `locale` such
+    /// as `en+zh` when the config is merged
+    #[serde(skip)]
+    pub second_locale: Option<String>,
     #[serde(default, alias = "customPrompt")]
     pub custom_prompt: Option<String>,
     #[serde(default, alias = "enableFooter")]
     pub enable_footer: Option<bool>,
+    /// Trailer appended when `enable_footer` is on; `{version}`, `{provider}`
+    /// and `{model}` are substituted
+    #[serde(default, alias = "footerTemplate")]
+    pub footer_template: Option<String>,
+    #[serde(default, alias = "hookTimeoutSecs")]
+    pub hook_timeout_secs: Option<u64>,
+    #[serde(default, alias = "defaultNum")]
+    pub default_num: Option<usize>,
+    #[serde(default, alias = "autoYes")]
+    pub auto_yes: Option<bool>,
+    #[serde(default, alias = "agentByDefault")]
+    pub agent_by_default: Option<bool>,
+    #[serde(default, alias = "copilotByDefault")]
+    pub copilot_by_default: Option<bool>,
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Message body: paragraph, bullets or none (subject only)
+    #[serde(default, alias = "bodyStyle")]
+    pub body_style: Option<String>,
+    #[serde(default, alias = "showUsage")]
+    pub show_usage: Option<bool>,
+    /// Print SHA, files, line counts, model and tokens after committing
+    /// (`ui.summary`)
+    #[serde(default, alias = "uiSummary", alias = "ui.summary")]
+    pub ui_summary: Option<bool>,
+    #[serde(default, alias = "promptPreset")]
+    pub prompt_preset: Option<String>,
+    #[serde(default, alias = "promptFile")]
+    pub prompt_file: Option<String>,
+    #[serde(default, alias = "authHeader")]
+    pub auth_header: Option<String>,
+    #[serde(default, alias = "allowedTypes")]
+    pub allowed_types: Option<Vec<String>>,
+    #[serde(default, alias = "allowedScopes")]
+    pub allowed_scopes: Option<Vec<String>>,
+    #[serde(default, alias = "ignoreWhitespace")]
+    pub ignore_whitespace: Option<bool>,
+    #[serde(default, alias = "expandContext")]
+    pub expand_context: Option<bool>,
+    /// Strip unchanged context and repeated hunks from the prompt diff
+    /// (default: off)
+    #[serde(default, alias = "compactDiff")]
+    pub compact_diff: Option<bool>,
+    /// Prompts estimated above this many tokens ask before they are sent
+    /// (default: 30000, 0 turns the check off)
+    #[serde(default, alias = "maxPromptTokens")]
+    pub max_prompt_tokens: Option<u64>,
+    /// USD per million input tokens, for the cost shown by that check
+    #[serde(default, alias = "promptPrice")]
+    pub prompt_price: Option<f64>,
+    /// Temperature 0 and a fixed seed on every request, so reruns produce the
+    /// same message where the provider supports seeding
+    #[serde(default)]
+    pub deterministic: Option<bool>,
+    /// Seed sent in deterministic mode (default: 42)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default, alias = "auditNotes")]
+    pub audit_notes: Option<bool>,
+    /// Append provider, model, file list and prompt hash of every request to
+    /// `<config>/git-ai-cli/audit.jsonl`
+    #[serde(default, alias = "auditLog")]
+    pub audit_log: Option<bool>,
+    /// Add a `Cc:` trailer naming the CODEOWNERS of the committed paths
+    #[serde(default, alias = "ownersTrailer")]
+    pub owners_trailer: Option<bool>,
+    /// List `TODO`/`FIXME`/`HACK` comments added by the change under "Known
+    /// follow-ups" in the commit body
+    #[serde(default, alias = "followUps")]
+    pub follow_ups: Option<bool>,
+    /// Risk level at which the pre-commit hook blocks: high, medium, low or
+    /// off (default: high)
+    #[serde(default, alias = "preCommitFailOn")]
+    pub pre_commit_fail_on: Option<String>,
+    /// Add the issue named by the branch (title and description) to the prompt
+    /// when a tracker token is set (default: off)
+    #[serde(default, alias = "issueContext")]
+    pub issue_context: Option<bool>,
+    /// Tracker for branch issue keys: github, jira or linear
+    #[serde(default, alias = "issueTracker")]
+    pub issue_tracker: Option<String>,
+    /// Regex whose first capture group is the issue id in the branch name
+    #[serde(default, alias = "issuePattern")]
+    pub issue_pattern: Option<String>,
+    /// Authors left out of reports (case-insensitive substring, e.g. "dependabot")
+    #[serde(default, alias = "excludeAuthors")]
+    pub exclude_authors: Option<Vec<String>>,
+    /// Commit subjects left out of reports (case-insensitive substring)
+    #[serde(default, alias = "excludePatterns")]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Markdown skeleton for `report` (path, relative to the repo root)
+    #[serde(default, alias = "reportTemplate")]
+    pub report_template: Option<String>,
+    /// Incoming webhook used by `report --post` when no URL is given
+    #[serde(default, alias = "slackWebhook")]
+    pub slack_webhook: Option<String>,
+    /// Trusted repository skills as `name@<command hash>` (managed by `git-ai skills`)
+    #[serde(default, alias = "enabledSkills")]
+    pub enabled_skills: Option<Vec<String>>,
+    /// Skills switched off with `git-ai skills disable`
+    #[serde(default, alias = "disabledSkills")]
+    pub disabled_skills: Option<Vec<String>>,
+    /// Commit conventions inferred from history by `git-ai learn`
+    #[serde(default)]
+    pub conventions: Option<Conventions>,
+}
+
+/// Commit message conventions of a repository, as learned from its history
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Conventions {
+    /// Commits the conventions were learned from
+    #[serde(default)]
+    pub sample_size: usize,
+    /// Most messages use `type(scope): subject` headers
+    #[serde(default)]
+    pub conventional: bool,
+    /// Commit types in use, most frequent first
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Scope vocabulary, most frequent first
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Mood of the subject: imperative, past or third-person
+    #[serde(default)]
+    pub tense: Option<String>,
+    /// Language of the messages (en/zh)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Ticket reference shape, e.g. `ABC-123` or `#123`
+    #[serde(default, alias = "ticketFormat")]
+    pub ticket_format: Option<String>,
+    /// Where tickets go: subject-prefix, subject-suffix or footer
+    #[serde(default, alias = "ticketPlacement")]
+    pub ticket_placement: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +170,8 @@ pub struct ProviderPreset {
     pub base_url: String,
     pub default_model: String,
     pub requires_key: bool,
+    /// Env vars that hold this provider's key, used when none is configured
+    pub key_env: &'static [&'static str],
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +182,36 @@ pub struct FileStat {
     pub deletions: u32,
 }
 
+/// Commits unique to each side of `base...head`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchComparison {
+    pub only_base: Vec<String>,
+    pub only_head: Vec<String>,
+    /// Patches present on both sides under different hashes (cherry-picks)
+    pub equivalent: usize,
+}
+
+/// Size and area of one commit, from `git log --numstat`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitStat {
+    pub hash: String,
+    pub files: usize,
+    pub insertions: u32,
+    pub deletions: u32,
+    /// Top-level directories (`src/`) or root files touched, in first-seen order
+    pub areas: Vec<String>,
+}
+
+/// Message and touched paths of one commit, as indexed by `git-ai search`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitRecord {
+    pub hash: String,
+    /// Committer date, YYYY-MM-DD
+    pub date: String,
+    pub message: String,
+    pub files: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffStatistics {
     pub total_insertions: u32,
@@ -53,6 +229,30 @@ pub struct CommitMessageOutput {
     pub truncated: bool,
     #[serde(default)]
     pub ignored_files: Vec<String>,
+    #[serde(default)]
+    pub follow_ups: Vec<FollowUp>,
+}
+
+/// A `TODO`/`FIXME`/`HACK` comment added by the change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub file: String,
+    /// Line in the new version of the file
+    pub line: usize,
+    pub tag: String,
+    pub text: String,
+}
+
+/// `en+zh` is written in `en` with the body repeated in `zh`
+pub fn split_locale(locale: &str) -> (String, Option<String>) {
+    match locale.split_once('+') {
+        Some((first, second)) => {
+            let (first, second) = (first.trim(), second.trim());
+            let second = (!second.is_empty() && second != first).then(|| second.to_string());
+            (first.to_string(), second)
+        }
+        None => (locale.to_string(), None),
+    }
 }
 
 impl Default for AIConfig {
@@ -63,13 +263,134 @@ impl Default for AIConfig {
             base_url: String::new(),
             model: String::new(),
             agent_model: None,
+            embedding_model: None,
             locale: "en".to_string(),
+            second_locale: None,
             custom_prompt: None,
-            enable_footer: Some(true),
+            enable_footer: Some(false),
+            footer_template: None,
+            hook_timeout_secs: None,
+            default_num: None,
+            auto_yes: None,
+            agent_by_default: None,
+            copilot_by_default: None,
+            style: None,
+            body_style: None,
+            show_usage: None,
+            ui_summary: None,
+            prompt_preset: None,
+            prompt_file: None,
+            auth_header: None,
+            allowed_types: None,
+            allowed_scopes: None,
+            ignore_whitespace: None,
+            expand_context: None,
+            compact_diff: None,
+            max_prompt_tokens: None,
+            prompt_price: None,
+            deterministic: None,
+            seed: None,
+            audit_notes: None,
+            audit_log: None,
+            owners_trailer: None,
+            follow_ups: None,
+            pre_commit_fail_on: None,
+            issue_context: None,
+            issue_tracker: None,
+            issue_pattern: None,
+            exclude_authors: None,
+            exclude_patterns: None,
+            report_template: None,
+            slack_webhook: None,
+            enabled_skills: None,
+            disabled_skills: None,
+            conventions: None,
         }
     }
 }
 
+/// A list of keys is kept comma-separated, the form GIT_AI_API_KEY takes
+fn key_or_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Keys::deserialize(deserializer)? {
+        Keys::One(key) => key,
+        Keys::Many(keys) => keys.join(","),
+    })
+}
+
+impl AIConfig {
+    /// The configured API keys, in rotation order
+    pub fn api_keys(&self) -> Vec<String> {
+        self.api_key
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// A config with no keys set, used for override-only layers (local, repo, env)
+    pub fn unset() -> Self {
+        Self {
+            locale: String::new(),
+            enable_footer: None,
+            ..Self::default()
+        }
+    }
+
+    /// Config for the cheaper analysis tier (agent analysis, per-chunk and
+    /// per-commit summaries), or `None` when `agent_model` is unset. A
+    /// `<provider>:<model>` value such as `ollama:qwen2.5-coder:7b` also
+    /// switches to that provider's preset endpoint, with the key from the
+    /// preset's env vars: the primary provider's key is never sent to another
+    /// provider. Fails when that provider needs a key and none is set.
+    pub fn agent_config(&self) -> crate::error::Result<Option<AIConfig>> {
+        let Some(agent_model) = self.agent_model.as_deref().map(str::trim) else {
+            return Ok(None);
+        };
+        if agent_model.is_empty() || agent_model == self.model {
+            return Ok(None);
+        }
+
+        let mut config = self.clone();
+        let presets = get_provider_presets();
+        let preset = agent_model
+            .split_once(':')
+            .and_then(|(provider, model)| Some((provider, presets.get(provider)?, model)))
+            .filter(|(provider, _, model)| *provider != self.provider && !model.is_empty());
+        match preset {
+            Some((provider, preset, model)) => {
+                config.provider = provider.to_string();
+                config.base_url = preset.base_url.clone();
+                config.model = model.to_string();
+                config.auth_header = None;
+                config.api_key = preset
+                    .key_env
+                    .iter()
+                    .find_map(|var| std::env::var(var).ok().filter(|key| !key.is_empty()))
+                    .unwrap_or_default();
+                if preset.requires_key && config.api_key.is_empty() {
+                    let hint = match preset.key_env {
+                        [] => format!("use a {} model instead", self.provider),
+                        vars => format!("set {}", vars.join(" or ")),
+                    };
+                    return Err(GitAiError::Config(format!(
+                        "agent_model '{}' needs a {} API key; {}",
+                        agent_model, provider, hint
+                    )));
+                }
+            }
+            None => config.model = agent_model.to_string(),
+        }
+        Ok(Some(config))
+    }
+}
+
 pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
     let mut presets = HashMap::new();
 
@@ -80,6 +401,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://api.deepseek.com/v1".to_string(),
             default_model: "deepseek-chat".to_string(),
             requires_key: true,
+            key_env: &["DEEPSEEK_API_KEY"],
         },
     );
 
@@ -89,6 +411,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://dashscope.aliyuncs.com/compatible-mode/v1".to_string(),
             default_model: "qwen-plus".to_string(),
             requires_key: true,
+            key_env: &[],
         },
     );
 
@@ -98,6 +421,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://open.bigmodel.cn/api/paas/v4".to_string(),
             default_model: "glm-4".to_string(),
             requires_key: true,
+            key_env: &[],
         },
     );
 
@@ -107,6 +431,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://api.moonshot.cn/v1".to_string(),
             default_model: "moonshot-v1-8k".to_string(),
             requires_key: true,
+            key_env: &[],
         },
     );
 
@@ -117,6 +442,28 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://api.openai.com/v1".to_string(),
             default_model: "gpt-4-turbo".to_string(),
             requires_key: true,
+            key_env: &["OPENAI_API_KEY"],
+        },
+    );
+
+    presets.insert(
+        "xai",
+        ProviderPreset {
+            base_url: "https://api.x.ai/v1".to_string(),
+            default_model: "grok-3-mini".to_string(),
+            requires_key: true,
+            key_env: &["XAI_API_KEY"],
+        },
+    );
+
+    // GitHub Models: a GitHub token (or `gh auth token`) is the key
+    presets.insert(
+        "github-models",
+        ProviderPreset {
+            base_url: "https://models.inference.ai.azure.com".to_string(),
+            default_model: "gpt-4o-mini".to_string(),
+            requires_key: true,
+            key_env: &["GITHUB_TOKEN", "GH_TOKEN"],
         },
     );
 
@@ -126,6 +473,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "https://api.siliconflow.cn/v1".to_string(),
             default_model: "deepseek-ai/deepseek-v2.5".to_string(),
             requires_key: true,
+            key_env: &[],
         },
     );
 
@@ -136,6 +484,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "http://localhost:11434/v1".to_string(),
             default_model: "llama2".to_string(),
             requires_key: false,
+            key_env: &[],
         },
     );
 
@@ -145,6 +494,7 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
             base_url: "http://localhost:1234/v1".to_string(),
             default_model: "local-model".to_string(),
             requires_key: false,
+            key_env: &[],
         },
     );
 
@@ -153,7 +503,21 @@ pub fn get_provider_presets() -> HashMap<&'static str, ProviderPreset> {
 
 #[cfg(test)]
 mod tests {
-    use super::AIConfig;
+    use super::{split_locale, AIConfig};
+
+    #[test]
+    fn splits_bilingual_locales() {
+        assert_eq!(
+            split_locale("en+zh"),
+            ("en".to_string(), Some("zh".to_string()))
+        );
+        assert_eq!(
+            split_locale("zh + en"),
+            ("zh".to_string(), Some("en".to_string()))
+        );
+        assert_eq!(split_locale("zh"), ("zh".to_string(), None));
+        assert_eq!(split_locale("en+en"), ("en".to_string(), None));
+    }
 
     #[test]
     fn parse_legacy_camel_case_config_fields() {
@@ -178,4 +542,44 @@ mod tests {
         assert_eq!(cfg.custom_prompt.as_deref(), Some("legacy"));
         assert_eq!(cfg.enable_footer, Some(true));
     }
+
+    #[test]
+    fn api_key_accepts_a_list() {
+        let cfg: AIConfig = serde_json::from_str(r#"{"api_key": ["sk-a", " sk-b "]}"#).unwrap();
+        assert_eq!(cfg.api_key, "sk-a, sk-b ");
+        assert_eq!(cfg.api_keys(), vec!["sk-a", "sk-b"]);
+
+        let cfg: AIConfig = serde_json::from_str(r#"{"apiKey": "sk-a"}"#).unwrap();
+        assert_eq!(cfg.api_keys(), vec!["sk-a"]);
+    }
+
+    #[test]
+    fn agent_config_switches_model_and_provider() {
+        let mut cfg = AIConfig {
+            provider: "deepseek".to_string(),
+            base_url: "https://api.deepseek.com/v1".to_string(),
+            model: "deepseek-reasoner".to_string(),
+            ..AIConfig::default()
+        };
+        assert!(cfg.agent_config().unwrap().is_none());
+
+        cfg.api_key = "sk-deepseek".to_string();
+        cfg.agent_model = Some("deepseek-chat".to_string());
+        let agent = cfg.agent_config().unwrap().unwrap();
+        assert_eq!(agent.provider, "deepseek");
+        assert_eq!(agent.model, "deepseek-chat");
+        assert_eq!(agent.api_key, "sk-deepseek");
+
+        cfg.agent_model = Some("ollama:qwen2.5-coder:7b".to_string());
+        let agent = cfg.agent_config().unwrap().unwrap();
+        assert_eq!(agent.provider, "ollama");
+        assert_eq!(agent.base_url, "http://localhost:11434/v1");
+        assert_eq!(agent.model, "qwen2.5-coder:7b");
+        // The DeepSeek key stays with DeepSeek
+        assert_eq!(agent.api_key, "");
+
+        // A provider needing a key without one configured is an error
+        cfg.agent_model = Some("moonshot:moonshot-v1-8k".to_string());
+        assert!(cfg.agent_config().is_err());
+    }
 }
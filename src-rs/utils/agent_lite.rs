@@ -1,11 +1,47 @@
 use crate::error::Result;
+use crate::utils::agent_skills;
+use crate::utils::ai::AIClient;
+use crate::utils::codeowners;
+use crate::utils::import_graph::ImportGraph;
+#[cfg(feature = "tree-sitter")]
+use crate::utils::syntax;
+use crate::utils::test_map;
 use crate::utils::GitManager;
 use regex::Regex;
 use std::sync::OnceLock;
 
+/// Files that get whole-function context when `expand_context` is on
+const CONTEXT_FOCUS_FILES: usize = 3;
+
+/// Dependent modules listed in the analysis context
+const MAX_AFFECTED_MODULES: usize = 10;
+
 pub struct AgentLite;
 
+/// Result of `AgentLite::run_analysis`
+#[derive(Debug, Default)]
+pub struct AgentAnalysis {
+    /// Prompt section describing the change
+    pub context: String,
+    /// Test files likely affected, as `path (reason)`
+    pub related_tests: Vec<String>,
+    /// CODEOWNERS of the changed files
+    pub owners: Vec<String>,
+}
+
 impl AgentLite {
+    /// Staged diff where the most important files carry their enclosing
+    /// functions and the rest stay at minimal context to save budget
+    pub fn build_expanded_diff(paths: &[String], ignore_whitespace: bool) -> Result<String> {
+        let stats = GitManager::get_file_stats(paths)?;
+        let focus: Vec<String> = Self::analyze_file_importance(&stats)
+            .into_iter()
+            .take(CONTEXT_FOCUS_FILES)
+            .map(|(file, _)| file)
+            .collect();
+        GitManager::get_expanded_diff(paths, ignore_whitespace, &focus)
+    }
+
     /// Analyze file importance based on insertions and deletions
     pub fn analyze_file_importance(stats: &[(String, u32, u32)]) -> Vec<(String, u32)> {
         let mut importance: Vec<(String, u32)> = stats
@@ -16,13 +52,20 @@ impl AgentLite {
             })
             .collect();
 
-        importance.sort_by(|a, b| b.1.cmp(&a.1));
+        importance.sort_by_key(|b| std::cmp::Reverse(b.1));
         importance.truncate(5); // Top 5 files
         importance
     }
 
-    /// Extract candidate symbols from diff (functions, classes, types)
+    /// Extract candidate symbols from diff (functions, classes, types).
+    /// With tree-sitter these are the definitions enclosing the changed lines,
+    /// qualified by their scope (`AuthService::refresh_token`).
     pub fn extract_candidate_symbols(diff: &str) -> Vec<String> {
+        #[cfg(feature = "tree-sitter")]
+        if let Some(symbols) = syntax::changed_symbols(diff, load_changed_file) {
+            return symbols;
+        }
+
         static RE_FUNC: OnceLock<Regex> = OnceLock::new();
         static RE_CLASS: OnceLock<Regex> = OnceLock::new();
 
@@ -98,9 +141,9 @@ impl AgentLite {
     }
 
     /// Run lightweight agent analysis
-    pub async fn run_analysis(diff: &str, branch_name: Option<&str>) -> Result<String> {
+    pub async fn run_analysis(diff: &str, branch_name: Option<&str>) -> Result<AgentAnalysis> {
         // Get file statistics
-        let stats = GitManager::get_file_stats()?;
+        let stats = GitManager::get_file_stats(&[])?;
         let important_files = Self::analyze_file_importance(&stats);
 
         // Extract symbols
@@ -110,7 +153,12 @@ impl AgentLite {
         let mut usage_info = String::new();
         let mut tasks = Vec::new();
         for symbol in &symbols {
-            let symbol = symbol.clone();
+            // Usage is searched by the bare name, not the qualified one
+            let symbol = symbol
+                .rsplit(['.', ':'])
+                .next()
+                .unwrap_or(symbol)
+                .to_string();
             tasks.push(tokio::task::spawn_blocking(move || {
                 let count = GitManager::search_code(&symbol)
                     .map(|results| results.len())
@@ -127,6 +175,22 @@ impl AgentLite {
             }
         }
 
+        // Modules and tests depending on the changed files; the graph is best-effort
+        let changed_files: Vec<String> = stats.iter().map(|(path, _, _)| path.clone()).collect();
+        let graph_files = changed_files.clone();
+        let (affected, related_tests) = tokio::task::spawn_blocking(move || {
+            ImportGraph::load().map(|graph| {
+                (
+                    graph.dependents_of(&graph_files),
+                    test_map::related_tests(&graph_files, &graph, load_changed_file),
+                )
+            })
+        })
+        .await
+        .ok()
+        .and_then(|result| result.ok())
+        .unwrap_or_default();
+
         // Detect breaking changes
         let breaking_changes = Self::detect_breaking_changes(diff);
 
@@ -151,6 +215,50 @@ impl AgentLite {
             }
         }
 
+        if !symbols.is_empty() {
+            context.push_str("\nModified symbols:\n");
+            for symbol in &symbols {
+                context.push_str(&format!("- `{}`\n", symbol));
+            }
+        }
+
+        if !affected.is_empty() {
+            context.push_str("\nAffected modules (import the changed files):\n");
+            for (module, imports) in affected.iter().take(MAX_AFFECTED_MODULES) {
+                context.push_str(&format!("- {} (uses {})\n", module, imports.join(", ")));
+            }
+            if affected.len() > MAX_AFFECTED_MODULES {
+                context.push_str(&format!(
+                    "- ... and {} more\n",
+                    affected.len() - MAX_AFFECTED_MODULES
+                ));
+            }
+        }
+
+        if !related_tests.is_empty() {
+            context.push_str("\nTests likely affected:\n");
+            for test in &related_tests {
+                context.push_str(&format!("- {}\n", test));
+            }
+        }
+
+        // Shown to the user only; owners do not belong in the message
+        let owners = codeowners::owners_to_notify(&changed_files);
+
+        // User-defined skills (e.g. compiler warnings) add their own findings
+        match agent_skills::run_enabled(&changed_files).await {
+            Ok(outputs) => {
+                for skill in outputs {
+                    context.push_str(&format!("\nSkill `{}`", skill.name));
+                    if !skill.description.is_empty() {
+                        context.push_str(&format!(" ({})", skill.description));
+                    }
+                    context.push_str(&format!(":\n{}\n", skill.output));
+                }
+            }
+            Err(err) => eprintln!("⚠️  Agent skills skipped: {}", err),
+        }
+
         if let Some(scope) = scope_hint {
             context.push_str(&format!("\nSuggested scope: {}\n", scope));
         }
@@ -159,6 +267,42 @@ impl AgentLite {
             context.push_str(&format!("\nSymbol usage:{}\n", usage_info));
         }
 
-        Ok(context)
+        Ok(AgentAnalysis {
+            context,
+            related_tests,
+            owners,
+        })
+    }
+
+    /// First tier of two-tier generation: the agent model reads the diff and
+    /// the heuristic context and writes a short analysis that the final model
+    /// turns into the commit message
+    pub async fn analyze_with_model(
+        client: &AIClient,
+        diff: &str,
+        context: &str,
+        locale: &str,
+    ) -> Result<String> {
+        let user_prompt = format!("{}\n\n## Diff\n{}", context.trim(), diff);
+        let analysis = client
+            .generate_commit_message(&get_analysis_system_prompt(locale), &user_prompt)
+            .await?;
+        Ok(format!("\n## Change Analysis\n{}\n", analysis.trim()))
+    }
+}
+
+fn get_analysis_system_prompt(locale: &str) -> String {
+    match locale {
+        "zh" => "你是代码变更分析助手，为随后生成提交信息的模型做准备。根据 diff 和分析上下文，用不超过 5 条要点说明：改动的意图、主要修改的模块或符号、对行为的影响以及潜在的破坏性变更。只陈述 diff 中能看出的事实，不要输出提交信息。".to_string(),
+        _ => "You are a code change analyst preparing input for a model that will write the commit message. From the diff and the analysis context, write at most 5 bullet points covering the intent of the change, the main modules or symbols touched, the effect on behavior and any breaking changes. State only what the diff shows and do not write the commit message itself.".to_string(),
+    }
+}
+
+/// New content of a changed file: the staged version, else the working tree
+fn load_changed_file(path: &str) -> Option<String> {
+    if let Ok(Some(content)) = GitManager::get_index_file(path) {
+        return Some(content);
     }
+    let root = GitManager::get_repo_root().ok()?;
+    std::fs::read_to_string(root.join(path)).ok()
 }
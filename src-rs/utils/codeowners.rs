@@ -0,0 +1,189 @@
+//! `CODEOWNERS` lookup: which teams and people own the changed paths, for
+//! the "owners to notify" line of the analysis and the optional `Cc:` trailer.
+
+use crate::error::Result;
+use crate::types::AIConfig;
+use crate::utils::GitManager;
+use regex::Regex;
+use std::fs;
+
+/// Where GitHub and GitLab look for the file, in lookup order
+const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// The repository's CODEOWNERS, or `None` when it has none
+    pub fn load() -> Result<Option<Self>> {
+        let root = GitManager::get_repo_root()?;
+        for location in LOCATIONS {
+            if let Ok(content) = fs::read_to_string(root.join(location)) {
+                return Ok(Some(Self::parse(&content)));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            // GitLab `[Section]` headers carry no paths
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = glob_to_regex(parts.next()?)?;
+                Some(Rule {
+                    pattern,
+                    // `@team # reviewers` ends at the inline comment
+                    owners: parts
+                        .take_while(|part| !part.starts_with('#'))
+                        .map(str::to_string)
+                        .collect(),
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of one path; the last matching rule wins, and a rule without
+    /// owners leaves the path unowned
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.is_match(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Owners of all `paths`, in first-seen order
+    pub fn owners_for(&self, paths: &[String]) -> Vec<String> {
+        let mut owners: Vec<String> = Vec::new();
+        for path in paths {
+            for owner in self.owners_of(path) {
+                if !owners.contains(owner) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+        owners
+    }
+}
+
+/// Owners of `paths`; empty when the repository has no CODEOWNERS
+pub fn owners_to_notify(paths: &[String]) -> Vec<String> {
+    match CodeOwners::load() {
+        Ok(Some(codeowners)) => codeowners.owners_for(paths),
+        _ => Vec::new(),
+    }
+}
+
+/// `Cc: @team, @person` trailer when `owners_trailer` is on
+pub fn owners_trailer(config: &AIConfig, paths: &[String]) -> Option<String> {
+    if !config.owners_trailer.unwrap_or(false) {
+        return None;
+    }
+    let owners = owners_to_notify(paths);
+    (!owners.is_empty()).then(|| format!("Cc: {}", owners.join(", ")))
+}
+
+/// gitignore-style pattern as used by CODEOWNERS: a leading or inner `/`
+/// anchors it at the root, otherwise it matches at any depth; a match also
+/// covers everything below a directory, except that a `*` in the last
+/// segment names files (`docs/*` owns `docs/a.md`, not `docs/api/b.md`)
+fn glob_to_regex(pattern: &str) -> Option<Regex> {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let directory_only = pattern.ends_with('/');
+    let glob = pattern.trim_start_matches('/').trim_end_matches('/');
+    if glob.is_empty() {
+        // `/` alone
+        return Regex::new(".*").ok();
+    }
+    let last_segment = glob.rsplit('/').next().unwrap_or(glob);
+    let files_only = last_segment.contains('*') && !last_segment.contains("**");
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str(if directory_only {
+        "/.*$"
+    } else if files_only {
+        "$"
+    } else {
+        "(?:/.*)?$"
+    });
+    Regex::new(&regex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let codeowners = CodeOwners::parse(
+            "# Default owners\n\
+             *       @acme/core\n\
+             *.md    @acme/docs\n\
+             /src/auth/ @acme/team-auth @alice\n\
+             src/**/tests/ @bob\n\
+             /vendor/\n",
+        );
+        assert_eq!(codeowners.owners_of("Cargo.toml"), ["@acme/core"]);
+        assert_eq!(codeowners.owners_of("docs/guide.md"), ["@acme/docs"]);
+        assert_eq!(
+            codeowners.owners_of("src/auth/token.rs"),
+            ["@acme/team-auth", "@alice"]
+        );
+        assert_eq!(codeowners.owners_of("src/api/tests/login.rs"), ["@bob"]);
+        assert!(codeowners.owners_of("vendor/lib.rs").is_empty());
+        // Anchored at the root
+        assert_eq!(codeowners.owners_of("lib/src/auth/x.rs"), ["@acme/core"]);
+
+        let changed = [
+            "src/auth/token.rs".to_string(),
+            "src/auth/session.rs".to_string(),
+            "README.md".to_string(),
+        ];
+        assert_eq!(
+            codeowners.owners_for(&changed),
+            ["@acme/team-auth", "@alice", "@acme/docs"]
+        );
+    }
+
+    #[test]
+    fn single_star_stays_in_one_segment() {
+        let codeowners = CodeOwners::parse(
+            "*        @acme/core\n\
+             docs/*   @acme/docs # technical writers\n",
+        );
+        assert_eq!(codeowners.owners_of("docs/intro.md"), ["@acme/docs"]);
+        assert_eq!(codeowners.owners_of("docs/api/auth.md"), ["@acme/core"]);
+        assert_eq!(codeowners.owners_of("src/main.rs"), ["@acme/core"]);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::error::Result;
+use crate::types::CommitRecord;
+use crate::utils::GitManager;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Characters of a commit that are embedded; long diffs are cut so one
+/// commit stays well inside an embedding model's input limit
+const MAX_DOCUMENT_CHARS: usize = 4000;
+
+/// Bumped when [`document`] changes, so older embeddings are rebuilt
+const INDEX_VERSION: u32 = 2;
+
+/// Embeddings of commit messages, touched paths and diffs, keyed by full sha
+/// and kept in `<git dir>/git-ai/commit-index.json`, so `git-ai search` only
+/// embeds commits it has not seen. Switching models starts a fresh index.
+#[derive(Debug, Default)]
+pub struct CommitIndex {
+    path: PathBuf,
+    file: IndexFile,
+    dirty: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    #[serde(default)]
+    version: u32,
+    model: String,
+    entries: BTreeMap<String, Vec<f32>>,
+}
+
+impl CommitIndex {
+    /// Index of the current repository for `model`
+    pub fn open(model: &str) -> Result<Self> {
+        Ok(Self::open_at(
+            GitManager::get_git_common_dir()?
+                .join("git-ai")
+                .join("commit-index.json"),
+            model,
+        ))
+    }
+
+    /// A missing or unreadable index, or one built with another model or
+    /// document layout, starts empty
+    pub fn open_at(path: PathBuf, model: &str) -> Self {
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<IndexFile>(&content).ok())
+            .filter(|file| file.model == model && file.version == INDEX_VERSION)
+            .unwrap_or_else(|| IndexFile {
+                version: INDEX_VERSION,
+                model: model.to_string(),
+                entries: BTreeMap::new(),
+            });
+        Self {
+            path,
+            file,
+            dirty: false,
+        }
+    }
+
+    pub fn get(&self, hash: &str) -> Option<&[f32]> {
+        self.file.entries.get(hash).map(Vec::as_slice)
+    }
+
+    pub fn insert(&mut self, hash: &str, embedding: Vec<f32>) {
+        self.file.entries.insert(hash.to_string(), embedding);
+        self.dirty = true;
+    }
+
+    /// Write the index back if anything was added
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(&self.file)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Text embedded for a commit: its message, the paths it touched, then as
+/// much of its diff as fits
+pub fn document(commit: &CommitRecord, patch: &str) -> String {
+    let text = format!(
+        "{}\n\nFiles: {}\n\n{}",
+        commit.message,
+        commit.files.join(", "),
+        patch
+    );
+    text.chars().take(MAX_DOCUMENT_CHARS).collect()
+}
+
+/// Cosine similarity; 0 when either vector is empty or zero
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if a.len() != b.len() || denominator == 0.0 {
+        return 0.0;
+    }
+    dot / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_round_trips_per_model() {
+        let path = std::env::temp_dir()
+            .join(format!("git-ai-index-{}", std::process::id()))
+            .join("commit-index.json");
+
+        let mut index = CommitIndex::open_at(path.clone(), "small");
+        index.insert("abc", vec![1.0, 0.0]);
+        index.save().unwrap();
+        assert_eq!(
+            CommitIndex::open_at(path.clone(), "small").get("abc"),
+            Some(&[1.0, 0.0][..])
+        );
+        assert_eq!(CommitIndex::open_at(path.clone(), "large").get("abc"), None);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        assert!((cosine(&[1.0, 1.0], &[2.0, 2.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn documents_include_the_diff() {
+        let commit = CommitRecord {
+            hash: "abc".to_string(),
+            date: "2024-06-01".to_string(),
+            message: "fix: retry uploads".to_string(),
+            files: vec!["src/upload.rs".to_string()],
+        };
+        let text = document(&commit, "+    for attempt in 0..3 {");
+        assert!(text.starts_with("fix: retry uploads\n\nFiles: src/upload.rs"));
+        assert!(text.ends_with("for attempt in 0..3 {"));
+        assert_eq!(
+            document(&commit, &"x".repeat(10_000)).chars().count(),
+            MAX_DOCUMENT_CHARS
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Recap printed after a commit when `ui.summary` is on: what landed in
+//! HEAD and which model wrote the message, for `--yes` runs nobody watched.
+
+use crate::types::AIConfig;
+use crate::utils::ai::TokenUsage;
+use crate::utils::GitManager;
+
+/// Files listed before the rest are folded into "… and N more"
+const MAX_FILES: usize = 5;
+
+/// Print the recap of HEAD when `ui.summary` is enabled. A failure to read
+/// the commit back only skips the recap.
+pub fn print(config: &AIConfig, usage: &TokenUsage) {
+    if !config.ui_summary.unwrap_or(false) {
+        return;
+    }
+    let Ok(head) = GitManager::rev_parse("HEAD") else {
+        return;
+    };
+    let (Ok((message, _)), Ok(stats)) = (
+        GitManager::get_commit_info(&head),
+        GitManager::get_commit_file_stats(&head),
+    ) else {
+        return;
+    };
+    println!(
+        "{}",
+        format(
+            &head,
+            message.lines().next().unwrap_or_default(),
+            &stats,
+            config,
+            usage
+        )
+    );
+}
+
+fn format(
+    commit: &str,
+    subject: &str,
+    stats: &[(String, u32, u32)],
+    config: &AIConfig,
+    usage: &TokenUsage,
+) -> String {
+    let insertions: u32 = stats.iter().map(|(_, added, _)| added).sum();
+    let deletions: u32 = stats.iter().map(|(_, _, removed)| removed).sum();
+    let mut lines = vec![
+        format!("\n📋 {} {}", &commit[..commit.len().min(7)], subject),
+        format!(
+            "   {} file{} changed, +{} -{}",
+            stats.len(),
+            if stats.len() == 1 { "" } else { "s" },
+            insertions,
+            deletions
+        ),
+    ];
+    for (file, added, removed) in stats.iter().take(MAX_FILES) {
+        lines.push(format!("     {} (+{} -{})", file, added, removed));
+    }
+    if stats.len() > MAX_FILES {
+        lines.push(format!("     … and {} more", stats.len() - MAX_FILES));
+    }
+    let tokens = if usage.total_tokens > 0 {
+        format!("{} tokens", usage.total_tokens)
+    } else {
+        "tokens not reported".to_string()
+    };
+    lines.push(format!(
+        "   {} / {} · {}",
+        config.provider, config.model, tokens
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_commit_recap() {
+        let config = AIConfig {
+            provider: "deepseek".to_string(),
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        let stats: Vec<(String, u32, u32)> =
+            (1..=6).map(|i| (format!("src/f{}.rs", i), i, 1)).collect();
+        let usage = TokenUsage {
+            prompt_tokens: 900,
+            completion_tokens: 34,
+            total_tokens: 934,
+        };
+        let recap = format("0123456789abcdef", "feat: add x", &stats, &config, &usage);
+        assert_eq!(
+            recap,
+            "\n📋 0123456 feat: add x\n   6 files changed, +21 -6\n     src/f1.rs (+1 -1)\n     \
+             src/f2.rs (+2 -1)\n     src/f3.rs (+3 -1)\n     src/f4.rs (+4 -1)\n     \
+             src/f5.rs (+5 -1)\n     … and 1 more\n   deepseek / deepseek-chat · 934 tokens"
+        );
+
+        let one = [("README.md".to_string(), 2, 0)];
+        assert!(format("abc", "docs: x", &one, &config, &TokenUsage::default())
+            .ends_with("1 file changed, +2 -0\n     README.md (+2 -0)\n   deepseek / deepseek-chat · tokens not reported"));
+    }
+}
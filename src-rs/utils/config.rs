@@ -1,11 +1,193 @@
 use crate::error::{GitAiError, Result};
-use crate::types::AIConfig;
+use crate::types::{get_provider_presets, split_locale, AIConfig};
+use crate::utils::{crypto, GitManager};
 use dirs::{config_dir, home_dir};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
 
 pub struct ConfigManager;
 
+/// Config file layer targeted by `config get/set/unset/reset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigScope {
+    /// User-wide config (~/.config/git-ai-cli/config.json)
+    Global,
+    /// Per-clone config inside the git dir (.git/git-ai.json), never committed
+    Repo,
+    /// Project config in the working tree (.git-ai.json)
+    Local,
+}
+
+/// Env vars read by `read_env_config` for each key, in precedence order
+const ENV_VARS: &[(&str, &[&str])] = &[
+    ("provider", &["GIT_AI_PROVIDER", "OCO_AI_PROVIDER"]),
+    ("api_key", &["GIT_AI_API_KEY", "OCO_API_KEY"]),
+    ("base_url", &["GIT_AI_BASE_URL"]),
+    ("model", &["GIT_AI_MODEL", "OCO_MODEL"]),
+    ("agent_model", &["GIT_AI_AGENT_MODEL"]),
+    ("locale", &["GIT_AI_LOCALE"]),
+    ("custom_prompt", &["GIT_AI_CUSTOM_PROMPT"]),
+    ("enable_footer", &["GIT_AI_ENABLE_FOOTER"]),
+    ("hook_timeout_secs", &["GIT_AI_HOOK_TIMEOUT"]),
+];
+
+/// Keys holding credentials: redacted wherever config is shown or shared, and
+/// never stored in the committed `.git-ai.json`
+pub const SECRET_KEYS: &[&str] = &["api_key", "slack_webhook"];
+
+/// Whether the normalized config key holds a credential
+pub fn is_secret_key(key: &str) -> bool {
+    SECRET_KEYS.contains(&key)
+}
+
+/// Keys naming a file that the committed `.git-ai.json` may only point at
+/// inside the repository, so cloning a repo cannot make git-ai read (and
+/// send to the provider) files from elsewhere on the machine
+pub const REPO_PATH_KEYS: &[&str] = &["prompt_file", "report_template"];
+
+/// Whether a config path resolves inside the repository: relative, without
+/// `~/` or `..` components
+pub fn stays_in_repo(value: &str) -> bool {
+    let path = Path::new(value);
+    !value.starts_with('~')
+        && path.is_relative()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Layer an effective config value was taken from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// Built-in default
+    Default,
+    /// A config file
+    File(ConfigScope, PathBuf),
+    /// An environment variable
+    Env(String),
+    /// Output of a command, such as `gh auth token`
+    Command(String),
+}
+
+impl std::fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::File(scope, path) => write!(f, "{} ({})", scope.name(), path.display()),
+            ConfigOrigin::Env(var) => write!(f, "env ${}", var),
+            ConfigOrigin::Command(command) => write!(f, "`{}`", command),
+        }
+    }
+}
+
+impl ConfigScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigScope::Global => "global",
+            ConfigScope::Repo => "repo",
+            ConfigScope::Local => "local",
+        }
+    }
+}
+
+/// Config file set by the `--config` flag; takes precedence over GIT_AI_CONFIG_PATH
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Passphrase used to decrypt the config during this run, reused for writes
+static CONFIG_PASSPHRASE: OnceLock<String> = OnceLock::new();
+
+/// Mask a secret for display, keeping a short prefix/suffix for recognition.
+/// Each key of a comma-separated list is masked on its own.
+pub fn redact_secret(value: &str) -> String {
+    if value.contains(',') {
+        return value
+            .split(',')
+            .map(|part| redact_secret(part.trim()))
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+    let chars: Vec<char> = value.chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let prefix: String = chars[..3].iter().collect();
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}****{}", prefix, suffix)
+}
+
+/// Resolve a path from config: `~/` is the home dir, relative paths are
+/// relative to the repository root (or the current directory outside a repo)
+pub fn resolve_config_path(value: &str) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(home) = home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = PathBuf::from(value);
+    if path.is_relative() {
+        if let Ok(root) = GitManager::get_repo_root() {
+            return root.join(path);
+        }
+    }
+    path
+}
+
+/// Drop full-line `//` comments and trailing commas so hand-edited config
+/// files can be annotated and keys toggled by (un)commenting single lines
+pub fn strip_json_comments(content: &str) -> String {
+    let json = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Normalize a config key to its snake_case field name (apiKey -> api_key)
+pub fn normalize_key(key: &str) -> String {
+    let mut normalized = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            normalized.push('_');
+            normalized.push(c.to_ascii_lowercase());
+        } else if c == '-' || c == '.' {
+            normalized.push('_');
+        } else {
+            normalized.push(c);
+        }
+    }
+    normalized
+}
+
 impl ConfigManager {
     /// Get the global config directory
     pub fn get_global_config_dir() -> Result<PathBuf> {
@@ -14,8 +196,27 @@ impl ConfigManager {
         Ok(config_dir.join("git-ai-cli"))
     }
 
+    /// Point the global config at an alternate file (the `--config` flag)
+    pub fn set_config_path_override(path: PathBuf) {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
+    }
+
+    /// Alternate config file from `--config` or GIT_AI_CONFIG_PATH, if any
+    fn get_config_path_override() -> Option<PathBuf> {
+        if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+            return Some(path.clone());
+        }
+        std::env::var("GIT_AI_CONFIG_PATH")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    }
+
     /// Get the global config file path
     pub fn get_global_config_path() -> Result<PathBuf> {
+        if let Some(path) = Self::get_config_path_override() {
+            return Ok(path);
+        }
         let dir = Self::get_global_config_dir()?;
         Ok(dir.join("config.json"))
     }
@@ -25,6 +226,38 @@ impl ConfigManager {
         PathBuf::from(".git-ai.json")
     }
 
+    /// Get the repo-private config file path (git-ai.json in the git dir)
+    pub fn get_repo_config_path() -> Result<PathBuf> {
+        Ok(GitManager::get_git_common_dir()?.join("git-ai.json"))
+    }
+
+    /// Path of the config file for a scope
+    pub fn get_scope_config_path(scope: ConfigScope) -> Result<PathBuf> {
+        match scope {
+            ConfigScope::Global => Self::get_global_config_path(),
+            ConfigScope::Repo => Self::get_repo_config_path(),
+            ConfigScope::Local => Ok(Self::get_local_config_path()),
+        }
+    }
+
+    /// Read the config file for a scope
+    pub fn read_scope_config(scope: ConfigScope) -> Result<AIConfig> {
+        match scope {
+            ConfigScope::Global => Self::read_global_config(),
+            ConfigScope::Repo => Self::read_repo_config(),
+            ConfigScope::Local => Self::read_local_config(),
+        }
+    }
+
+    /// Write the config file for a scope
+    pub fn write_scope_config(scope: ConfigScope, config: &AIConfig) -> Result<()> {
+        match scope {
+            ConfigScope::Global => Self::write_global_config(config),
+            ConfigScope::Repo => Self::write_repo_config(config),
+            ConfigScope::Local => Self::write_local_config(config),
+        }
+    }
+
     /// Read global config from file
     pub fn read_global_config() -> Result<AIConfig> {
         let path = Self::get_global_config_path()?;
@@ -32,14 +265,13 @@ impl ConfigManager {
             return Self::read_config_file(&path, "global");
         }
 
-        // Backward-compat: v1 Node.js config locations.
-        for legacy_path in Self::get_legacy_global_config_paths() {
-            if !legacy_path.exists() {
-                continue;
-            }
-
-            let config = Self::read_config_file(&legacy_path, "legacy global")?;
+        // An explicit config location never falls back to (or migrates) legacy files
+        if Self::get_config_path_override().is_some() {
+            return Ok(AIConfig::default());
+        }
 
+        // Backward-compat: v1 Node.js config locations.
+        if let Some((_, config)) = Self::read_legacy_global_config()? {
             // Best-effort migration to the Rust config path.
             let _ = Self::write_global_config(&config);
             return Ok(config);
@@ -52,15 +284,105 @@ impl ConfigManager {
     pub fn read_local_config() -> Result<AIConfig> {
         let path = Self::get_local_config_path();
         if !path.exists() {
-            return Ok(AIConfig::default());
+            return Ok(AIConfig::unset());
         }
 
-        Self::read_config_file(&path, "local")
+        // The team config is shared through git, so hold it to the strict schema
+        let content = fs::read_to_string(&path).map_err(|e| {
+            GitAiError::Config(format!(
+                "Failed to read local config ({}): {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::validate_config_text(&content, ConfigScope::Local).map_err(|errors| {
+            let details: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+            GitAiError::Config(format!(
+                "Invalid team config {}:\n{}\nFix it with 'git-ai config edit --local'.",
+                path.display(),
+                details.join("\n")
+            ))
+        })
+    }
+
+    /// Check config JSON against the known keys and their types, reporting
+    /// every offending field. The committed local file may not hold secrets.
+    pub fn validate_config_text(
+        content: &str,
+        scope: ConfigScope,
+    ) -> std::result::Result<AIConfig, Vec<String>> {
+        let value: Value = serde_json::from_str(&strip_json_comments(content))
+            .map_err(|e| vec![format!("invalid JSON: {}", e)])?;
+        let Some(fields) = value.as_object() else {
+            return Err(vec!["config must be a JSON object".to_string()]);
+        };
+
+        let known = serde_json::to_value(AIConfig::unset()).map_err(|e| vec![e.to_string()])?;
+        let mut errors = Vec::new();
+        for (key, field) in fields {
+            let name = normalize_key(key);
+            if known.get(&name).is_none() {
+                errors.push(format!(
+                    "{}: unknown key (see 'git-ai config describe')",
+                    key
+                ));
+                continue;
+            }
+
+            let is_set = !(field.is_null() || field.as_str() == Some(""));
+            if scope == ConfigScope::Local && is_set && is_secret_key(&name) {
+                let env_var = ENV_VARS
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .and_then(|(_, vars)| vars.first());
+                let set_env = env_var
+                    .map(|var| format!("set {} or ", var))
+                    .unwrap_or_default();
+                errors.push(format!(
+                    "{}: secrets must not be committed; {}run 'git-ai config set {} <value> --repo'",
+                    key, set_env, name
+                ));
+                continue;
+            }
+            if scope == ConfigScope::Local && REPO_PATH_KEYS.contains(&name.as_str()) {
+                if let Some(path) = field
+                    .as_str()
+                    .filter(|p| !p.is_empty() && !stays_in_repo(p))
+                {
+                    errors.push(format!(
+                        "{}: '{}' is outside the repository; commit the file and use a relative path, or run 'git-ai config set {} <path> --repo'",
+                        key, path, name
+                    ));
+                    continue;
+                }
+            }
+
+            // Deserialize the field on its own so type errors name the key
+            let single = Value::Object(Map::from_iter([(key.clone(), field.clone())]));
+            if let Err(e) = serde_json::from_value::<AIConfig>(single) {
+                errors.push(format!("{}: {}", key, e));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        serde_json::from_value(value).map_err(|e| vec![e.to_string()])
+    }
+
+    /// Read repo-private config from the git dir
+    pub fn read_repo_config() -> Result<AIConfig> {
+        let path = Self::get_repo_config_path()?;
+        if !path.exists() {
+            return Ok(AIConfig::unset());
+        }
+
+        Self::read_config_file(&path, "repo")
     }
 
     /// Read config from environment variables
     pub fn read_env_config() -> AIConfig {
-        let mut config = AIConfig::default();
+        let mut config = AIConfig::unset();
 
         // Provider
         if let Ok(provider) = std::env::var("GIT_AI_PROVIDER") {
@@ -113,11 +435,16 @@ impl ConfigManager {
             ));
         }
 
+        // Hook Timeout
+        if let Ok(timeout) = std::env::var("GIT_AI_HOOK_TIMEOUT") {
+            config.hook_timeout_secs = timeout.parse::<u64>().ok();
+        }
+
         config
     }
 
     fn read_config_file(path: &PathBuf, scope: &str) -> Result<AIConfig> {
-        let content = fs::read_to_string(path).map_err(|e| {
+        let mut content = fs::read_to_string(path).map_err(|e| {
             GitAiError::Config(format!(
                 "Failed to read {} config ({}): {}",
                 scope,
@@ -126,7 +453,11 @@ impl ConfigManager {
             ))
         })?;
 
-        serde_json::from_str(&content).map_err(|e| {
+        if crypto::is_encrypted(&content) {
+            content = crypto::decrypt(&content, &Self::get_config_passphrase(path)?)?;
+        }
+
+        serde_json::from_str(&strip_json_comments(&content)).map_err(|e| {
             GitAiError::Config(format!(
                 "Invalid {} config JSON ({}): {}",
                 scope,
@@ -136,6 +467,17 @@ impl ConfigManager {
         })
     }
 
+    /// First config left behind by the v1 Node.js version, if any
+    pub fn read_legacy_global_config() -> Result<Option<(PathBuf, AIConfig)>> {
+        for legacy_path in Self::get_legacy_global_config_paths() {
+            if legacy_path.exists() {
+                let config = Self::read_config_file(&legacy_path, "legacy global")?;
+                return Ok(Some((legacy_path, config)));
+            }
+        }
+        Ok(None)
+    }
+
     fn get_legacy_global_config_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
@@ -152,81 +494,129 @@ impl ConfigManager {
         paths
     }
 
-    /// Merge configs with priority: env > local > global
+    /// Merge configs with priority: env > local > repo > global
     ///
-    /// Generic env vars (OPENAI_API_KEY, DEEPSEEK_API_KEY) are only used as a
-    /// last-resort fallback when no API key is configured in any config file or
-    /// explicit git-ai env var.
+    /// Each layer only overrides the keys it actually sets. Generic env vars
+    /// (OPENAI_API_KEY, DEEPSEEK_API_KEY) are only used as a last-resort
+    /// fallback when no API key is configured in any config file or explicit
+    /// git-ai env var.
     pub fn get_merged_config() -> Result<AIConfig> {
-        let global = Self::read_global_config()?;
-        let local = Self::read_local_config()?;
-        let env = Self::read_env_config();
-
-        let mut merged = global;
+        let (values, _) = Self::resolve_config()?;
+        let mut config: AIConfig = serde_json::from_value(Value::Object(values))?;
+        (config.locale, config.second_locale) = split_locale(&config.locale);
+        Ok(config)
+    }
 
-        // Merge local config
-        if !local.provider.is_empty() {
-            merged.provider = local.provider;
-        }
-        if !local.api_key.is_empty() {
-            merged.api_key = local.api_key;
-        }
-        if !local.base_url.is_empty() {
-            merged.base_url = local.base_url;
-        }
-        if !local.model.is_empty() {
-            merged.model = local.model;
-        }
-        if local.agent_model.is_some() {
-            merged.agent_model = local.agent_model;
-        }
-        if !local.locale.is_empty() {
-            merged.locale = local.locale;
-        }
-        if local.custom_prompt.is_some() {
-            merged.custom_prompt = local.custom_prompt;
-        }
-        if local.enable_footer.is_some() {
-            merged.enable_footer = local.enable_footer;
-        }
+    /// Effective value of every config key together with the layer that set it
+    pub fn get_config_origins() -> Result<Vec<(String, Value, Option<ConfigOrigin>)>> {
+        let (values, mut origins) = Self::resolve_config()?;
+        let config: AIConfig = serde_json::from_value(Value::Object(values))?;
+        let all_values = match serde_json::to_value(&config)? {
+            Value::Object(values) => values,
+            _ => Map::new(),
+        };
+
+        Ok(all_values
+            .into_iter()
+            .map(|(key, value)| {
+                let origin = origins.remove(&key);
+                (key, value, origin)
+            })
+            .collect())
+    }
 
-        // Merge env config (highest priority -- only explicit git-ai vars)
-        if !env.provider.is_empty() {
-            merged.provider = env.provider;
-        }
-        if !env.api_key.is_empty() {
-            merged.api_key = env.api_key;
-        }
-        if !env.base_url.is_empty() {
-            merged.base_url = env.base_url;
-        }
-        if !env.model.is_empty() {
-            merged.model = env.model;
-        }
-        if env.agent_model.is_some() {
-            merged.agent_model = env.agent_model;
-        }
-        if !env.locale.is_empty() {
-            merged.locale = env.locale;
-        }
-        if env.custom_prompt.is_some() {
-            merged.custom_prompt = env.custom_prompt;
-        }
-        if env.enable_footer.is_some() {
-            merged.enable_footer = env.enable_footer;
+    fn resolve_config() -> Result<(Map<String, Value>, HashMap<String, ConfigOrigin>)> {
+        let mut values = Self::config_values(&AIConfig::default())?;
+        let mut origins: HashMap<String, ConfigOrigin> = values
+            .keys()
+            .map(|key| (key.clone(), ConfigOrigin::Default))
+            .collect();
+
+        let mut scopes = vec![ConfigScope::Global];
+        // The repo layer only exists inside a git repository
+        if GitManager::is_in_git_repo().unwrap_or(false) {
+            scopes.push(ConfigScope::Repo);
+        }
+        scopes.push(ConfigScope::Local);
+
+        for scope in scopes {
+            let layer = Self::config_values(&Self::read_scope_config(scope)?)?;
+            let path = Self::get_scope_config_path(scope)?;
+            for (key, value) in layer {
+                origins.insert(key.clone(), ConfigOrigin::File(scope, path.clone()));
+                values.insert(key, value);
+            }
         }
 
-        // Last-resort fallback: use generic env vars only when no API key is
-        // configured from any file or explicit env var.
-        if merged.api_key.is_empty() {
-            if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
-                merged.api_key = api_key;
-            } else if let Ok(api_key) = std::env::var("DEEPSEEK_API_KEY") {
-                merged.api_key = api_key;
+        for (key, value) in Self::config_values(&Self::read_env_config())? {
+            let var = ENV_VARS
+                .iter()
+                .find(|(field, _)| *field == key)
+                .and_then(|(_, vars)| vars.iter().find(|v| std::env::var(v).is_ok()))
+                .copied()
+                .unwrap_or("environment");
+            origins.insert(key.clone(), ConfigOrigin::Env(var.to_string()));
+            values.insert(key, value);
+        }
+
+        // Last-resort fallback: use the provider's own env vars (and `gh auth
+        // token` for GitHub Models), only when no API key is configured from
+        // any file or explicit env var. The generic ones are only for custom
+        // endpoints without a preset, so an OPENAI_API_KEY is never sent to
+        // another known provider.
+        if !values.contains_key("api_key") {
+            let provider = values
+                .get("provider")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let presets = get_provider_presets();
+            let preset = presets.get(provider.as_str());
+            let preset_vars = preset.map(|preset| preset.key_env).unwrap_or_default();
+            let generic_vars: &[&str] = if preset.is_none() {
+                &["OPENAI_API_KEY", "DEEPSEEK_API_KEY"]
+            } else {
+                &[]
+            };
+            let from_env = |vars: &[&str]| {
+                vars.iter().find_map(|var| {
+                    std::env::var(var)
+                        .ok()
+                        .filter(|k| !k.is_empty())
+                        .map(|key| (key, ConfigOrigin::Env(var.to_string())))
+                })
+            };
+            let found = from_env(preset_vars)
+                .or_else(|| {
+                    (provider == "github-models")
+                        .then(crate::utils::github::gh_auth_token)
+                        .flatten()
+                        .map(|token| (token, ConfigOrigin::Command("gh auth token".to_string())))
+                })
+                .or_else(|| from_env(generic_vars));
+            if let Some((api_key, origin)) = found {
+                values.insert("api_key".to_string(), Value::String(api_key));
+                origins.insert("api_key".to_string(), origin);
             }
         }
 
-        Ok(merged)
+        Ok((values, origins))
+    }
+
+    /// Keys a config layer actually sets (empty strings and nulls are unset)
+    pub fn config_values(config: &AIConfig) -> Result<Map<String, Value>> {
+        let values = match serde_json::to_value(config)? {
+            Value::Object(values) => values,
+            _ => Map::new(),
+        };
+        Ok(values
+            .into_iter()
+            .filter(|(_, value)| match value {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                _ => true,
+            })
+            .collect())
     }
 
     /// Get validated config (provider is required)
@@ -243,22 +633,125 @@ impl ConfigManager {
 
     /// Write global config
     pub fn write_global_config(config: &AIConfig) -> Result<()> {
-        let dir = Self::get_global_config_dir()?;
-        fs::create_dir_all(&dir)
-            .map_err(|e| GitAiError::Config(format!("Failed to create config directory: {}", e)))?;
+        let path = Self::get_global_config_path()?;
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| {
+                GitAiError::Config(format!("Failed to create config directory: {}", e))
+            })?;
+        }
 
-        let path = dir.join("config.json");
-        let json = serde_json::to_string_pretty(config)
+        let mut json = serde_json::to_string_pretty(config)
             .map_err(|e| GitAiError::Config(format!("Failed to serialize config: {}", e)))?;
 
+        // Keep an encrypted config encrypted
+        if Self::is_global_config_encrypted()? {
+            json = crypto::encrypt(&json, &Self::get_config_passphrase(&path)?)?;
+        }
+
         fs::write(&path, json)
             .map_err(|e| GitAiError::Config(format!("Failed to write config: {}", e)))?;
 
         Ok(())
     }
 
+    /// Whether the global config file is stored encrypted
+    pub fn is_global_config_encrypted() -> Result<bool> {
+        let path = Self::get_global_config_path()?;
+        Ok(fs::read_to_string(&path)
+            .map(|content| crypto::is_encrypted(&content))
+            .unwrap_or(false))
+    }
+
+    /// Rewrite the global config encrypted with `passphrase`, or in plaintext when `None`
+    pub fn write_global_config_encryption(
+        config: &AIConfig,
+        passphrase: Option<&str>,
+    ) -> Result<()> {
+        let path = Self::get_global_config_path()?;
+        let json = serde_json::to_string_pretty(config)
+            .map_err(|e| GitAiError::Config(format!("Failed to serialize config: {}", e)))?;
+        let content = match passphrase {
+            Some(passphrase) => crypto::encrypt(&json, passphrase)?,
+            None => json,
+        };
+
+        fs::write(&path, content)
+            .map_err(|e| GitAiError::Config(format!("Failed to write config: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Passphrase for an encrypted config: GIT_AI_CONFIG_KEY, GIT_AI_CONFIG_KEY_FILE,
+    /// or an interactive prompt (asked once per run)
+    fn get_config_passphrase(path: &Path) -> Result<String> {
+        if let Some(passphrase) = CONFIG_PASSPHRASE.get() {
+            return Ok(passphrase.clone());
+        }
+
+        let passphrase = if let Some(key) = Self::get_env_config_key()? {
+            key
+        } else if crate::utils::ci::interactive() {
+            dialoguer::Password::new()
+                .with_prompt(format!("Passphrase for {}", path.display()))
+                .interact()
+                .map_err(|e| GitAiError::Config(format!("Failed to read passphrase: {}", e)))?
+        } else {
+            return Err(GitAiError::Config(format!(
+                "{} is encrypted. Set GIT_AI_CONFIG_KEY or GIT_AI_CONFIG_KEY_FILE to decrypt it.",
+                path.display()
+            )));
+        };
+
+        Ok(CONFIG_PASSPHRASE.get_or_init(|| passphrase).clone())
+    }
+
+    /// Passphrase from GIT_AI_CONFIG_KEY or the file named by GIT_AI_CONFIG_KEY_FILE
+    pub fn get_env_config_key() -> Result<Option<String>> {
+        if let Ok(key) = std::env::var("GIT_AI_CONFIG_KEY") {
+            if !key.is_empty() {
+                return Ok(Some(key));
+            }
+        }
+        if let Ok(file) = std::env::var("GIT_AI_CONFIG_KEY_FILE") {
+            let key = fs::read_to_string(&file).map_err(|e| {
+                GitAiError::Config(format!("Failed to read key file {}: {}", file, e))
+            })?;
+            return Ok(Some(key.trim_end_matches(['\r', '\n']).to_string()));
+        }
+        Ok(None)
+    }
+
     /// Write local config
     pub fn write_local_config(config: &AIConfig) -> Result<()> {
+        if !config.api_key.is_empty() {
+            return Err(GitAiError::Config(
+                "Refusing to store api_key in the committed .git-ai.json; use GIT_AI_API_KEY or '--repo' instead"
+                    .to_string(),
+            ));
+        }
+        if config
+            .slack_webhook
+            .as_deref()
+            .is_some_and(|url| !url.is_empty())
+        {
+            return Err(GitAiError::Config(
+                "Refusing to store slack_webhook in the committed .git-ai.json; use '--repo' instead"
+                    .to_string(),
+            ));
+        }
+
+        let values = Self::config_values(config)?;
+        for key in REPO_PATH_KEYS {
+            if let Some(path) = values.get(*key).and_then(Value::as_str) {
+                if !stays_in_repo(path) {
+                    return Err(GitAiError::Config(format!(
+                        "Refusing to point {} outside the repository in the committed .git-ai.json; use a relative path or '--repo' instead",
+                        key
+                    )));
+                }
+            }
+        }
+
         let path = Self::get_local_config_path();
         let json = serde_json::to_string_pretty(config)
             .map_err(|e| GitAiError::Config(format!("Failed to serialize config: {}", e)))?;
@@ -269,8 +762,32 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Write repo-private config
+    pub fn write_repo_config(config: &AIConfig) -> Result<()> {
+        let path = Self::get_repo_config_path()?;
+        let json = serde_json::to_string_pretty(config)
+            .map_err(|e| GitAiError::Config(format!("Failed to serialize config: {}", e)))?;
+
+        fs::write(&path, json)
+            .map_err(|e| GitAiError::Config(format!("Failed to write config: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Clear the config file for a scope
+    pub fn clear_scope_config(scope: ConfigScope) -> Result<()> {
+        if scope == ConfigScope::Global {
+            return Self::clear_global_config();
+        }
+        let path = Self::get_scope_config_path(scope)?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| GitAiError::Config(format!("Failed to delete config: {}", e)))?;
+        }
+        Ok(())
+    }
+
     /// Clear global config
-    #[allow(dead_code)]
     pub fn clear_global_config() -> Result<()> {
         let path = Self::get_global_config_path()?;
         if path.exists() {
@@ -280,3 +797,115 @@ impl ConfigManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_key, redact_secret, stays_in_repo, strip_json_comments, ConfigManager,
+        ConfigScope,
+    };
+    use crate::types::AIConfig;
+
+    #[test]
+    fn redact_secret_masks_middle() {
+        assert_eq!(redact_secret(""), "");
+        assert_eq!(redact_secret("short"), "****");
+        assert_eq!(redact_secret("sk-1234567890abcdef"), "sk-****cdef");
+        assert_eq!(
+            redact_secret("sk-1234567890abcdef, sk-abcdefghij1234"),
+            "sk-****cdef,sk-****1234"
+        );
+    }
+
+    #[test]
+    fn normalize_key_accepts_camel_and_kebab_case() {
+        assert_eq!(normalize_key("apiKey"), "api_key");
+        assert_eq!(normalize_key("hook-timeout-secs"), "hook_timeout_secs");
+        assert_eq!(normalize_key("model"), "model");
+        assert_eq!(normalize_key("ui.summary"), "ui_summary");
+    }
+
+    #[test]
+    fn strip_json_comments_keeps_values() {
+        let content = "// header\n{\n  \"locale\": \"zh\",\n  // \"model\": \"x\"\n  \"types\": [\"a,]\",],\n}";
+        let value: serde_json::Value = serde_json::from_str(&strip_json_comments(content)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "locale": "zh", "types": ["a,]"] })
+        );
+    }
+
+    #[test]
+    fn team_config_rejects_secrets_and_reports_each_field() {
+        let content =
+            r#"{"provider": "openai", "apiKey": "sk-1", "modle": "x", "default_num": "two"}"#;
+        let errors = ConfigManager::validate_config_text(content, ConfigScope::Local).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.starts_with("apiKey: secrets")));
+        assert!(errors.iter().any(|e| e.starts_with("modle: unknown key")));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("default_num: invalid type")));
+
+        // An empty key is what write_local_config serializes; other scopes may hold keys
+        assert!(
+            ConfigManager::validate_config_text(r#"{"api_key": ""}"#, ConfigScope::Local).is_ok()
+        );
+        assert!(
+            ConfigManager::validate_config_text(r#"{"api_key": "sk-1"}"#, ConfigScope::Repo)
+                .is_ok()
+        );
+        let errors = ConfigManager::validate_config_text(
+            r#"{"slackWebhook": "https://hooks.slack.com/services/T0/B0/x"}"#,
+            ConfigScope::Local,
+        )
+        .unwrap_err();
+        assert!(errors[0].starts_with("slackWebhook: secrets must not be committed; run"));
+    }
+
+    #[test]
+    fn team_config_keeps_template_files_inside_the_repo() {
+        assert!(stays_in_repo(".git-ai/prompt.md"));
+        assert!(stays_in_repo("./prompts/commit.md"));
+        for path in [
+            "~/.ssh/id_rsa",
+            "/etc/passwd",
+            "../outside.md",
+            "prompts/../../x",
+        ] {
+            assert!(!stays_in_repo(path), "{}", path);
+            let content = format!(r#"{{"promptFile": "{}"}}"#, path);
+            let errors =
+                ConfigManager::validate_config_text(&content, ConfigScope::Local).unwrap_err();
+            assert!(errors[0].starts_with("promptFile: '"), "{}", errors[0]);
+        }
+        let errors = ConfigManager::validate_config_text(
+            r#"{"reportTemplate": "~/.aws/credentials"}"#,
+            ConfigScope::Local,
+        )
+        .unwrap_err();
+        assert!(errors[0].starts_with("reportTemplate: '"));
+        assert!(ConfigManager::validate_config_text(
+            r#"{"report_template": ".github/weekly-report.md"}"#,
+            ConfigScope::Local
+        )
+        .is_ok());
+        assert!(ConfigManager::validate_config_text(
+            r#"{"prompt_file": "~/prompt.md"}"#,
+            ConfigScope::Global
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn config_values_skip_unset_keys() {
+        let mut layer = AIConfig::unset();
+        layer.model = "gpt-4o".to_string();
+        layer.enable_footer = Some(false);
+
+        let values = ConfigManager::config_values(&layer).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["model"], "gpt-4o");
+        assert_eq!(values["enable_footer"], false);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::types::AIConfig;
+use crate::utils::ai::PromptTemplates;
+
+/// Header of a Conventional Commits message: `type(scope)!: subject`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalHeader {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+}
+
+impl ConventionalHeader {
+    /// Parse the first line of a commit message
+    pub fn parse(message: &str) -> Option<Self> {
+        let header = message.lines().next()?.trim();
+        let (prefix, subject) = header.split_once(':')?;
+        let subject = subject.trim();
+        if subject.is_empty() {
+            return None;
+        }
+
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+
+        let (commit_type, scope) = match prefix.split_once('(') {
+            Some((commit_type, rest)) => {
+                let scope = rest.strip_suffix(')')?.trim();
+                if scope.is_empty() {
+                    return None;
+                }
+                (commit_type, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+
+        if commit_type.is_empty()
+            || !commit_type
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return None;
+        }
+
+        Some(Self {
+            commit_type: commit_type.to_string(),
+            scope,
+            breaking,
+            subject: subject.to_string(),
+        })
+    }
+}
+
+/// Subject lines longer than this are sent back for a shorter one
+pub const MAX_SUBJECT_CHARS: usize = 72;
+
+/// Team restrictions on the commit vocabulary (`allowed_types` / `allowed_scopes`)
+/// and the format the prompt asked for
+#[derive(Debug, Clone, Default)]
+pub struct MessageRules {
+    pub allowed_types: Vec<String>,
+    pub allowed_scopes: Vec<String>,
+    /// Expect a `type(scope): subject` header
+    pub conventional: bool,
+    /// Longest subject line in characters (0: unchecked)
+    pub max_subject_chars: usize,
+    /// Language of the subject: "en" or "zh"
+    pub language: Option<String>,
+}
+
+impl MessageRules {
+    pub fn from_config(config: &AIConfig) -> Self {
+        Self {
+            allowed_types: config.allowed_types.clone().unwrap_or_default(),
+            allowed_scopes: config.allowed_scopes.clone().unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    /// Also check the format the built-in prompts ask for in `locale` and
+    /// `style`. A custom prompt or prompt file may ask for anything, so it is
+    /// only held to the subject length.
+    pub fn with_format(mut self, config: &AIConfig, locale: &str, style: Option<&str>) -> Self {
+        let builtin_prompt = config.custom_prompt.is_none()
+            && config.prompt_file.is_none()
+            && (config.prompt_preset.is_some()
+                || PromptTemplates::provider_override(&config.provider, locale).is_none());
+        self.conventional = builtin_prompt
+            && matches!(
+                PromptTemplates::preset_for_style(config.prompt_preset.as_deref(), style),
+                None | Some("conventional" | "detailed-body" | "minimal")
+            )
+            && matches!(style, None | Some("conventional"))
+            && config.conventions.as_ref().is_none_or(|c| c.conventional);
+        self.max_subject_chars = MAX_SUBJECT_CHARS;
+        self.language =
+            (builtin_prompt && matches!(locale, "en" | "zh")).then(|| locale.to_string());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allowed_types.is_empty() && self.allowed_scopes.is_empty()
+    }
+
+    /// Rules appended to the system prompt
+    pub fn prompt_section(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut section = String::from("Allowed vocabulary:");
+        if !self.allowed_types.is_empty() {
+            section.push_str(&format!(
+                "\n- type MUST be one of: {}",
+                self.allowed_types.join(", ")
+            ));
+        }
+        if !self.allowed_scopes.is_empty() {
+            section.push_str(&format!(
+                "\n- scope, if present, MUST be one of: {}",
+                self.allowed_scopes.join(", ")
+            ));
+        }
+        Some(section)
+    }
+
+    /// Check a generated message; the error explains the violation
+    pub fn check(&self, message: &str) -> std::result::Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let header = ConventionalHeader::parse(message).ok_or_else(|| {
+            format!(
+                "'{}' is not in 'type(scope): subject' form",
+                message.lines().next().unwrap_or("").trim()
+            )
+        })?;
+
+        if !self.allowed_types.is_empty() && !self.allowed_types.contains(&header.commit_type) {
+            return Err(format!(
+                "type '{}' is not allowed (allowed: {})",
+                header.commit_type,
+                self.allowed_types.join(", ")
+            ));
+        }
+
+        if let Some(scope) = &header.scope {
+            if !self.allowed_scopes.is_empty() && !self.allowed_scopes.contains(scope) {
+                return Err(format!(
+                    "scope '{}' is not allowed (allowed: {})",
+                    scope,
+                    self.allowed_scopes.join(", ")
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the shape of a message. Unlike `check`, a problem here earns one
+    /// corrective retry but does not reject the message.
+    pub fn check_format(&self, message: &str) -> std::result::Result<(), String> {
+        let subject = message.lines().next().unwrap_or("").trim();
+        if subject.is_empty() {
+            return Err("the message is empty".to_string());
+        }
+        if self.conventional && ConventionalHeader::parse(message).is_none() {
+            return Err(format!(
+                "'{}' has no 'type(scope): subject' header",
+                subject
+            ));
+        }
+
+        let length = subject.chars().count();
+        if self.max_subject_chars > 0 && length > self.max_subject_chars {
+            return Err(format!(
+                "the subject line has {} characters, the limit is {}",
+                length, self.max_subject_chars
+            ));
+        }
+
+        let has_cjk = subject.chars().any(is_cjk);
+        match self.language.as_deref() {
+            Some("zh") if !has_cjk => Err("the subject is not written in Chinese".to_string()),
+            Some("en") if has_cjk => Err("the subject is not written in English".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conventional_headers() {
+        let header = ConventionalHeader::parse("feat(api)!: drop v1 endpoints\n\nbody").unwrap();
+        assert_eq!(header.commit_type, "feat");
+        assert_eq!(header.scope.as_deref(), Some("api"));
+        assert!(header.breaking);
+        assert_eq!(header.subject, "drop v1 endpoints");
+
+        assert!(ConventionalHeader::parse("fix: typo").is_some());
+        assert!(ConventionalHeader::parse("Update readme").is_none());
+        assert!(ConventionalHeader::parse("feat(): empty scope").is_none());
+    }
+
+    #[test]
+    fn rules_reject_types_and_scopes_outside_lists() {
+        let rules = MessageRules {
+            allowed_types: vec!["feat".to_string(), "fix".to_string()],
+            allowed_scopes: vec!["api".to_string()],
+            ..Default::default()
+        };
+
+        assert!(rules.check("feat(api): add endpoint").is_ok());
+        assert!(rules.check("fix: handle null").is_ok());
+        assert!(rules.check("chore: bump deps").is_err());
+        assert!(rules.check("feat(ui): add button").is_err());
+        assert!(rules.check("Add button").is_err());
+        assert!(MessageRules::default().check("Add button").is_ok());
+    }
+
+    #[test]
+    fn format_check_flags_header_length_and_language() {
+        let config = AIConfig::default();
+        let en = MessageRules::default().with_format(&config, "en", None);
+        assert!(en.check_format("feat(api): add endpoint\n\nbody").is_ok());
+        assert!(en.check_format("Add endpoint").is_err());
+        assert!(en
+            .check_format(&format!("fix: {}", "x".repeat(80)))
+            .is_err());
+        assert!(en.check_format("fix: 修复空指针").is_err());
+
+        let zh = MessageRules::default().with_format(&config, "zh", None);
+        assert!(zh.check_format("fix(api): 修复空指针").is_ok());
+        assert!(zh.check_format("fix(api): handle null").is_err());
+
+        // Gitmoji messages have no conventional header
+        let gitmoji = MessageRules::default().with_format(&config, "en", Some("gitmoji"));
+        assert!(gitmoji.check_format("✨ add endpoint").is_ok());
+        assert!(MessageRules::default().check_format("Add endpoint").is_ok());
+    }
+}
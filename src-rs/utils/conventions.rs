@@ -0,0 +1,313 @@
+use crate::types::Conventions;
+use crate::utils::conventional::ConventionalHeader;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Types and scopes kept in the learned vocabulary
+const MAX_TYPES: usize = 10;
+const MAX_SCOPES: usize = 15;
+
+/// Irregular past forms that do not end in "-ed"
+const IRREGULAR_PAST: &[&str] = &["made", "built", "wrote", "ran", "got", "began"];
+
+fn jira_ticket() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b([A-Z][A-Z0-9]{1,9})-\d+\b").unwrap())
+}
+
+fn github_ticket() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|[\s(\[])#\d+\b").unwrap())
+}
+
+impl Conventions {
+    /// Infer conventions from full commit messages, newest first. Merge and
+    /// revert commits are git's wording and left out.
+    pub fn learn(messages: &[String]) -> Self {
+        let messages: Vec<&str> = messages
+            .iter()
+            .map(|message| message.trim())
+            .filter(|message| {
+                !message.is_empty()
+                    && !message.starts_with("Merge ")
+                    && !message.starts_with("Revert \"")
+            })
+            .collect();
+        let total = messages.len();
+        if total == 0 {
+            return Self::default();
+        }
+        // Small histories keep everything; larger ones drop one-offs
+        let min_uses = if total >= 20 { 2 } else { 1 };
+
+        let headers: Vec<ConventionalHeader> = messages
+            .iter()
+            .filter_map(|message| ConventionalHeader::parse(message))
+            .collect();
+        let types = most_frequent(
+            headers.iter().map(|h| h.commit_type.to_lowercase()),
+            min_uses,
+            MAX_TYPES,
+        );
+        let scopes = most_frequent(
+            headers.iter().filter_map(|h| h.scope.clone()),
+            min_uses,
+            MAX_SCOPES,
+        );
+
+        let subjects: Vec<String> = messages
+            .iter()
+            .map(|message| match ConventionalHeader::parse(message) {
+                Some(header) => header.subject,
+                None => message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            })
+            .collect();
+        let (ticket_format, ticket_placement) = match detect_tickets(&messages) {
+            Some((format, placement)) => (Some(format), Some(placement.to_string())),
+            None => (None, None),
+        };
+
+        Self {
+            sample_size: total,
+            conventional: headers.len() * 2 >= total,
+            types,
+            scopes,
+            tense: detect_tense(&subjects).map(str::to_string),
+            language: Some(detect_language(&subjects).to_string()),
+            ticket_format,
+            ticket_placement,
+        }
+    }
+
+    /// Instructions appended to the system prompt
+    pub fn prompt_section(&self) -> String {
+        let mut section = format!(
+            "Conventions of this repository (learned from its last {} commits):",
+            self.sample_size
+        );
+        if self.conventional {
+            if !self.types.is_empty() {
+                section.push_str(&format!(
+                    "\n- Commit types used here: {}",
+                    self.types.join(", ")
+                ));
+            }
+            if !self.scopes.is_empty() {
+                section.push_str(&format!(
+                    "\n- Scope vocabulary: {} (reuse one of these when it fits)",
+                    self.scopes.join(", ")
+                ));
+            }
+        } else {
+            section.push_str(
+                "\n- Most commits here have no type(scope) prefix; write a plain subject line",
+            );
+        }
+        match self.tense.as_deref() {
+            Some("past") => {
+                section.push_str("\n- Write the subject in the past tense (\"added\", \"fixed\")")
+            }
+            Some("third-person") => {
+                section.push_str("\n- Write the subject in the third person (\"adds\", \"fixes\")")
+            }
+            Some("imperative") => section.push_str(
+                "\n- Write the subject in the imperative mood (\"add\", not \"added\" or \"adds\")",
+            ),
+            _ => {}
+        }
+        if let Some(format) = &self.ticket_format {
+            let placement = match self.ticket_placement.as_deref() {
+                Some("subject-prefix") => "at the start of the subject",
+                Some("subject-suffix") => "at the end of the subject",
+                _ => "in a footer line",
+            };
+            section.push_str(&format!(
+                "\n- Tickets are referenced like {} {}; add one only when the branch name or context gives it, never invent one",
+                format, placement
+            ));
+        }
+        section
+    }
+}
+
+/// Values used at least `min_uses` times, most frequent first (ties keep
+/// first-seen order)
+fn most_frequent(values: impl Iterator<Item = String>, min_uses: usize, max: usize) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(existing, _)| *existing == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    counts.retain(|(_, count)| *count >= min_uses);
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+        .into_iter()
+        .take(max)
+        .map(|(value, _)| value)
+        .collect()
+}
+
+/// Mood of most English subjects, judged by their first word
+fn detect_tense(subjects: &[String]) -> Option<&'static str> {
+    let mut votes: HashMap<&'static str, usize> = HashMap::new();
+    for subject in subjects {
+        if !subject.is_ascii() {
+            continue;
+        }
+        // "[ABC-1] Added x" is judged by "added"
+        let subject = jira_ticket().replace_all(subject, "");
+        let subject = github_ticket().replace_all(&subject, "").to_lowercase();
+        let Some(word) = subject
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .find(|word| !word.is_empty())
+        else {
+            continue;
+        };
+        if word.len() < 3 {
+            continue;
+        }
+        let tense = if word.ends_with("ed") || IRREGULAR_PAST.contains(&word) {
+            "past"
+        } else if word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") {
+            "third-person"
+        } else {
+            "imperative"
+        };
+        *votes.entry(tense).or_default() += 1;
+    }
+
+    let total: usize = votes.values().sum();
+    if total < 3 {
+        return None;
+    }
+    let (tense, count) = votes.into_iter().max_by_key(|(_, count)| *count)?;
+    (count * 2 > total).then_some(tense)
+}
+
+/// "zh" when most subjects contain CJK characters
+fn detect_language(subjects: &[String]) -> &'static str {
+    let cjk = subjects
+        .iter()
+        .filter(|subject| {
+            subject
+                .chars()
+                .any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c))
+        })
+        .count();
+    if cjk * 2 > subjects.len() {
+        "zh"
+    } else {
+        "en"
+    }
+}
+
+/// Ticket shape and placement used by at least a fifth of the messages
+fn detect_tickets(messages: &[&str]) -> Option<(String, &'static str)> {
+    let mut seen: Vec<((String, &'static str), usize)> = Vec::new();
+    for message in messages {
+        let subject = message.lines().next().unwrap_or_default().trim();
+        let body: String = message.lines().skip(1).collect::<Vec<_>>().join("\n");
+
+        let jira = |text: &str| {
+            jira_ticket().captures(text).map(|caps| {
+                (
+                    caps.get(0).map(|m| m.start()).unwrap_or(0),
+                    format!("{}-123", &caps[1]),
+                )
+            })
+        };
+        let github = |text: &str| {
+            github_ticket()
+                .find(text)
+                .map(|m| (m.start(), "#123".to_string()))
+        };
+
+        let found = match jira(subject).or_else(|| github(subject)) {
+            Some((start, format)) => {
+                let placement = if start <= 1 {
+                    "subject-prefix"
+                } else if start * 2 >= subject.len() {
+                    "subject-suffix"
+                } else {
+                    continue;
+                };
+                Some((format, placement))
+            }
+            None => jira(&body)
+                .or_else(|| github(&body))
+                .map(|(_, format)| (format, "footer")),
+        };
+        if let Some(key) = found {
+            match seen.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, count)) => *count += 1,
+                None => seen.push((key, 1)),
+            }
+        }
+    }
+
+    let (key, count) = seen.into_iter().max_by_key(|(_, count)| *count)?;
+    (count * 5 >= messages.len()).then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn learn(messages: &[&str]) -> Conventions {
+        let messages: Vec<String> = messages.iter().map(|m| m.to_string()).collect();
+        Conventions::learn(&messages)
+    }
+
+    #[test]
+    fn learns_conventional_history() {
+        let conventions = learn(&[
+            "feat(auth): add token refresh\n\nRefs: AUTH-12",
+            "fix(auth): handle expired sessions\n\nRefs: AUTH-15",
+            "feat(report): add --per-tag",
+            "Merge branch 'main' into feature",
+            "docs: update README\n\nRefs: DOC-3",
+        ]);
+        assert_eq!(conventions.sample_size, 4);
+        assert!(conventions.conventional);
+        assert_eq!(conventions.types, vec!["feat", "fix", "docs"]);
+        assert_eq!(conventions.scopes, vec!["auth", "report"]);
+        assert_eq!(conventions.tense.as_deref(), Some("imperative"));
+        assert_eq!(conventions.language.as_deref(), Some("en"));
+        assert_eq!(conventions.ticket_format.as_deref(), Some("AUTH-123"));
+        assert_eq!(conventions.ticket_placement.as_deref(), Some("footer"));
+
+        let section = conventions.prompt_section();
+        assert!(section.contains("Commit types used here: feat, fix, docs"));
+        assert!(section.contains("imperative mood"));
+        assert!(section.contains("AUTH-123 in a footer line"));
+    }
+
+    #[test]
+    fn learns_plain_past_tense_history() {
+        let conventions = learn(&[
+            "[PAY-7] Added refund endpoint",
+            "[PAY-9] Fixed rounding in totals",
+            "Updated dependencies",
+            "修复登录问题",
+        ]);
+        assert!(!conventions.conventional);
+        assert_eq!(conventions.tense.as_deref(), Some("past"));
+        assert_eq!(conventions.language.as_deref(), Some("en"));
+        assert_eq!(conventions.ticket_format.as_deref(), Some("PAY-123"));
+        assert_eq!(
+            conventions.ticket_placement.as_deref(),
+            Some("subject-prefix")
+        );
+        assert!(conventions
+            .prompt_section()
+            .contains("no type(scope) prefix"));
+    }
+}
@@ -1,4 +1,9 @@
 use crate::error::{GitAiError, Result};
+use crate::utils::GitManager;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
 use std::process::Command;
 use tokio::process::Command as AsyncCommand;
 
@@ -7,12 +12,150 @@ use tokio::process::Command as AsyncCommand;
 /// Instead, it provides intelligent code impact analysis and risk detection
 pub struct CopilotCLI;
 
-#[derive(Debug, Clone)]
+/// Analyses kept in the on-disk cache
+const MAX_CACHED_ANALYSES: usize = 20;
+
+/// Role given to the model for the IMPACT/RISKS/AFFECTED/TESTS analysis
+pub const ANALYSIS_ROLE: &str = "You are a code review expert.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "high" | "critical" | "major" => Some(Self::High),
+            "medium" | "moderate" => Some(Self::Medium),
+            "low" | "minor" | "info" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// One risk from the RISKS section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// File the risk is in, when the model named one
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+impl Finding {
+    /// `[severity] path:line: message`; an unmarked risk counts as medium
+    fn parse(item: &str) -> Self {
+        let mut rest = item.trim();
+        let mut severity = Severity::Medium;
+        if let Some((marker, tail)) = rest
+            .strip_prefix('[')
+            .or_else(|| rest.strip_prefix('('))
+            .and_then(|inner| inner.split_once([']', ')']))
+        {
+            if let Some(parsed) = Severity::parse(marker) {
+                severity = parsed;
+                rest = tail.trim_start();
+            }
+        }
+
+        let mut file = None;
+        let mut line = None;
+        if let Some((location, message)) = rest.split_once(": ") {
+            let location = location.trim_matches('`');
+            let (path, line_number) = match location.rsplit_once(':') {
+                Some((path, number)) => match number.parse::<u32>() {
+                    Ok(number) => (path, Some(number)),
+                    Err(_) => (location, None),
+                },
+                None => (location, None),
+            };
+            let looks_like_path = !path.is_empty()
+                && !path.contains(char::is_whitespace)
+                && (path.contains('/') || path.contains('.'));
+            if looks_like_path {
+                file = Some(path.to_string());
+                line = line_number;
+                rest = message.trim();
+            }
+        }
+
+        Self {
+            severity,
+            file,
+            line,
+            message: rest.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] ", self.severity.as_str())?;
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}: ", file, line)?,
+            (Some(file), None) => write!(f, "{}: ", file)?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeAnalysis {
     pub impact_summary: String,
-    pub potential_issues: Vec<String>,
+    #[serde(rename = "risks")]
+    pub potential_issues: Vec<Finding>,
     pub affected_areas: Vec<String>,
     pub test_recommendations: Vec<String>,
+    /// CODEOWNERS of the changed paths, filled in by the caller
+    #[serde(default)]
+    pub owners: Vec<String>,
+    /// Whether the response had a RISKS section; free text cannot show that
+    /// a change is free of risk
+    #[serde(default = "parsed_by_default")]
+    pub parsed: bool,
+}
+
+/// Analyses cached before `parsed` existed
+fn parsed_by_default() -> bool {
+    true
+}
+
+impl CodeAnalysis {
+    /// 0-100: each high risk adds 40, medium 15 and low 5
+    pub fn risk_score(&self) -> u32 {
+        let score: u32 = self
+            .potential_issues
+            .iter()
+            .map(|issue| match issue.severity {
+                Severity::High => 40,
+                Severity::Medium => 15,
+                Severity::Low => 5,
+            })
+            .sum();
+        score.min(100)
+    }
+
+    /// Risks at `threshold` or above
+    pub fn issues_at_least(&self, threshold: Severity) -> usize {
+        self.potential_issues
+            .iter()
+            .filter(|issue| issue.severity >= threshold)
+            .count()
+    }
 }
 
 impl CopilotCLI {
@@ -26,46 +169,119 @@ impl CopilotCLI {
             .unwrap_or(false)
     }
 
-    /// Perform deep code impact analysis using Copilot CLI
-    pub async fn analyze_code_impact(diff: &str, staged_files: &[String]) -> Result<CodeAnalysis> {
+    /// Perform deep code impact analysis using Copilot CLI; with `json` the
+    /// analysis is requested as a JSON document instead of the text layout
+    pub async fn analyze_code_impact(
+        diff: &str,
+        staged_files: &[String],
+        json: bool,
+    ) -> Result<CodeAnalysis> {
         if diff.is_empty() {
             return Ok(CodeAnalysis {
                 impact_summary: "No changes detected".to_string(),
                 potential_issues: vec![],
                 affected_areas: vec![],
                 test_recommendations: vec![],
+                owners: vec![],
+                parsed: true,
             });
         }
 
-        let files_list = staged_files.join(", ");
-
         let prompt = format!(
-            "You are a code review expert. Analyze the following git diff and provide a structured analysis.\n\n\
+            "{} {}",
+            ANALYSIS_ROLE,
+            Self::analysis_prompt(diff, staged_files, json)
+        );
+
+        let analysis_text = Self::run_copilot_explain(&prompt).await?;
+
+        // Parse the structured response
+        Ok(Self::parse_response(&analysis_text, json))
+    }
+
+    /// `analyze_code_impact`, reusing the analysis of an identical diff from
+    /// `.git/git-ai/copilot-analysis.json` (regenerating a message or
+    /// re-running after a cancel does not ask Copilot again)
+    pub async fn analyze_code_impact_cached(
+        diff: &str,
+        staged_files: &[String],
+        json: bool,
+    ) -> Result<CodeAnalysis> {
+        let key = format!(
+            "{:x}",
+            Sha256::digest(format!("{}\0{}", staged_files.join("\n"), diff).as_bytes())
+        );
+        let path = analysis_cache_path();
+        let mut cache: Vec<(String, CodeAnalysis)> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if let Some((_, analysis)) = cache.iter().find(|(hash, _)| *hash == key) {
+            return Ok(analysis.clone());
+        }
+
+        let analysis = Self::analyze_code_impact(diff, staged_files, json).await?;
+        // Best effort: a failed write only costs the next run another request.
+        // An unparsed answer is worth asking for again.
+        if let Some(path) = path.filter(|_| analysis.parsed) {
+            cache.insert(0, (key, analysis.clone()));
+            cache.truncate(MAX_CACHED_ANALYSES);
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(content) = serde_json::to_string(&cache) {
+                let _ = fs::write(path, content);
+            }
+        }
+        Ok(analysis)
+    }
+
+    /// Instructions and diff for the IMPACT/RISKS/AFFECTED/TESTS analysis,
+    /// shared by `--copilot` and `git-ai review`; `json` asks for the
+    /// `CodeAnalysis` document instead of the text layout
+    pub fn analysis_prompt(diff: &str, files: &[String], json: bool) -> String {
+        if json {
+            return format!(
+                "Analyze the following git diff and provide a structured analysis.\n\n\
+                 Changed files: {}\n\n\
+                 Git diff:\n{}\n\n\
+                 Respond with only a JSON object of this shape:\n\
+                 {{\"impact_summary\": \"what changed and why it matters\", \
+                 \"risks\": [{{\"severity\": \"high|medium|low\", \"file\": \"path/to/file or null\", \
+                 \"line\": null, \"message\": \"potential issue, breaking change or bug\"}}], \
+                 \"affected_areas\": [\"other files or modules that might be affected\"], \
+                 \"test_recommendations\": [\"what should be tested\"]}}\n\n\
+                 Leave a list empty rather than inventing items.",
+                files.join(", "),
+                diff
+            );
+        }
+        format!(
+            "Analyze the following git diff and provide a structured analysis.\n\n\
              Changed files: {}\n\n\
              Git diff:\n{}\n\n\
              Please provide:\n\
              1. IMPACT: A brief summary of what changed and why it matters\n\
-             2. RISKS: List potential issues, breaking changes, or bugs this might introduce\n\
+             2. RISKS: List potential issues, breaking changes, or bugs this might introduce, \
+             each with a severity (high, medium or low) and the file it is in\n\
              3. AFFECTED: List other files/modules that might be affected by these changes\n\
              4. TESTS: Suggest what should be tested to verify these changes\n\n\
              Format your response as:\n\
              IMPACT: [summary]\n\
              RISKS:\n\
-             - [risk 1]\n\
-             - [risk 2]\n\
+             - [high] path/to/file: [risk 1]\n\
+             - [low] path/to/file: [risk 2]\n\
              AFFECTED:\n\
              - [area 1]\n\
              - [area 2]\n\
              TESTS:\n\
              - [test 1]\n\
-             - [test 2]",
-            files_list, diff
-        );
-
-        let analysis_text = Self::run_copilot_explain(&prompt).await?;
-
-        // Parse the structured response
-        Self::parse_analysis(&analysis_text)
+             - [test 2]\n\n\
+             Leave a section empty rather than inventing items.",
+            files.join(", "),
+            diff
+        )
     }
 
     /// Run Copilot CLI explain command
@@ -90,13 +306,82 @@ impl CopilotCLI {
     }
 
     /// Parse the structured analysis response
-    fn parse_analysis(text: &str) -> Result<CodeAnalysis> {
+    /// Parse a response to `analysis_prompt(.., json)`
+    pub fn parse_response(text: &str, json: bool) -> CodeAnalysis {
+        if json {
+            Self::parse_analysis_json(text)
+        } else {
+            Self::parse_analysis(text)
+        }
+    }
+
+    /// Parse the JSON document requested by `analysis_prompt(.., true)`,
+    /// tolerating code fences and loosely written severities. A response that
+    /// is not such a document falls back to the text layout.
+    pub fn parse_analysis_json(text: &str) -> CodeAnalysis {
+        use serde_json::Value;
+        let document = text
+            .find('{')
+            .zip(text.rfind('}'))
+            .filter(|(start, end)| start < end)
+            .and_then(|(start, end)| serde_json::from_str::<Value>(&text[start..=end]).ok())
+            .filter(|document| document["risks"].is_array());
+        let Some(document) = document else {
+            return Self::parse_analysis(text);
+        };
+
+        let text_of = |value: &Value| {
+            value
+                .as_str()
+                .map(str::trim)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let strings = |key: &str| -> Vec<String> {
+            document[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(text_of)
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+        let potential_issues = document["risks"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|risk| Finding {
+                severity: risk["severity"]
+                    .as_str()
+                    .and_then(Severity::parse)
+                    .unwrap_or(Severity::Medium),
+                file: Some(text_of(&risk["file"])).filter(|file| !file.is_empty()),
+                line: risk["line"]
+                    .as_u64()
+                    .and_then(|line| u32::try_from(line).ok()),
+                message: text_of(&risk["message"]),
+            })
+            .filter(|finding| !finding.message.is_empty())
+            .collect();
+
+        CodeAnalysis {
+            impact_summary: text_of(&document["impact_summary"]),
+            potential_issues,
+            affected_areas: strings("affected_areas"),
+            test_recommendations: strings("test_recommendations"),
+            owners: Vec::new(),
+            parsed: true,
+        }
+    }
+
+    pub fn parse_analysis(text: &str) -> CodeAnalysis {
         let mut impact_summary = String::new();
         let mut potential_issues = Vec::new();
         let mut affected_areas = Vec::new();
         let mut test_recommendations = Vec::new();
 
         let mut current_section = "";
+        let mut parsed = false;
 
         for line in text.lines() {
             let line = line.trim();
@@ -110,6 +395,7 @@ impl CopilotCLI {
                     .to_string();
             } else if line.starts_with("RISKS:") {
                 current_section = "risks";
+                parsed = true;
             } else if line.starts_with("AFFECTED:") {
                 current_section = "affected";
             } else if line.starts_with("TESTS:") {
@@ -122,7 +408,7 @@ impl CopilotCLI {
                     .to_string();
                 if !item.is_empty() {
                     match current_section {
-                        "risks" => potential_issues.push(item),
+                        "risks" => potential_issues.push(Finding::parse(&item)),
                         "affected" => affected_areas.push(item),
                         "tests" => test_recommendations.push(item),
                         _ => {}
@@ -136,15 +422,23 @@ impl CopilotCLI {
             impact_summary = text.lines().take(3).collect::<Vec<_>>().join(" ");
         }
 
-        Ok(CodeAnalysis {
+        CodeAnalysis {
             impact_summary,
             potential_issues,
             affected_areas,
             test_recommendations,
-        })
+            owners: Vec::new(),
+            parsed,
+        }
     }
 }
 
+fn analysis_cache_path() -> Option<PathBuf> {
+    GitManager::get_git_dir()
+        .ok()
+        .map(|dir| dir.join("git-ai").join("copilot-analysis.json"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,10 +461,99 @@ mod tests {
                     - Test login flow\n\
                     - Test error cases";
 
-        let analysis = CopilotCLI::parse_analysis(text).unwrap();
+        let analysis = CopilotCLI::parse_analysis(text);
         assert_eq!(analysis.impact_summary, "Updated authentication logic");
         assert_eq!(analysis.potential_issues.len(), 2);
         assert_eq!(analysis.affected_areas.len(), 2);
         assert_eq!(analysis.test_recommendations.len(), 2);
+        assert!(analysis.parsed);
+
+        let free_text = CopilotCLI::parse_analysis("Looks fine to me, nothing risky here.");
+        assert!(free_text.potential_issues.is_empty());
+        assert!(!free_text.parsed);
+    }
+
+    #[test]
+    fn test_parse_analysis_json() {
+        let text = "```json\n{\"impact_summary\": \"Token refresh reworked\", \
+                    \"risks\": [{\"severity\": \"High\", \"file\": \"src/auth.rs\", \"line\": 42, \
+                    \"message\": \"expired tokens are accepted\"}, \
+                    {\"severity\": \"low\", \"file\": null, \"message\": \"log is noisy\"}], \
+                    \"affected_areas\": [\"login\"], \"test_recommendations\": []}\n```";
+        let analysis = CopilotCLI::parse_response(text, true);
+        assert!(analysis.parsed);
+        assert_eq!(analysis.impact_summary, "Token refresh reworked");
+        assert_eq!(
+            analysis.potential_issues[0].to_string(),
+            "[high] src/auth.rs:42: expired tokens are accepted"
+        );
+        assert_eq!(analysis.potential_issues[1].file, None);
+        assert_eq!(analysis.affected_areas, vec!["login"]);
+
+        // Not a document: read as the text layout
+        let fallback = CopilotCLI::parse_response("IMPACT: x\nRISKS:\n- [low] y", true);
+        assert!(fallback.parsed);
+        assert_eq!(fallback.potential_issues.len(), 1);
+        assert!(!CopilotCLI::parse_response("No JSON here", true).parsed);
+    }
+
+    #[test]
+    fn test_risk_score() {
+        let analysis = CopilotCLI::parse_analysis(
+            "IMPACT: x\nRISKS:\n- [high] a.rs: one\n- [low] b.rs: two\n- [medium] three",
+        );
+        assert_eq!(analysis.risk_score(), 60);
+        assert_eq!(analysis.issues_at_least(Severity::High), 1);
+        assert_eq!(analysis.issues_at_least(Severity::Medium), 2);
+
+        let risky = CopilotCLI::parse_analysis("RISKS:\n- [high] a\n- [high] b\n- [high] c");
+        assert_eq!(risky.risk_score(), 100);
+    }
+
+    #[test]
+    fn test_parse_findings() {
+        let analysis = CopilotCLI::parse_analysis(
+            "IMPACT: Token refresh reworked\n\
+             RISKS:\n\
+             - [HIGH] `src/auth.rs:42`: refresh no longer checks expiry\n\
+             - (low) README.md: outdated example\n\
+             - Callers may need an update",
+        );
+        assert_eq!(
+            analysis.potential_issues,
+            vec![
+                Finding {
+                    severity: Severity::High,
+                    file: Some("src/auth.rs".to_string()),
+                    line: Some(42),
+                    message: "refresh no longer checks expiry".to_string(),
+                },
+                Finding {
+                    severity: Severity::Low,
+                    file: Some("README.md".to_string()),
+                    line: None,
+                    message: "outdated example".to_string(),
+                },
+                Finding {
+                    severity: Severity::Medium,
+                    file: None,
+                    line: None,
+                    message: "Callers may need an update".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            analysis.potential_issues[0].to_string(),
+            "[high] src/auth.rs:42: refresh no longer checks expiry"
+        );
+        assert_eq!(
+            serde_json::to_value(&analysis.potential_issues[0]).unwrap(),
+            serde_json::json!({
+                "severity": "high",
+                "file": "src/auth.rs",
+                "line": 42,
+                "message": "refresh no longer checks expiry",
+            })
+        );
     }
 }
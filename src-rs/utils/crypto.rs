@@ -0,0 +1,129 @@
+use crate::error::{GitAiError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Format marker stored in encrypted config files
+const ENVELOPE_FORMAT: &str = "git-ai-encrypted-v1";
+
+/// On-disk layout of an encrypted config file
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Whether file content is an encrypted config envelope
+pub fn is_encrypted(content: &str) -> bool {
+    serde_json::from_str::<Envelope>(content)
+        .map(|envelope| envelope.format == ENVELOPE_FORMAT)
+        .unwrap_or(false)
+}
+
+/// Derive a 256-bit key from a passphrase with Argon2id
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| GitAiError::Config(format!("Failed to derive encryption key: {}", e)))?;
+    Ok(key)
+}
+
+/// Encrypt config JSON into an envelope (AES-256-GCM, Argon2id passphrase key)
+pub fn encrypt(plaintext: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| GitAiError::Config("Failed to encrypt config".to_string()))?;
+
+    let envelope = Envelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        kdf: "argon2id".to_string(),
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+/// Random 256-bit secret, hex-encoded (e.g. an API bearer token)
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decrypt an envelope produced by [`encrypt`]
+pub fn decrypt(content: &str, passphrase: &str) -> Result<String> {
+    let envelope: Envelope = serde_json::from_str(content)
+        .map_err(|e| GitAiError::Config(format!("Invalid encrypted config: {}", e)))?;
+    if envelope.format != ENVELOPE_FORMAT {
+        return Err(GitAiError::Config(format!(
+            "Unsupported encrypted config format: {}",
+            envelope.format
+        )));
+    }
+
+    let decode = |field: &str, value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|e| GitAiError::Config(format!("Invalid encrypted config {}: {}", field, e)))
+    };
+    let salt = decode("salt", &envelope.salt)?;
+    let nonce = decode("nonce", &envelope.nonce)?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err(GitAiError::Config(
+            "Invalid encrypted config nonce".to_string(),
+        ));
+    }
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| {
+            GitAiError::Config(
+                "Cannot decrypt config: wrong passphrase or corrupted file".to_string(),
+            )
+        })?;
+
+    String::from_utf8(plaintext)
+        .map_err(|e| GitAiError::Config(format!("Decrypted config is not UTF-8: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_and_wrong_passphrase() {
+        let content = encrypt("{\"api_key\":\"sk-123\"}", "correct horse").unwrap();
+        assert!(is_encrypted(&content));
+        assert!(!content.contains("sk-123"));
+
+        assert_eq!(
+            decrypt(&content, "correct horse").unwrap(),
+            "{\"api_key\":\"sk-123\"}"
+        );
+        assert!(decrypt(&content, "wrong").is_err());
+    }
+
+    #[test]
+    fn plain_config_is_not_encrypted() {
+        assert!(!is_encrypted("{\"provider\":\"openai\"}"));
+        assert!(!is_encrypted("not json"));
+    }
+}
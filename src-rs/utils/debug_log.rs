@@ -0,0 +1,97 @@
+//! `--debug` / `GIT_AI_DEBUG=1`: provider requests and responses, timings and
+//! retries are appended to `<config>/git-ai-cli/logs/git-ai-<date>.log`, so a
+//! provider-specific failure can be reported with what was actually sent and
+//! received. Callers redact secrets before recording.
+
+use crate::error::Result;
+use crate::utils::ConfigManager;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+    record("start", &format!("git-ai {}", env!("CARGO_PKG_VERSION")));
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// `GIT_AI_DEBUG` is set to something other than `0`/`false`
+pub fn env_requested() -> bool {
+    std::env::var("GIT_AI_DEBUG").is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
+/// `<config>/git-ai-cli/logs`
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(ConfigManager::get_global_config_dir()?.join("logs"))
+}
+
+/// Most recently written log file, if any
+pub fn latest_log() -> Result<Option<PathBuf>> {
+    let Ok(entries) = fs::read_dir(log_dir()?) else {
+        return Ok(None);
+    };
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok()))
+}
+
+/// Append a timestamped entry; a log that cannot be written never fails the
+/// command
+pub fn record(event: &str, detail: &str) {
+    if !enabled() {
+        return;
+    }
+    let _ = write_entry(event, detail);
+}
+
+fn write_entry(event: &str, detail: &str) -> Result<()> {
+    let dir = log_dir()?;
+    fs::create_dir_all(&dir)?;
+    let now = chrono::Local::now();
+    let path = dir.join(format!("git-ai-{}.log", now.format("%Y-%m-%d")));
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(
+        file,
+        "{}",
+        format_entry(&now.format("%H:%M:%S%.3f").to_string(), event, detail)
+    )?;
+    Ok(())
+}
+
+fn format_entry(time: &str, event: &str, detail: &str) -> String {
+    let mut entry = format!("[{}] {}", time, event);
+    for line in detail.trim_end().lines() {
+        entry.push_str("\n    ");
+        entry.push_str(line);
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_entry;
+
+    #[test]
+    fn entries_indent_multiline_details() {
+        assert_eq!(
+            format_entry(
+                "12:00:00.000",
+                "response 200 in 12ms",
+                "{\n  \"ok\": true\n}\n"
+            ),
+            "[12:00:00.000] response 200 in 12ms\n    {\n      \"ok\": true\n    }"
+        );
+        assert_eq!(
+            format_entry("12:00:00.000", "start", ""),
+            "[12:00:00.000] start"
+        );
+    }
+}
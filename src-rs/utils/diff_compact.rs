@@ -0,0 +1,185 @@
+//! Shrink a unified diff before it goes into the prompt: unchanged context is
+//! cut to one line around each change, whitespace-only rewrites collapse to a
+//! note, and a hunk repeated in several files (license headers, generated
+//! boilerplate) is sent once. Large diffs typically lose half their size, so
+//! truncation starts later.
+
+use std::collections::HashMap;
+
+/// Runs of whitespace-only changed lines at least this long are collapsed
+const MIN_TRIVIAL_RUN: usize = 3;
+
+/// Smaller hunks are cheaper to repeat than to reference
+const MIN_DEDUP_LINES: usize = 3;
+
+pub fn compact(diff: &str) -> String {
+    let mut output: Vec<String> = Vec::new();
+    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut file = String::new();
+    let mut hunk: Option<(String, Vec<&str>)> = None;
+
+    for line in diff.lines() {
+        if let Some((_, body)) = hunk.as_mut() {
+            if is_hunk_line(line) {
+                body.push(line);
+                continue;
+            }
+        }
+        if let Some((header, body)) = hunk.take() {
+            flush_hunk(&mut output, &mut seen, &file, header, &body);
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            file = rest
+                .rsplit_once(" b/")
+                .map(|(_, path)| path.to_string())
+                .unwrap_or_else(|| rest.to_string());
+            output.push(line.to_string());
+        } else if line.starts_with("@@") {
+            hunk = Some((line.to_string(), Vec::new()));
+        } else if !line.starts_with("index ") {
+            // Blob hashes tell the model nothing
+            output.push(line.to_string());
+        }
+    }
+    if let Some((header, body)) = hunk.take() {
+        flush_hunk(&mut output, &mut seen, &file, header, &body);
+    }
+
+    let mut compacted = output.join("\n");
+    if diff.ends_with('\n') {
+        compacted.push('\n');
+    }
+    compacted
+}
+
+fn is_hunk_line(line: &str) -> bool {
+    line.is_empty() || line.starts_with([' ', '+', '-', '\\'])
+}
+
+fn is_change(line: &str) -> bool {
+    line.starts_with(['+', '-', '~', '\\'])
+}
+
+fn flush_hunk(
+    output: &mut Vec<String>,
+    seen: &mut HashMap<String, String>,
+    file: &str,
+    header: String,
+    body: &[&str],
+) {
+    let body = strip_context(&collapse_whitespace_runs(body));
+    output.push(header);
+    if body.len() >= MIN_DEDUP_LINES {
+        let key = body.join("\n");
+        if let Some(first) = seen.get(&key) {
+            output.push(format!("~ same change as in {}", first));
+            return;
+        }
+        seen.insert(key, file.to_string());
+    }
+    output.extend(body);
+}
+
+/// Replace runs of changed lines that differ only in whitespace (re-indented
+/// blocks, added or removed blank lines) with one `~` note
+fn collapse_whitespace_runs(body: &[&str]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut index = 0;
+    while index < body.len() {
+        let end = body[index..]
+            .iter()
+            .position(|line| !line.starts_with(['+', '-']))
+            .map_or(body.len(), |offset| index + offset);
+        if end == index {
+            lines.push(body[index].to_string());
+            index += 1;
+            continue;
+        }
+
+        let block = &body[index..end];
+        match whitespace_only_lines(block) {
+            Some(count) if count >= MIN_TRIVIAL_RUN => {
+                lines.push(format!("~ {} lines changed only in whitespace", count));
+            }
+            _ => lines.extend(block.iter().map(|line| line.to_string())),
+        }
+        index = end;
+    }
+    lines
+}
+
+/// Number of lines a block of `-`/`+` lines changes, if every change is
+/// whitespace: blank lines only, or removed and added lines that match once
+/// whitespace is ignored
+fn whitespace_only_lines(block: &[&str]) -> Option<usize> {
+    let squeeze = |line: &str| -> String { line[1..].split_whitespace().collect() };
+    if block.iter().all(|line| line[1..].trim().is_empty()) {
+        return Some(block.len());
+    }
+    let removed: Vec<String> = block
+        .iter()
+        .filter(|line| line.starts_with('-'))
+        .map(|line| squeeze(line))
+        .collect();
+    let added: Vec<String> = block
+        .iter()
+        .filter(|line| line.starts_with('+'))
+        .map(|line| squeeze(line))
+        .collect();
+    (removed == added).then_some(removed.len())
+}
+
+/// Keep only context lines next to a change; a gap between two changes in
+/// the same hunk is marked with `...`
+fn strip_context(body: &[String]) -> Vec<String> {
+    let changed: Vec<bool> = body.iter().map(|line| is_change(line)).collect();
+    let keep = |index: usize| {
+        changed[index]
+            || (index > 0 && changed[index - 1])
+            || changed.get(index + 1).copied().unwrap_or(false)
+    };
+
+    let mut lines = Vec::new();
+    let mut skipped = false;
+    for (index, line) in body.iter().enumerate() {
+        if keep(index) {
+            if skipped && !lines.is_empty() {
+                lines.push("...".to_string());
+            }
+            lines.push(line.clone());
+            skipped = false;
+        } else {
+            skipped = true;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compact;
+
+    #[test]
+    fn strips_context_collapses_whitespace_and_dedups() {
+        let hunk = "@@ -1,9 +1,9 @@\n a\n b\n c\n-old\n+new\n d\n e\n f\n g\n h\n-x\n+y\n i\n";
+        let diff = format!(
+            "diff --git a/one.rs b/one.rs\nindex 1..2 100644\n--- a/one.rs\n+++ b/one.rs\n{}\
+             diff --git a/two.rs b/two.rs\n--- a/two.rs\n+++ b/two.rs\n{}\
+             diff --git a/fmt.rs b/fmt.rs\n--- a/fmt.rs\n+++ b/fmt.rs\n@@ -1,3 +1,3 @@\n-if x {{\n-y\n-}}\n+if x {{\n+    y\n+}}\n",
+            hunk, hunk
+        );
+
+        let compacted = compact(&diff);
+        assert_eq!(
+            compacted,
+            "diff --git a/one.rs b/one.rs\n--- a/one.rs\n+++ b/one.rs\n\
+             @@ -1,9 +1,9 @@\n c\n-old\n+new\n d\n...\n h\n-x\n+y\n i\n\
+             diff --git a/two.rs b/two.rs\n--- a/two.rs\n+++ b/two.rs\n\
+             @@ -1,9 +1,9 @@\n~ same change as in one.rs\n\
+             diff --git a/fmt.rs b/fmt.rs\n--- a/fmt.rs\n+++ b/fmt.rs\n\
+             @@ -1,3 +1,3 @@\n~ 3 lines changed only in whitespace\n"
+        );
+        assert!(compacted.len() < diff.len());
+    }
+}
@@ -0,0 +1,121 @@
+//! Walk the changed lines of a unified diff with their position in the new
+//! version of each file, for the scanners that report changes by file and
+//! line (follow-up markers, tree-sitter symbols).
+
+/// An added or removed line of a unified diff
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangedLine<'a> {
+    /// Path on the new side, `b/` stripped
+    pub path: &'a str,
+    /// 1-based line in the new file; for a removed line, the line that now
+    /// follows the removal (0 when nothing does)
+    pub line: usize,
+    /// Text of an added line without its `+`; `None` for a removed line
+    pub added: Option<&'a str>,
+}
+
+/// Changed lines of `diff` in order. Deleted files are skipped, since their
+/// lines have no place in the new version.
+pub fn changed_lines(diff: &str) -> ChangedLines<'_> {
+    ChangedLines {
+        lines: diff.lines(),
+        path: None,
+        line: 0,
+        in_header: false,
+    }
+}
+
+/// Iterator returned by [`changed_lines`]
+pub struct ChangedLines<'a> {
+    lines: std::str::Lines<'a>,
+    path: Option<&'a str>,
+    line: usize,
+    // `+++ b/path` only counts between `diff --git` and the first hunk, so an
+    // added line reading `++ x` is not taken for a file header
+    in_header: bool,
+}
+
+impl<'a> Iterator for ChangedLines<'a> {
+    type Item = ChangedLine<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for text in self.lines.by_ref() {
+            if text.starts_with("diff --git ") {
+                self.path = None;
+                self.in_header = true;
+            } else if let Some(path) = text.strip_prefix("+++ ").filter(|_| self.in_header) {
+                self.path = (path != "/dev/null").then(|| path.strip_prefix("b/").unwrap_or(path));
+            } else if let Some(header) = text.strip_prefix("@@ ") {
+                self.in_header = false;
+                // @@ -a,b +c,d @@
+                self.line = header
+                    .split_whitespace()
+                    .find_map(|part| part.strip_prefix('+'))
+                    .and_then(|range| range.split(',').next()?.parse().ok())
+                    .unwrap_or(0);
+            } else if self.in_header {
+                continue;
+            } else if let Some(path) = self.path {
+                if let Some(added) = text.strip_prefix('+') {
+                    self.line += 1;
+                    return Some(ChangedLine {
+                        path,
+                        line: self.line - 1,
+                        added: Some(added),
+                    });
+                } else if text.starts_with('-') {
+                    return Some(ChangedLine {
+                        path,
+                        line: self.line,
+                        added: None,
+                    });
+                } else if text.starts_with(' ') {
+                    self.line += 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_changed_lines_in_the_new_file() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,3 +3,3 @@ fn main() {
+     let a = 1;
+-    let b = 2;
++    let b = 3;
+++ not a header
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/new.md b/new.md
+--- /dev/null
++++ b/new.md
+@@ -0,0 +1 @@
++# Title
+";
+        let lines: Vec<(&str, usize, Option<&str>)> = changed_lines(diff)
+            .map(|line| (line.path, line.line, line.added))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                ("src/lib.rs", 4, None),
+                ("src/lib.rs", 4, Some("    let b = 3;")),
+                ("src/lib.rs", 5, Some("+ not a header")),
+                ("new.md", 1, Some("# Title")),
+            ]
+        );
+    }
+}
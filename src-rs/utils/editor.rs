@@ -0,0 +1,37 @@
+use crate::error::{GitAiError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Editor from $EDITOR / $VISUAL, falling back to the platform default
+pub fn resolve_editor() -> String {
+    std::env::var("EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .unwrap_or_else(|_| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+/// Open `path` in the user's editor and wait for it to exit.
+/// Editors given with arguments (e.g. `code --wait`) are supported.
+pub fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = resolve_editor();
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| GitAiError::Other("EDITOR is empty".to_string()))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| GitAiError::Other(format!("Failed to open editor: {}", e)))?;
+
+    if !status.success() {
+        return Err(GitAiError::Other("Editor exited with error".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! `TODO`/`FIXME`/`HACK` comments added by a change, listed under "Known
+//! follow-ups" in the commit body when `follow_ups` is on, so the debt a
+//! commit introduces is visible in the log.
+
+use crate::types::{AIConfig, FollowUp};
+use crate::utils::diff_lines::changed_lines;
+use crate::utils::footer;
+use crate::utils::GitManager;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Heading of the section in the commit body
+const HEADING: &str = "Known follow-ups:";
+
+/// Items listed before the rest are folded into "… and N more"
+const MAX_LISTED: usize = 20;
+
+/// A marker right after a comment leader: `// TODO: x`, `# FIXME(bob) x`,
+/// `/* HACK - x */`, `-- TODO x`, `<!-- TODO x -->`
+fn marker() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| {
+        Regex::new(r"(?://+|#+|/\*+|\*|--|<!--|;+)\s*(TODO|FIXME|HACK)\b(?:\([^)]*\))?[\s:\-]*(.*)")
+            .expect("valid follow-up regex")
+    })
+}
+
+/// Follow-ups added by the staged (or working-tree) changes; empty when
+/// `follow_ups` is off or the diff cannot be read
+pub fn collect(config: &AIConfig, paths: &[String], working_tree: bool) -> Vec<FollowUp> {
+    if !config.follow_ups.unwrap_or(false) {
+        return Vec::new();
+    }
+    let diff = if working_tree {
+        GitManager::get_working_tree_diff(paths, false)
+    } else {
+        GitManager::get_staged_diff(paths, false)
+    };
+    diff.map(|diff| scan(&diff)).unwrap_or_default()
+}
+
+/// Markers on the added lines of a unified diff, with their new line numbers
+pub fn scan(diff: &str) -> Vec<FollowUp> {
+    changed_lines(diff)
+        .filter_map(|changed| {
+            let captures = marker().captures(changed.added?)?;
+            let text = captures[2]
+                .trim()
+                .trim_end_matches("-->")
+                .trim_end_matches("*/")
+                .trim();
+            Some(FollowUp {
+                file: changed.path.to_string(),
+                line: changed.line,
+                tag: captures[1].to_string(),
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Add the "Known follow-ups" section to `message`, ahead of its trailer
+/// block; a message that already has one is left alone
+pub fn append(message: &str, follow_ups: &[FollowUp]) -> String {
+    let message = message.trim_end();
+    if follow_ups.is_empty() || message.lines().any(|line| line.trim() == HEADING) {
+        return message.to_string();
+    }
+
+    let mut section = vec![HEADING.to_string()];
+    for follow_up in follow_ups.iter().take(MAX_LISTED) {
+        let mut item = format!("- {}:{} {}", follow_up.file, follow_up.line, follow_up.tag);
+        if !follow_up.text.is_empty() {
+            item.push_str(&format!(": {}", follow_up.text));
+        }
+        section.push(item);
+    }
+    if follow_ups.len() > MAX_LISTED {
+        section.push(format!("- … and {} more", follow_ups.len() - MAX_LISTED));
+    }
+    let section = section.join("\n");
+
+    match message.rsplit_once("\n\n") {
+        Some((rest, trailers)) if trailers.lines().all(footer::is_trailer) => {
+            format!("{}\n\n{}\n\n{}", rest, section, trailers)
+        }
+        _ => format!("{}\n\n{}", message, section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_added_markers_before_trailers() {
+        let diff = "\
+diff --git a/src/net.rs b/src/net.rs
+--- a/src/net.rs
++++ b/src/net.rs
+@@ -10,3 +10,6 @@ fn fetch() {
+     let client = Client::new();
+-    // TODO: old note
++    // TODO: retry on 503
++    let todo_list = TODO_ITEMS;
++    /* FIXME(alice): handle redirects */
+     send(client)
++    # HACK
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -0,0 +1 @@
++<!-- TODO document flags -->
+";
+        let follow_ups = scan(diff);
+        let listed: Vec<String> = follow_ups
+            .iter()
+            .map(|f| format!("{}:{} {} {}", f.file, f.line, f.tag, f.text))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                "src/net.rs:11 TODO retry on 503",
+                "src/net.rs:13 FIXME handle redirects",
+                "src/net.rs:15 HACK ",
+                "README.md:1 TODO document flags",
+            ]
+        );
+
+        let message = append(
+            "feat(net): retry requests\n\nAdds a retry loop.\n\nRefs: #12",
+            &follow_ups[..2],
+        );
+        assert_eq!(
+            message,
+            "feat(net): retry requests\n\nAdds a retry loop.\n\nKnown follow-ups:\n\
+             - src/net.rs:11 TODO: retry on 503\n- src/net.rs:13 FIXME: handle redirects\n\nRefs: #12"
+        );
+        assert_eq!(append(&message, &follow_ups), message);
+        assert_eq!(
+            append("fix: x", &follow_ups[2..3]),
+            "fix: x\n\nKnown follow-ups:\n- src/net.rs:15 HACK"
+        );
+        assert_eq!(append("fix: x\n", &[]), "fix: x");
+    }
+}
@@ -0,0 +1,120 @@
+use crate::types::AIConfig;
+
+/// Trailer used when `enable_footer` is on and no `footer_template` is set
+pub const DEFAULT_FOOTER_TEMPLATE: &str = "Generated-by: git-ai/{version} ({model})";
+
+/// Render the attribution trailer for committed messages.
+/// `None` unless `enable_footer` is on.
+pub fn render(config: &AIConfig) -> Option<String> {
+    if !config.enable_footer.unwrap_or(false) {
+        return None;
+    }
+
+    let template = config
+        .footer_template
+        .as_deref()
+        .unwrap_or(DEFAULT_FOOTER_TEMPLATE)
+        .trim();
+    if template.is_empty() {
+        return None;
+    }
+
+    Some(
+        template
+            .replace("{version}", env!("CARGO_PKG_VERSION"))
+            .replace("{provider}", &config.provider)
+            .replace("{model}", &config.model),
+    )
+}
+
+/// Append `footer` to `message` as a git trailer, joining an existing
+/// trailer block (e.g. `BREAKING CHANGE:`, `Refs:`) instead of opening a new one
+pub fn append(message: &str, footer: Option<&str>) -> String {
+    let message = message.trim_end();
+    let Some(footer) = footer else {
+        return message.to_string();
+    };
+    if message.lines().any(|line| line.trim() == footer) {
+        return message.to_string();
+    }
+
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or("");
+    let has_body = message.contains("\n\n");
+    if has_body && last_paragraph.lines().all(is_trailer) {
+        format!("{}\n{}", message, footer)
+    } else {
+        format!("{}\n\n{}", message, footer)
+    }
+}
+
+/// Join trailers into one block, skipping the absent ones
+pub fn join(trailers: impl IntoIterator<Item = Option<String>>) -> Option<String> {
+    let trailers: Vec<String> = trailers.into_iter().flatten().collect();
+    (!trailers.is_empty()).then(|| trailers.join("\n"))
+}
+
+/// `Token: value` or `BREAKING CHANGE: value`
+pub fn is_trailer(line: &str) -> bool {
+    let Some((token, value)) = line.split_once(": ") else {
+        return false;
+    };
+    !value.trim().is_empty()
+        && (token == "BREAKING CHANGE"
+            || (!token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_and_suppresses_footer() {
+        let mut config = AIConfig {
+            provider: "deepseek".to_string(),
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        // Attribution is opt-in
+        assert_eq!(render(&config), None);
+
+        config.enable_footer = Some(true);
+        assert_eq!(
+            render(&config),
+            Some(format!(
+                "Generated-by: git-ai/{} (deepseek-chat)",
+                env!("CARGO_PKG_VERSION")
+            ))
+        );
+
+        config.footer_template = Some("Assisted-by: {provider}/{model}".to_string());
+        assert_eq!(
+            render(&config).as_deref(),
+            Some("Assisted-by: deepseek/deepseek-chat")
+        );
+
+        config.footer_template = Some(String::new());
+        assert_eq!(render(&config), None);
+
+        config.footer_template = None;
+        config.enable_footer = Some(false);
+        assert_eq!(render(&config), None);
+    }
+
+    #[test]
+    fn appends_as_trailer() {
+        let footer = Some("Generated-by: git-ai");
+        assert_eq!(
+            append("feat: add x\n", footer),
+            "feat: add x\n\nGenerated-by: git-ai"
+        );
+        assert_eq!(
+            append("feat!: drop v1\n\nBREAKING CHANGE: v1 is gone", footer),
+            "feat!: drop v1\n\nBREAKING CHANGE: v1 is gone\nGenerated-by: git-ai"
+        );
+        assert_eq!(
+            append("fix: typo\n\nGenerated-by: git-ai", footer),
+            "fix: typo\n\nGenerated-by: git-ai"
+        );
+        assert_eq!(append("fix: typo", None), "fix: typo");
+    }
+}
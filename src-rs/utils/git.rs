@@ -1,6 +1,11 @@
 use crate::error::{GitAiError, Result};
-use std::collections::HashSet;
+use crate::types::{AIConfig, BranchComparison, CommitRecord, CommitStat};
+#[cfg(feature = "libgit2")]
+use crate::utils::git_native;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Debug, Clone)]
 pub struct UnstagedFileEntry {
@@ -8,34 +13,278 @@ pub struct UnstagedFileEntry {
     pub paths: Vec<String>,
 }
 
+/// Commits a report covers: a time window or a `from..to` ref range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogScope {
+    Days(usize),
+    Range { from: String, to: String },
+}
+
+impl LogScope {
+    /// `git log` arguments selecting the commits
+    fn log_args(&self) -> Vec<String> {
+        match self {
+            LogScope::Days(days) => vec![format!("--since={} days ago", days)],
+            LogScope::Range { from, to } => vec![format!("{}..{}", from, to)],
+        }
+    }
+}
+
+/// Commits left out of reports: merges and automated noise
+#[derive(Debug, Clone, Default)]
+pub struct CommitFilter {
+    pub no_merges: bool,
+    pub exclude_authors: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+}
+
+impl CommitFilter {
+    pub fn from_config(config: &AIConfig, no_merges: bool) -> Self {
+        let lowercase = |values: &Option<Vec<String>>| {
+            values
+                .iter()
+                .flatten()
+                .map(|v| v.trim().to_lowercase())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+        Self {
+            no_merges,
+            exclude_authors: lowercase(&config.exclude_authors),
+            exclude_patterns: lowercase(&config.exclude_patterns),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.no_merges || !self.exclude_authors.is_empty() || !self.exclude_patterns.is_empty()
+    }
+
+    /// Whether a `(author, "%h %cd %s")` commit is excluded by the author/subject lists
+    pub fn excludes(&self, author: &str, line: &str) -> bool {
+        let author = author.to_lowercase();
+        let subject = line.splitn(3, ' ').nth(2).unwrap_or(line).to_lowercase();
+        self.exclude_authors.iter().any(|a| author.contains(a))
+            || self.exclude_patterns.iter().any(|p| subject.contains(p))
+    }
+}
+
+/// `git rev-parse` answers by (working directory, option)
+type LocationCache = HashMap<(PathBuf, &'static str), Option<String>>;
+
+/// Git access for every command. Hot queries run in-process through libgit2
+/// (feature `libgit2`) and fall back to the `git` binary when libgit2 cannot
+/// answer or `GIT_AI_GIT_BACKEND=cli` is set.
 pub struct GitManager;
 
 impl GitManager {
+    /// Operate on the repository at `dir` (`-C`). Git subprocesses, the
+    /// libgit2 backend and `.git-ai.json` lookup all follow the process cwd.
+    pub fn change_dir(dir: &Path) -> Result<()> {
+        std::env::set_current_dir(dir).map_err(|e| {
+            GitAiError::InvalidArgument(format!("Cannot change to {}: {}", dir.display(), e))
+        })
+    }
+
     /// Check if git is installed
     pub fn is_git_installed() -> bool {
-        Command::new("git")
-            .arg("--version")
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+        Self::git_version().is_some()
+    }
+
+    /// Get the installed git version string (e.g. "git version 2.43.0")
+    pub fn get_git_version() -> Result<String> {
+        Self::git_version().ok_or(GitAiError::GitNotInstalled)
+    }
+
+    /// `git --version`, run once per process
+    fn git_version() -> Option<String> {
+        static VERSION: OnceLock<Option<String>> = OnceLock::new();
+        VERSION
+            .get_or_init(|| {
+                Command::new("git")
+                    .arg("--version")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())
+                    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            })
+            .clone()
     }
 
     /// Check if we're in a git repository
     pub fn is_in_git_repo() -> Result<bool> {
+        Ok(Self::repo_location("--git-dir")?.is_some())
+    }
+
+    /// Get the top-level directory of the working tree
+    pub fn get_repo_root() -> Result<PathBuf> {
+        Self::repo_location("--show-toplevel")?
+            .map(PathBuf::from)
+            .ok_or(GitAiError::NotInGitRepo)
+    }
+
+    /// Get the git dir shared by all worktrees (the main checkout's `.git`)
+    pub fn get_git_common_dir() -> Result<PathBuf> {
+        Self::rev_parse_dir("--git-common-dir")
+    }
+
+    /// Get the git dir of the current worktree (`.git/worktrees/<name>` in a
+    /// linked worktree), where in-progress merge/rebase state lives
+    pub fn get_git_dir() -> Result<PathBuf> {
+        Self::rev_parse_dir("--git-dir")
+    }
+
+    /// Resolve a `rev-parse` directory option to an absolute path; git prints
+    /// these relative to the current directory (`.git` or `../main/.git`)
+    fn rev_parse_dir(option: &'static str) -> Result<PathBuf> {
+        let dir = PathBuf::from(Self::repo_location(option)?.ok_or(GitAiError::NotInGitRepo)?);
+        if dir.is_absolute() {
+            return Ok(dir);
+        }
+        Ok(std::env::current_dir()?.join(dir))
+    }
+
+    /// `git rev-parse <option>` output, or `None` outside a repository.
+    /// Commands ask for the repository's location many times, so answers are
+    /// cached per working directory (`serve` and `hook install --all` move
+    /// between repositories).
+    fn repo_location(option: &'static str) -> Result<Option<String>> {
+        static CACHE: OnceLock<Mutex<LocationCache>> = OnceLock::new();
+        let key = (std::env::current_dir()?, option);
+        let cache = CACHE.get_or_init(Default::default);
+        if let Some(value) = cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            return Ok(value);
+        }
+
         let output = Command::new("git")
             .arg("rev-parse")
-            .arg("--git-dir")
+            .arg(option)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to run git rev-parse: {}", e)))?;
+        let value = output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string());
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(key, value.clone());
+        }
+        Ok(value)
+    }
+
+    /// Read a git config value, if set
+    pub fn get_config_value(key: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .arg("config")
+            .arg(key)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read git config {}: {}", key, e)))?;
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || value.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(value))
+    }
+
+    /// Read a path-valued git config key (with `~` expansion), if set
+    pub fn get_config_path(key: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .arg("config")
+            .arg("--type=path")
+            .arg(key)
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to check git repo: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to read git config {}: {}", key, e)))?;
+
+        // Exit code 1 means the key is not set
+        if !output.status.success() {
+            return Ok(None);
+        }
 
-        Ok(output.status.success())
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(value))
     }
 
-    /// Get staged diff
-    pub fn get_staged_diff() -> Result<String> {
+    /// Read a path-valued key from the global git config only, if set
+    pub fn get_global_config_path(key: &str) -> Result<Option<String>> {
         let output = Command::new("git")
-            .arg("diff")
-            .arg("--cached")
+            .arg("config")
+            .arg("--global")
+            .arg("--type=path")
+            .arg(key)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read git config {}: {}", key, e)))?;
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || value.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(value))
+    }
+
+    /// Set a key in the global git config (`git config --global`)
+    pub fn set_global_config(key: &str, value: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("config")
+            .arg("--global")
+            .arg(key)
+            .arg(value)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to set git config {}: {}", key, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to set git config {}: {}",
+                key,
+                stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Remove a key from the global git config; a key that is not set is fine
+    pub fn unset_global_config(key: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("config")
+            .arg("--global")
+            .arg("--unset")
+            .arg(key)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to unset git config {}: {}", key, e)))?;
+
+        // Exit code 5 means the key was not set
+        if !output.status.success() && output.status.code() != Some(5) {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to unset git config {}: {}",
+                key,
+                stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get staged diff, limited to `paths` (git pathspecs) unless empty,
+    /// optionally with `-w --ignore-blank-lines`
+    pub fn get_staged_diff(paths: &[String], ignore_whitespace: bool) -> Result<String> {
+        #[cfg(feature = "libgit2")]
+        if paths.is_empty() && !ignore_whitespace {
+            if let Some(result) = git_native::staged_diff() {
+                return Ok(result);
+            }
+        }
+
+        let mut cmd = Command::new("git");
+        cmd.arg("diff").arg("--cached");
+        if ignore_whitespace {
+            cmd.arg("-w").arg("--ignore-blank-lines");
+        }
+
+        let output = cmd
+            .arg("--")
+            .args(paths)
             .output()
             .map_err(|e| GitAiError::Git(format!("Failed to get staged diff: {}", e)))?;
 
@@ -46,12 +295,133 @@ impl GitManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    /// Get list of staged files
-    pub fn get_staged_files() -> Result<Vec<String>> {
+    /// Get diff of the working tree against HEAD (what `git commit -a` would record)
+    pub fn get_working_tree_diff(paths: &[String], ignore_whitespace: bool) -> Result<String> {
+        let mut cmd = Command::new("git");
+        cmd.arg("diff");
+        if ignore_whitespace {
+            cmd.arg("-w").arg("--ignore-blank-lines");
+        }
+        // Before the first commit there is no HEAD; compare against the index instead
+        if Self::rev_parse("HEAD").is_ok() {
+            cmd.arg("HEAD");
+        }
+        cmd.arg("--").args(paths);
+
+        let output = cmd
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get working tree diff: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git(
+                "Failed to get working tree diff".to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Get the diff to show the model and whether the change is formatting
+    /// only (nothing left once whitespace is ignored, e.g. a rustfmt run).
+    /// Formatting-only changes keep the full diff so there is something to describe.
+    pub fn get_prompt_diff(
+        paths: &[String],
+        working_tree: bool,
+        ignore_whitespace: bool,
+    ) -> Result<(String, bool)> {
+        let diff_with = |ignore| {
+            if working_tree {
+                Self::get_working_tree_diff(paths, ignore)
+            } else {
+                Self::get_staged_diff(paths, ignore)
+            }
+        };
+
+        let full = diff_with(false)?;
+        let significant = diff_with(true)?;
+        let formatting_only = has_hunks(&full) && !has_hunks(&significant);
+        if ignore_whitespace && !formatting_only {
+            return Ok((significant, false));
+        }
+        Ok((full, formatting_only))
+    }
+
+    /// Get the staged diff with enclosing functions (`-U15 --function-context`)
+    /// for `focus` files, listed first, and `-U1` for every other file
+    pub fn get_expanded_diff(
+        paths: &[String],
+        ignore_whitespace: bool,
+        focus: &[String],
+    ) -> Result<String> {
+        let diff = |context: &[&str], pathspecs: &[String]| -> Result<String> {
+            let mut cmd = Command::new("git");
+            cmd.arg("diff").arg("--cached").args(context);
+            if ignore_whitespace {
+                cmd.arg("-w").arg("--ignore-blank-lines");
+            }
+            let output = cmd
+                .arg("--")
+                .args(pathspecs)
+                .output()
+                .map_err(|e| GitAiError::Git(format!("Failed to get staged diff: {}", e)))?;
+            if !output.status.success() {
+                return Err(GitAiError::Git("Failed to get staged diff".to_string()));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        };
+
+        let focused: Vec<String> = focus.iter().map(|f| format!(":(literal){}", f)).collect();
+        let mut rest = paths.to_vec();
+        rest.extend(focus.iter().map(|f| format!(":(exclude,literal){}", f)));
+
+        let mut expanded = String::new();
+        if !focused.is_empty() {
+            expanded.push_str(&diff(&["-U15", "--function-context"], &focused)?);
+        }
+        expanded.push_str(&diff(&["-U1"], &rest)?);
+        Ok(expanded)
+    }
+
+    /// Get list of tracked files changed in the working tree against HEAD
+    pub fn get_working_tree_files(paths: &[String]) -> Result<Vec<String>> {
+        let mut cmd = Command::new("git");
+        cmd.arg("diff").arg("--name-only");
+        if Self::rev_parse("HEAD").is_ok() {
+            cmd.arg("HEAD");
+        }
+        cmd.arg("--").args(paths);
+
+        let output = cmd
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get changed files: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git("Failed to get changed files".to_string()));
+        }
+
+        let files = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+
+        Ok(files)
+    }
+
+    /// Get list of staged files matching `paths` (all when empty)
+    pub fn get_staged_files(paths: &[String]) -> Result<Vec<String>> {
+        #[cfg(feature = "libgit2")]
+        if paths.is_empty() {
+            if let Some(result) = git_native::staged_files() {
+                return Ok(result);
+            }
+        }
+
         let output = Command::new("git")
             .arg("diff")
             .arg("--cached")
             .arg("--name-only")
+            .arg("--")
+            .args(paths)
             .output()
             .map_err(|e| GitAiError::Git(format!("Failed to get staged files: {}", e)))?;
 
@@ -126,173 +496,1178 @@ impl GitManager {
                 continue;
             }
 
-            if !path.is_empty() && seen.insert(path.clone()) {
-                results.push(UnstagedFileEntry {
-                    label: path.clone(),
-                    paths: vec![path],
-                });
-            }
+            if !path.is_empty() && seen.insert(path.clone()) {
+                results.push(UnstagedFileEntry {
+                    label: path.clone(),
+                    paths: vec![path],
+                });
+            }
+
+            i += 1;
+        }
+
+        Ok(results)
+    }
+
+    /// Get current branch name
+    /// `None` on a detached HEAD; a fresh repo reports its unborn branch
+    pub fn get_current_branch() -> Result<Option<String>> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::current_branch() {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("symbolic-ref")
+            .arg("--quiet")
+            .arg("--short")
+            .arg("HEAD")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get branch name: {}", e)))?;
+
+        match output.status.code() {
+            Some(0) => Ok(Some(
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            )),
+            // --quiet exits with 1 (and no message) when HEAD is not symbolic
+            Some(1) => Ok(None),
+            _ => Err(GitAiError::Git("Failed to get branch name".to_string())),
+        }
+    }
+
+    /// Whether HEAD points to a commit (false before the first commit)
+    pub fn has_commits() -> bool {
+        Self::rev_parse("HEAD").is_ok()
+    }
+
+    /// Get recent commits
+    pub fn get_recent_commits(count: usize) -> Result<Vec<String>> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::recent_commits(count) {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!("-{}", count))
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get recent commits: {}", e)))?;
+
+        if !output.status.success() {
+            // `git log` fails on an unborn branch; there is simply no history yet
+            if !Self::has_commits() {
+                return Ok(Vec::new());
+            }
+            return Err(GitAiError::Git("Failed to get recent commits".to_string()));
+        }
+
+        let commits = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+
+        Ok(commits)
+    }
+
+    /// Full messages of the last `count` non-merge commits, newest first
+    pub fn get_commit_messages(count: usize) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!("-{}", count))
+            .arg("--no-merges")
+            .arg("--format=%B%x00")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read commit messages: {}", e)))?;
+
+        if !output.status.success() {
+            if !Self::has_commits() {
+                return Ok(Vec::new());
+            }
+            return Err(GitAiError::Git(
+                "Failed to read commit messages".to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())
+            .collect())
+    }
+
+    /// Get commits from last N days
+    pub fn get_commits_by_days(days: usize) -> Result<Vec<String>> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::commits_since(days) {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!("--since={} days ago", days))
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commits: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git("Failed to get commits".to_string()));
+        }
+
+        let commits = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+
+        Ok(commits)
+    }
+
+    /// Get latest reachable tag from HEAD
+    pub fn get_latest_tag() -> Result<Option<String>> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::latest_tag().map(Some) {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("describe")
+            .arg("--tags")
+            .arg("--abbrev=0")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get latest tag: {}", e)))?;
+
+        if output.status.success() {
+            let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if tag.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some(tag));
+        }
+
+        // No tags in repository is a normal case for some projects.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("No names found") || stderr.contains("cannot describe") {
+            return Ok(None);
+        }
+
+        Err(GitAiError::Git(format!(
+            "Failed to get latest tag: {}",
+            stderr.trim()
+        )))
+    }
+
+    /// Get commits between two refs
+    pub fn get_commits_between_refs(from_ref: &str, to_ref: &str) -> Result<Vec<String>> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::commits_between(from_ref, to_ref) {
+            return Ok(result);
+        }
+
+        let range = format!("{}..{}", from_ref, to_ref);
+        let output = Command::new("git")
+            .arg("log")
+            .arg(range)
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commits by range: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get commits by range: {}",
+                stderr.trim()
+            )));
+        }
+
+        let commits = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+
+        Ok(commits)
+    }
+
+    /// All commits reachable from `rev`, back to the root commit
+    pub fn get_commits_up_to(rev: &str) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg(rev)
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .arg("--")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commits: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get commits: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Commits in `scope` as `(author, "%h %cd %s")`, newest first.
+    /// Authors go through `.mailmap` so one person is not split across emails.
+    pub fn get_authored_commits(
+        scope: &LogScope,
+        no_merges: bool,
+    ) -> Result<Vec<(String, String)>> {
+        let output = Command::new("git")
+            .arg("log")
+            .args(scope.log_args())
+            .args(no_merges.then_some("--no-merges"))
+            .arg("--format=%aN%x00%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commits: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get commits: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\0'))
+            .map(|(author, commit)| (author.to_string(), commit.to_string()))
+            .collect())
+    }
+
+    /// Per-commit `--numstat` totals for the commits in `scope`
+    pub fn get_commit_stats(scope: &LogScope) -> Result<Vec<CommitStat>> {
+        let output = Command::new("git")
+            .arg("log")
+            .args(scope.log_args())
+            .arg("--format=%x00%H")
+            .arg("--numstat")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commit stats: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get commit stats: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(parse_commit_numstat(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// The newest `limit` non-merge commits of HEAD with their messages and
+    /// touched paths
+    pub fn get_commit_records(limit: usize) -> Result<Vec<CommitRecord>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!("-{}", limit))
+            .arg("--no-merges")
+            .arg("--format=%x1e%H%x00%cs%x00%B%x00")
+            .arg("--name-only")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read history: {}", e)))?;
+
+        if !output.status.success() {
+            if !Self::has_commits() {
+                return Ok(Vec::new());
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to read history: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(parse_commit_records(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Commits on any local branch by `author` (matched against name/email)
+    /// since `since` (any date `git log --since` accepts), newest first
+    pub fn get_commits_by_author_since(author: &str, since: &str) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg("--branches")
+            .arg(format!("--author={}", author))
+            .arg(format!("--since={}", since))
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commits: {}", e)))?;
+
+        if !output.status.success() {
+            if !Self::has_commits() {
+                return Ok(Vec::new());
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get commits: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect())
+    }
+
+    /// The repository's main line: `main` or `master`, whichever exists locally
+    pub fn get_default_branch() -> Result<Option<String>> {
+        for branch in ["main", "master"] {
+            if Self::rev_parse(&format!("refs/heads/{}", branch)).is_ok() {
+                return Ok(Some(branch.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Local branches with commits not on `base`, as `(branch, commits ahead)`
+    pub fn get_branches_ahead_of(base: &str) -> Result<Vec<(String, usize)>> {
+        let output = Command::new("git")
+            .arg("for-each-ref")
+            .arg("--format=%(refname:short)")
+            .arg("refs/heads")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to list branches: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git("Failed to list branches".to_string()));
+        }
+
+        let mut ahead = Vec::new();
+        for branch in String::from_utf8_lossy(&output.stdout).lines() {
+            if branch == base {
+                continue;
+            }
+            let count = Command::new("git")
+                .arg("rev-list")
+                .arg("--count")
+                .arg(format!("{}..{}", base, branch))
+                .output()
+                .map_err(|e| GitAiError::Git(format!("Failed to compare branches: {}", e)))?;
+            let count = String::from_utf8_lossy(&count.stdout)
+                .trim()
+                .parse::<usize>()
+                .unwrap_or(0);
+            if count > 0 {
+                ahead.push((branch.to_string(), count));
+            }
+        }
+        Ok(ahead)
+    }
+
+    /// `git log --left-right --cherry-mark base...head`, split per side
+    pub fn compare_branches(base: &str, head: &str) -> Result<BranchComparison> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg("--left-right")
+            .arg("--cherry-mark")
+            .arg("--format=%m %h %cd %s")
+            .arg("--date=short")
+            .arg(format!("{}...{}", base, head))
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to compare branches: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to compare branches: {}",
+                stderr.trim()
+            )));
+        }
+
+        let mut comparison = BranchComparison::default();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            match line.split_once(' ') {
+                Some(("<", commit)) => comparison.only_base.push(commit.to_string()),
+                Some((">", commit)) => comparison.only_head.push(commit.to_string()),
+                // Each cherry-picked pair shows up once per side
+                Some(("=", _)) => comparison.equivalent += 1,
+                _ => {}
+            }
+        }
+        comparison.equivalent /= 2;
+        Ok(comparison)
+    }
+
+    /// Whether `name` is an existing tag
+    pub fn is_tag(name: &str) -> bool {
+        Command::new("git")
+            .arg("show-ref")
+            .arg("--verify")
+            .arg("--quiet")
+            .arg(format!("refs/tags/{}", name))
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    /// Whether `name` is a valid tag name (`git check-ref-format`) that git
+    /// will not read as an option
+    pub fn is_valid_tag_name(name: &str) -> bool {
+        !name.starts_with('-')
+            && Command::new("git")
+                .arg("check-ref-format")
+                .arg(format!("refs/tags/{}", name))
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false)
+    }
+
+    /// Tags reachable from `to` that contain `from`, oldest first, without
+    /// `from` and `to` themselves
+    pub fn get_tags_between(from: &str, to: &str) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .arg("tag")
+            .arg("--list")
+            .arg(format!("--contains={}", from))
+            .arg(format!("--merged={}", to))
+            .arg("--sort=creatordate")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to list tags: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to list tags between {} and {}: {}",
+                from,
+                to,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty() && *tag != from && *tag != to)
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Committer date of `rev` as YYYY-MM-DD
+    pub fn get_commit_date(rev: &str) -> Result<String> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg("-1")
+            .arg("--format=%cs")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get commit date: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git(format!("Unknown revision: {}", rev)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Full message and parent ids of a single commit
+    pub fn get_commit_info(rev: &str) -> Result<(String, Vec<String>)> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg("-1")
+            .arg("--format=%P%x00%B")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read {}: {}", rev, e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git(format!("Unknown revision: {}", rev)));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (parents, message) = stdout.split_once('\0').unwrap_or(("", &stdout));
+        Ok((
+            message.trim_end().to_string(),
+            parents.split_whitespace().map(str::to_string).collect(),
+        ))
+    }
+
+    /// Move HEAD to `rev`, keeping the index and working tree (`git reset --soft`)
+    pub fn reset_soft(rev: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("reset")
+            .arg("--soft")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to reset to {}: {}", rev, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to reset to {}: {}",
+                rev,
+                stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Whether a `git bisect` session is in progress
+    pub fn is_bisecting() -> Result<bool> {
+        Ok(Self::get_git_dir()?.join("BISECT_START").exists())
+    }
+
+    /// Run `git bisect <args>` and return what it printed
+    pub fn bisect(args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .arg("bisect")
+            .args(args)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to run git bisect: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "git bisect {} failed: {}",
+                args.first().unwrap_or(&""),
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Resolve a revision to its full object id
+    pub fn rev_parse(rev: &str) -> Result<String> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::rev_parse(rev) {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to resolve {}: {}", rev, e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git(format!("Unknown revision: {}", rev)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Get the upstream branch of HEAD (e.g. "origin/main"), if one is set
+    pub fn get_upstream_branch() -> Result<Option<String>> {
+        let output = Command::new("git")
+            .arg("rev-parse")
+            .arg("--abbrev-ref")
+            .arg("--symbolic-full-name")
+            .arg("@{u}")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get upstream branch: {}", e)))?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let upstream = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if upstream.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(upstream))
+    }
+
+    /// Check whether `ancestor` is reachable from `descendant`
+    pub fn is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
+        #[cfg(feature = "libgit2")]
+        if let Some(result) = git_native::is_ancestor(ancestor, descendant) {
+            return Ok(result);
+        }
+
+        let output = Command::new("git")
+            .arg("merge-base")
+            .arg("--is-ancestor")
+            .arg(ancestor)
+            .arg(descendant)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to compare commits: {}", e)))?;
+
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Err(GitAiError::Git(format!(
+                    "Failed to compare commits: {}",
+                    stderr.trim()
+                )))
+            }
+        }
+    }
+
+    /// Get up to `limit` commits reachable from `rev` that no ref of
+    /// `remote` contains, newest first
+    pub fn get_commits_not_on_remote(rev: &str, remote: &str, limit: usize) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!("--max-count={}", limit))
+            .arg(rev)
+            .arg("--not")
+            .arg(format!("--remotes={}", remote))
+            .arg("--format=%h %cd %s")
+            .arg("--date=short")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get outgoing commits: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to get outgoing commits: {}",
+                stderr.trim()
+            )));
+        }
+
+        let commits = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+
+        Ok(commits)
+    }
+
+    /// List stash entries as `(stash@{n}, "WIP on <branch>: <subject>")`
+    pub fn get_stash_entries() -> Result<Vec<(String, String)>> {
+        let output = Command::new("git")
+            .arg("stash")
+            .arg("list")
+            .arg("--format=%gd%x00%gs")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to list stashes: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git("Failed to list stashes".to_string()));
+        }
+
+        let entries = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\0'))
+            .map(|(name, subject)| (name.to_string(), subject.to_string()))
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Get the patch a stash entry would apply (`git stash show -p`)
+    pub fn get_stash_diff(stash: &str) -> Result<String> {
+        let output = Command::new("git")
+            .arg("stash")
+            .arg("show")
+            .arg("-p")
+            .arg(stash)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to show {}: {}", stash, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to show {}: {}",
+                stash,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Message, stat and patch of a single commit (`git show`)
+    pub fn get_commit_patch(rev: &str) -> Result<String> {
+        let output = Command::new("git")
+            .arg("show")
+            .arg("--no-color")
+            .arg("--stat")
+            .arg("--patch")
+            .arg("--format=%B")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to show {}: {}", rev, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to show {}: {}",
+                rev,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Patches of `hashes` (without messages) in one `git log --no-walk`,
+    /// keyed by full hash
+    pub fn get_commit_patches(hashes: &[&str]) -> Result<HashMap<String, String>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let output = Command::new("git")
+            .arg("log")
+            .arg("--no-walk=unsorted")
+            .arg("--no-color")
+            .arg("--patch")
+            .arg("--format=%x1e%H")
+            .args(hashes)
+            .arg("--")
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read commit patches: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to read commit patches: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\x1e')
+            .filter_map(|record| {
+                let (hash, patch) = record.split_once('\n').unwrap_or((record, ""));
+                let hash = hash.trim();
+                (!hash.is_empty()).then(|| (hash.to_string(), patch.trim().to_string()))
+            })
+            .collect())
+    }
+
+    /// Newest `count` commits that touched `file` (or lines `start..=end` of
+    /// it, via `git log -L`), each record starting with `\x1e<hash> <date>
+    /// <author>: <subject>` and followed by its message body and patch
+    pub fn get_line_history(
+        file: &str,
+        lines: Option<(usize, usize)>,
+        count: usize,
+    ) -> Result<String> {
+        let mut command = Command::new("git");
+        command
+            .arg("log")
+            .arg("--no-color")
+            .arg(format!("-n{}", count))
+            .arg("--date=short")
+            .arg("--format=%x1e%h %ad %an: %s%n%b");
+        match lines {
+            Some((start, end)) => {
+                command.arg(format!("-L{},{}:{}", start, end, file));
+            }
+            None => {
+                command.arg("--follow").arg("--patch").arg("--").arg(file);
+            }
+        }
+        let output = command
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read history of {}: {}", file, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to read history of {}: {}",
+                file,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Stat and patch of a revision range, passed to `git diff` as given
+    /// (`from...to` diffs `to` against the merge base)
+    pub fn get_range_diff(range: &str) -> Result<String> {
+        let output = Command::new("git")
+            .arg("diff")
+            .arg("--no-color")
+            .arg("--stat")
+            .arg("--patch")
+            .arg(range)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to diff {}: {}", range, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to diff {}: {}",
+                range,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Changes on HEAD since it forked from `base` (`git diff base...HEAD`)
+    /// and the files they touch
+    pub fn get_branch_diff(base: &str) -> Result<(String, Vec<String>)> {
+        // `--output=<file>...HEAD` would be read as an option, not a range
+        if base.starts_with('-') {
+            return Err(GitAiError::InvalidArgument(format!(
+                "Invalid base '{}': a branch or commit cannot start with '-'",
+                base
+            )));
+        }
+        let range = format!("{}...HEAD", base);
+        let run = |extra: &[&str]| -> Result<String> {
+            let output = Command::new("git")
+                .arg("diff")
+                .arg("--no-color")
+                .args(extra)
+                .arg(&range)
+                .arg("--")
+                .output()
+                .map_err(|e| GitAiError::Git(format!("Failed to diff {}: {}", range, e)))?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(GitAiError::Git(format!(
+                    "Failed to diff {}: {}",
+                    range,
+                    stderr.trim()
+                )));
+            }
+
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        };
 
-            i += 1;
+        let files = run(&["--name-only"])?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok((run(&[])?, files))
+    }
+
+    /// Stage files
+    pub fn add_files(files: &[String]) -> Result<()> {
+        let mut cmd = Command::new("git");
+        cmd.arg("add").arg("-A").arg("--");
+
+        for file in files {
+            cmd.arg(file);
         }
 
-        Ok(results)
+        let output = cmd
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to stage files: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git("Failed to stage files".to_string()));
+        }
+
+        Ok(())
     }
 
-    /// Get current branch name
-    pub fn get_current_branch() -> Result<String> {
+    /// Create a commit; with `paths`, only the staged content of those paths
+    /// is committed and the rest of the index is left staged
+    pub fn commit(message: &str, paths: &[String]) -> Result<()> {
+        if !paths.is_empty() {
+            return Self::commit_staged_paths(message, paths);
+        }
+
         let output = Command::new("git")
-            .arg("rev-parse")
-            .arg("--abbrev-ref")
-            .arg("HEAD")
+            .arg("commit")
+            .arg("-m")
+            .arg(message)
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to get branch name: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to create commit: {}", e)))?;
 
         if !output.status.success() {
-            return Err(GitAiError::Git("Failed to get branch name".to_string()));
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to create commit: {}",
+                stderr
+            )));
         }
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        Ok(())
     }
 
-    /// Get recent commits
-    pub fn get_recent_commits(count: usize) -> Result<Vec<String>> {
-        let output = Command::new("git")
-            .arg("log")
-            .arg(format!("-{}", count))
-            .arg("--format=%h %cd %s")
-            .arg("--date=short")
-            .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to get recent commits: {}", e)))?;
+    /// Commit the staged entries under `paths` through a temporary index
+    /// holding HEAD plus those entries. `git commit -- <paths>` would take the
+    /// working-tree content instead, which is not what the message describes.
+    fn commit_staged_paths(message: &str, paths: &[String]) -> Result<()> {
+        let index = Self::get_git_dir()?.join(format!("git-ai-index-{}", std::process::id()));
+        let result = Self::commit_with_index(&index, message, paths);
+        let _ = std::fs::remove_file(&index);
+        result
+    }
 
+    fn commit_with_index(index: &Path, message: &str, paths: &[String]) -> Result<()> {
+        let fail = |what: &str, stderr: &[u8]| {
+            GitAiError::Git(format!(
+                "Failed to create commit: {}: {}",
+                what,
+                String::from_utf8_lossy(stderr).trim()
+            ))
+        };
+        let git = |args: &[&str], input: Option<&[u8]>, temp_index: bool| {
+            let mut cmd = Command::new("git");
+            cmd.args(args);
+            if temp_index {
+                cmd.env("GIT_INDEX_FILE", index);
+            }
+            let mut child = cmd
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| GitAiError::Git(format!("Failed to create commit: {}", e)))?;
+            if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+                use std::io::Write;
+                stdin.write_all(input)?;
+            }
+            child
+                .wait_with_output()
+                .map_err(|e| GitAiError::Git(format!("Failed to create commit: {}", e)))
+        };
+        let pathspec = |command: &[&str]| -> Vec<String> {
+            command
+                .iter()
+                .map(|arg| arg.to_string())
+                .chain(std::iter::once("--".to_string()))
+                .chain(paths.iter().cloned())
+                .collect()
+        };
+
+        let base: &[&str] = if Self::has_commits() {
+            &["read-tree", "HEAD"]
+        } else {
+            &["read-tree", "--empty"]
+        };
+        let output = git(base, None, true)?;
         if !output.status.success() {
-            return Err(GitAiError::Git("Failed to get recent commits".to_string()));
+            return Err(fail("read-tree", &output.stderr));
         }
 
-        let commits = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
+        // Staged entries of the paths, and the paths whose deletion is staged
+        let args = pathspec(&["ls-files", "--stage", "-z"]);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let entries = git(&args, None, false)?;
+        if !entries.status.success() {
+            return Err(fail("ls-files", &entries.stderr));
+        }
+        let args = pathspec(&["diff", "--cached", "--name-only", "--diff-filter=D", "-z"]);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let deleted = git(&args, None, false)?;
+        if !deleted.status.success() {
+            return Err(fail("diff", &deleted.stderr));
+        }
 
-        Ok(commits)
+        let output = git(
+            &["update-index", "-z", "--index-info"],
+            Some(&entries.stdout),
+            true,
+        )?;
+        if !output.status.success() {
+            return Err(fail("update-index", &output.stderr));
+        }
+        let output = git(
+            &["update-index", "-z", "--force-remove", "--stdin"],
+            Some(&deleted.stdout),
+            true,
+        )?;
+        if !output.status.success() {
+            return Err(fail("update-index", &output.stderr));
+        }
+
+        let output = git(&["commit", "-m", message], None, true)?;
+        if !output.status.success() {
+            return Err(fail("commit", &output.stderr));
+        }
+        Ok(())
     }
 
-    /// Get commits from last N days
-    pub fn get_commits_by_days(days: usize) -> Result<Vec<String>> {
+    /// Create an annotated tag on HEAD, GPG-signed (`git tag -s`) when `sign`
+    pub fn create_annotated_tag(tag: &str, message: &str, sign: bool) -> Result<()> {
         let output = Command::new("git")
-            .arg("log")
-            .arg(format!("--since={}d", days))
-            .arg("--format=%h %cd %s")
-            .arg("--date=short")
+            .arg("tag")
+            .arg(if sign { "-s" } else { "-a" })
+            .arg(tag)
+            .arg("-m")
+            .arg(message)
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to get commits: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to create tag: {}", e)))?;
 
         if !output.status.success() {
-            return Err(GitAiError::Git("Failed to get commits".to_string()));
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to create tag: {}",
+                stderr.trim()
+            )));
         }
 
-        let commits = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(commits)
+        Ok(())
     }
 
-    /// Get latest reachable tag from HEAD
-    pub fn get_latest_tag() -> Result<Option<String>> {
+    /// Push a single ref (e.g. a tag) to `remote`
+    pub fn push_ref(remote: &str, refname: &str) -> Result<()> {
         let output = Command::new("git")
-            .arg("describe")
-            .arg("--tags")
-            .arg("--abbrev=0")
+            .arg("push")
+            .arg(remote)
+            .arg(refname)
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to get latest tag: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to push {}: {}", refname, e)))?;
 
-        if output.status.success() {
-            let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if tag.is_empty() {
-                return Ok(None);
-            }
-            return Ok(Some(tag));
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to push {}: {}",
+                refname,
+                stderr.trim()
+            )));
         }
 
-        // No tags in repository is a normal case for some projects.
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("No names found") || stderr.contains("cannot describe") {
+        Ok(())
+    }
+
+    /// URL of `remote`, if configured
+    pub fn get_remote_url(remote: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .arg("remote")
+            .arg("get-url")
+            .arg(remote)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read remote {}: {}", remote, e)))?;
+
+        if !output.status.success() {
             return Ok(None);
         }
 
-        Err(GitAiError::Git(format!(
-            "Failed to get latest tag: {}",
-            stderr.trim()
-        )))
+        Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ))
     }
 
-    /// Get commits between two refs
-    pub fn get_commits_between_refs(from_ref: &str, to_ref: &str) -> Result<Vec<String>> {
-        let range = format!("{}..{}", from_ref, to_ref);
+    /// Attach (or replace) a note on `rev` under `refs/notes/<notes_ref>`
+    pub fn add_note(notes_ref: &str, rev: &str, content: &str) -> Result<()> {
         let output = Command::new("git")
-            .arg("log")
-            .arg(range)
-            .arg("--format=%h %cd %s")
-            .arg("--date=short")
+            .arg("notes")
+            .arg(format!("--ref={}", notes_ref))
+            .arg("add")
+            .arg("-f")
+            .arg("-m")
+            .arg(content)
+            .arg(rev)
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to get commits by range: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to add note: {}", e)))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(GitAiError::Git(format!(
-                "Failed to get commits by range: {}",
+                "Failed to add note: {}",
                 stderr.trim()
             )));
         }
 
-        let commits = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(commits)
+        Ok(())
     }
 
-    /// Stage files
-    pub fn add_files(files: &[String]) -> Result<()> {
-        let mut cmd = Command::new("git");
-        cmd.arg("add").arg("-A").arg("--");
+    /// Read the note on `rev` under `refs/notes/<notes_ref>`, if any
+    pub fn get_note(notes_ref: &str, rev: &str) -> Result<Option<String>> {
+        // Resolve first so a bad revision is an error rather than "no note"
+        let commit = Self::rev_parse(rev)?;
+        let output = Command::new("git")
+            .arg("notes")
+            .arg(format!("--ref={}", notes_ref))
+            .arg("show")
+            .arg(&commit)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read note: {}", e)))?;
 
-        for file in files {
-            cmd.arg(file);
+        if !output.status.success() {
+            return Ok(None);
         }
 
-        let output = cmd
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    }
+
+    /// `(blob id, path)` of every file in the index
+    pub fn get_index_entries() -> Result<Vec<(String, String)>> {
+        let output = Command::new("git")
+            .arg("ls-files")
+            .arg("--stage")
+            .arg("-z")
             .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to stage files: {}", e)))?;
+            .map_err(|e| GitAiError::Git(format!("Failed to list files: {}", e)))?;
 
         if !output.status.success() {
-            return Err(GitAiError::Git("Failed to stage files".to_string()));
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitAiError::Git(format!(
+                "Failed to list files: {}",
+                stderr.trim()
+            )));
         }
 
-        Ok(())
+        // <mode> <blob> <stage>\t<path>\0
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter_map(|entry| {
+                let (meta, path) = entry.split_once('\t')?;
+                let blob = meta.split(' ').nth(1)?;
+                Some((blob.to_string(), path.to_string()))
+            })
+            .collect())
     }
 
-    /// Create a commit
-    pub fn commit(message: &str) -> Result<()> {
-        let output = Command::new("git")
-            .arg("commit")
-            .arg("-m")
-            .arg(message)
-            .output()
-            .map_err(|e| GitAiError::Git(format!("Failed to create commit: {}", e)))?;
+    /// Contents of blobs by id, read through one `git cat-file --batch`.
+    /// Ids that don't exist are left out.
+    pub fn read_blobs(ids: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        let mut child = Command::new("git")
+            .arg("cat-file")
+            .arg("--batch")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| GitAiError::Git(format!("Failed to read blobs: {}", e)))?;
+        let input: String = ids.iter().map(|id| format!("{}\n", id)).collect();
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // Written from another thread so a full stdout pipe cannot block it
+        let writer = std::thread::spawn(move || {
+            use std::io::Write;
+            stdin.write_all(input.as_bytes())
+        });
+        let output = child
+            .wait_with_output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read blobs: {}", e)))?;
+        let _ = writer.join();
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(GitAiError::Git(format!(
-                "Failed to create commit: {}",
-                stderr
+                "Failed to read blobs: {}",
+                stderr.trim()
             )));
         }
+        Ok(parse_cat_file_batch(&output.stdout))
+    }
 
-        Ok(())
+    /// Content of `path` (relative to the repo root) as staged in the index
+    pub fn get_index_file(path: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .arg("show")
+            .arg(format!(":{}", path))
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to read {}: {}", path, e)))?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
     }
 
     /// Search code using git grep
@@ -334,11 +1709,20 @@ impl GitManager {
     }
 
     /// Get file statistics (insertions/deletions)
-    pub fn get_file_stats() -> Result<Vec<(String, u32, u32)>> {
+    pub fn get_file_stats(paths: &[String]) -> Result<Vec<(String, u32, u32)>> {
+        #[cfg(feature = "libgit2")]
+        if paths.is_empty() {
+            if let Some(result) = git_native::staged_file_stats() {
+                return Ok(result);
+            }
+        }
+
         let output = Command::new("git")
             .arg("diff")
             .arg("--cached")
             .arg("--numstat")
+            .arg("--")
+            .args(paths)
             .output()
             .map_err(|e| GitAiError::Git(format!("Failed to get file stats: {}", e)))?;
 
@@ -346,27 +1730,29 @@ impl GitManager {
             return Err(GitAiError::Git("Failed to get file stats".to_string()));
         }
 
-        let stats = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 3 {
-                    let insertions = parts[0].parse::<u32>().unwrap_or(0);
-                    let deletions = parts[1].parse::<u32>().unwrap_or(0);
-                    let file = parts[2].to_string();
-                    Some((file, insertions, deletions))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+    }
 
-        Ok(stats)
+    /// Insertions/deletions per file of a single commit
+    pub fn get_commit_file_stats(rev: &str) -> Result<Vec<(String, u32, u32)>> {
+        let output = Command::new("git")
+            .arg("show")
+            .arg("--numstat")
+            .arg("--format=")
+            .arg(rev)
+            .output()
+            .map_err(|e| GitAiError::Git(format!("Failed to get file stats: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Git(format!("Unknown revision: {}", rev)));
+        }
+
+        Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
     }
 
     /// Get detailed diff statistics
-    pub fn get_diff_statistics() -> Result<crate::types::DiffStatistics> {
-        let file_stats_raw = Self::get_file_stats()?;
+    pub fn get_diff_statistics(paths: &[String]) -> Result<crate::types::DiffStatistics> {
+        let file_stats_raw = Self::get_file_stats(paths)?;
 
         let mut total_insertions = 0u32;
         let mut total_deletions = 0u32;
@@ -395,8 +1781,8 @@ impl GitManager {
     }
 
     /// Get formatted diff statistics summary
-    pub fn get_diff_summary() -> Result<String> {
-        let stats = Self::get_diff_statistics()?;
+    pub fn get_diff_summary(paths: &[String]) -> Result<String> {
+        let stats = Self::get_diff_statistics(paths)?;
 
         let summary = format!(
             "📊 Diff Statistics:\n   • Files changed: {}\n   • Insertions: +{}\n   • Deletions: -{}\n   • Modifications: {}",
@@ -410,9 +1796,134 @@ impl GitManager {
     }
 }
 
+/// `(path, insertions, deletions)` per line of `--numstat` output; binary
+/// files count as zero lines
+pub(crate) fn parse_numstat(output: &str) -> Vec<(String, u32, u32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() >= 3 {
+                let insertions = parts[0].parse::<u32>().unwrap_or(0);
+                let deletions = parts[1].parse::<u32>().unwrap_or(0);
+                let file = rename_target(parts[2]);
+                Some((file, insertions, deletions))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// New path of a `--numstat` rename (`old => new`, `dir/{old => new}/file`),
+/// the path itself otherwise, matching what the libgit2 backend reports
+fn rename_target(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.rfind('}')) {
+        if let Some((_, new)) = path[open + 1..close].split_once(" => ") {
+            let path = format!("{}{}{}", &path[..open], new, &path[close + 1..]);
+            return path.replace("//", "/").trim_start_matches('/').to_string();
+        }
+    }
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Parse `git cat-file --batch` output: `<id> <type> <size>\n<content>\n`
+/// per object, or `<id> missing\n`
+fn parse_cat_file_batch(mut data: &[u8]) -> HashMap<String, Vec<u8>> {
+    let mut blobs = HashMap::new();
+    while let Some(end) = data.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&data[..end]).into_owned();
+        data = &data[end + 1..];
+        let fields: Vec<&str> = header.split(' ').collect();
+        let [id, _, size] = fields[..] else {
+            continue;
+        };
+        let Ok(size) = size.parse::<usize>() else {
+            break;
+        };
+        let Some(content) = data.get(..size) else {
+            break;
+        };
+        blobs.insert(id.to_string(), content.to_vec());
+        data = data.get(size + 1..).unwrap_or_default();
+    }
+    blobs
+}
+
+/// Parse `git log --format=%x1e%H%x00%cs%x00%B%x00 --name-only` output
+fn parse_commit_records(output: &str) -> Vec<CommitRecord> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.splitn(4, '\0');
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            Some(CommitRecord {
+                hash: hash.to_string(),
+                date: fields.next()?.to_string(),
+                message: fields.next()?.trim().to_string(),
+                files: fields
+                    .next()
+                    .unwrap_or_default()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `git log --format=%x00%H --numstat` output. Binary files count as
+/// touched with no line changes.
+fn parse_commit_numstat(output: &str) -> Vec<CommitStat> {
+    let mut stats: Vec<CommitStat> = Vec::new();
+    for line in output.lines() {
+        if let Some(hash) = line.strip_prefix('\0') {
+            stats.push(CommitStat {
+                hash: hash.trim().to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let (Some(current), Some((insertions, rest))) = (stats.last_mut(), line.split_once('\t'))
+        else {
+            continue;
+        };
+        let Some((deletions, path)) = rest.split_once('\t') else {
+            continue;
+        };
+
+        current.files += 1;
+        current.insertions += insertions.parse::<u32>().unwrap_or(0);
+        current.deletions += deletions.parse::<u32>().unwrap_or(0);
+        let area = match path.split_once('/') {
+            // Renames inside a directory read `dir/{old => new}`
+            Some((dir, _)) if !dir.contains('{') => format!("{}/", dir),
+            _ => path.to_string(),
+        };
+        if !current.areas.contains(&area) {
+            current.areas.push(area);
+        }
+    }
+    stats
+}
+
+/// Whether a diff changes any content (mode-only changes and pure renames have no hunks)
+fn has_hunks(diff: &str) -> bool {
+    diff.lines().any(|line| line.starts_with("@@ "))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::types::{DiffStatistics, FileStat};
+    use crate::types::{CommitRecord, CommitStat, DiffStatistics, FileStat};
 
     // Helper function to create test DiffStatistics
     fn create_test_stats(
@@ -443,7 +1954,7 @@ mod tests {
     #[test]
     fn test_parse_file_stats_single_file_additions() {
         // Test parsing a single file with only insertions
-        let stats = vec![("src/main.rs".to_string(), 10, 0)];
+        let stats = [("src/main.rs".to_string(), 10, 0)];
 
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].0, "src/main.rs");
@@ -454,7 +1965,7 @@ mod tests {
     #[test]
     fn test_parse_file_stats_single_file_deletions() {
         // Test parsing a single file with only deletions
-        let stats = vec![("src/old.rs".to_string(), 0, 5)];
+        let stats = [("src/old.rs".to_string(), 0, 5)];
 
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].0, "src/old.rs");
@@ -465,7 +1976,7 @@ mod tests {
     #[test]
     fn test_parse_file_stats_single_file_modifications() {
         // Test parsing a single file with both insertions and deletions
-        let stats = vec![("src/lib.rs".to_string(), 15, 8)];
+        let stats = [("src/lib.rs".to_string(), 15, 8)];
 
         assert_eq!(stats.len(), 1);
         assert_eq!(stats[0].0, "src/lib.rs");
@@ -476,7 +1987,7 @@ mod tests {
     #[test]
     fn test_parse_file_stats_multiple_files() {
         // Test parsing multiple files with mixed changes
-        let stats = vec![
+        let stats = [
             ("src/main.rs".to_string(), 10, 2),
             ("src/lib.rs".to_string(), 5, 3),
             ("tests/test.rs".to_string(), 20, 0),
@@ -546,6 +2057,78 @@ mod tests {
         assert_eq!(stats.file_stats.len(), 4);
     }
 
+    #[test]
+    fn test_parse_commit_numstat() {
+        let output = "\0aaa\n\n10\t2\tsrc-rs/main.rs\n3\t0\tsrc-rs/utils/git.rs\n-\t-\tlogo.png\n\0bbb\n\n1\t1\tREADME.md\n";
+        assert_eq!(
+            super::parse_commit_numstat(output),
+            vec![
+                CommitStat {
+                    hash: "aaa".to_string(),
+                    files: 3,
+                    insertions: 13,
+                    deletions: 2,
+                    areas: vec!["src-rs/".to_string(), "logo.png".to_string()],
+                },
+                CommitStat {
+                    hash: "bbb".to_string(),
+                    files: 1,
+                    insertions: 1,
+                    deletions: 1,
+                    areas: vec!["README.md".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_commit_records() {
+        let output = "\x1eaaa\x002024-05-01\x00feat: add search\n\nUses embeddings\n\x00\n\nsrc/search.rs\nREADME.md\n\x1ebbb\x002024-04-30\x00chore: empty\n\x00\n";
+        assert_eq!(
+            super::parse_commit_records(output),
+            vec![
+                CommitRecord {
+                    hash: "aaa".to_string(),
+                    date: "2024-05-01".to_string(),
+                    message: "feat: add search\n\nUses embeddings".to_string(),
+                    files: vec!["src/search.rs".to_string(), "README.md".to_string()],
+                },
+                CommitRecord {
+                    hash: "bbb".to_string(),
+                    date: "2024-04-30".to_string(),
+                    message: "chore: empty".to_string(),
+                    files: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_filter_matches_authors_and_subjects() {
+        let config = crate::types::AIConfig {
+            exclude_authors: Some(vec!["Dependabot".to_string()]),
+            exclude_patterns: Some(vec!["chore(release)".to_string()]),
+            ..Default::default()
+        };
+        let filter = super::CommitFilter::from_config(&config, false);
+        assert!(filter.is_active());
+        assert!(filter.excludes("dependabot[bot]", "a1 2024-05-01 build: bump serde"));
+        assert!(filter.excludes("Alice", "a2 2024-05-01 chore(release): 1.2.0"));
+        assert!(!filter.excludes("Alice", "a3 2024-05-01 feat: release notes"));
+        assert!(!super::CommitFilter::default().is_active());
+    }
+
+    #[test]
+    fn test_has_hunks_ignores_header_only_diffs() {
+        let rename =
+            "diff --git a/a.rs b/b.rs\nsimilarity index 100%\nrename from a.rs\nrename to b.rs\n";
+        assert!(!super::has_hunks(rename));
+        assert!(super::has_hunks(
+            "diff --git a/a b/a\n--- a/a\n+++ b/a\n@@ -1 +1 @@\n-x\n+y\n"
+        ));
+        assert!(!super::has_hunks(""));
+    }
+
     #[test]
     fn test_diff_statistics_empty_diff() {
         // Test DiffStatistics with no changes
@@ -764,4 +2347,71 @@ mod tests {
         assert!(stats.total_insertions > 0);
         assert!(stats.total_deletions > 0);
     }
+
+    #[test]
+    fn branch_diff_rejects_option_like_bases() {
+        let err = super::GitManager::get_branch_diff("--output=/tmp/x").unwrap_err();
+        assert!(matches!(err, crate::error::GitAiError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn tag_names_are_checked_like_refs() {
+        assert!(super::GitManager::is_valid_tag_name("v1.4.0"));
+        assert!(super::GitManager::is_valid_tag_name("release/2024-06"));
+        assert!(!super::GitManager::is_valid_tag_name("v1..4"));
+        assert!(!super::GitManager::is_valid_tag_name("bad name"));
+        assert!(!super::GitManager::is_valid_tag_name("-f"));
+    }
+
+    #[test]
+    fn cat_file_batch_output_is_split_per_blob() {
+        let blobs = super::parse_cat_file_batch(b"aaa blob 4\nab\nc\nbbb missing\nccc blob 0\n\n");
+        assert_eq!(blobs["aaa"], b"ab\nc");
+        assert_eq!(blobs["ccc"], b"");
+        assert!(!blobs.contains_key("bbb"));
+    }
+
+    #[test]
+    fn numstat_reports_the_new_path_of_renames() {
+        let stats = super::parse_numstat(
+            "1\t2\tsrc/{old => new}/lib.rs\n0\t0\ta.txt => b.txt\n3\t0\t{ => sub}/c.rs\n",
+        );
+        let paths: Vec<&str> = stats.iter().map(|(path, _, _)| path.as_str()).collect();
+        assert_eq!(paths, vec!["src/new/lib.rs", "b.txt", "sub/c.rs"]);
+    }
+
+    #[test]
+    fn day_scope_selects_commits_of_the_window() {
+        use super::LogScope;
+        use std::process::Command;
+
+        let dir = std::env::temp_dir().join(format!("git-ai-log-scope-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str], days_ago: i64| {
+            let date = chrono::Utc::now() - chrono::Duration::days(days_ago);
+            let date = format!("{} +0000", date.timestamp());
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", &date)
+                .env("GIT_COMMITTER_DATE", &date)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        git(&["init", "-q"], 0);
+        git(&["commit", "-q", "--allow-empty", "-m", "old"], 40);
+        git(&["commit", "-q", "--allow-empty", "-m", "recent"], 2);
+
+        let mut args = vec!["log".to_string(), "--format=%s".to_string()];
+        args.extend(LogScope::Days(30).log_args());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        assert_eq!(git(&args, 0).trim(), "recent");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,304 @@
+//! In-process implementations of the hot `GitManager` queries on top of
+//! libgit2. Every function returns `None` when the native path cannot answer
+//! (backend disabled, repository not openable, unsupported state) so the
+//! caller falls back to running `git`.
+
+use chrono::{FixedOffset, TimeZone};
+use git2::{Commit, Diff, DiffFindOptions, DiffFormat, DiffOptions, Repository, Sort};
+
+/// Env var that forces the subprocess backend (`GIT_AI_GIT_BACKEND=cli`)
+pub const BACKEND_ENV: &str = "GIT_AI_GIT_BACKEND";
+
+/// Open the repository for the current directory, honouring GIT_DIR & co.
+fn open() -> Option<Repository> {
+    if std::env::var(BACKEND_ENV).is_ok_and(|v| v.eq_ignore_ascii_case("cli")) {
+        return None;
+    }
+    Repository::open_from_env().ok()
+}
+
+/// Run a query against the repository, discarding libgit2 errors
+fn with_repo<T>(query: impl FnOnce(&Repository) -> Result<T, git2::Error>) -> Option<T> {
+    let repo = open()?;
+    query(&repo).ok()
+}
+
+/// HEAD tree against the index, with rename detection like `git diff --cached`
+fn staged(repo: &Repository) -> Result<Diff<'_>, git2::Error> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e),
+    };
+
+    let mut diff =
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut DiffOptions::new()))?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+    Ok(diff)
+}
+
+/// Path git reports for a delta (the new side, except for deletions)
+fn delta_path(delta: &git2::DiffDelta) -> Option<String> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+}
+
+/// Equivalent of `git diff --cached`
+pub fn staged_diff() -> Option<String> {
+    with_repo(staged_diff_in)
+}
+
+fn staged_diff_in(repo: &Repository) -> Result<String, git2::Error> {
+    let diff = staged(repo)?;
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })?;
+    Ok(String::from_utf8_lossy(&patch).to_string())
+}
+
+/// Equivalent of `git diff --cached --name-only`
+pub fn staged_files() -> Option<Vec<String>> {
+    with_repo(staged_files_in)
+}
+
+fn staged_files_in(repo: &Repository) -> Result<Vec<String>, git2::Error> {
+    Ok(staged(repo)?
+        .deltas()
+        .filter_map(|d| delta_path(&d))
+        .collect())
+}
+
+/// Equivalent of `git diff --cached --numstat` (binary files count as 0/0)
+pub fn staged_file_stats() -> Option<Vec<(String, u32, u32)>> {
+    with_repo(staged_file_stats_in)
+}
+
+fn staged_file_stats_in(repo: &Repository) -> Result<Vec<(String, u32, u32)>, git2::Error> {
+    let diff = staged(repo)?;
+    let mut stats = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta_path(&delta) else {
+            continue;
+        };
+        let (insertions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) if !delta.flags().is_binary() => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions as u32, deletions as u32)
+            }
+            _ => (0, 0),
+        };
+        stats.push((path, insertions, deletions));
+    }
+    Ok(stats)
+}
+
+/// Equivalent of `git symbolic-ref --short HEAD`; `Some(None)` when detached.
+/// Works on an unborn branch, where HEAD names a branch with no commits yet.
+pub fn current_branch() -> Option<Option<String>> {
+    with_repo(current_branch_in)
+}
+
+fn current_branch_in(repo: &Repository) -> Result<Option<String>, git2::Error> {
+    let head = repo.find_reference("HEAD")?;
+    let Some(target) = head.symbolic_target() else {
+        return Ok(None);
+    };
+    Ok(Some(
+        target
+            .strip_prefix("refs/heads/")
+            .unwrap_or(target)
+            .to_string(),
+    ))
+}
+
+/// Equivalent of `git rev-parse --verify <rev>`
+pub fn rev_parse(rev: &str) -> Option<String> {
+    with_repo(|repo| Ok(repo.revparse_single(rev)?.id().to_string()))
+}
+
+/// Equivalent of `git merge-base --is-ancestor <ancestor> <descendant>`
+pub fn is_ancestor(ancestor: &str, descendant: &str) -> Option<bool> {
+    with_repo(|repo| {
+        let ancestor = repo.revparse_single(ancestor)?.peel_to_commit()?.id();
+        let descendant = repo.revparse_single(descendant)?.peel_to_commit()?.id();
+        Ok(ancestor == descendant || repo.graph_descendant_of(descendant, ancestor)?)
+    })
+}
+
+/// Equivalent of `git describe --tags --abbrev=0`; no tags yields `None`
+/// so the subprocess path decides between "no tags" and a real error
+pub fn latest_tag() -> Option<String> {
+    with_repo(latest_tag_in)
+}
+
+fn latest_tag_in(repo: &Repository) -> Result<String, git2::Error> {
+    let describe = repo.describe(git2::DescribeOptions::new().describe_tags())?;
+    describe.format(Some(git2::DescribeFormatOptions::new().abbreviated_size(0)))
+}
+
+/// `%h %cd %s` with `--date=short`, as used for every log listing
+fn format_commit(commit: &Commit) -> Option<String> {
+    let when = commit.committer().when();
+    let date = FixedOffset::east_opt(when.offset_minutes() * 60)?
+        .timestamp_opt(when.seconds(), 0)
+        .single()?
+        .format("%Y-%m-%d");
+    let short_id = commit.as_object().short_id().ok()?;
+    Some(format!(
+        "{} {} {}",
+        short_id.as_str()?,
+        date,
+        commit.summary().unwrap_or("")
+    ))
+}
+
+/// Walk history newest first, like `git log`
+fn log(
+    repo: &Repository,
+    setup: impl FnOnce(&mut git2::Revwalk) -> Result<(), git2::Error>,
+    limit: Option<usize>,
+    since: Option<i64>,
+) -> Result<Vec<String>, git2::Error> {
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TIME)?;
+    setup(&mut walk)?;
+
+    let mut lines = Vec::new();
+    for oid in walk {
+        if limit.is_some_and(|limit| lines.len() >= limit) {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        if since.is_some_and(|since| commit.committer().when().seconds() < since) {
+            continue;
+        }
+        let line =
+            format_commit(&commit).ok_or_else(|| git2::Error::from_str("cannot format commit"))?;
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+/// Equivalent of `git log -<count>`
+pub fn recent_commits(count: usize) -> Option<Vec<String>> {
+    with_repo(|repo| log(repo, |walk| walk.push_head(), Some(count), None))
+}
+
+/// Equivalent of `git log --since="<days> days ago"`
+pub fn commits_since(days: usize) -> Option<Vec<String>> {
+    let since = chrono::Utc::now().timestamp() - days as i64 * 86_400;
+    with_repo(|repo| log(repo, |walk| walk.push_head(), None, Some(since)))
+}
+
+/// Equivalent of `git log <from>..<to>`
+pub fn commits_between(from_ref: &str, to_ref: &str) -> Option<Vec<String>> {
+    with_repo(|repo| commits_between_in(repo, from_ref, to_ref))
+}
+
+fn commits_between_in(
+    repo: &Repository,
+    from_ref: &str,
+    to_ref: &str,
+) -> Result<Vec<String>, git2::Error> {
+    let range = format!("{}..{}", from_ref, to_ref);
+    log(repo, |walk| walk.push_range(&range), None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(str::to_string).collect()
+    }
+
+    /// Each native query answers exactly what the `git` command it replaces
+    /// prints, on the same repository
+    #[test]
+    fn matches_the_git_cli() {
+        let dir = std::env::temp_dir().join(format!("git-ai-native-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        let write = |name: &str, content: &str| std::fs::write(dir.join(name), content).unwrap();
+
+        write("a.txt", "one\ntwo\nthree\n");
+        write("old.txt", "moved\ncontent\nstays\nthe\nsame\n");
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "feat: first"]);
+        git(&dir, &["tag", "v1.0.0"]);
+        write("a.txt", "one\n2\nthree\nfour\n");
+        git(&dir, &["commit", "-q", "-am", "fix: second"]);
+
+        write("a.txt", "one\n2\n");
+        write("new.txt", "hello\n");
+        git(&dir, &["mv", "old.txt", "renamed.txt"]);
+        git(&dir, &["add", "."]);
+
+        let repo = Repository::open(&dir).unwrap();
+        assert_eq!(
+            staged_files_in(&repo).unwrap(),
+            lines(&git(&dir, &["diff", "--cached", "--name-only", "-M"]))
+        );
+        assert_eq!(
+            staged_file_stats_in(&repo).unwrap(),
+            crate::utils::git::parse_numstat(&git(&dir, &["diff", "--cached", "--numstat", "-M"]))
+        );
+        let hunks = |diff: &str| -> Vec<String> {
+            diff.lines()
+                .filter(|line| !line.starts_with("index "))
+                .map(str::to_string)
+                .collect()
+        };
+        assert_eq!(
+            hunks(&staged_diff_in(&repo).unwrap()),
+            hunks(&git(&dir, &["diff", "--cached", "-M"]))
+        );
+
+        assert_eq!(
+            current_branch_in(&repo).unwrap().as_deref(),
+            Some(git(&dir, &["symbolic-ref", "--short", "HEAD"]).trim())
+        );
+        assert_eq!(
+            latest_tag_in(&repo).unwrap(),
+            git(&dir, &["describe", "--tags", "--abbrev=0"]).trim()
+        );
+        let log_format = ["--format=%h %cd %s", "--date=short"];
+        assert_eq!(
+            log(&repo, |walk| walk.push_head(), Some(5), None).unwrap(),
+            lines(&git(&dir, &["log", "-5", log_format[0], log_format[1]]))
+        );
+        assert_eq!(
+            commits_between_in(&repo, "v1.0.0", "HEAD").unwrap(),
+            lines(&git(
+                &dir,
+                &["log", "v1.0.0..HEAD", log_format[0], log_format[1]]
+            ))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+//! GitHub access shared by `release` and `pr`: the `gh` CLI when installed,
+//! otherwise the REST API with GITHUB_TOKEN / GH_TOKEN.
+
+use crate::error::{GitAiError, Result};
+use crate::utils::GitManager;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const API_URL: &str = "https://api.github.com";
+
+/// Whether the GitHub CLI is installed
+pub fn gh_available() -> bool {
+    Command::new("gh")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Token of the account `gh` is logged in to
+pub fn gh_auth_token() -> Option<String> {
+    let output = Command::new("gh").args(["auth", "token"]).output().ok()?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
+
+/// Run `gh` with `input` on stdin; returns its trimmed stdout
+pub fn run_gh(args: &[&str], input: &str) -> Result<String> {
+    let mut child = Command::new("gh")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitAiError::Other(format!("Failed to run gh: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitAiError::Other(format!(
+            "gh {} failed: {}",
+            args.iter().take(2).copied().collect::<Vec<_>>().join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// REST client for the repository behind `remote`
+pub struct GitHubApi {
+    token: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+impl GitHubApi {
+    /// `what` completes "Install the GitHub CLI (gh) or set GITHUB_TOKEN to ..."
+    pub fn for_remote(remote: &str, what: &str) -> Result<Self> {
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .map_err(|_| {
+                GitAiError::Config(format!(
+                    "Install the GitHub CLI (gh) or set GITHUB_TOKEN to {}",
+                    what
+                ))
+            })?;
+        let remote_url = GitManager::get_remote_url(remote)?.ok_or_else(|| {
+            GitAiError::InvalidArgument(format!("Remote '{}' is not configured", remote))
+        })?;
+        let (owner, repo) = parse_github_repo(&remote_url).ok_or_else(|| {
+            GitAiError::InvalidArgument(format!("{} is not a GitHub repository", remote_url))
+        })?;
+        Ok(Self { token, owner, repo })
+    }
+
+    /// Send a request to `/repos/<owner>/<repo>/<path>` and return the JSON
+    /// response, or the error message GitHub gave
+    pub async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let url = format!("{}/repos/{}/{}/{}", API_URL, self.owner, self.repo, path);
+        let mut request = reqwest::Client::new()
+            .request(method, url)
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", concat!("git-ai/", env!("CARGO_PKG_VERSION")));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| GitAiError::Http(format!("GitHub request failed: {}", e)))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| GitAiError::Http(format!("Invalid GitHub response: {}", e)))?;
+        if !status.is_success() {
+            return Err(GitAiError::Http(format!(
+                "GitHub returned {}: {}",
+                status,
+                body["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(body)
+    }
+}
+
+/// `owner/repo` of a GitHub remote (SSH or HTTPS form)
+pub fn parse_github_repo(url: &str) -> Option<(String, String)> {
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))?;
+    let (owner, repo) = path.trim_end_matches('/').split_once('/')?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_github_repo;
+
+    #[test]
+    fn parses_github_remotes() {
+        let expected = Some(("dongowu".to_string(), "git-ai-cli".to_string()));
+        assert_eq!(
+            parse_github_repo("git@github.com:dongowu/git-ai-cli.git"),
+            expected
+        );
+        assert_eq!(
+            parse_github_repo("https://github.com/dongowu/git-ai-cli"),
+            expected
+        );
+        assert_eq!(
+            parse_github_repo("https://gitlab.com/dongowu/git-ai-cli"),
+            None
+        );
+    }
+}
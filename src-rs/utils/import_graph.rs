@@ -0,0 +1,655 @@
+use crate::error::Result;
+use crate::utils::GitManager;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Bump when the parsers change so stale caches are rebuilt
+const CACHE_VERSION: u32 = 1;
+
+/// Files larger than this are not scanned for imports
+const MAX_SOURCE_BYTES: u64 = 1024 * 1024;
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "py", "go", "java",
+];
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Raw import specifiers of one file, valid while its blob is unchanged
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    blob: String,
+    imports: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GraphCache {
+    version: u32,
+    files: BTreeMap<String, CachedFile>,
+}
+
+/// Which tracked files import which, from per-language `use`/`import`
+/// parsing. Specifiers are cached on disk by blob id; resolving them to paths
+/// happens per query so it always matches the current file list.
+#[derive(Debug, Default)]
+pub struct ImportGraph {
+    imports: BTreeMap<String, Vec<String>>,
+    tracked: HashSet<String>,
+    /// Java sources by class name, for `import com.acme.Foo;`
+    java_classes: HashMap<String, Vec<String>>,
+    /// `module` line of the root go.mod
+    go_module: Option<String>,
+}
+
+impl ImportGraph {
+    /// Build the graph for the current repository, reparsing only files whose
+    /// blob changed since the cache under `.git/git-ai/` was written. Imports
+    /// are parsed from the staged blob, the same content the cache is keyed by.
+    pub fn load() -> Result<Self> {
+        let root = GitManager::get_repo_root()?;
+        let cache_path = GitManager::get_git_dir()?
+            .join("git-ai")
+            .join("import-graph.json");
+        let mut cache: GraphCache = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .filter(|cache: &GraphCache| cache.version == CACHE_VERSION)
+            .unwrap_or_default();
+
+        let entries = GitManager::get_index_entries()?;
+        let mut files = BTreeMap::new();
+        let mut stale = Vec::new();
+        for (blob, path) in &entries {
+            if !is_source(path) {
+                continue;
+            }
+            match cache.files.remove(path) {
+                Some(entry) if entry.blob == *blob => {
+                    files.insert(path.clone(), entry);
+                }
+                _ => stale.push((blob, path)),
+            }
+        }
+        // Anything left in the old cache was deleted or renamed
+        let changed = !stale.is_empty() || !cache.files.is_empty();
+
+        let ids: Vec<String> = stale.iter().map(|(blob, _)| blob.to_string()).collect();
+        let contents = if ids.is_empty() {
+            HashMap::new()
+        } else {
+            GitManager::read_blobs(&ids)?
+        };
+        for (blob, path) in stale {
+            let imports = contents
+                .get(blob)
+                .filter(|content| content.len() as u64 <= MAX_SOURCE_BYTES)
+                .map(|content| parse_imports(path, &String::from_utf8_lossy(content)))
+                .unwrap_or_default();
+            files.insert(
+                path.clone(),
+                CachedFile {
+                    blob: blob.clone(),
+                    imports,
+                },
+            );
+        }
+
+        if changed {
+            let cache = GraphCache {
+                version: CACHE_VERSION,
+                files,
+            };
+            if let Some(parent) = cache_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&cache_path, serde_json::to_string(&cache)?)?;
+            files = cache.files;
+        }
+
+        let go_module = entries
+            .iter()
+            .any(|(_, path)| path == "go.mod")
+            .then(|| fs::read_to_string(root.join("go.mod")).ok())
+            .flatten()
+            .and_then(|content| parse_go_module(&content));
+
+        Ok(Self::new(
+            entries.into_iter().map(|(_, path)| path).collect(),
+            files
+                .into_iter()
+                .map(|(path, entry)| (path, entry.imports))
+                .collect(),
+            go_module,
+        ))
+    }
+
+    fn new(
+        tracked: HashSet<String>,
+        imports: BTreeMap<String, Vec<String>>,
+        go_module: Option<String>,
+    ) -> Self {
+        let mut java_classes: HashMap<String, Vec<String>> = HashMap::new();
+        for path in tracked.iter().filter(|path| path.ends_with(".java")) {
+            if let Some(stem) = Path::new(path).file_stem().and_then(|s| s.to_str()) {
+                java_classes
+                    .entry(stem.to_string())
+                    .or_default()
+                    .push(path.clone());
+            }
+        }
+        Self {
+            imports,
+            tracked,
+            java_classes,
+            go_module,
+        }
+    }
+
+    /// Graph over in-memory sources, without go.mod
+    #[cfg(test)]
+    pub fn from_sources(sources: &[(&str, &str)]) -> Self {
+        Self::new(
+            sources.iter().map(|(path, _)| path.to_string()).collect(),
+            sources
+                .iter()
+                .filter(|(path, _)| is_source(path))
+                .map(|(path, content)| (path.to_string(), parse_imports(path, content)))
+                .collect(),
+            None,
+        )
+    }
+
+    /// Tracked source files, sorted
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.imports.keys().map(String::as_str)
+    }
+
+    /// Files that directly import one of `changed`, each with the changed
+    /// files it imports. The changed files themselves are left out.
+    pub fn dependents_of(&self, changed: &[String]) -> Vec<(String, Vec<String>)> {
+        let changed_set: HashSet<&str> = changed.iter().map(String::as_str).collect();
+        let mut dependents = Vec::new();
+
+        for (path, specs) in &self.imports {
+            if changed_set.contains(path.as_str()) {
+                continue;
+            }
+            let mut hits: Vec<String> = Vec::new();
+            for target in specs.iter().filter_map(|spec| self.resolve(path, spec)) {
+                for file in changed {
+                    let matches = match target.strip_suffix('/') {
+                        // Go packages and Java wildcards import a directory
+                        Some(dir) => parent_dir(file) == dir,
+                        None => target == *file,
+                    };
+                    if matches && !hits.contains(file) {
+                        hits.push(file.clone());
+                    }
+                }
+            }
+            if !hits.is_empty() {
+                dependents.push((path.clone(), hits));
+            }
+        }
+        dependents
+    }
+
+    /// Path (or `dir/` for package imports) a specifier points at
+    fn resolve(&self, from: &str, spec: &str) -> Option<String> {
+        match extension(from) {
+            "rs" => self.resolve_rust(from, spec),
+            "py" => self.resolve_python(from, spec),
+            "go" => {
+                let module = self.go_module.as_deref()?;
+                let dir = if spec == module {
+                    ""
+                } else {
+                    spec.strip_prefix(module)?.strip_prefix('/')?
+                };
+                Some(format!("{}/", dir))
+            }
+            "java" => self.resolve_java(spec),
+            _ => self.resolve_js(from, spec),
+        }
+    }
+
+    fn resolve_rust(&self, from: &str, spec: &str) -> Option<String> {
+        let mut segments: Vec<&str> = spec.split("::").collect();
+        let base = match segments.first().copied()? {
+            "crate" => {
+                segments.remove(0);
+                self.rust_crate_root(from)?
+            }
+            "super" => {
+                let mut dir = rust_module_dir(from);
+                while segments.first() == Some(&"super") {
+                    segments.remove(0);
+                    dir = parent_dir(&dir).to_string();
+                }
+                dir
+            }
+            "self" => {
+                segments.remove(0);
+                rust_module_dir(from)
+            }
+            // 2018-style relative path to a child module
+            _ => rust_module_dir(from),
+        };
+
+        // Longest prefix that names a module file wins
+        (1..=segments.len()).rev().find_map(|len| {
+            let module = join(&base, &segments[..len].join("/"));
+            [format!("{}.rs", module), format!("{}/mod.rs", module)]
+                .into_iter()
+                .find(|candidate| self.tracked.contains(candidate))
+        })
+    }
+
+    /// Directory holding the `main.rs` / `lib.rs` above `from`
+    fn rust_crate_root(&self, from: &str) -> Option<String> {
+        let mut dir = parent_dir(from);
+        loop {
+            if ["main.rs", "lib.rs"]
+                .iter()
+                .any(|root| self.tracked.contains(&join(dir, root)))
+            {
+                return Some(dir.to_string());
+            }
+            if dir.is_empty() {
+                return None;
+            }
+            dir = parent_dir(dir);
+        }
+    }
+
+    fn resolve_python(&self, from: &str, spec: &str) -> Option<String> {
+        let dots = spec.len() - spec.trim_start_matches('.').len();
+        let module = spec[dots..].replace('.', "/");
+        let bases: Vec<String> = if dots > 0 {
+            let mut dir = parent_dir(from);
+            for _ in 1..dots {
+                dir = parent_dir(dir);
+            }
+            vec![dir.to_string()]
+        } else {
+            vec![String::new(), "src".to_string()]
+        };
+
+        bases.iter().find_map(|base| {
+            let module = join(base, &module);
+            [format!("{}.py", module), join(&module, "__init__.py")]
+                .into_iter()
+                .find(|candidate| self.tracked.contains(candidate))
+        })
+    }
+
+    fn resolve_java(&self, spec: &str) -> Option<String> {
+        let segments: Vec<&str> = spec.split('.').collect();
+        if segments.last() == Some(&"*") {
+            let package = segments[..segments.len() - 1].join("/");
+            return self
+                .tracked
+                .iter()
+                .filter(|path| path.ends_with(".java"))
+                .map(|path| parent_dir(path))
+                .find(|dir| *dir == package || dir.ends_with(&format!("/{}", package)))
+                .map(|dir| format!("{}/", dir));
+        }
+        // `import static a.B.member` names the class one segment earlier
+        (1..=segments.len()).rev().find_map(|len| {
+            let class = segments[len - 1];
+            let suffix = format!("{}.java", segments[..len].join("/"));
+            self.java_classes
+                .get(class)?
+                .iter()
+                .find(|path| path.ends_with(&suffix))
+                .cloned()
+        })
+    }
+
+    fn resolve_js(&self, from: &str, spec: &str) -> Option<String> {
+        if !spec.starts_with('.') {
+            return None;
+        }
+        let target = normalize(&join(parent_dir(from), spec));
+        // TS sources are imported with the extension they compile to
+        let stem = Path::new(&target)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| JS_EXTENSIONS.contains(ext))
+            .map(|ext| target[..target.len() - ext.len() - 1].to_string());
+
+        let mut candidates = vec![target.clone()];
+        for base in std::iter::once(&target).chain(stem.as_ref()) {
+            for ext in JS_EXTENSIONS {
+                candidates.push(format!("{}.{}", base, ext));
+                candidates.push(format!("{}/index.{}", base, ext));
+            }
+        }
+        candidates
+            .into_iter()
+            .find(|candidate| self.tracked.contains(candidate))
+    }
+}
+
+fn is_source(path: &str) -> bool {
+    SOURCE_EXTENSIONS.contains(&extension(path))
+}
+
+fn extension(path: &str) -> &str {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+/// Collapse `.` and `..` segments of a repo-relative path
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Directory whose files are the children of the module defined by `path`
+fn rust_module_dir(path: &str) -> String {
+    let dir = parent_dir(path);
+    match Path::new(path).file_stem().and_then(|s| s.to_str()) {
+        Some("mod" | "main" | "lib") | None => dir.to_string(),
+        Some(stem) => join(dir, stem),
+    }
+}
+
+fn parse_go_module(go_mod: &str) -> Option<String> {
+    go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().trim_matches('"').to_string())
+}
+
+/// Import specifiers of one source file, in the syntax of its language
+fn parse_imports(path: &str, content: &str) -> Vec<String> {
+    let mut imports = match extension(path) {
+        "rs" => parse_rust_imports(content),
+        "py" => parse_python_imports(content),
+        "go" => parse_go_imports(content),
+        "java" => parse_java_imports(content),
+        _ => parse_js_imports(content),
+    };
+    imports.sort();
+    imports.dedup();
+    imports
+}
+
+fn parse_rust_imports(content: &str) -> Vec<String> {
+    static RE_USE: OnceLock<Regex> = OnceLock::new();
+    let use_regex = RE_USE.get_or_init(|| {
+        Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+([^;]+);").expect("valid regex")
+    });
+
+    let mut imports = Vec::new();
+    for cap in use_regex.captures_iter(content) {
+        let tree: String = cap[1].chars().filter(|c| !c.is_whitespace()).collect();
+        let (prefix, group) = match tree.split_once('{') {
+            Some((prefix, group)) => (prefix.trim_end_matches("::"), Some(group)),
+            None => (tree.as_str(), None),
+        };
+        if prefix.is_empty() || prefix.starts_with("std::") {
+            continue;
+        }
+        imports.push(prefix.to_string());
+        // `crate::utils::{git, ai::AIClient}`: each item may be a submodule
+        for item in group.into_iter().flat_map(|group| group.split(',')) {
+            let item = item
+                .split("::")
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|c| c == '{' || c == '}');
+            if !item.is_empty() && item != "self" {
+                imports.push(format!("{}::{}", prefix, item));
+            }
+        }
+    }
+    imports
+}
+
+fn parse_js_imports(content: &str) -> Vec<String> {
+    static RE_IMPORT: OnceLock<Regex> = OnceLock::new();
+    let import_regex = RE_IMPORT.get_or_init(|| {
+        Regex::new(r#"(?:\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)['"]([^'"]+)['"]"#)
+            .expect("valid regex")
+    });
+    import_regex
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .filter(|spec| spec.starts_with('.'))
+        .collect()
+}
+
+fn parse_python_imports(content: &str) -> Vec<String> {
+    static RE_FROM: OnceLock<Regex> = OnceLock::new();
+    static RE_IMPORT: OnceLock<Regex> = OnceLock::new();
+    let from_regex = RE_FROM.get_or_init(|| {
+        Regex::new(r"(?m)^\s*from\s+(\.*[\w.]*)\s+import\s+\(?([\w\s,.*]+)").expect("valid regex")
+    });
+    let import_regex =
+        RE_IMPORT.get_or_init(|| Regex::new(r"(?m)^\s*import\s+([\w., ]+)").expect("valid regex"));
+
+    let mut imports = Vec::new();
+    for cap in from_regex.captures_iter(content) {
+        let module = &cap[1];
+        if !module.trim_start_matches('.').is_empty() {
+            imports.push(module.to_string());
+        }
+        // `from pkg import submodule`
+        for name in cap[2].split(',') {
+            let name = name.split_whitespace().next().unwrap_or_default();
+            if !name.is_empty() && name != "*" {
+                let separator = if module.ends_with('.') { "" } else { "." };
+                imports.push(format!("{}{}{}", module, separator, name));
+            }
+        }
+    }
+    for cap in import_regex.captures_iter(content) {
+        for module in cap[1].split(',') {
+            if let Some(module) = module.split_whitespace().next() {
+                imports.push(module.to_string());
+            }
+        }
+    }
+    imports
+}
+
+fn parse_go_imports(content: &str) -> Vec<String> {
+    static RE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    static RE_SINGLE: OnceLock<Regex> = OnceLock::new();
+    static RE_PATH: OnceLock<Regex> = OnceLock::new();
+    let block_regex =
+        RE_BLOCK.get_or_init(|| Regex::new(r"(?s)\bimport\s*\(([^)]*)\)").expect("valid regex"));
+    let single_regex = RE_SINGLE.get_or_init(|| {
+        Regex::new(r#"(?m)^\s*import\s+(?:[\w.]+\s+)?"([^"]+)""#).expect("valid regex")
+    });
+    let path_regex = RE_PATH.get_or_init(|| Regex::new(r#""([^"]+)""#).expect("valid regex"));
+
+    let mut imports: Vec<String> = single_regex
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    for block in block_regex.captures_iter(content) {
+        imports.extend(
+            path_regex
+                .captures_iter(&block[1])
+                .map(|cap| cap[1].to_string()),
+        );
+    }
+    imports
+}
+
+fn parse_java_imports(content: &str) -> Vec<String> {
+    static RE_IMPORT: OnceLock<Regex> = OnceLock::new();
+    let import_regex = RE_IMPORT.get_or_init(|| {
+        Regex::new(r"(?m)^\s*import\s+(?:static\s+)?([\w.]+(?:\.\*)?)\s*;").expect("valid regex")
+    });
+    import_regex
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(sources: &[(&str, &str)], go_module: Option<&str>) -> ImportGraph {
+        ImportGraph::new(
+            sources.iter().map(|(path, _)| path.to_string()).collect(),
+            sources
+                .iter()
+                .filter(|(path, _)| is_source(path))
+                .map(|(path, content)| (path.to_string(), parse_imports(path, content)))
+                .collect(),
+            go_module.map(str::to_string),
+        )
+    }
+
+    fn dependents(graph: &ImportGraph, changed: &str) -> Vec<String> {
+        graph
+            .dependents_of(&[changed.to_string()])
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    #[test]
+    fn finds_rust_and_js_dependents() {
+        let rust = graph(
+            &[
+                (
+                    "src/main.rs",
+                    "mod utils;\nuse crate::utils::git::GitManager;\n",
+                ),
+                (
+                    "src/utils/mod.rs",
+                    "pub mod git;\npub use git::GitManager;\n",
+                ),
+                ("src/utils/git.rs", "use std::process::Command;\n"),
+                (
+                    "src/commands/report.rs",
+                    "use crate::utils::{git, ai::AIClient};\nuse super::audit;\n",
+                ),
+                ("src/commands/audit.rs", ""),
+            ],
+            None,
+        );
+        assert_eq!(
+            dependents(&rust, "src/utils/git.rs"),
+            vec!["src/commands/report.rs", "src/main.rs", "src/utils/mod.rs"]
+        );
+        assert_eq!(
+            dependents(&rust, "src/commands/audit.rs"),
+            vec!["src/commands/report.rs"]
+        );
+
+        let js = graph(
+            &[
+                (
+                    "web/app.ts",
+                    "import { api } from './lib/api.js';\nconst x = require('../x');\n",
+                ),
+                ("web/lib/api.ts", "export const api = 1;\n"),
+                (
+                    "web/page.tsx",
+                    "import Api from \"./lib/api\";\nimport React from 'react';\n",
+                ),
+            ],
+            None,
+        );
+        assert_eq!(
+            dependents(&js, "web/lib/api.ts"),
+            vec!["web/app.ts", "web/page.tsx"]
+        );
+    }
+
+    #[test]
+    fn finds_python_go_and_java_dependents() {
+        let python = graph(
+            &[
+                (
+                    "app/main.py",
+                    "from app.services import billing\nimport app.models\n",
+                ),
+                ("app/services/billing.py", "from ..models import Invoice\n"),
+                ("app/models.py", ""),
+            ],
+            None,
+        );
+        assert_eq!(
+            dependents(&python, "app/models.py"),
+            vec!["app/main.py", "app/services/billing.py"]
+        );
+
+        let go = graph(
+            &[
+                ("go.mod", ""),
+                (
+                    "cmd/main.go",
+                    "import (\n\t\"fmt\"\n\tapi \"example.com/svc/internal/api\"\n)\n",
+                ),
+                ("internal/api/server.go", "package api\n"),
+            ],
+            Some("example.com/svc"),
+        );
+        assert_eq!(
+            dependents(&go, "internal/api/server.go"),
+            vec!["cmd/main.go"]
+        );
+
+        let java = graph(
+            &[
+                (
+                    "src/main/java/com/acme/App.java",
+                    "import com.acme.auth.Token;\n",
+                ),
+                (
+                    "src/main/java/com/acme/Job.java",
+                    "import static com.acme.auth.Token.parse;\n",
+                ),
+                (
+                    "src/main/java/com/acme/auth/Token.java",
+                    "package com.acme.auth;\n",
+                ),
+            ],
+            None,
+        );
+        assert_eq!(
+            dependents(&java, "src/main/java/com/acme/auth/Token.java"),
+            vec![
+                "src/main/java/com/acme/App.java",
+                "src/main/java/com/acme/Job.java"
+            ]
+        );
+    }
+}
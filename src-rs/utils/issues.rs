@@ -0,0 +1,329 @@
+//! Issue context: when the branch names an issue (`feature/1234-login`,
+//! `ABC-42-fix-totals`), fetch its title and description from GitHub, Jira
+//! or Linear so the message reflects the requirement, not only the diff.
+//!
+//! Credentials come from the environment only: GITHUB_TOKEN / GH_TOKEN (or
+//! an authenticated `gh`), JIRA_BASE_URL with JIRA_EMAIL + JIRA_API_TOKEN or
+//! a JIRA_TOKEN personal access token, and LINEAR_API_KEY. A tracker without
+//! credentials is skipped, and nothing is fetched unless `issue_context` is
+//! turned on.
+
+use crate::error::{GitAiError, Result};
+use crate::types::AIConfig;
+use crate::utils::github::{self, GitHubApi};
+use crate::utils::GitManager;
+use regex::Regex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Fetching must not hold up a commit for long
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Description characters added to the prompt
+const MAX_DESCRIPTION_CHARS: usize = 1500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tracker {
+    GitHub,
+    Jira,
+    Linear,
+}
+
+impl Tracker {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "github" => Some(Self::GitHub),
+            "jira" => Some(Self::Jira),
+            "linear" => Some(Self::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// Issue referenced by a branch name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    /// `1234` for GitHub, `ABC-42` for Jira and Linear
+    pub id: String,
+    /// `None` when a key could belong to either Jira or Linear
+    pub tracker: Option<Tracker>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+}
+
+impl Issue {
+    /// Section appended to the user prompt
+    pub fn prompt_section(&self) -> String {
+        let mut section = format!("Linked issue {}: {}", self.id, self.title);
+        let description = self.description.trim();
+        if !description.is_empty() {
+            let description: String = description.chars().take(MAX_DESCRIPTION_CHARS).collect();
+            section.push_str(&format!("\n{}", description));
+        }
+        section.push_str(
+            "\n(Use the issue to explain why the change is made; describe only what the diff actually does.)",
+        );
+        section
+    }
+}
+
+// The issue leads the branch name or follows a single `type/` prefix, so
+// a number deeper in the name (`deps/bump/serde-2`) is not taken for one
+fn issue_key() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^(?:[a-z]+/)?([a-z][a-z0-9]{1,9}-\d+)(?:[/_-]|$)").unwrap())
+}
+
+fn issue_number() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^(?:[a-z]+/)?(?:gh-|issues?-|#)?(\d+)(?:[/_-]|$)").unwrap())
+}
+
+/// Issue named by `branch`, using `issue_pattern` (first capture group) and
+/// `issue_tracker` from the config when set
+pub fn parse_branch(branch: &str, config: &AIConfig) -> Option<IssueRef> {
+    let configured = config.issue_tracker.as_deref().and_then(Tracker::parse);
+    if let Some(pattern) = config.issue_pattern.as_deref().filter(|p| !p.is_empty()) {
+        let id = Regex::new(pattern)
+            .ok()?
+            .captures(branch)?
+            .get(1)?
+            .as_str()
+            .to_uppercase();
+        let tracker = configured.or_else(|| {
+            id.chars()
+                .all(|c| c.is_ascii_digit())
+                .then_some(Tracker::GitHub)
+        });
+        return Some(IssueRef { id, tracker });
+    }
+
+    if configured != Some(Tracker::GitHub) {
+        let key = issue_key()
+            .captures(branch)
+            .map(|caps| caps[1].to_uppercase())
+            // `gh-56` and `issue-56` are GitHub numbers
+            .filter(|key| !key.starts_with("GH-") && !key.starts_with("ISSUE"));
+        if let Some(id) = key {
+            return Some(IssueRef {
+                id,
+                tracker: configured,
+            });
+        }
+    }
+    if matches!(configured, None | Some(Tracker::GitHub)) {
+        if let Some(caps) = issue_number().captures(branch) {
+            return Some(IssueRef {
+                id: caps[1].to_string(),
+                tracker: Some(Tracker::GitHub),
+            });
+        }
+    }
+    None
+}
+
+/// The issue behind the current branch, or `None` when the branch names
+/// none, no tracker is configured for it, or `issue_context` is off. Errors
+/// are for the caller to report; they should not stop a commit.
+pub async fn fetch_for_branch(config: &AIConfig, branch: Option<&str>) -> Result<Option<Issue>> {
+    if !config.issue_context.unwrap_or(false) {
+        return Ok(None);
+    }
+    let Some(issue) = branch.and_then(|branch| parse_branch(branch, config)) else {
+        return Ok(None);
+    };
+
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let tracker = match issue.tracker {
+        Some(tracker) => tracker,
+        // An `ABC-42` key: whichever of Jira and Linear is set up
+        None if env("JIRA_BASE_URL").is_some() => Tracker::Jira,
+        None if env("LINEAR_API_KEY").is_some() => Tracker::Linear,
+        None => return Ok(None),
+    };
+
+    let fetch = async {
+        match tracker {
+            Tracker::GitHub => fetch_github(&issue.id).await,
+            Tracker::Jira => fetch_jira(&issue.id).await,
+            Tracker::Linear => fetch_linear(&issue.id).await,
+        }
+    };
+    tokio::time::timeout(FETCH_TIMEOUT, fetch)
+        .await
+        .map_err(|_| {
+            GitAiError::Http(format!(
+                "Timed out fetching issue {} after {}s",
+                issue.id,
+                FETCH_TIMEOUT.as_secs()
+            ))
+        })?
+}
+
+async fn fetch_github(number: &str) -> Result<Option<Issue>> {
+    let is_github = GitManager::get_remote_url("origin")?
+        .and_then(|url| github::parse_github_repo(&url))
+        .is_some();
+    if !is_github {
+        return Ok(None);
+    }
+    let has_token = std::env::var("GITHUB_TOKEN").is_ok() || std::env::var("GH_TOKEN").is_ok();
+    if !has_token && github::gh_available() {
+        let output = Command::new("gh")
+            .args(["issue", "view", number, "--json", "title,body"])
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(GitAiError::Other(format!(
+                "gh issue view {} failed: {}",
+                number,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let issue: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        return Ok(Some(to_issue(
+            format!("#{}", number),
+            &issue["title"],
+            &issue["body"],
+        )));
+    }
+    if !has_token {
+        return Ok(None);
+    }
+
+    let api = GitHubApi::for_remote("origin", "fetch issues")?;
+    let issue = api
+        .request(reqwest::Method::GET, &format!("issues/{}", number), None)
+        .await?;
+    Ok(Some(to_issue(
+        format!("#{}", number),
+        &issue["title"],
+        &issue["body"],
+    )))
+}
+
+async fn fetch_jira(key: &str) -> Result<Option<Issue>> {
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let Some(base_url) = env("JIRA_BASE_URL") else {
+        return Ok(None);
+    };
+    let request = reqwest::Client::new().get(format!(
+        "{}/rest/api/2/issue/{}?fields=summary,description",
+        base_url.trim_end_matches('/'),
+        key
+    ));
+    let request = match (env("JIRA_EMAIL"), env("JIRA_API_TOKEN"), env("JIRA_TOKEN")) {
+        (Some(email), Some(token), _) => request.basic_auth(email, Some(token)),
+        (_, _, Some(token)) => request.bearer_auth(token),
+        _ => return Ok(None),
+    };
+
+    let issue = send_json(request, "Jira").await?;
+    Ok(Some(to_issue(
+        key.to_string(),
+        &issue["fields"]["summary"],
+        &issue["fields"]["description"],
+    )))
+}
+
+async fn fetch_linear(key: &str) -> Result<Option<Issue>> {
+    let Some(api_key) = std::env::var("LINEAR_API_KEY")
+        .ok()
+        .filter(|v| !v.is_empty())
+    else {
+        return Ok(None);
+    };
+    let request = reqwest::Client::new()
+        .post("https://api.linear.app/graphql")
+        .header("Authorization", api_key)
+        .json(&serde_json::json!({
+            "query": "query($id: String!) { issue(id: $id) { title description } }",
+            "variables": { "id": key },
+        }));
+
+    let response = send_json(request, "Linear").await?;
+    let issue = &response["data"]["issue"];
+    if issue.is_null() {
+        return Err(GitAiError::Http(format!("Linear has no issue {}", key)));
+    }
+    Ok(Some(to_issue(
+        key.to_string(),
+        &issue["title"],
+        &issue["description"],
+    )))
+}
+
+async fn send_json(request: reqwest::RequestBuilder, service: &str) -> Result<serde_json::Value> {
+    let response = request
+        .header("User-Agent", concat!("git-ai/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await
+        .map_err(|e| GitAiError::Http(format!("{} request failed: {}", service, e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(GitAiError::Http(format!("{} returned {}", service, status)));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Invalid {} response: {}", service, e)))
+}
+
+fn to_issue(id: String, title: &serde_json::Value, description: &serde_json::Value) -> Issue {
+    Issue {
+        id,
+        title: title.as_str().unwrap_or_default().trim().to_string(),
+        description: description.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_issues_in_branch_names() {
+        let config = AIConfig::default();
+        let parse = |branch: &str| parse_branch(branch, &config);
+        assert_eq!(
+            parse("feature/1234-add-login"),
+            Some(IssueRef {
+                id: "1234".to_string(),
+                tracker: Some(Tracker::GitHub)
+            })
+        );
+        assert_eq!(parse("fix/gh-56").unwrap().id, "56");
+        assert_eq!(
+            parse("feature/abc-42-fix-totals"),
+            Some(IssueRef {
+                id: "ABC-42".to_string(),
+                tracker: None
+            })
+        );
+        assert_eq!(parse("release/1.2"), None);
+        assert_eq!(parse("fix/add-login-2"), None);
+        assert_eq!(parse("main"), None);
+        assert_eq!(parse("deps/bump/serde-2"), None);
+        assert_eq!(parse("renovate/lodash/4-x"), None);
+
+        let config = AIConfig {
+            issue_tracker: Some("linear".to_string()),
+            issue_pattern: Some(r"^[^/]+/(eng-\d+)".to_string()),
+            ..AIConfig::default()
+        };
+        assert_eq!(
+            parse_branch("alice/eng-7-onboarding", &config),
+            Some(IssueRef {
+                id: "ENG-7".to_string(),
+                tracker: Some(Tracker::Linear)
+            })
+        );
+    }
+}
@@ -2,10 +2,62 @@ pub mod agent;
 pub mod agent_lite;
 pub mod agent_skills;
 pub mod ai;
+pub mod audit;
+pub mod ci;
+pub mod codeowners;
+pub mod commit_index;
+pub mod commit_summary;
 pub mod config;
+pub mod conventional;
+pub mod conventions;
 pub mod copilot;
+pub mod crypto;
+pub mod debug_log;
+pub mod diff_compact;
+pub mod diff_lines;
+pub mod editor;
+pub mod follow_ups;
+pub mod footer;
 pub mod git;
+#[cfg(feature = "libgit2")]
+pub mod git_native;
+pub mod github;
+pub mod import_graph;
+pub mod issues;
+pub mod repo_state;
+pub mod summary_cache;
+#[cfg(feature = "tree-sitter")]
+pub mod syntax;
+pub mod test_map;
+pub mod update;
+pub mod webhook;
+pub mod workspace;
 
 pub use config::ConfigManager;
 pub use copilot::CopilotCLI;
 pub use git::GitManager;
+
+/// Longest prefix of `text` that is at most `max_bytes` long and ends on a
+/// char boundary, so multi-byte characters are never split
+pub fn truncate_at_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::truncate_at_char_boundary;
+
+    #[test]
+    fn truncate_respects_char_boundaries() {
+        assert_eq!(truncate_at_char_boundary("héllo", 2), "h");
+        assert_eq!(truncate_at_char_boundary("héllo", 3), "hé");
+        assert_eq!(truncate_at_char_boundary("abc", 10), "abc");
+    }
+}
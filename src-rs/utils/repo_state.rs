@@ -0,0 +1,188 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::GitManager;
+use std::fs;
+use std::path::Path;
+
+/// Commits listed per side of a merge in the prompt
+const MAX_MERGE_COMMITS: usize = 20;
+
+/// Multi-step git operation the working tree is in the middle of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoState {
+    Clean,
+    /// `git merge` stopped before committing; `title` is git's prepared header
+    Merging {
+        title: Option<String>,
+    },
+    /// `git rebase` (or `git am`) is in progress
+    Rebasing {
+        /// Stopped at an `edit` step, where new commits are expected
+        editing: bool,
+    },
+    /// `git cherry-pick` or `git revert` stopped; `title` is the original header
+    Picking {
+        revert: bool,
+        title: Option<String>,
+    },
+}
+
+impl RepoState {
+    /// Inspect the current worktree's git dir (MERGE_HEAD, rebase-merge/, ...)
+    pub fn detect() -> Result<Self> {
+        Ok(Self::from_git_dir(&GitManager::get_git_dir()?))
+    }
+
+    fn from_git_dir(git_dir: &Path) -> Self {
+        let rebase_merge = git_dir.join("rebase-merge");
+        if rebase_merge.is_dir() || git_dir.join("rebase-apply").is_dir() {
+            return Self::Rebasing {
+                // git writes `amend` only when an `edit` step hands control back
+                editing: rebase_merge.join("amend").exists(),
+            };
+        }
+
+        let title = read_merge_title(git_dir);
+        if git_dir.join("MERGE_HEAD").exists() {
+            Self::Merging { title }
+        } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+            Self::Picking {
+                revert: false,
+                title,
+            }
+        } else if git_dir.join("REVERT_HEAD").exists() {
+            Self::Picking {
+                revert: true,
+                title,
+            }
+        } else {
+            Self::Clean
+        }
+    }
+
+    pub fn is_merge(&self) -> bool {
+        matches!(self, Self::Merging { .. })
+    }
+
+    /// Refuse to generate while a rebase stopped on conflicts: git will reuse
+    /// the original message on `--continue`, and a fresh commit would be wrong
+    pub fn ensure_can_generate(&self) -> Result<()> {
+        match self {
+            Self::Rebasing { editing: false } => Err(GitAiError::Git(
+                "A rebase is in progress. Resolve conflicts, 'git add' the files and run 'git rebase --continue'; git keeps the original commit message.".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Extra system prompt instruction for the current operation
+    pub fn system_instruction(&self, locale: &str) -> Option<&'static str> {
+        match (self, locale) {
+            (Self::Merging { .. }, "zh") => Some(
+                "本次提交是一个合并提交。不要使用 type(scope) 格式：第一行保持 git 提供的合并标题（如 \"Merge branch 'x' into y\"），空一行后用 2-4 条要点概括被合并分支带来的改动，必要时说明冲突是如何解决的。",
+            ),
+            (Self::Merging { .. }, _) => Some(
+                "This commit concludes a merge. Do NOT use a type(scope) header: keep git's merge title as the first line (e.g. \"Merge branch 'x' into y\"), then a blank line and 2-4 bullet points summarizing what the merged side brings and, if visible, how conflicts were resolved.",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Context appended to the user prompt
+    pub fn prompt_context(&self) -> Option<String> {
+        match self {
+            Self::Merging { title } => {
+                let mut context = String::from("Merge in progress");
+                if let Some(title) = title {
+                    context.push_str(&format!(" (git's title: {})", title));
+                }
+                let ours =
+                    GitManager::get_commits_between_refs("MERGE_HEAD", "HEAD").unwrap_or_default();
+                let theirs =
+                    GitManager::get_commits_between_refs("HEAD", "MERGE_HEAD").unwrap_or_default();
+                context.push_str(&format_side("Commits being merged in", &theirs));
+                context.push_str(&format_side("Commits only on the current branch", &ours));
+                Some(context)
+            }
+            Self::Picking { revert, title } => {
+                let action = if *revert {
+                    "Reverting"
+                } else {
+                    "Cherry-picking"
+                };
+                Some(match title {
+                    Some(title) => format!(
+                        "{} a commit whose message was: {}\nKeep the message close to that intent.",
+                        action, title
+                    ),
+                    None => format!("{} an existing commit.", action),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// First non-comment line of MERGE_MSG, which git prepares for the commit
+fn read_merge_title(git_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(git_dir.join("MERGE_MSG")).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+fn format_side(label: &str, commits: &[String]) -> String {
+    if commits.is_empty() {
+        return String::new();
+    }
+    let mut section = format!("\n\n{} ({}):", label, commits.len());
+    for commit in commits.iter().take(MAX_MERGE_COMMITS) {
+        section.push_str(&format!("\n- {}", commit));
+    }
+    if commits.len() > MAX_MERGE_COMMITS {
+        section.push_str(&format!(
+            "\n- ... and {} more",
+            commits.len() - MAX_MERGE_COMMITS
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RepoState;
+    use std::fs;
+
+    #[test]
+    fn detects_operations_from_git_dir() {
+        let dir = std::env::temp_dir().join(format!("git-ai-state-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(RepoState::from_git_dir(&dir), RepoState::Clean);
+
+        fs::write(dir.join("MERGE_HEAD"), "abc\n").unwrap();
+        fs::write(
+            dir.join("MERGE_MSG"),
+            "Merge branch 'feature'\n\n# Conflicts:\n",
+        )
+        .unwrap();
+        let state = RepoState::from_git_dir(&dir);
+        assert_eq!(
+            state,
+            RepoState::Merging {
+                title: Some("Merge branch 'feature'".to_string())
+            }
+        );
+        assert!(state.ensure_can_generate().is_ok());
+
+        fs::create_dir_all(dir.join("rebase-merge")).unwrap();
+        let state = RepoState::from_git_dir(&dir);
+        assert_eq!(state, RepoState::Rebasing { editing: false });
+        assert!(state.ensure_can_generate().is_err());
+
+        fs::write(dir.join("rebase-merge/amend"), "abc\n").unwrap();
+        assert!(RepoState::from_git_dir(&dir).ensure_can_generate().is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+use crate::error::Result;
+use crate::utils::ConfigManager;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Per-commit AI summaries keyed by `<locale>:<full sha>`, kept under the
+/// config dir so repeated reports only pay for commits they have not seen
+#[derive(Debug, Default)]
+pub struct SummaryCache {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+    dirty: bool,
+}
+
+impl SummaryCache {
+    /// `<config dir>/git-ai-cli/cache/commit-summaries.json`
+    pub fn open() -> Result<Self> {
+        Ok(Self::open_at(
+            ConfigManager::get_global_config_dir()?
+                .join("cache")
+                .join("commit-summaries.json"),
+        ))
+    }
+
+    /// A missing or unreadable cache starts empty instead of failing the report
+    pub fn open_at(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries,
+            dirty: false,
+        }
+    }
+
+    fn key(locale: &str, sha: &str) -> String {
+        format!("{}:{}", locale, sha)
+    }
+
+    pub fn get(&self, locale: &str, sha: &str) -> Option<&str> {
+        self.entries
+            .get(&Self::key(locale, sha))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, locale: &str, sha: &str, summary: String) {
+        self.entries.insert(Self::key(locale, sha), summary);
+        self.dirty = true;
+    }
+
+    /// Write the cache back if anything was added
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SummaryCache;
+
+    #[test]
+    fn round_trips_summaries_per_locale() {
+        let dir = std::env::temp_dir().join(format!("git-ai-summaries-{}", std::process::id()));
+        let path = dir.join("cache").join("commit-summaries.json");
+
+        let mut cache = SummaryCache::open_at(path.clone());
+        assert_eq!(cache.get("en", "abc"), None);
+        cache.insert("en", "abc", "Adds CSV export".to_string());
+        cache.save().unwrap();
+
+        let cache = SummaryCache::open_at(path);
+        assert_eq!(cache.get("en", "abc"), Some("Adds CSV export"));
+        assert_eq!(cache.get("zh", "abc"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+//! Symbol extraction on top of tree-sitter: map the lines a diff touches to
+//! the functions and types that enclose them in the new version of each file.
+//! Files in unsupported languages (or that cannot be loaded) are skipped; when
+//! nothing could be parsed the caller falls back to the regex heuristics.
+
+use crate::utils::diff_lines::changed_lines;
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Point};
+
+/// Qualified symbols reported per diff
+const MAX_SYMBOLS: usize = 5;
+
+/// Lines touched in the new version of one file (0-based rows)
+#[derive(Debug, Default, PartialEq)]
+struct FileChanges {
+    path: String,
+    rows: Vec<usize>,
+}
+
+/// Changed functions/types as `Scope::name` (Rust) or `Scope.name`, in diff
+/// order. `load` returns the new content of a path relative to the repo root.
+/// `None` when no changed file is in a supported language.
+pub fn changed_symbols(diff: &str, load: impl Fn(&str) -> Option<String>) -> Option<Vec<String>> {
+    let mut parsed_any = false;
+    let mut symbols: Vec<String> = Vec::new();
+
+    for file in parse_diff(diff) {
+        let Some(language) = language_for(&file.path) else {
+            continue;
+        };
+        let Some(source) = load(&file.path) else {
+            continue;
+        };
+        let mut parser = Parser::new();
+        if parser.set_language(&language).is_err() {
+            continue;
+        }
+        let Some(tree) = parser.parse(&source, None) else {
+            continue;
+        };
+        parsed_any = true;
+
+        let separator = if file.path.ends_with(".rs") {
+            "::"
+        } else {
+            "."
+        };
+        for row in file.rows {
+            let Some(symbol) = symbol_at(tree.root_node(), &source, row, separator) else {
+                continue;
+            };
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+    }
+
+    if !parsed_any {
+        return None;
+    }
+    symbols.truncate(MAX_SYMBOLS);
+    Some(symbols)
+}
+
+fn language_for(path: &str) -> Option<Language> {
+    let extension = Path::new(path).extension()?.to_str()?;
+    Some(match extension {
+        "rs" => tree_sitter_rust::LANGUAGE.into(),
+        "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE.into(),
+        "ts" | "mts" | "cts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "py" => tree_sitter_python::LANGUAGE.into(),
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        "java" => tree_sitter_java::LANGUAGE.into(),
+        _ => return None,
+    })
+}
+
+/// New-side rows of added lines, plus the row where lines were removed
+fn parse_diff(diff: &str) -> Vec<FileChanges> {
+    let mut files: Vec<FileChanges> = Vec::new();
+    for changed in changed_lines(diff) {
+        if files.last().is_none_or(|file| file.path != changed.path) {
+            files.push(FileChanges {
+                path: changed.path.to_string(),
+                rows: Vec::new(),
+            });
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        let row = changed.line.saturating_sub(1);
+        if changed.added.is_some() || file.rows.last() != Some(&row) {
+            file.rows.push(row);
+        }
+    }
+    files
+}
+
+/// Innermost definition around `row`, qualified with its enclosing ones
+fn symbol_at(root: Node, source: &str, row: usize, separator: &str) -> Option<String> {
+    let line = source.lines().nth(row)?;
+    let column = line.len() - line.trim_start().len();
+    let point = Point::new(row, column);
+    let mut node = root.descendant_for_point_range(point, point)?;
+
+    let mut names = Vec::new();
+    let mut found_symbol = false;
+    loop {
+        if let Some((name, is_symbol)) = definition_name(node, source) {
+            // Only scopes (impl blocks, modules) above the first real symbol
+            if is_symbol || found_symbol {
+                found_symbol = true;
+                names.push(name);
+            }
+        }
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+
+    if !found_symbol {
+        return None;
+    }
+    names.reverse();
+    Some(names.join(separator))
+}
+
+/// Name of a definition node and whether it is a symbol in its own right
+/// (`false` for pure scopes such as Rust `impl` blocks)
+fn definition_name(node: Node, source: &str) -> Option<(String, bool)> {
+    let text = |node: Node| node.utf8_text(source.as_bytes()).ok().map(str::to_string);
+
+    match node.kind() {
+        // Rust
+        "function_item" | "function_signature_item" | "struct_item" | "enum_item"
+        | "trait_item" | "type_item" | "union_item" | "macro_definition" | "mod_item"
+        // JS / TS
+        | "function_declaration" | "generator_function_declaration" | "class_declaration"
+        | "abstract_class_declaration" | "method_definition" | "interface_declaration"
+        | "type_alias_declaration" | "enum_declaration"
+        // Python
+        | "function_definition" | "class_definition"
+        // Go
+        | "type_spec"
+        // Java
+        | "record_declaration" | "constructor_declaration" => {
+            Some((text(node.child_by_field_name("name")?)?, true))
+        }
+        // Go methods are qualified by their receiver type; Java methods by
+        // the enclosing class, which the walk already provides
+        "method_declaration" => {
+            let name = text(node.child_by_field_name("name")?)?;
+            let receiver = node
+                .child_by_field_name("receiver")
+                .and_then(|receiver| receiver.named_child(0))
+                .and_then(|param| param.child_by_field_name("type"))
+                .and_then(text);
+            Some(match receiver {
+                Some(receiver) => (format!("{}.{}", receiver.trim_start_matches('*'), name), true),
+                None => (name, true),
+            })
+        }
+        // `impl Trait for Type` qualifies its items with the type
+        "impl_item" => {
+            let ty = text(node.child_by_field_name("type")?)?;
+            let base = ty.split('<').next().unwrap_or(&ty).trim().to_string();
+            Some((base, false))
+        }
+        // const handler = () => {...}
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            if !matches!(
+                value.kind(),
+                "arrow_function" | "function_expression" | "function"
+            ) {
+                return None;
+            }
+            Some((text(node.child_by_field_name("name")?)?, true))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols_for(path: &str, source: &str, diff_body: &str) -> Vec<String> {
+        let diff = format!(
+            "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n{diff_body}",
+            path = path
+        );
+        changed_symbols(&diff, |p| (p == path).then(|| source.to_string())).unwrap()
+    }
+
+    #[test]
+    fn names_rust_methods_with_their_impl() {
+        let source = "struct AuthService;\n\nimpl AuthService {\n    fn refresh_token(&self) {\n        let ttl = 60;\n    }\n}\n";
+        assert_eq!(
+            symbols_for(
+                "src/auth.rs",
+                source,
+                "@@ -4,2 +4,2 @@\n     fn refresh_token(&self) {\n-        let ttl = 30;\n+        let ttl = 60;\n"
+            ),
+            vec!["AuthService::refresh_token"]
+        );
+    }
+
+    #[test]
+    fn names_python_go_and_typescript_symbols() {
+        let python = "class Cache:\n    def get(self, key):\n        return None\n";
+        assert_eq!(
+            symbols_for(
+                "cache.py",
+                python,
+                "@@ -3 +3 @@\n-        pass\n+        return None\n"
+            ),
+            vec!["Cache.get"]
+        );
+
+        let go = "package api\n\nfunc (s *Server) Handle() {\n\tlog()\n}\n";
+        assert_eq!(
+            symbols_for("api.go", go, "@@ -4 +4 @@\n+\tlog()\n"),
+            vec!["Server.Handle"]
+        );
+
+        let ts = "export class Store {\n  load() {\n    return 1;\n  }\n}\nconst save = () => {\n  return 2;\n};\n";
+        assert_eq!(
+            symbols_for(
+                "store.ts",
+                ts,
+                "@@ -3 +3 @@\n+    return 1;\n@@ -7 +7 @@\n+  return 2;\n"
+            ),
+            vec!["Store.load", "save"]
+        );
+    }
+
+    #[test]
+    fn added_lines_are_not_file_headers() {
+        let ts = "function bump() {\n++ n;\n}\n";
+        assert_eq!(
+            symbols_for("bump.ts", ts, "@@ -2 +2 @@\n+++ n;\n"),
+            vec!["bump"]
+        );
+    }
+
+    #[test]
+    fn skips_unsupported_files() {
+        let diff = "diff --git a/README.md b/README.md\n--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n+# Title\n";
+        assert_eq!(
+            changed_symbols(diff, |_| Some("# Title\n".to_string())),
+            None
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::utils::import_graph::ImportGraph;
+use std::path::Path;
+
+/// Related tests reported per analysis
+const MAX_RELATED_TESTS: usize = 8;
+
+/// Directory names that hold tests
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec", "specs"];
+
+/// File stems too generic to match tests by name; the directory is used instead
+const GENERIC_STEMS: &[&str] = &["mod", "lib", "main", "index", "__init__"];
+
+/// Test files likely exercising `changed`, each as `path (reason)`: tests
+/// named after a changed file, inline `#[cfg(test)]` modules, and test files
+/// importing a changed module. `read` returns the content of a changed file.
+pub fn related_tests(
+    changed: &[String],
+    graph: &ImportGraph,
+    read: impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut tests: Vec<(String, String)> = Vec::new();
+    let mut add = |path: &str, reason: String| {
+        if !tests.iter().any(|(existing, _)| existing == path) {
+            tests.push((path.to_string(), reason));
+        }
+    };
+
+    let sources: Vec<&String> = changed.iter().filter(|path| !is_test_path(path)).collect();
+    for source in &sources {
+        if source.ends_with(".rs")
+            && read(source).is_some_and(|content| content.contains("#[cfg(test)]"))
+        {
+            add(source, "inline #[cfg(test)] module".to_string());
+        }
+
+        let Some(subject) = source_subject(source) else {
+            continue;
+        };
+        for test in graph.files().filter(|path| is_test_path(path)) {
+            // Go tests live next to the code they test
+            if source.ends_with(".go") && parent_dir(test) != parent_dir(source) {
+                continue;
+            }
+            if test_subject(test).as_deref() == Some(subject.as_str()) {
+                add(test, format!("named after {}", source));
+            }
+        }
+    }
+
+    let sources: Vec<String> = sources.into_iter().cloned().collect();
+    for (dependent, imports) in graph.dependents_of(&sources) {
+        if is_test_path(&dependent) {
+            add(&dependent, format!("imports {}", imports.join(", ")));
+        }
+    }
+
+    tests
+        .into_iter()
+        .take(MAX_RELATED_TESTS)
+        .map(|(path, reason)| format!("{} ({})", path, reason))
+        .collect()
+}
+
+/// Whether `path` looks like a test file by directory or naming convention
+pub fn is_test_path(path: &str) -> bool {
+    let mut components: Vec<&str> = path.split('/').collect();
+    let Some(file) = components.pop() else {
+        return false;
+    };
+    if components.iter().any(|dir| TEST_DIRS.contains(dir)) {
+        return true;
+    }
+    let stem = file.split('.').next().unwrap_or(file);
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || file.contains(".test.")
+        || file.contains(".spec.")
+        || (file.ends_with(".java") && (stem.ends_with("Test") || stem.ends_with("Tests")))
+}
+
+/// Lowercase name a source file is tested under
+fn source_subject(path: &str) -> Option<String> {
+    let file = path.rsplit('/').next()?;
+    let stem = file.split('.').next()?;
+    let subject = if GENERIC_STEMS.contains(&stem) {
+        parent_dir(path).rsplit('/').next()?
+    } else {
+        stem
+    };
+    (!subject.is_empty()).then(|| subject.to_lowercase())
+}
+
+/// Lowercase subject of a test file: `test_foo.py`, `foo_test.go`,
+/// `foo.spec.ts`, `FooTest.java` and `tests/foo.rs` all test `foo`
+fn test_subject(path: &str) -> Option<String> {
+    let file = path.rsplit('/').next()?;
+    let stem = file.split('.').next()?;
+    let stem = stem
+        .strip_prefix("test_")
+        .or_else(|| stem.strip_suffix("_test"))
+        .or_else(|| stem.strip_suffix("_tests"))
+        .or_else(|| stem.strip_suffix("_spec"))
+        .or_else(|| stem.strip_suffix("Tests"))
+        .or_else(|| stem.strip_suffix("Test"))
+        .unwrap_or(stem);
+    (!stem.is_empty() && !GENERIC_STEMS.contains(&stem)).then(|| stem.to_lowercase())
+}
+
+fn parent_dir(path: &str) -> &str {
+    Path::new(path)
+        .parent()
+        .and_then(|dir| dir.to_str())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_test_files() {
+        assert!(is_test_path("tests/report.rs"));
+        assert!(is_test_path("web/__tests__/api.ts"));
+        assert!(is_test_path("web/api.spec.ts"));
+        assert!(is_test_path("app/test_models.py"));
+        assert!(is_test_path("internal/api/server_test.go"));
+        assert!(is_test_path("src/test/java/com/acme/TokenTest.java"));
+        assert!(!is_test_path("src/commands/report.rs"));
+        assert!(!is_test_path("src/latest.rs"));
+    }
+
+    #[test]
+    fn maps_sources_to_tests() {
+        let graph = ImportGraph::from_sources(&[
+            ("src/lib.rs", ""),
+            ("src/report.rs", ""),
+            ("tests/report.rs", ""),
+            ("tests/cli.rs", "use git_ai::report;\n"),
+            ("web/api.ts", ""),
+            ("web/api.test.ts", ""),
+            ("web/flows.test.ts", "import { call } from './api';\n"),
+            ("svc/server.go", ""),
+            ("svc/server_test.go", ""),
+            ("other/server_test.go", ""),
+        ]);
+        let changed = [
+            "src/report.rs".to_string(),
+            "web/api.ts".to_string(),
+            "svc/server.go".to_string(),
+        ];
+        let tests = related_tests(&changed, &graph, |path| {
+            (path == "src/report.rs").then(|| "#[cfg(test)]\nmod tests {}\n".to_string())
+        });
+        assert_eq!(
+            tests,
+            vec![
+                "src/report.rs (inline #[cfg(test)] module)",
+                "tests/report.rs (named after src/report.rs)",
+                "web/api.test.ts (named after web/api.ts)",
+                "svc/server_test.go (named after svc/server.go)",
+                "web/flows.test.ts (imports web/api.ts)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Update check against the GitHub release feed and `git-ai self-update`.
+//!
+//! The check runs in the background at most once a day and only records the
+//! latest version; the notice is printed from that record, so a command waits
+//! on the network for half a second at most. `--no-update-check`, GIT_AI_NO_UPDATE_CHECK=1, CI
+//! and non-interactive runs skip both.
+
+use crate::error::{GitAiError, Result};
+use crate::utils::ConfigManager;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/dongowu/git-ai-cli/releases/latest";
+
+/// Checksum file published next to the binaries
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+const CHECK_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Longest a finished command waits for a pending check
+const FINISH_GRACE: Duration = Duration::from_millis(500);
+
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `<config>/git-ai-cli/update-check.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckRecord {
+    checked_at: i64,
+    latest: Option<String>,
+}
+
+/// Release as returned by the GitHub API
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Whether this run may check for updates and print the notice
+pub fn enabled(no_update_check: bool) -> bool {
+    let env_set = |name: &str| std::env::var(name).is_ok_and(|v| !v.is_empty() && v != "0");
+    !no_update_check
+        && !env_set("GIT_AI_NO_UPDATE_CHECK")
+        && !env_set("CI")
+        // Hooks run `git-ai msg` with GIT_AI_RUNNING=1
+        && !env_set("GIT_AI_RUNNING")
+        && std::io::stderr().is_terminal()
+}
+
+/// Refresh the record in the background when it is older than a day
+pub fn spawn_check() -> Option<JoinHandle<()>> {
+    let record = read_record();
+    if chrono::Utc::now().timestamp() - record.checked_at < CHECK_INTERVAL_SECS {
+        return None;
+    }
+    Some(tokio::spawn(async {
+        let latest = tokio::time::timeout(CHECK_TIMEOUT, fetch_latest_release())
+            .await
+            .ok()
+            .and_then(|release| release.ok())
+            .map(|release| release.version().to_string());
+        let record = CheckRecord {
+            checked_at: chrono::Utc::now().timestamp(),
+            latest,
+        };
+        if let (Ok(path), Ok(content)) = (record_path(), serde_json::to_string(&record)) {
+            let _ = fs::write(path, content);
+        }
+    }))
+}
+
+/// Give a check still running when the command ends a moment to finish; it
+/// is retried on the next run otherwise
+pub async fn finish_check(check: JoinHandle<()>) {
+    let _ = tokio::time::timeout(FINISH_GRACE, check).await;
+}
+
+/// "A newer version is available" line, from the last recorded check
+pub fn notice() -> Option<String> {
+    let latest = read_record().latest?;
+    is_newer(&latest, CURRENT_VERSION).then(|| {
+        format!(
+            "🆕 git-ai {} is available (you have {}). Run 'git-ai self-update' to upgrade.",
+            latest, CURRENT_VERSION
+        )
+    })
+}
+
+fn record_path() -> Result<PathBuf> {
+    let dir = ConfigManager::get_global_config_dir()?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join("update-check.json"))
+}
+
+fn read_record() -> CheckRecord {
+    record_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(concat!("git-ai/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| GitAiError::Http(format!("Failed to create HTTP client: {}", e)))
+}
+
+pub async fn fetch_latest_release() -> Result<Release> {
+    let url =
+        std::env::var("GIT_AI_RELEASES_URL").unwrap_or_else(|_| LATEST_RELEASE_URL.to_string());
+    let response = client()?
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Release check failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(GitAiError::Http(format!(
+            "Release feed returned {}",
+            response.status()
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Invalid release feed: {}", e)))
+}
+
+async fn download(url: &str) -> Result<Vec<u8>> {
+    let response = client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Download failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(GitAiError::Http(format!(
+            "Download of {} returned {}",
+            url,
+            response.status()
+        )));
+    }
+    Ok(response
+        .bytes()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Download failed: {}", e)))?
+        .to_vec())
+}
+
+/// Release asset built for this platform, e.g. `git-ai-cli-linux-x64`
+pub fn platform_asset_name() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("git-ai-cli-linux-x64"),
+        ("linux", "aarch64") => Some("git-ai-cli-linux-arm64"),
+        ("macos", "x86_64") => Some("git-ai-cli-darwin-x64"),
+        ("macos", "aarch64") => Some("git-ai-cli-darwin-arm64"),
+        ("windows", "x86_64") => Some("git-ai-cli-win32-x64.exe"),
+        _ => None,
+    }
+}
+
+/// Download this platform's binary from `release`, check it against the
+/// published SHA256SUMS and replace the running executable with it
+pub async fn install(release: &Release) -> Result<PathBuf> {
+    let name = platform_asset_name().ok_or_else(|| {
+        GitAiError::Other(format!(
+            "No prebuilt binary for {}-{}; build from source instead",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+    let missing = |file: &str| {
+        GitAiError::Other(format!(
+            "Release {} has no {} asset",
+            release.tag_name, file
+        ))
+    };
+    let asset = release.asset(name).ok_or_else(|| missing(name))?;
+    let checksums = release
+        .asset(CHECKSUMS_ASSET)
+        .ok_or_else(|| missing(CHECKSUMS_ASSET))?;
+
+    let checksums =
+        String::from_utf8_lossy(&download(&checksums.browser_download_url).await?).to_string();
+    let expected = expected_checksum(&checksums, name)
+        .ok_or_else(|| GitAiError::Other(format!("{} does not list {}", CHECKSUMS_ASSET, name)))?;
+    let binary = download(&asset.browser_download_url).await?;
+    let actual: String = Sha256::digest(&binary)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual != expected {
+        return Err(GitAiError::Other(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            name, expected, actual
+        )));
+    }
+
+    let exe = std::env::current_exe()?;
+    let staged = exe.with_extension("new");
+    fs::write(&staged, &binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows cannot overwrite a running executable, but it can rename it
+    if cfg!(windows) {
+        let old = exe.with_extension("old");
+        let _ = fs::remove_file(&old);
+        fs::rename(&exe, &old)?;
+    }
+    fs::rename(&staged, &exe)?;
+    Ok(exe)
+}
+
+/// Hex digest for `name` in `sha256sum` output (`<hex>  <name>` or `<hex> *<name>`)
+pub fn expected_checksum(checksums: &str, name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (hex, file) = line.trim().split_once(char::is_whitespace)?;
+        (file.trim().trim_start_matches('*') == name).then(|| hex.to_lowercase())
+    })
+}
+
+/// `latest` is a higher `major.minor.patch` than `current`; pre-release and
+/// malformed versions never count as newer
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Option<(u64, u64, u64)> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+        let version = (
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        );
+        parts.next().is_none().then_some(version)
+    };
+    match (parse(latest), parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_and_reads_checksums() {
+        assert!(is_newer("v2.1.0", "2.0.5"));
+        assert!(is_newer("2.0.10", "2.0.9"));
+        assert!(!is_newer("2.0.5", "2.0.5"));
+        assert!(!is_newer("2.1.0-beta.1", "2.0.5"));
+        assert!(!is_newer("nightly", "2.0.5"));
+
+        let sums = "ABC123  git-ai-cli-linux-x64\ndef456 *git-ai-cli-win32-x64.exe\n";
+        assert_eq!(
+            expected_checksum(sums, "git-ai-cli-linux-x64").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            expected_checksum(sums, "git-ai-cli-win32-x64.exe").as_deref(),
+            Some("def456")
+        );
+        assert_eq!(expected_checksum(sums, "git-ai-cli-darwin-x64"), None);
+    }
+}
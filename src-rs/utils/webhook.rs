@@ -0,0 +1,132 @@
+use crate::error::{GitAiError, Result};
+use crate::utils::truncate_at_char_boundary;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Slack rejects section blocks with more text than this
+const SLACK_SECTION_LIMIT: usize = 3000;
+
+/// Send a generated report to an incoming webhook. Slack URLs get Block Kit
+/// sections; anything else (Teams, Mattermost, ...) gets a markdown `text`.
+pub async fn post_report(url: &str, title: &str, report: &str) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(30))
+        .json(&build_payload(url, title, report))
+        .send()
+        .await
+        .map_err(|e| GitAiError::Http(format!("Webhook request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(GitAiError::Http(format!(
+            "Webhook returned {}: {}",
+            status,
+            body.trim()
+        )));
+    }
+    Ok(())
+}
+
+fn is_slack(url: &str) -> bool {
+    url.contains("hooks.slack.com")
+}
+
+fn build_payload(url: &str, title: &str, report: &str) -> Value {
+    if !is_slack(url) {
+        return json!({ "text": format!("**{}**\n\n{}", title, report.trim()) });
+    }
+
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": title.chars().take(150).collect::<String>() }
+    })];
+    for chunk in split_sections(&to_slack_markdown(report), SLACK_SECTION_LIMIT) {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": chunk }
+        }));
+    }
+    // `text` is the notification fallback
+    json!({ "text": title, "blocks": blocks })
+}
+
+/// Slack mrkdwn has no headings and uses single `*` for bold
+fn to_slack_markdown(markdown: &str) -> String {
+    markdown
+        .trim()
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') {
+                let heading = trimmed.trim_start_matches('#').trim().replace("**", "");
+                return format!("*{}*", heading);
+            }
+            let line = line.replace("**", "*");
+            match line.trim_start().strip_prefix("- ") {
+                Some(item) => {
+                    let indent = line.len() - line.trim_start().len();
+                    format!("{}• {}", " ".repeat(indent), item)
+                }
+                None => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split on line boundaries so every piece stays under `limit` bytes
+fn split_sections(text: &str, limit: usize) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if !current.is_empty() && current.len() + line.len() + 1 > limit {
+            sections.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        // A single oversized line is cut at a char boundary
+        let mut line = line;
+        while line.len() > limit {
+            let cut = truncate_at_char_boundary(line, limit);
+            sections.push(cut.to_string());
+            line = &line[cut.len()..];
+        }
+        current.push_str(line);
+    }
+    if !current.trim().is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_slack_and_generic_payloads() {
+        assert_eq!(
+            to_slack_markdown("## Highlights\n- **Export** to CSV\n  - nested"),
+            "*Highlights*\n• *Export* to CSV\n  • nested"
+        );
+
+        let slack = build_payload("https://hooks.slack.com/services/x", "Weekly", "# A\nbody");
+        assert_eq!(slack["text"], "Weekly");
+        assert_eq!(slack["blocks"][1]["text"]["text"], "*A*\nbody");
+
+        let teams = build_payload("https://example.webhook.office.com/x", "Weekly", "# A\n");
+        assert_eq!(teams["text"], "**Weekly**\n\n# A");
+    }
+
+    #[test]
+    fn splits_long_reports_into_sections() {
+        let text = ["a".repeat(6), "b".repeat(6), "c".repeat(12)].join("\n");
+        assert_eq!(
+            split_sections(&text, 10),
+            vec!["aaaaaa", "bbbbbb", "cccccccccc", "cc"]
+        );
+    }
+}
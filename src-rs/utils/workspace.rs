@@ -0,0 +1,379 @@
+//! Monorepo package layout: Cargo workspaces, pnpm / yarn / npm workspaces
+//! and Nx projects. The package owning the staged files is suggested to the
+//! model as the Conventional Commits scope, ahead of whatever the branch name
+//! hints at.
+
+use crate::utils::GitManager;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory levels searched for `**` patterns and Nx `project.json` files
+const MAX_DEPTH: usize = 4;
+
+/// Directories never searched for packages
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", ".git"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// Name without an npm `@org/` prefix
+    pub name: String,
+    /// Directory relative to the repository root, `/`-separated
+    pub dir: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Workspace {
+    packages: Vec<Package>,
+}
+
+impl Workspace {
+    /// Packages of the repository at `root`; empty when it is not a monorepo
+    pub fn detect(root: &Path) -> Self {
+        let mut dirs = cargo_members(root);
+        dirs.extend(js_workspace_members(root));
+        if root.join("nx.json").is_file() {
+            dirs.extend(
+                find_dirs(root, MAX_DEPTH)
+                    .into_iter()
+                    .filter(|dir| dir.join("project.json").is_file()),
+            );
+        }
+
+        let mut packages: Vec<Package> = Vec::new();
+        for dir in dirs {
+            let Some(name) = package_name(&dir) else {
+                continue;
+            };
+            let Ok(relative) = dir.strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if relative.is_empty() || packages.iter().any(|p| p.dir == relative) {
+                continue;
+            }
+            packages.push(Package {
+                name,
+                dir: relative,
+            });
+        }
+        Self { packages }
+    }
+
+    /// Package owning `path`: the one with the deepest directory containing it
+    pub fn package_of(&self, path: &str) -> Option<&Package> {
+        self.packages
+            .iter()
+            .filter(|p| path.starts_with(&p.dir) && path[p.dir.len()..].starts_with('/'))
+            .max_by_key(|p| p.dir.len())
+    }
+
+    /// Packages touched by `paths`, most files first
+    pub fn packages_for(&self, paths: &[String]) -> Vec<&Package> {
+        let mut counts: Vec<(&Package, usize)> = Vec::new();
+        for package in paths.iter().filter_map(|path| self.package_of(path)) {
+            match counts.iter_mut().find(|(p, _)| p.dir == package.dir) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((package, 1)),
+            }
+        }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts.into_iter().map(|(package, _)| package).collect()
+    }
+
+    /// Prompt line suggesting the scope for `paths`; `allowed_scopes`, when
+    /// set, drops packages whose name is not in it
+    pub fn scope_hint(&self, paths: &[String], allowed_scopes: &[String]) -> Option<String> {
+        let packages: Vec<&Package> = self
+            .packages_for(paths)
+            .into_iter()
+            .filter(|p| allowed_scopes.is_empty() || allowed_scopes.contains(&p.name))
+            .collect();
+        match packages.as_slice() {
+            [] => None,
+            [package] => Some(format!(
+                "Workspace package: the changes are in `{}` ({}). Use `{}` as the scope, rather than a scope suggested by the branch name.",
+                package.name, package.dir, package.name
+            )),
+            [main, ..] => Some(format!(
+                "Workspace packages touched: {}. Use the package with the main change as the scope (most files: `{}`), rather than a scope suggested by the branch name.",
+                packages
+                    .iter()
+                    .map(|p| format!("`{}` ({})", p.name, p.dir))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                main.name
+            )),
+        }
+    }
+}
+
+/// Scope suggestion for the staged `paths` of the current repository
+pub fn scope_hint(paths: &[String], allowed_scopes: Option<&[String]>) -> Option<String> {
+    let root = GitManager::get_repo_root().ok()?;
+    Workspace::detect(&root).scope_hint(paths, allowed_scopes.unwrap_or_default())
+}
+
+/// `[workspace] members` of the root `Cargo.toml`
+fn cargo_members(root: &Path) -> Vec<PathBuf> {
+    let Some(manifest) = read_toml(&root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = manifest.get("workspace") else {
+        return Vec::new();
+    };
+    let excluded: Vec<PathBuf> = string_list(workspace.get("exclude"))
+        .iter()
+        .flat_map(|pattern| expand(root, pattern))
+        .collect();
+    string_list(workspace.get("members"))
+        .iter()
+        .flat_map(|pattern| expand(root, pattern))
+        .filter(|dir| !excluded.contains(dir))
+        .collect()
+}
+
+/// `packages` of `pnpm-workspace.yaml`, else `workspaces` of `package.json`
+/// (yarn and npm, as a list or as `{ "packages": [...] }`)
+fn js_workspace_members(root: &Path) -> Vec<PathBuf> {
+    let patterns = match fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        Ok(yaml) => pnpm_packages(&yaml),
+        Err(_) => {
+            let Some(manifest) = read_json(&root.join("package.json")) else {
+                return Vec::new();
+            };
+            let workspaces = manifest.get("workspaces");
+            let list = workspaces
+                .and_then(|w| w.get("packages"))
+                .or(workspaces)
+                .and_then(|w| w.as_array());
+            list.into_iter()
+                .flatten()
+                .filter_map(|p| p.as_str().map(str::to_string))
+                .collect()
+        }
+    };
+    let (excluded, included): (Vec<String>, Vec<String>) =
+        patterns.into_iter().partition(|p| p.starts_with('!'));
+    let excluded: Vec<PathBuf> = excluded
+        .iter()
+        .flat_map(|pattern| expand(root, &pattern[1..]))
+        .collect();
+    included
+        .iter()
+        .flat_map(|pattern| expand(root, pattern))
+        .filter(|dir| !excluded.contains(dir))
+        .collect()
+}
+
+/// Items of the top-level `packages:` list of a pnpm workspace file
+fn pnpm_packages(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let (true, Some(item)) = (in_packages, trimmed.strip_prefix('-')) {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            patterns.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Directories matching a workspace glob: `*` matches within one path
+/// segment, `**` any number of segments
+fn expand(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut current = vec![root.to_path_buf()];
+    for segment in pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+    {
+        current = match segment {
+            "" | "." => current,
+            "**" => current
+                .iter()
+                .flat_map(|dir| {
+                    let mut all = vec![dir.clone()];
+                    all.extend(find_dirs(dir, MAX_DEPTH));
+                    all
+                })
+                .collect(),
+            _ if segment.contains('*') => current
+                .iter()
+                .flat_map(|dir| child_dirs(dir))
+                .filter(|dir| {
+                    dir.file_name()
+                        .is_some_and(|name| wildcard_match(segment, &name.to_string_lossy()))
+                })
+                .collect(),
+            _ => current
+                .iter()
+                .map(|dir| dir.join(segment))
+                .filter(|dir| dir.is_dir())
+                .collect(),
+        };
+    }
+    current
+}
+
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path.file_name().is_some_and(|name| {
+                    let name = name.to_string_lossy();
+                    !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+                })
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Subdirectories of `dir` down to `depth` levels
+fn find_dirs(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    child_dirs(dir)
+        .into_iter()
+        .flat_map(|child| {
+            let mut found = find_dirs(&child, depth - 1);
+            found.insert(0, child);
+            found
+        })
+        .collect()
+}
+
+/// `*` matches any run of characters
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || name.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &name[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Name from the package's `Cargo.toml`, `package.json` or Nx `project.json`
+fn package_name(dir: &Path) -> Option<String> {
+    let name = read_toml(&dir.join("Cargo.toml"))
+        .and_then(|m| m.get("package")?.get("name")?.as_str().map(str::to_string))
+        .or_else(|| json_name(&dir.join("package.json")))
+        .or_else(|| json_name(&dir.join("project.json")))?;
+    // `@acme/ui` is scoped `ui`
+    Some(name.rsplit('/').next().unwrap_or(&name).to_string())
+}
+
+fn json_name(path: &Path) -> Option<String> {
+    read_json(path)?
+        .get("name")?
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn string_list(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pnpm_packages, wildcard_match, Workspace};
+    use std::fs;
+
+    #[test]
+    fn maps_files_to_workspace_packages() {
+        let root = std::env::temp_dir().join(format!("git-ai-workspace-{}", std::process::id()));
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n",
+        );
+        write("crates/core/Cargo.toml", "[package]\nname = \"app-core\"\n");
+        write(
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n",
+        );
+        write(
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'web/*' # apps\n  - \"!web/legacy\"\nonlyBuiltDependencies:\n  - esbuild\n",
+        );
+        write("web/ui/package.json", "{\"name\": \"@acme/ui\"}");
+        write("web/legacy/package.json", "{\"name\": \"legacy\"}");
+
+        let workspace = Workspace::detect(&root);
+        let names: Vec<&str> = workspace.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app-core", "ui"]);
+        assert_eq!(
+            workspace
+                .package_of("crates/core/src/lib.rs")
+                .map(|p| p.dir.as_str()),
+            Some("crates/core")
+        );
+        assert!(workspace.package_of("crates/core-extra/x.rs").is_none());
+
+        let files = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let hint = workspace.scope_hint(&files(&["crates/core/src/lib.rs", "README.md"]), &[]);
+        assert!(hint.unwrap().contains("Use `app-core` as the scope"));
+        let hint = workspace
+            .scope_hint(
+                &files(&["web/ui/a.ts", "web/ui/b.ts", "crates/core/x.rs"]),
+                &[],
+            )
+            .unwrap();
+        assert!(hint.contains("`ui` (web/ui), `app-core` (crates/core)"));
+        assert!(workspace
+            .scope_hint(&files(&["crates/core/x.rs"]), &["api".to_string()])
+            .is_none());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn parses_pnpm_packages_and_wildcards() {
+        assert_eq!(
+            pnpm_packages("packages:\n- apps/*\n-   'libs/**'\ncatalog:\n  react: ^18\n"),
+            vec!["apps/*", "libs/**"]
+        );
+        assert!(wildcard_match("*", "api"));
+        assert!(wildcard_match("svc-*", "svc-auth"));
+        assert!(!wildcard_match("svc-*", "lib-auth"));
+        assert!(wildcard_match("a*c*e", "abcde"));
+    }
+}